@@ -0,0 +1,146 @@
+use std::{collections::HashMap, fmt::Write as _};
+
+use anyhow::{Context, Result, anyhow, bail};
+use librqbit_bencode::{BencodeValue, ByteBuf};
+use url::Url;
+
+use crate::scrape::{TIMEOUT, decode_hash, exchange, transaction_id, udp_connect};
+
+/// What a client tells a tracker in a regular (event-less) announce.
+#[derive(Debug, Clone)]
+pub struct AnnounceRequest {
+    /// 40-character hex.
+    pub info_hash: String,
+    pub peer_id: [u8; 20],
+    pub port: u16,
+    pub uploaded: u64,
+    pub downloaded: u64,
+    pub left: u64,
+}
+
+/// Announces once and returns the tracker's response decoded to text: the bencoded dict
+/// for HTTP(S) trackers, the BEP 15 fields for UDP ones. Failure reasons come back as a
+/// response, not an error, since they're what this is for.
+pub async fn announce(url: &str, request: &AnnounceRequest) -> Result<String> {
+    let hash = decode_hash(&request.info_hash)?;
+    let url = Url::parse(url).with_context(|| format!("invalid tracker URL {url}"))?;
+    let call = async {
+        match url.scheme() {
+            "http" | "https" => announce_http(&url, &hash, request).await,
+            "udp" => announce_udp(&url, &hash, request).await,
+            scheme => bail!("unsupported tracker scheme {scheme}"),
+        }
+    };
+    tokio::time::timeout(TIMEOUT, call)
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", TIMEOUT.as_secs()))?
+}
+
+async fn announce_http(url: &Url, hash: &[u8; 20], request: &AnnounceRequest) -> Result<String> {
+    let mut url = url.clone();
+    let mut query = url.query().map(|q| format!("{q}&")).unwrap_or_default();
+    let _ = write!(
+        query,
+        "info_hash={}&peer_id={}&port={}&uploaded={}&downloaded={}&left={}&compact=1",
+        percent_encode(hash),
+        percent_encode(&request.peer_id),
+        request.port,
+        request.uploaded,
+        request.downloaded,
+        request.left
+    );
+    url.set_query(Some(&query));
+    let response = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .context("request failed")?;
+    let status = response.status();
+    let body = response.bytes().await.context("failed to read response")?;
+    let decoded = match librqbit_bencode::dyn_from_bytes::<ByteBuf>(&body) {
+        Ok(value) => render(&value, None),
+        Err(_) => {
+            let start = String::from_utf8_lossy(&body[..body.len().min(200)]);
+            format!("(not bencode) {start:?}")
+        }
+    };
+    if status.is_success() {
+        Ok(decoded)
+    } else {
+        Ok(format!("HTTP {status} {decoded}"))
+    }
+}
+
+async fn announce_udp(url: &Url, hash: &[u8; 20], request: &AnnounceRequest) -> Result<String> {
+    let (socket, connection) = udp_connect(url).await?;
+    let transaction = transaction_id();
+    let mut packet = Vec::with_capacity(98);
+    packet.extend_from_slice(&connection.to_be_bytes());
+    packet.extend_from_slice(&1u32.to_be_bytes());
+    packet.extend_from_slice(&transaction.to_be_bytes());
+    packet.extend_from_slice(hash);
+    packet.extend_from_slice(&request.peer_id);
+    packet.extend_from_slice(&request.downloaded.to_be_bytes());
+    packet.extend_from_slice(&request.left.to_be_bytes());
+    packet.extend_from_slice(&request.uploaded.to_be_bytes());
+    // Event none, default IP, a throwaway key, default number of peers.
+    packet.extend_from_slice(&0u32.to_be_bytes());
+    packet.extend_from_slice(&0u32.to_be_bytes());
+    packet.extend_from_slice(&transaction_id().to_be_bytes());
+    packet.extend_from_slice(&(-1i32).to_be_bytes());
+    packet.extend_from_slice(&request.port.to_be_bytes());
+    let response = exchange(&socket, &packet, transaction, 1).await?;
+    let field = |at: usize| {
+        response
+            .get(at..at + 4)
+            .and_then(|b| b.try_into().ok())
+            .map(u32::from_be_bytes)
+            .context("short announce response")
+    };
+    Ok(format!(
+        "{{interval: {}, leechers: {}, seeders: {}, peers: <{} peers>}}",
+        field(0)?,
+        field(4)?,
+        field(8)?,
+        response.len().saturating_sub(12) / 6
+    ))
+}
+
+/// Compact text for a decoded value, with dict keys sorted and binary strings summarised;
+/// `key` is the dict key the value sits under, to count compact peer lists.
+fn render(value: &BencodeValue<ByteBuf>, key: Option<&[u8]>) -> String {
+    match value {
+        BencodeValue::Integer(n) => n.to_string(),
+        BencodeValue::Bytes(bytes) => {
+            let bytes = bytes.as_ref();
+            match (key, std::str::from_utf8(bytes)) {
+                (Some(b"peers"), _) => format!("<{} peers>", bytes.len() / 6),
+                (Some(b"peers6"), _) => format!("<{} peers>", bytes.len() / 18),
+                (_, Ok(text)) if !text.chars().any(char::is_control) => format!("{text:?}"),
+                _ => format!("<{} bytes>", bytes.len()),
+            }
+        }
+        BencodeValue::List(items) => {
+            let items: Vec<String> = items.iter().map(|item| render(item, None)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        BencodeValue::Dict(dict) => render_dict(dict),
+    }
+}
+
+fn render_dict(dict: &HashMap<ByteBuf, BencodeValue<ByteBuf>>) -> String {
+    let mut entries: Vec<_> = dict.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+    let entries: Vec<String> = entries
+        .into_iter()
+        .map(|(key, value)| {
+            let key = key.as_ref();
+            format!("{}: {}", String::from_utf8_lossy(key), render(value, Some(key)))
+        })
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+fn percent_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("%{b:02X}")).collect()
+}
@@ -0,0 +1,253 @@
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+    sync::{mpsc, oneshot},
+};
+use tokio_native_tls::TlsAcceptor;
+
+use crate::auth;
+
+/// Requests that don't finish sending in time are dropped.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a request waits for the app to take it, which happens between two effects.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// What a remote client asked for, handed to the app loop.
+#[derive(Debug, Clone)]
+pub enum ApiCommand {
+    List,
+    Add {
+        magnet: String,
+        label: Option<String>,
+        dir: Option<PathBuf>,
+    },
+    Pause(usize),
+    Resume(usize),
+    /// Drops the torrent from the session, and its data with `files`.
+    Delete { id: usize, files: bool },
+}
+
+#[derive(Debug)]
+pub struct ApiRequest {
+    pub command: ApiCommand,
+    /// The peer address, kept as the add source.
+    pub client: String,
+    pub reply: oneshot::Sender<ApiReply>,
+}
+
+#[derive(Debug)]
+pub struct ApiReply {
+    pub status: &'static str,
+    pub body: Value,
+}
+
+impl ApiReply {
+    pub fn ok(body: Value) -> Self {
+        Self { status: "200 OK", body }
+    }
+
+    /// The action was queued as an effect and runs on the app's next turn.
+    pub fn accepted() -> Self {
+        Self {
+            status: "202 Accepted",
+            body: json!({ "status": "accepted" }),
+        }
+    }
+
+    pub fn error(status: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AddBody {
+    magnet: String,
+    label: Option<String>,
+    dir: Option<PathBuf>,
+}
+
+/// Serves the remote-control API on `listen` until the process exits, over HTTPS when
+/// given an acceptor. Every request needs `token`. Returns the bound address.
+pub async fn serve(
+    listen: &str,
+    requests: mpsc::UnboundedSender<ApiRequest>,
+    token: String,
+    tls: Option<TlsAcceptor>,
+) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("failed to listen on {listen}"))?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, peer)) = listener.accept().await else {
+                continue;
+            };
+            let requests = requests.clone();
+            let token = token.clone();
+            let tls = tls.clone();
+            tokio::spawn(async move {
+                let client = peer.ip().to_string();
+                let _ = match tls {
+                    Some(tls) => match tls.accept(stream).await {
+                        Ok(stream) => handle(stream, &requests, &token, client).await,
+                        Err(_) => return,
+                    },
+                    None => handle(stream, &requests, &token, client).await,
+                };
+            });
+        }
+    });
+    Ok(addr)
+}
+
+async fn handle<S>(
+    mut stream: S,
+    requests: &mpsc::UnboundedSender<ApiRequest>,
+    token: &str,
+    client: String,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await;
+    let reply = match request {
+        Err(_) => ApiReply::error("408 Request Timeout", "request timed out"),
+        Ok(Err(err)) => ApiReply::error("400 Bad Request", format!("{err:#}")),
+        Ok(Ok((headers, _))) if !auth::authorized(&headers, token) => {
+            ApiReply::error("401 Unauthorized", "missing or wrong token")
+        }
+        Ok(Ok((headers, body))) => match route(&headers, &body) {
+            Ok(command) => send(requests, command, client).await,
+            Err(reply) => reply,
+        },
+    };
+    let body = format!("{}\n", reply.body);
+    let challenge = if reply.status.starts_with("401") {
+        "WWW-Authenticate: Bearer\r\n"
+    } else {
+        ""
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         {challenge}Cache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        reply.status,
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// The headers as text and the body, as long as `Content-Length` says.
+async fn read_request<S>(stream: &mut S) -> Result<(String, Vec<u8>)>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    let header_end = loop {
+        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if request.len() > MAX_HEADER_BYTES {
+            bail!("headers too large");
+        }
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            bail!("connection closed mid-request");
+        }
+        request.extend_from_slice(&buf[..read]);
+    };
+    let headers = String::from_utf8_lossy(&request[..header_end]).into_owned();
+    let length = headers
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim().parse::<usize>())
+        .transpose()
+        .context("invalid Content-Length")?
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        bail!("body too large");
+    }
+    let mut body = request.split_off(header_end + 4);
+    while body.len() < length {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            bail!("connection closed mid-body");
+        }
+        body.extend_from_slice(&buf[..read]);
+    }
+    body.truncate(length);
+    Ok((headers, body))
+}
+
+fn route(headers: &str, body: &[u8]) -> Result<ApiCommand, ApiReply> {
+    let mut parts = headers.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let id = |segment: &str| {
+        segment
+            .parse::<usize>()
+            .map_err(|_| ApiReply::error("404 Not Found", "not found"))
+    };
+    match (method, segments.as_slice()) {
+        ("GET", ["api", "torrents"]) => Ok(ApiCommand::List),
+        ("POST", ["api", "torrents"]) => {
+            let add: AddBody = serde_json::from_slice(body).map_err(|err| {
+                ApiReply::error("400 Bad Request", format!("invalid request body: {err}"))
+            })?;
+            if add.magnet.trim().is_empty() {
+                return Err(ApiReply::error("400 Bad Request", "magnet is empty"));
+            }
+            Ok(ApiCommand::Add {
+                magnet: add.magnet.trim().to_string(),
+                label: add.label,
+                dir: add.dir,
+            })
+        }
+        ("POST", ["api", "torrents", segment, "pause"]) => Ok(ApiCommand::Pause(id(segment)?)),
+        ("POST", ["api", "torrents", segment, "resume"]) => Ok(ApiCommand::Resume(id(segment)?)),
+        ("DELETE", ["api", "torrents", segment]) => Ok(ApiCommand::Delete {
+            id: id(segment)?,
+            files: query.split('&').any(|pair| pair == "files=true"),
+        }),
+        (_, ["api", "torrents", ..]) => {
+            Err(ApiReply::error("405 Method Not Allowed", "method not allowed"))
+        }
+        _ => Err(ApiReply::error("404 Not Found", "not found")),
+    }
+}
+
+async fn send(
+    requests: &mpsc::UnboundedSender<ApiRequest>,
+    command: ApiCommand,
+    client: String,
+) -> ApiReply {
+    let (reply, answer) = oneshot::channel();
+    let request = ApiRequest {
+        command,
+        client,
+        reply,
+    };
+    if requests.send(request).is_err() {
+        return ApiReply::error("503 Service Unavailable", "shutting down");
+    }
+    match tokio::time::timeout(REPLY_TIMEOUT, answer).await {
+        Ok(Ok(reply)) => reply,
+        _ => ApiReply::error("503 Service Unavailable", "busy, try again"),
+    }
+}
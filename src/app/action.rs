@@ -1,8 +1,12 @@
-use super::{FocusPanel, View};
+use super::{
+    ActivityFilter, FocusPanel, LabelTarget, LimitKind, NotesEdit, PendingAdd, RuleTesterEdit,
+    ScheduleEdit, View,
+};
 
 #[derive(Debug, Clone)]
 pub enum Action {
     Paste(String),
+    DebugToggle,
     HelpOpen,
     HelpClose,
     HelpScroll(i16),
@@ -11,18 +15,124 @@ pub enum Action {
     ConfirmDeleteSelect(bool),
     ConfirmDeleteConfirm,
     ConfirmDeleteCancel,
+    QuitPressed,
+    Lock,
+    CycleTheme,
+    Unlock,
+    Focus(bool),
     ConfirmQuitOpen,
     ConfirmQuitSelect(bool),
     ConfirmQuitConfirm,
     ConfirmQuitCancel,
+    ConfirmSpaceOpen { add: PendingAdd, free: u64 },
+    ConfirmSpaceSelect(bool),
+    ConfirmSpaceConfirm,
+    ConfirmSpaceCancel,
+    CollisionSelect(isize),
+    CollisionConfirm(Option<usize>),
+    CollisionCancel,
+    ImportCancel,
+    ImportClose,
+    MaintenanceConfirmSelect(bool),
+    MaintenanceConfirm,
+    MaintenanceConfirmCancel,
+    MaintenanceCancel,
+    MaintenanceClose,
+    /// Progress of a long-running operation; `pct` is 0-100 when it can be measured.
+    Progress { id: String, pct: Option<u8>, msg: String },
+    ProgressDone { id: String },
+    RetryNow,
+    RetryCancel,
+    /// Esc in the busy dialog before its effect has started.
+    BusyCancel,
+    TasksOpen,
+    TasksClose,
+    TasksMove(i32),
+    TasksCancel,
+    TalkersOpen,
+    TalkersClose,
+    TalkersMove(isize),
+    TalkersSort,
+    TalkersJump,
+    DuplicatesClose,
+    DuplicatesScroll(i32),
+    DirChoiceMove(isize),
+    TorrentFileClose,
+    TorrentFileScroll(i16),
+    TorrentFileAdd,
+    ArchiveOpen,
+    ArchiveClose,
+    ArchiveMove(isize),
+    ArchiveRestore,
+    RuleTesterOpen,
+    RuleTesterEdit(RuleTesterEdit),
+    RuleTesterClose,
+    ActivityOpen(ActivityFilter),
+    ActivityFilterNext,
+    ActivityScroll(isize),
+    ActivityClose,
+    ContextMenuOpen,
+    ContextMenuMove(isize),
+    ContextMenuConfirm,
+    ContextMenuClose,
+    ScheduleOpen,
+    ScheduleEdit(ScheduleEdit),
+    ScheduleSave,
+    ScheduleClose,
+    MagnetPreviewFetch,
+    MagnetPreviewEdit,
+    MagnetPreviewReject,
+    PathReviewSelect(bool),
+    PathReviewConfirm,
+    PathReviewCancel,
+    InspectorOpen,
+    InspectorClose,
+    InspectorMove(isize),
+    InspectorToggleFold,
+    InspectorFoldAll(bool),
+    InspectorSearchStart,
+    InspectorFind(bool),
+    ColumnResizeOpen,
+    ColumnResizeSelect(isize),
+    ColumnResize(isize),
+    ColumnResizeReset,
+    ColumnResizeSave,
+    ColumnResizeCancel,
     ViewSet(View),
     FocusToggle,
     FocusSet(FocusPanel),
     MoveSelection(isize),
     MoveFilter(isize),
+    FilesMove(isize),
+    FilesOpen,
+    FileOpenSystem,
+    CopyStreamUrl,
+    PeersMove(isize),
+    CopyPeerAddress,
+    InfoScroll(i16),
+    InfoTop,
+    CopyInfoHash,
+    CastOpen,
+    CastMove(isize),
+    CastConfirm,
+    CastRefresh,
+    CastClose,
+    GroupCycle,
+    GroupToggle,
     SetFilter(usize),
     TogglePause,
+    ForceStart,
+    RateLimitBump(LimitKind, i64),
+    RateLimitsClear,
     StartAdd,
+    StartLabelEdit(LabelTarget),
+    StartCommand,
+    StartRename,
+    StartLocate,
+    NotesOpen,
+    NotesEdit(NotesEdit),
+    NotesSave,
+    NotesCancel,
     InputChar(char),
     InputBackspace,
     InputDelete,
@@ -37,10 +147,14 @@ pub enum Action {
     FilePickerToggle,
     FilePickerAll,
     FilePickerNone,
+    FilePickerSmart,
+    FilePickerToggleStorage,
     FilePickerConfirm,
+    RiskyFilesSelect(bool),
+    RiskyFilesConfirm,
+    RiskyFilesCancel,
     FilePickerCancel,
     Refresh,
-    PreflightAddResult { magnet: String },
     RunEffect(super::effect::Effect),
 }
 
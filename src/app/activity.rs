@@ -0,0 +1,238 @@
+use std::{collections::VecDeque, io::Write, path::PathBuf};
+
+use chrono::{DateTime, Local};
+
+use super::state::{App, Dialog};
+
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone)]
+pub struct ActivityEntry {
+    pub at: DateTime<Local>,
+    pub message: String,
+    /// Set when the entry records an automation running rather than plain activity.
+    pub run: Option<AutomationRun>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Automation {
+    Hook,
+    Budget,
+    Move,
+    Permissions,
+    Library,
+}
+
+const AUTOMATIONS: [Automation; 5] = [
+    Automation::Hook,
+    Automation::Budget,
+    Automation::Move,
+    Automation::Permissions,
+    Automation::Library,
+];
+
+impl Automation {
+    pub fn label(self) -> &'static str {
+        match self {
+            Automation::Hook => "hook",
+            Automation::Budget => "budget",
+            Automation::Move => "move",
+            Automation::Permissions => "permissions",
+            Automation::Library => "library",
+        }
+    }
+}
+
+/// Which rule ran and whether it worked.
+#[derive(Debug, Clone)]
+pub struct AutomationRun {
+    pub kind: Automation,
+    /// The config entry behind it, e.g. `hooks #2` or `labels.tv budget_gb`.
+    pub rule: String,
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActivityFilter {
+    #[default]
+    All,
+    Automation,
+    Failed,
+    Kind(Automation),
+}
+
+impl ActivityFilter {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "all" => Some(Self::All),
+            "automation" => Some(Self::Automation),
+            "failed" => Some(Self::Failed),
+            _ => AUTOMATIONS
+                .into_iter()
+                .find(|kind| kind.label() == text)
+                .map(Self::Kind),
+        }
+    }
+
+    /// All, automation, failed, then each kind in turn.
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Automation,
+            Self::Automation => Self::Failed,
+            Self::Failed => Self::Kind(AUTOMATIONS[0]),
+            Self::Kind(kind) => match AUTOMATIONS.iter().position(|&k| k == kind) {
+                Some(idx) if idx + 1 < AUTOMATIONS.len() => Self::Kind(AUTOMATIONS[idx + 1]),
+                _ => Self::All,
+            },
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Automation => "automation",
+            Self::Failed => "failed",
+            Self::Kind(kind) => kind.label(),
+        }
+    }
+
+    pub fn matches(self, entry: &ActivityEntry) -> bool {
+        match (self, &entry.run) {
+            (Self::All, _) => true,
+            (_, None) => false,
+            (Self::Automation, Some(_)) => true,
+            (Self::Failed, Some(run)) => !run.ok,
+            (Self::Kind(kind), Some(run)) => run.kind == kind,
+        }
+    }
+}
+
+/// The `:activity` dialog.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityView {
+    pub filter: ActivityFilter,
+    pub scroll: usize,
+}
+
+/// In-memory ring of recent activity, mirrored to `activity.log` in the data dir.
+#[derive(Debug, Default)]
+pub struct ActivityLog {
+    entries: VecDeque<ActivityEntry>,
+    file: Option<PathBuf>,
+}
+
+impl ActivityLog {
+    pub fn new(file: Option<PathBuf>) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            file,
+        }
+    }
+
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.push_entry(ActivityEntry {
+            at: Local::now(),
+            message: message.into(),
+            run: None,
+        });
+    }
+
+    /// Records an automation's outcome so it can be filtered for later.
+    pub fn record(&mut self, kind: Automation, rule: impl Into<String>, ok: bool, message: String) {
+        self.push_entry(ActivityEntry {
+            at: Local::now(),
+            message,
+            run: Some(AutomationRun {
+                kind,
+                rule: rule.into(),
+                ok,
+            }),
+        });
+    }
+
+    fn push_entry(&mut self, entry: ActivityEntry) {
+        if let Some(path) = self.file.as_ref() {
+            let _ = append_line(path, &entry);
+        }
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Stops mirroring to `activity.log`.
+    pub fn read_only(&mut self) {
+        self.file = None;
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &ActivityEntry> {
+        self.entries.iter()
+    }
+}
+
+impl App {
+    pub(super) fn open_activity(&mut self, filter: ActivityFilter) {
+        self.activity_view = Some(ActivityView { filter, scroll: 0 });
+        self.dialog = Dialog::Activity;
+    }
+
+    pub(super) fn close_activity(&mut self) {
+        self.activity_view = None;
+        if self.dialog == Dialog::Activity {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    pub(super) fn cycle_activity_filter(&mut self) {
+        if let Some(view) = &mut self.activity_view {
+            view.filter = view.filter.next();
+            view.scroll = 0;
+        }
+    }
+
+    pub(super) fn scroll_activity(&mut self, delta: isize) {
+        let Some(filter) = self.activity_view.as_ref().map(|v| v.filter) else {
+            return;
+        };
+        let count = self.activity.entries().filter(|e| filter.matches(e)).count();
+        if let Some(view) = &mut self.activity_view {
+            view.scroll = view
+                .scroll
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
+        }
+    }
+
+    pub fn activity_view(&self) -> Option<&ActivityView> {
+        self.activity_view.as_ref()
+    }
+
+    /// Entries the view's filter lets through, newest first.
+    pub fn activity_entries(&self) -> Vec<&ActivityEntry> {
+        let filter = self.activity_view.as_ref().map(|v| v.filter).unwrap_or_default();
+        self.activity
+            .entries()
+            .rev()
+            .filter(|e| filter.matches(e))
+            .collect()
+    }
+}
+
+fn append_line(path: &PathBuf, entry: &ActivityEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let at = entry.at.format("%Y-%m-%d %H:%M:%S");
+    let message = super::redact::redact(&entry.message);
+    match &entry.run {
+        Some(run) => {
+            let outcome = if run.ok { "ok" } else { "failed" };
+            writeln!(file, "{at} [{}: {}, {outcome}] {message}", run.kind.label(), run.rule)
+        }
+        None => writeln!(file, "{at} {message}"),
+    }
+}
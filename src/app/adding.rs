@@ -0,0 +1,294 @@
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
+use librqbit::{AddTorrentOptions, api::ApiAddTorrentResponse};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    cancel::cancellable,
+    retry::{NetworkError, RetryPolicy, brief, with_timeout},
+    state::{App, Dialog, Mode},
+    util::{MagnetLink, build_add_torrent, parse_magnet},
+};
+
+/// Where an add is before its download directory is asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddStage {
+    Fetching,
+    /// The fetch failed on the network and runs again at `next_at`.
+    Retrying {
+        attempts: u32,
+        first_failed: Instant,
+        next_at: Instant,
+        last_error: String,
+    },
+    /// Fetched; waits for the UI to be free to ask for the download directory.
+    Ready,
+    /// The download directory prompt is open for this add.
+    Prompting,
+}
+
+/// A magnet, URL or `.torrent` path whose metadata is being fetched in the background.
+pub struct AddOperation {
+    pub input: String,
+    /// Torrent name, once fetched.
+    pub name: Option<String>,
+    pub started: Instant,
+    pub stage: AddStage,
+    task: Option<JoinHandle<Result<ApiAddTorrentResponse>>>,
+    /// Stops the fetch, and any retry of it, when the add is cancelled.
+    cancel: CancellationToken,
+}
+
+/// A pasted magnet, shown parsed so a wrong paste can be dropped before anything is fetched.
+#[derive(Debug, Clone)]
+pub struct MagnetPreview {
+    pub input: String,
+    pub link: MagnetLink,
+    /// Display name of the torrent already in the session with this info hash.
+    pub existing: Option<String>,
+}
+
+impl App {
+    /// Adds `input` right away, or first previews it when it's a magnet.
+    pub(super) fn submit_add(&mut self, input: String) -> Result<()> {
+        if !input.starts_with("magnet:") {
+            self.begin_add(input)?;
+            self.status = "Checking torrent...".to_string();
+            return Ok(());
+        }
+        let link = parse_magnet(&input)?;
+        let existing = self
+            .torrents
+            .iter()
+            .find(|t| t.info_hash.as_deref() == Some(link.info_hash.as_str()))
+            .map(|t| t.name.clone());
+        self.magnet_preview = Some(MagnetPreview {
+            input,
+            link,
+            existing,
+        });
+        self.status = "Check the magnet: Enter fetches it".to_string();
+        self.dialog = Dialog::MagnetPreview;
+        Ok(())
+    }
+
+    pub(super) fn fetch_magnet_preview(&mut self) -> Result<()> {
+        let Some(preview) = self.magnet_preview.take() else {
+            return Ok(());
+        };
+        self.dialog = Dialog::None;
+        self.begin_add(preview.input)?;
+        self.status = "Checking torrent...".to_string();
+        Ok(())
+    }
+
+    /// Reopens the add prompt with the pasted text, to fix it.
+    pub(super) fn edit_magnet_preview(&mut self) {
+        let Some(preview) = self.magnet_preview.take() else {
+            return;
+        };
+        self.mode = Mode::EnterMagnet;
+        self.input = preview.input;
+        self.input_cursor = self.input.chars().count();
+        self.status = "Paste magnet/URL/path and press Enter".to_string();
+        self.dialog = Dialog::AddTorrent;
+    }
+
+    pub(super) fn reject_magnet_preview(&mut self) {
+        self.magnet_preview = None;
+        self.status = "Add cancelled".to_string();
+        self.dialog = Dialog::None;
+    }
+
+    /// Starts fetching `input`'s metadata without blocking the UI; several can be in flight.
+    pub(super) fn begin_add(&mut self, input: String) -> Result<()> {
+        if self.adds.iter().any(|op| op.input == input) {
+            return Err(anyhow!("That torrent is already being added"));
+        }
+        let cancel = CancellationToken::new();
+        let task = Some(self.spawn_fetch(&input, &cancel)?);
+        self.adds.push(AddOperation {
+            input,
+            name: None,
+            started: Instant::now(),
+            stage: AddStage::Fetching,
+            task,
+            cancel,
+        });
+        Ok(())
+    }
+
+    fn spawn_fetch(
+        &self,
+        input: &str,
+        cancel: &CancellationToken,
+    ) -> Result<JoinHandle<Result<ApiAddTorrentResponse>>> {
+        let add = build_add_torrent(input)?;
+        let cancel = cancel.clone();
+        let backend = self.backend.clone();
+        let output_folder = self.download_dir.to_string_lossy().into_owned();
+        let secs = self.config.timeouts.metadata_secs;
+        Ok(tokio::spawn(async move {
+            let list = backend.add_torrent(
+                add,
+                AddTorrentOptions {
+                    list_only: true,
+                    output_folder: Some(output_folder),
+                    ..Default::default()
+                },
+            );
+            let fetch = async {
+                with_timeout(secs, list)
+                    .await
+                    .map_err(NetworkError::wrap("error listing files"))
+            };
+            cancellable(&cancel, fetch).await
+        }))
+    }
+
+    /// The add whose download directory prompt is open.
+    pub(super) fn prompting_add(&self) -> Option<&AddOperation> {
+        self.adds.iter().find(|op| op.stage == AddStage::Prompting)
+    }
+
+    pub(super) fn take_prompting_add(&mut self) -> Option<AddOperation> {
+        let idx = self
+            .adds
+            .iter()
+            .position(|op| op.stage == AddStage::Prompting)?;
+        Some(self.adds.remove(idx))
+    }
+
+    pub(super) fn cancel_add(&mut self, input: &str) {
+        if let Some(idx) = self.adds.iter().position(|op| op.input == input) {
+            self.adds.remove(idx).cancel.cancel();
+        }
+    }
+
+    pub(super) fn cancel_adds(&mut self) -> usize {
+        let count = self.adds.len();
+        for op in self.adds.drain(..) {
+            op.cancel.cancel();
+        }
+        count
+    }
+
+    /// Collects finished fetches, restarts due retries, and asks for the download directory
+    /// of the oldest ready add once nothing else is on screen.
+    pub(super) async fn poll_adds(&mut self) {
+        let mut outcomes = Vec::new();
+        for op in &mut self.adds {
+            if let Some(task) = op.task.take_if(|task| task.is_finished()) {
+                let result = task
+                    .await
+                    .unwrap_or_else(|err| Err(anyhow!("metadata fetch stopped: {err}")));
+                outcomes.push((op.input.clone(), result));
+            }
+        }
+        for (input, result) in outcomes {
+            let Some(idx) = self.adds.iter().position(|op| op.input == input) else {
+                continue;
+            };
+            match result {
+                Ok(response) => self.fetched(idx, response),
+                Err(err) => self.fetch_failed(idx, err),
+            }
+        }
+        let now = Instant::now();
+        let due: Vec<(String, CancellationToken)> = self
+            .adds
+            .iter()
+            .filter(|op| {
+                op.task.is_none()
+                    && matches!(op.stage, AddStage::Retrying { next_at, .. } if next_at <= now)
+            })
+            .map(|op| (op.input.clone(), op.cancel.clone()))
+            .collect();
+        for (input, cancel) in due {
+            let spawned = self.spawn_fetch(&input, &cancel);
+            let Some(idx) = self.adds.iter().position(|op| op.input == input) else {
+                continue;
+            };
+            match spawned {
+                Ok(task) => self.adds[idx].task = Some(task),
+                Err(err) => self.fail_add(idx, err),
+            }
+        }
+        let idle = self.mode == Mode::Normal && self.dialog == Dialog::None;
+        if idle
+            && self.prompting_add().is_none()
+            && let Some(op) = self.adds.iter_mut().find(|op| op.stage == AddStage::Ready)
+        {
+            op.stage = AddStage::Prompting;
+            self.load_dir_choices();
+            self.mode = Mode::EnterTorrentDir;
+            self.input = self.download_dir.to_string_lossy().into_owned();
+            self.input_cursor = self.input.chars().count();
+            self.status = "Set download dir for this torrent".to_string();
+            self.dialog = Dialog::AddTorrent;
+        }
+    }
+
+    fn fetched(&mut self, idx: usize, response: ApiAddTorrentResponse) {
+        let info_hash = response.details.info_hash.as_str();
+        let existing = self
+            .backend
+            .torrent_list(false)
+            .iter()
+            .any(|t| t.info_hash == info_hash);
+        if existing {
+            self.fail_add(
+                idx,
+                anyhow!("Torrent already added; duplicate locations are not supported"),
+            );
+            return;
+        }
+        let op = &mut self.adds[idx];
+        op.name = response.details.name.clone();
+        op.stage = AddStage::Ready;
+    }
+
+    fn fetch_failed(&mut self, idx: usize, err: anyhow::Error) {
+        if err.downcast_ref::<NetworkError>().is_none() {
+            self.fail_add(idx, err);
+            return;
+        }
+        let policy = RetryPolicy::NETWORK;
+        let now = Instant::now();
+        let (attempts, first_failed) = match self.adds[idx].stage {
+            AddStage::Retrying {
+                attempts,
+                first_failed,
+                ..
+            } => (attempts + 1, first_failed),
+            _ => (1, now),
+        };
+        let next_at = now + policy.delay(attempts);
+        let last_error = brief(&err.to_string());
+        if next_at > first_failed + policy.max_elapsed {
+            self.fail_add(idx, anyhow!("gave up after {attempts} attempts: {last_error}"));
+            return;
+        }
+        self.adds[idx].stage = AddStage::Retrying {
+            attempts,
+            first_failed,
+            next_at,
+            last_error,
+        };
+    }
+
+    /// Drops the add, reporting why: in the error dialog when nothing else is open, so an
+    /// add the user is busy with elsewhere isn't interrupted, in a toast otherwise.
+    fn fail_add(&mut self, idx: usize, err: anyhow::Error) {
+        let op = self.adds.remove(idx);
+        let what = op.name.unwrap_or_else(|| self.masked(&op.input).into_owned());
+        self.activity.push(format!("Add of {what} failed: {err}"));
+        if self.mode == Mode::Normal && self.dialog == Dialog::None {
+            self.set_error(err);
+        } else {
+            self.show_toast(format!("Add of {what} failed"));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::config::AlertMetric;
+
+use super::{
+    bus::SessionEvent,
+    disk::available_space,
+    groups::tracker_host,
+    state::{App, FilterKind},
+};
+
+const GIB: f64 = (1024 * 1024 * 1024) as f64;
+
+/// An `[[alerts]]` rule whose condition holds, with the value that tripped it.
+#[derive(Debug, Clone)]
+pub struct FiringAlert {
+    pub name: String,
+    pub value: f64,
+}
+
+impl App {
+    /// Checks every `[[alerts]]` condition against the latest refresh. One that starts to
+    /// hold is logged and published for `[[hooks]]` with `on = ["alert"]`.
+    pub(super) fn check_alerts(&mut self) {
+        let mut firing = Vec::new();
+        for (idx, rule) in self.config.alerts.iter().enumerate() {
+            let Some(value) = self.alert_value(&rule.when.metric) else {
+                continue;
+            };
+            if !rule.when.op.holds(value, rule.when.threshold) {
+                continue;
+            }
+            firing.push((idx, FiringAlert {
+                name: rule.label().to_string(),
+                value,
+            }));
+        }
+        let started: Vec<(usize, FiringAlert)> = firing
+            .iter()
+            .filter(|(idx, _)| !self.alerts.iter().any(|(old, _)| old == idx))
+            .cloned()
+            .collect();
+        for (idx, _) in &self.alerts {
+            if !firing.iter().any(|(new, _)| new == idx) {
+                let name = self.config.alerts[*idx].label();
+                self.activity.push(format!("Alert cleared: {name}"));
+            }
+        }
+        for (idx, alert) in started {
+            let value = round(alert.value);
+            self.activity.push(format!("Alert: {} (now {value})", alert.name));
+            self.bus.publish(SessionEvent::Alert {
+                name: alert.name,
+                condition: self.config.alerts[idx].when.text.clone(),
+                value,
+            });
+        }
+        self.alerts = firing;
+    }
+
+    /// The metric's current value, or None when there is nothing to measure yet (no
+    /// session stats, nothing downloaded for a ratio).
+    fn alert_value(&self, metric: &AlertMetric) -> Option<f64> {
+        Some(match metric {
+            AlertMetric::Count(name) => {
+                let kind = match *name {
+                    "downloading" => FilterKind::Downloading,
+                    "seeding" => FilterKind::Seeding,
+                    "paused" => FilterKind::Paused,
+                    "stopped" => FilterKind::Stopped,
+                    "errors" => FilterKind::Error,
+                    "idle" => FilterKind::Idle,
+                    _ => FilterKind::All,
+                };
+                self.filter_summary().count(kind) as f64
+            }
+            AlertMetric::FreeGb => available_space(&self.download_dir)? as f64 / GIB,
+            AlertMetric::Ratio(host) => {
+                let (mut uploaded, mut downloaded) = (0u64, 0u64);
+                for t in &self.torrents {
+                    let Some(stats) = t.stats.as_ref() else {
+                        continue;
+                    };
+                    if let Some(host) = host
+                        && !t.trackers.iter().any(|url| tracker_matches(url, host))
+                    {
+                        continue;
+                    }
+                    uploaded += stats.uploaded_bytes;
+                    downloaded += stats.progress_bytes;
+                }
+                if downloaded == 0 {
+                    return None;
+                }
+                uploaded as f64 / downloaded as f64
+            }
+            AlertMetric::DownMib => self.session_stats()?.download_speed.mbps,
+            AlertMetric::UpMib => self.session_stats()?.upload_speed.mbps,
+        })
+    }
+
+    pub fn firing_alerts(&self) -> impl Iterator<Item = &FiringAlert> {
+        self.alerts.iter().map(|(_, alert)| alert)
+    }
+}
+
+/// Whether the announce URL's host is `host` or one of its subdomains.
+fn tracker_matches(url: &str, host: &str) -> bool {
+    tracker_host(url).is_some_and(|h| {
+        let h = h.to_ascii_lowercase();
+        let host = host.to_ascii_lowercase();
+        h == host || h.ends_with(&format!(".{host}"))
+    })
+}
+
+/// Two decimals are plenty for a log line or a hook.
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
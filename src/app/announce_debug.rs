@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use tokio::task::JoinHandle;
+
+use super::state::App;
+
+/// How long `:debug-announce` waits to be run again to confirm.
+const CONFIRM_WINDOW: Duration = Duration::from_secs(10);
+
+type AnnounceResults = Vec<(String, Result<String>)>;
+
+/// A one-shot `:debug-announce` round, with `[announce] debug_responses` on.
+#[derive(Debug, Default)]
+pub struct AnnounceDebug {
+    /// Torrent the warning was shown for, and when; running the command again sends.
+    armed: Option<(usize, Instant)>,
+    round: Option<(usize, JoinHandle<AnnounceResults>)>,
+}
+
+impl App {
+    /// Warns what `:debug-announce` sends on the first run, and sends one announce to each
+    /// of the selected torrent's trackers when it's run again right after.
+    pub(super) fn debug_announce(&mut self) -> Result<()> {
+        if !self.config.announce.debug_responses || self.config.low_memory {
+            bail!("set [announce] debug_responses = true to use debug-announce");
+        }
+        if self.announce_debug.round.is_some() {
+            bail!("a debug announce is already running");
+        }
+        let Some((id, name)) = self.selected_torrent().map(|t| (t.id, t.name.clone())) else {
+            bail!("no torrent selected");
+        };
+        let urls = self.backend.trackers(id);
+        if urls.is_empty() {
+            bail!("{name} has no trackers");
+        }
+        let debug = &mut self.announce_debug;
+        let confirmed = debug
+            .armed
+            .take()
+            .is_some_and(|(armed, at)| armed == id && at.elapsed() < CONFIRM_WINDOW);
+        if !confirmed {
+            debug.armed = Some((id, Instant::now()));
+            self.show_toast(format!(
+                "Extra announce to {} trackers with this session's peer id; private trackers \
+                 may object. Run again to send",
+                urls.len()
+            ));
+            return Ok(());
+        }
+        let backend = self.backend.clone();
+        let round = tokio::spawn(async move {
+            let announces: Vec<_> = urls
+                .into_iter()
+                .map(|url| {
+                    let backend = backend.clone();
+                    tokio::spawn(async move {
+                        let result = backend.debug_announce(id, url.clone()).await;
+                        (url, result)
+                    })
+                })
+                .collect();
+            let mut results = Vec::with_capacity(announces.len());
+            for announce in announces {
+                if let Ok(result) = announce.await {
+                    results.push(result);
+                }
+            }
+            results
+        });
+        debug.round = Some((id, round));
+        self.show_toast(format!("Announcing {name}; responses go to its log (l)"));
+        Ok(())
+    }
+
+    /// Logs the decoded responses of a finished `:debug-announce` round.
+    pub(super) async fn poll_announce_debug(&mut self) {
+        let Some((id, round)) = self
+            .announce_debug
+            .round
+            .take_if(|(_, round)| round.is_finished())
+        else {
+            return;
+        };
+        for (url, result) in round.await.unwrap_or_default() {
+            let message = match result {
+                Ok(response) => format!("Announce {url}: {response}"),
+                Err(err) => format!("Announce {url} failed: {err:#}"),
+            };
+            self.log_torrent(id, message);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::{Component, PathBuf};
+
+use anyhow::Result;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+
+use crate::api::{self, ApiCommand, ApiReply, ApiRequest};
+
+use super::{
+    action::Action,
+    effect::Effect,
+    meta::AddSource,
+    progress::percent,
+    redact::redact,
+    state::{App, TorrentRow},
+    torrent_log::state_label,
+};
+
+/// The `[api]` server's requests, once it listens.
+#[derive(Debug, Default)]
+pub struct ApiState {
+    requests: Option<mpsc::UnboundedReceiver<ApiRequest>>,
+    /// Starting is tried once, so a port in use isn't retried every tick.
+    started: bool,
+}
+
+impl App {
+    /// Starts the `[api]` server on the first tick when `listen` is set.
+    pub(super) async fn poll_api(&mut self) {
+        if self.api.started {
+            return;
+        }
+        let Some(listen) = self.config.api.listen.clone() else {
+            return;
+        };
+        self.api.started = true;
+        if let Err(err) = self.start_api(&listen).await {
+            self.activity.push(format!("API: {err:#}"));
+        }
+    }
+
+    async fn start_api(&mut self, listen: &str) -> Result<()> {
+        let (token, generated) = crate::auth::load_or_create_token(&self.config.auth)?;
+        if generated && let Some(path) = crate::auth::token_path() {
+            self.activity
+                .push(format!("API: generated an auth token in {}", path.display()));
+        }
+        let tls = crate::tls::acceptor(&self.config.tls)?;
+        let scheme = if tls.is_some() { "https" } else { "http" };
+        let (tx, rx) = mpsc::unbounded_channel();
+        let addr = api::serve(listen, tx, token, tls).await?;
+        self.activity
+            .push(format!("API listening on {scheme}://{addr}/api/torrents"));
+        self.api.requests = Some(rx);
+        Ok(())
+    }
+
+    /// Answers listings from the current state and turns every change into the effect the
+    /// UI runs for it, so remote actions go through the same queue, busy dialog and
+    /// error handling.
+    pub(super) fn api_actions(&mut self) -> Vec<Action> {
+        let Some(requests) = self.api.requests.as_mut() else {
+            return Vec::new();
+        };
+        let mut received = Vec::new();
+        while let Ok(request) = requests.try_recv() {
+            received.push(request);
+        }
+        let mut actions = Vec::new();
+        for request in received {
+            let (reply, effect) = self.api_command(request.command, request.client);
+            actions.extend(effect.map(Action::RunEffect));
+            // The client may have given up waiting; the effect still runs.
+            let _ = request.reply.send(reply);
+        }
+        actions
+    }
+
+    fn api_command(&self, command: ApiCommand, client: String) -> (ApiReply, Option<Effect>) {
+        let effect = match command {
+            ApiCommand::List => {
+                let torrents: Vec<Value> =
+                    self.torrents.iter().map(|t| self.api_torrent(t)).collect();
+                return (ApiReply::ok(json!({ "torrents": torrents })), None);
+            }
+            ApiCommand::Pause(id) | ApiCommand::Resume(id) | ApiCommand::Delete { id, .. }
+                if !self.torrents.iter().any(|t| t.id == id) =>
+            {
+                return (ApiReply::error("404 Not Found", format!("no torrent {id}")), None);
+            }
+            ApiCommand::Add { magnet, label, dir } => {
+                let dir = match dir.map(|dir| self.api_dir(dir)).transpose() {
+                    Ok(dir) => dir,
+                    Err(reply) => return (reply, None),
+                };
+                Effect::AddMagnet {
+                    magnet,
+                    label,
+                    dir,
+                    source: AddSource::Api(client),
+                }
+            }
+            ApiCommand::Pause(id) => Effect::PauseTorrents(vec![id]),
+            ApiCommand::Resume(id) => Effect::StartTorrents(vec![id]),
+            ApiCommand::Delete { id, files } => Effect::Remove {
+                id,
+                delete_files: files,
+            },
+        };
+        (ApiReply::accepted(), Some(effect))
+    }
+
+    /// A client's `dir`, resolved against the download directory. It has to stay inside
+    /// the download directory or one of `download_dirs`, so a remote client can't write
+    /// anywhere else.
+    fn api_dir(&self, dir: PathBuf) -> Result<PathBuf, ApiReply> {
+        if dir.components().any(|c| c == Component::ParentDir) {
+            return Err(ApiReply::error("400 Bad Request", "dir can't contain .."));
+        }
+        let dir = self.download_dir.join(dir);
+        let allowed = std::iter::once(&self.download_dir).chain(&self.config.download_dirs);
+        for root in allowed {
+            if dir.starts_with(root) {
+                return Ok(dir);
+            }
+        }
+        Err(ApiReply::error(
+            "403 Forbidden",
+            "dir must be inside the download directory or download_dirs",
+        ))
+    }
+
+    fn api_torrent(&self, t: &TorrentRow) -> Value {
+        let stats = t.stats.as_ref();
+        let rate = |mbps: Option<f64>| (mbps.unwrap_or(0.0) * 1024.0 * 1024.0) as u64;
+        let live = stats.and_then(|s| s.live.as_ref());
+        json!({
+            "id": t.id,
+            "info_hash": t.info_hash,
+            "name": t.name,
+            "label": self.label_of(t),
+            "state": stats.map(|s| state_label(&s.state)),
+            "error": stats.and_then(|s| s.error.as_deref()).map(redact),
+            "percent": stats.map(|s| percent(s.progress_bytes, s.total_bytes)),
+            "progress_bytes": stats.map(|s| s.progress_bytes),
+            "total_bytes": stats.map(|s| s.total_bytes),
+            "download_rate": rate(live.map(|l| l.download_speed.mbps)),
+            "upload_rate": rate(live.map(|l| l.upload_speed.mbps)),
+            "output_folder": t.output_folder,
+        })
+    }
+}
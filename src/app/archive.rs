@@ -0,0 +1,433 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Local};
+use librqbit::{AddTorrentOptions, TorrentStatsState};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use super::{
+    meta::StorageKind,
+    state::{App, Dialog},
+    util::build_add_torrent,
+};
+
+/// A torrent offloaded to cold storage and out of the session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedTorrent {
+    pub info_hash: String,
+    pub name: String,
+    /// Where the data was downloaded to, and goes back to on restore.
+    pub original_folder: String,
+    pub archived_folder: String,
+    /// What to add it again from: the saved `.torrent` file, or a magnet.
+    pub source: String,
+    #[serde(default)]
+    pub only_files: Option<Vec<usize>>,
+    pub bytes: u64,
+    pub archived_at: DateTime<Local>,
+}
+
+/// Offloaded torrents persisted as `archive.json` in the data dir, with their `.torrent`
+/// files kept next to it under `archive/`.
+#[derive(Debug, Default)]
+pub struct Archive {
+    items: Vec<ArchivedTorrent>,
+    path: Option<PathBuf>,
+}
+
+impl Archive {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let items = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { items, path }
+    }
+
+    pub fn items(&self) -> &[ArchivedTorrent] {
+        &self.items
+    }
+
+    fn push(&mut self, item: ArchivedTorrent) -> Result<()> {
+        self.items.retain(|i| i.info_hash != item.info_hash);
+        self.items.push(item);
+        self.save()
+    }
+
+    fn remove(&mut self, info_hash: &str) -> Result<()> {
+        self.items.retain(|i| i.info_hash != info_hash);
+        self.save()
+    }
+
+    fn set_archived_folder(&mut self, info_hash: &str, folder: &str) -> Result<()> {
+        for item in self.items.iter_mut().filter(|i| i.info_hash == info_hash) {
+            item.archived_folder = folder.to_string();
+        }
+        self.save()
+    }
+
+    /// Keeps what was loaded but stops writing changes back, or `.torrent` files.
+    pub fn read_only(&mut self) {
+        self.path = None;
+    }
+
+    fn torrent_file(&self, info_hash: &str) -> Option<PathBuf> {
+        let dir = self.path.as_ref()?.parent()?.join("archive");
+        Some(dir.join(format!("{info_hash}.torrent")))
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("failed to create data dir")?;
+        }
+        let text = serde_json::to_string_pretty(&self.items)?;
+        std::fs::write(path, text).context("failed to save archive")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveDirection {
+    Offload,
+    Restore,
+}
+
+/// Data on its way to or from the archive, moved off the UI thread.
+#[derive(Debug)]
+pub struct ArchiveMove {
+    pub direction: ArchiveDirection,
+    pub entry: ArchivedTorrent,
+    /// Session id of a torrent being offloaded, which stays paused until its data is moved.
+    id: Option<usize>,
+    was_live: bool,
+    task: JoinHandle<Result<()>>,
+}
+
+impl App {
+    /// Pauses the selected torrent and starts moving its data under `[archive] dir`; it
+    /// leaves the session once the move is done.
+    pub(super) async fn offload_selected(&mut self) -> Result<()> {
+        if self.safe_mode {
+            bail!("Safe mode doesn't save the archive; restart normally to offload");
+        }
+        let Some(dir) = self.config.archive.dir.clone() else {
+            bail!("No archive directory; set [archive] dir");
+        };
+        let t = self
+            .selected_torrent()
+            .ok_or_else(|| anyhow!("No torrent selected"))?;
+        let Some(info_hash) = t.info_hash.clone() else {
+            bail!("{} has no metadata yet", t.name);
+        };
+        if self.storage_of(t) == StorageKind::Memory {
+            bail!("{} is held in memory; there is nothing to offload", t.name);
+        }
+        if self.is_missing_files(t) {
+            bail!("{}'s download folder is missing", t.name);
+        }
+        if self.archive_move(&info_hash).is_some() {
+            bail!("{} is already being moved", t.name);
+        }
+        let (id, name) = (t.id, t.name.clone());
+        let from = PathBuf::from(&t.output_folder);
+        // The whole folder moves, so it mustn't hold (or sit inside) another torrent's data,
+        // as with a `{label}` save path or the download dir itself.
+        if let Some(other) = self.torrents.iter().find(|other| {
+            let theirs = Path::new(&other.output_folder);
+            other.id != id && (theirs.starts_with(&from) || from.starts_with(theirs))
+        }) {
+            bail!("{name} shares its folder with {}; offloading would move both", other.name);
+        }
+        let folder = from
+            .file_name()
+            .ok_or_else(|| anyhow!("{} has no folder name", from.display()))?;
+        let to = dir.join(folder);
+        let simulated = self.backend.is_simulated();
+        if !simulated && to.exists() {
+            bail!("{} already exists", to.display());
+        }
+        let was_live = t.stats.as_ref().is_some_and(|s| {
+            matches!(s.state, TorrentStatsState::Live | TorrentStatsState::Initializing)
+        });
+        let bytes = t.stats.as_ref().map_or(0, |s| s.total_bytes);
+        let only_files = self.backend.torrent_details(id)?.files.map(|files| {
+            files
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, file)| file.included.then_some(idx))
+                .collect()
+        });
+        let source = if simulated {
+            format!("magnet:?xt=urn:btih:{info_hash}&dn={name}")
+        } else {
+            let path = self
+                .archive
+                .torrent_file(&info_hash)
+                .ok_or_else(|| anyhow!("no data directory to keep the .torrent file in"))?;
+            let torrent = self.backend.torrent_bytes(id)?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).context("failed to create archive dir")?;
+            }
+            std::fs::write(&path, torrent).context("failed to save the .torrent file")?;
+            path.to_string_lossy().into_owned()
+        };
+        self.backend
+            .pause(id)
+            .await
+            .context("error pausing torrent")?;
+        let entry = ArchivedTorrent {
+            info_hash,
+            name: name.clone(),
+            original_folder: from.to_string_lossy().into_owned(),
+            archived_folder: to.to_string_lossy().into_owned(),
+            source,
+            only_files,
+            bytes,
+            archived_at: Local::now(),
+        };
+        self.log_torrent(id, format!("Offloading to {}", entry.archived_folder));
+        self.archive_moves.push(ArchiveMove {
+            direction: ArchiveDirection::Offload,
+            task: spawn_move(simulated, from, to),
+            entry,
+            id: Some(id),
+            was_live,
+        });
+        self.status = format!("Offloading {name}...");
+        self.refresh();
+        Ok(())
+    }
+
+    /// Moves the archived torrent selected in the Archive dialog back to its folder; it's
+    /// added again, and rechecked, once the data is there.
+    pub(super) fn restore_archived(&mut self) -> Result<()> {
+        if self.safe_mode {
+            bail!("Safe mode doesn't save the archive; restart normally to restore");
+        }
+        let Some(entry) = self.archive.items().get(self.archive_selected).cloned() else {
+            return Ok(());
+        };
+        if self.archive_move(&entry.info_hash).is_some() {
+            bail!("{} is already being moved", entry.name);
+        }
+        let in_session = self
+            .torrents
+            .iter()
+            .any(|t| t.info_hash.as_deref() == Some(entry.info_hash.as_str()));
+        if in_session {
+            bail!("{} is already in the session", entry.name);
+        }
+        let from = PathBuf::from(&entry.archived_folder);
+        let to = PathBuf::from(&entry.original_folder);
+        let simulated = self.backend.is_simulated();
+        // A restore whose add failed has already moved the data back.
+        let moved = from == to;
+        if !simulated && !moved && to.exists() {
+            bail!("{} already exists", to.display());
+        }
+        self.status = format!("Restoring {}...", entry.name);
+        self.archive_moves.push(ArchiveMove {
+            direction: ArchiveDirection::Restore,
+            task: spawn_move(simulated || moved, from, to),
+            entry,
+            id: None,
+            was_live: false,
+        });
+        Ok(())
+    }
+
+    /// Finishes offloads and restores whose data has been moved.
+    pub(super) async fn poll_archive(&mut self) {
+        if self.archive_moves.iter().all(|m| !m.task.is_finished()) {
+            return;
+        }
+        let (done, running): (Vec<_>, Vec<_>) = std::mem::take(&mut self.archive_moves)
+            .into_iter()
+            .partition(|m| m.task.is_finished());
+        self.archive_moves = running;
+        for mv in done {
+            let result = match mv.task.await {
+                Ok(result) => result,
+                Err(err) => Err(anyhow!("move stopped: {err}")),
+            };
+            let outcome = match mv.direction {
+                ArchiveDirection::Offload => {
+                    self.finish_offload(mv.entry, mv.id, mv.was_live, result).await
+                }
+                ArchiveDirection::Restore => self.finish_restore(mv.entry, result).await,
+            };
+            if let Err(err) = outcome {
+                self.activity.push(format!("Archive: {err:#}"));
+                self.show_toast(format!("{err:#}"));
+            }
+        }
+        self.refresh();
+    }
+
+    async fn finish_offload(
+        &mut self,
+        entry: ArchivedTorrent,
+        id: Option<usize>,
+        was_live: bool,
+        moved: Result<()>,
+    ) -> Result<()> {
+        let Some(id) = id else {
+            return Ok(());
+        };
+        if let Err(err) = moved {
+            if was_live {
+                let _ = self.backend.start(id).await;
+            }
+            if Path::new(&entry.source).is_file() {
+                let _ = std::fs::remove_file(&entry.source);
+            }
+            return Err(err.context(format!("offload of {} failed", entry.name)));
+        }
+        self.archive.push(entry.clone())?;
+        self.backend
+            .forget(id)
+            .await
+            .with_context(|| format!("{} was archived but is still in the session", entry.name))?;
+        self.torrent_logs
+            .push(&entry.info_hash, format!("Offloaded to {}", entry.archived_folder));
+        self.activity
+            .push(format!("Offloaded {} to {}", entry.name, entry.archived_folder));
+        self.show_toast(format!("Offloaded {}", entry.name));
+        Ok(())
+    }
+
+    async fn finish_restore(&mut self, entry: ArchivedTorrent, moved: Result<()>) -> Result<()> {
+        moved.with_context(|| format!("restore of {} failed", entry.name))?;
+        let added = self
+            .backend
+            .add_torrent(
+                build_add_torrent(&entry.source)?,
+                AddTorrentOptions {
+                    only_files: entry.only_files.clone(),
+                    output_folder: Some(entry.original_folder.clone()),
+                    overwrite: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+        if let Err(err) = added {
+            // The data is back in its folder; a retry only needs the add.
+            self.archive
+                .set_archived_folder(&entry.info_hash, &entry.original_folder)?;
+            return Err(err.context(format!(
+                "{} is back in {} but couldn't be added",
+                entry.name, entry.original_folder
+            )));
+        }
+        self.archive.remove(&entry.info_hash)?;
+        if self.archive.torrent_file(&entry.info_hash).as_deref() == Some(Path::new(&entry.source))
+        {
+            let _ = std::fs::remove_file(&entry.source);
+        }
+        self.torrent_logs
+            .push(&entry.info_hash, "Restored from archive, rechecking");
+        self.activity
+            .push(format!("Restored {} to {}", entry.name, entry.original_folder));
+        self.show_toast(format!("Restored {}; rechecking", entry.name));
+        self.archive_selected = self
+            .archive_selected
+            .min(self.archive.items().len().saturating_sub(1));
+        Ok(())
+    }
+
+    pub(super) fn open_archive(&mut self) -> Result<()> {
+        if self.archive.items().is_empty() && self.archive_moves.is_empty() {
+            bail!("Nothing is archived; :offload moves the selected torrent to the archive");
+        }
+        self.archive_open = true;
+        self.archive_selected = 0;
+        self.dialog = Dialog::Archive;
+        Ok(())
+    }
+
+    pub(super) fn close_archive(&mut self) {
+        self.archive_open = false;
+        if self.dialog == Dialog::Archive {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    pub(super) fn move_archive_selection(&mut self, delta: isize) {
+        self.archive_selected = self
+            .archive_selected
+            .saturating_add_signed(delta)
+            .min(self.archive.items().len().saturating_sub(1));
+    }
+
+    pub fn archived(&self) -> &[ArchivedTorrent] {
+        self.archive.items()
+    }
+
+    /// Which way the torrent with `info_hash` is being moved, if it is.
+    pub fn archive_move(&self, info_hash: &str) -> Option<ArchiveDirection> {
+        self.archive_moves
+            .iter()
+            .find(|m| m.entry.info_hash == info_hash)
+            .map(|m| m.direction)
+    }
+
+    /// Offloads still moving data, which aren't in the archive yet.
+    pub fn offloads_running(&self) -> usize {
+        self.archive_moves
+            .iter()
+            .filter(|m| m.direction == ArchiveDirection::Offload)
+            .count()
+    }
+}
+
+fn spawn_move(skip: bool, from: PathBuf, to: PathBuf) -> JoinHandle<Result<()>> {
+    tokio::task::spawn_blocking(move || if skip { Ok(()) } else { move_tree(&from, &to) })
+}
+
+/// Renames `from` to `to`, or copies it over and deletes the original when they're on
+/// different filesystems. A copy that fails part way is removed again.
+fn move_tree(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    match std::fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(err) if err.kind() == ErrorKind::CrossesDevices => {}
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to move {} to {}", from.display(), to.display()));
+        }
+    }
+    if let Err(err) = copy_tree(from, to) {
+        let _ = std::fs::remove_dir_all(to);
+        return Err(err);
+    }
+    std::fs::remove_dir_all(from)
+        .with_context(|| format!("copied, but failed to remove {}", from.display()))
+}
+
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    let meta = std::fs::symlink_metadata(from)
+        .with_context(|| format!("failed to read {}", from.display()))?;
+    if meta.is_dir() {
+        std::fs::create_dir_all(to).with_context(|| format!("failed to create {}", to.display()))?;
+        let entries =
+            std::fs::read_dir(from).with_context(|| format!("failed to list {}", from.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else if meta.is_file() {
+        std::fs::copy(from, to)
+            .with_context(|| format!("failed to copy {} to {}", from.display(), to.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+
+use crate::config::BudgetPolicy;
+
+use super::{activity::Automation, disk::same_filesystem, state::App};
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Completed torrents a `remove_oldest` budget picked to make room for an add. Nothing is
+/// removed until [`App::evict_for_budget`] runs, once the add went through.
+#[derive(Debug, Default)]
+pub struct BudgetEvictions {
+    label: String,
+    /// (id, name, on-disk bytes, output folder)
+    removals: Vec<(usize, String, u64, PathBuf)>,
+}
+
+impl BudgetEvictions {
+    /// Bytes the removals free on `folder`'s filesystem.
+    pub fn freed_on(&self, folder: &Path) -> u64 {
+        self.removals
+            .iter()
+            .filter(|(.., output)| same_filesystem(output, folder))
+            .map(|(_, _, bytes, _)| bytes)
+            .sum()
+    }
+}
+
+impl App {
+    /// On-disk bytes used by torrents carrying `label`, optionally ignoring one torrent.
+    pub(super) fn label_usage(&self, label: &str, exclude: Option<&str>) -> u64 {
+        self.torrents
+            .iter()
+            .filter(|t| {
+                t.info_hash.as_deref().is_some_and(|h| {
+                    Some(h) != exclude && self.meta.label(h) == Some(label)
+                })
+            })
+            .filter_map(|t| t.stats.as_ref())
+            .map(|s| s.progress_bytes)
+            .sum()
+    }
+
+    /// Checks that `incoming` bytes fit under `label`'s budget and picks what
+    /// `remove_oldest` would remove for them, without removing anything yet. `exclude` is
+    /// the torrent being relabelled, which is neither counted nor removed.
+    pub(super) fn plan_label_budget(
+        &mut self,
+        label: Option<&str>,
+        incoming: u64,
+        exclude: Option<&str>,
+    ) -> Result<BudgetEvictions> {
+        let Some(label) = label else {
+            return Ok(BudgetEvictions::default());
+        };
+        let Some(cfg) = self.config.labels.get(label) else {
+            return Ok(BudgetEvictions::default());
+        };
+        let Some(budget_gb) = cfg.budget_gb else {
+            return Ok(BudgetEvictions::default());
+        };
+        let budget = budget_gb.saturating_mul(GIB);
+        let mut usage = self.label_usage(label, exclude);
+        if usage.saturating_add(incoming) <= budget {
+            return Ok(BudgetEvictions::default());
+        }
+        if cfg.budget_policy == BudgetPolicy::Block {
+            return Err(anyhow!(
+                "Label '{label}' budget exceeded: {:.1} GiB used, {:.1} GiB more needed, limit {budget_gb} GiB",
+                usage as f64 / GIB as f64,
+                incoming as f64 / GIB as f64,
+            ));
+        }
+
+        let mut candidates: Vec<_> = self
+            .torrents
+            .iter()
+            .filter_map(|t| {
+                let hash = t.info_hash.as_deref()?;
+                let stats = t.stats.as_ref()?;
+                if Some(hash) == exclude || !stats.finished || self.meta.label(hash) != Some(label)
+                {
+                    return None;
+                }
+                let meta = self.meta.get(hash);
+                let age = meta.and_then(|m| m.completed_at.or(m.added_at));
+                let output = PathBuf::from(&t.output_folder);
+                Some((age, t.id, t.name.clone(), stats.progress_bytes, output))
+            })
+            .collect();
+        // Oldest first; torrents with no recorded date go last rather than counting as oldest.
+        candidates.sort_by_key(|(age, ..)| (age.is_none(), *age));
+
+        let mut removals = Vec::new();
+        for (_, id, name, bytes, output) in candidates {
+            if usage.saturating_add(incoming) <= budget {
+                break;
+            }
+            usage = usage.saturating_sub(bytes);
+            removals.push((id, name, bytes, output));
+        }
+        if usage.saturating_add(incoming) > budget {
+            let err = anyhow!(
+                "Label '{label}' budget of {budget_gb} GiB can't fit this torrent even after removing completed ones"
+            );
+            let rule = format!("labels.{label} budget_gb");
+            self.activity.record(Automation::Budget, rule, false, format!("{err}"));
+            return Err(err);
+        }
+        Ok(BudgetEvictions {
+            label: label.to_string(),
+            removals,
+        })
+    }
+
+    /// Runs an added torrent's evictions. The add already went through, so a failed removal
+    /// is reported but doesn't fail it.
+    pub(super) async fn evict_after_add(&mut self, evictions: BudgetEvictions) {
+        if let Err(err) = self.evict_for_budget(evictions).await {
+            self.show_toast(format!("{err}"));
+        }
+    }
+
+    /// Removes the torrents `plan_label_budget` picked, stopping at the first that fails.
+    pub(super) async fn evict_for_budget(&mut self, evictions: BudgetEvictions) -> Result<()> {
+        let BudgetEvictions { label, removals } = evictions;
+        let rule = format!("labels.{label} budget_gb");
+        for (id, name, bytes, _) in removals {
+            if let Err(err) = self.backend.delete(id).await {
+                let err = anyhow!("error removing {name} for label budget: {err}");
+                self.activity.record(Automation::Budget, rule, false, format!("{err}"));
+                return Err(err);
+            }
+            self.activity.record(
+                Automation::Budget,
+                rule.clone(),
+                true,
+                format!(
+                    "Label budget '{label}': removed {name} ({:.1} GiB)",
+                    bytes as f64 / GIB as f64
+                ),
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use super::{progress::percent, state::App};
+
+/// Download progress reported on the way to completion, in percent.
+const PROGRESS_MILESTONES: [u8; 3] = [25, 50, 75];
+
+/// Something that happened to the session, for consumers outside the UI such as
+/// `--json-events` and `[[hooks]]`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    Added {
+        info_hash: String,
+        name: String,
+        source: String,
+    },
+    Progress {
+        info_hash: String,
+        name: String,
+        percent: u8,
+    },
+    Completed {
+        info_hash: String,
+        name: String,
+    },
+    /// The torrent's first live peer this session.
+    FirstPeer {
+        info_hash: String,
+        name: String,
+    },
+    /// A torrent error (with its hash and name) or an app-level one (without).
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        info_hash: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        message: String,
+    },
+    /// An `[[alerts]]` condition started to hold.
+    Alert {
+        name: String,
+        condition: String,
+        value: f64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StampedEvent {
+    pub time: DateTime<Local>,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
+
+/// Queue of session events, drained by whoever consumes them. Off by default so the
+/// interactive UI doesn't collect events nobody reads.
+#[derive(Debug, Default)]
+pub struct EventBus {
+    enabled: bool,
+    queue: VecDeque<StampedEvent>,
+    /// Events waiting for `[[hooks]]`, kept apart so hooks run whether or not anything
+    /// drains `queue`.
+    hooks_enabled: bool,
+    hook_queue: VecDeque<StampedEvent>,
+    /// Highest milestone reported per info hash.
+    milestones: HashMap<String, u8>,
+    /// Last torrent error reported per info hash.
+    errors: HashMap<String, String>,
+    /// Whether each torrent has had a live peer yet.
+    peered: HashMap<String, bool>,
+}
+
+impl EventBus {
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn enable_hooks(&mut self) {
+        self.hooks_enabled = true;
+    }
+
+    /// Whether anything listens for events at all.
+    fn listening(&self) -> bool {
+        self.enabled || self.hooks_enabled
+    }
+
+    pub fn publish(&mut self, event: SessionEvent) {
+        if !self.listening() {
+            return;
+        }
+        let stamped = StampedEvent {
+            time: Local::now(),
+            event,
+        };
+        if self.hooks_enabled {
+            self.hook_queue.push_back(stamped.clone());
+        }
+        if self.enabled {
+            self.queue.push_back(stamped);
+        }
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = StampedEvent> + '_ {
+        self.queue.drain(..)
+    }
+
+    pub(super) fn drain_hooks(&mut self) -> impl Iterator<Item = StampedEvent> + '_ {
+        self.hook_queue.drain(..)
+    }
+}
+
+impl App {
+    /// Publishes progress milestones, first peers and new torrent errors seen in the latest
+    /// refresh. Torrents seen for the first time only record where they are.
+    pub(super) fn publish_torrent_events(&mut self) {
+        if !self.bus.listening() {
+            return;
+        }
+        let mut events = Vec::new();
+        for t in &self.torrents {
+            let (Some(hash), Some(stats)) = (t.info_hash.as_ref(), t.stats.as_ref()) else {
+                continue;
+            };
+            let percent = percent(stats.progress_bytes, stats.total_bytes);
+            let reached = PROGRESS_MILESTONES
+                .iter()
+                .copied()
+                .filter(|&m| percent >= m)
+                .max()
+                .unwrap_or(0);
+            match self.bus.milestones.insert(hash.clone(), reached) {
+                Some(previous) if reached > previous && !stats.finished => {
+                    events.push(SessionEvent::Progress {
+                        info_hash: hash.clone(),
+                        name: t.name.clone(),
+                        percent: reached,
+                    });
+                }
+                _ => {}
+            }
+            let peered = stats.live.as_ref().is_some_and(|l| l.snapshot.peer_stats.live > 0);
+            let had_peer = self.bus.peered.get(hash).copied();
+            if had_peer != Some(true) {
+                self.bus.peered.insert(hash.clone(), peered);
+            }
+            if had_peer == Some(false) && peered {
+                events.push(SessionEvent::FirstPeer {
+                    info_hash: hash.clone(),
+                    name: t.name.clone(),
+                });
+            }
+            match stats.error.as_ref() {
+                Some(error) if self.bus.errors.get(hash) != Some(error) => {
+                    self.bus.errors.insert(hash.clone(), error.clone());
+                    events.push(SessionEvent::Error {
+                        info_hash: Some(hash.clone()),
+                        name: Some(t.name.clone()),
+                        message: super::redact::redact(error).into_owned(),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    self.bus.errors.remove(hash);
+                }
+            }
+        }
+        for event in events {
+            self.bus.publish(event);
+        }
+    }
+}
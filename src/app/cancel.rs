@@ -0,0 +1,120 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::Result;
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    effect::Effect,
+    state::{App, Dialog, Mode},
+};
+
+/// Returned by an operation aborted with Esc.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Token of the cancellable effect being awaited right now. The input thread cancels it on
+/// Esc, since the main loop can't read keys until the effect returns.
+#[derive(Debug, Clone, Default)]
+pub struct EffectCancel(Arc<Mutex<Option<CancellationToken>>>);
+
+impl EffectCancel {
+    pub fn arm(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        *self.lock() = Some(token.clone());
+        token
+    }
+
+    pub fn disarm(&self) {
+        self.lock().take();
+    }
+
+    /// Cancels the running effect; false when there is none, so the key is handled as usual.
+    pub fn cancel(&self) -> bool {
+        self.lock().take().map(|token| token.cancel()).is_some()
+    }
+
+    /// The running effect's token, or one that is never cancelled.
+    pub fn token(&self) -> CancellationToken {
+        self.lock().clone().unwrap_or_default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<CancellationToken>> {
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Runs `op` until it finishes or `token` is cancelled, dropping it in that case.
+pub async fn cancellable<T>(
+    token: &CancellationToken,
+    op: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    token
+        .run_until_cancelled(op)
+        .await
+        .unwrap_or_else(|| Err(Cancelled.into()))
+}
+
+/// A cancellable effect with a dialog of its own, shown for a frame before it runs so the
+/// dialog is on screen (with its Esc hint) while the effect is awaited.
+#[derive(Debug)]
+pub struct BusyEffect {
+    pub label: &'static str,
+    pub started: Instant,
+    /// Taken once the dialog has been drawn.
+    effect: Option<Effect>,
+}
+
+impl App {
+    /// Holds back `effect` until the busy dialog has been drawn; false when it should run
+    /// now.
+    pub(super) fn defer_busy(&mut self, effect: &Effect) -> bool {
+        let Some(label) = effect.busy_label() else {
+            return false;
+        };
+        if self.busy.is_some() {
+            return false;
+        }
+        self.busy = Some(BusyEffect {
+            label,
+            started: Instant::now(),
+            effect: Some(effect.clone()),
+        });
+        self.dialog = Dialog::Busy;
+        true
+    }
+
+    pub(super) fn due_busy(&mut self) -> Option<Effect> {
+        self.busy.as_mut()?.effect.take()
+    }
+
+    pub(super) fn finish_busy(&mut self) {
+        if self.busy.as_ref().is_some_and(|busy| busy.effect.is_none()) {
+            self.busy = None;
+            if self.dialog == Dialog::Busy {
+                self.dialog = Dialog::None;
+            }
+        }
+    }
+
+    /// Drops whatever add or cast the cancelled effect belonged to.
+    pub(super) fn effect_cancelled(&mut self) {
+        self.file_picker = None;
+        self.cast = None;
+        self.mode = Mode::Normal;
+        self.dialog = Dialog::None;
+        self.status = "Ready".to_string();
+        self.show_toast("Cancelled".to_string());
+    }
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::cast::{self, CastDevice, CastMedia};
+
+use super::{
+    cancel::cancellable,
+    retry::{NetworkError, with_timeout},
+    state::{App, Dialog},
+};
+
+/// A finished media file from the Files view, to be played on a device.
+#[derive(Debug, Clone)]
+pub struct CastFile {
+    pub torrent: usize,
+    /// Index in the torrent's file list.
+    pub file: usize,
+    pub name: String,
+    pub mime: String,
+}
+
+/// Devices that answered discovery, shown until one is picked or the picker is closed.
+#[derive(Debug)]
+pub struct CastPicker {
+    pub file: CastFile,
+    pub devices: Vec<CastDevice>,
+    pub selected: usize,
+}
+
+impl App {
+    /// The highlighted file in the Files view, if casting is on and it can be played.
+    pub(super) fn cast_file(&self) -> Result<CastFile> {
+        if !self.config.cast.enabled {
+            bail!("Casting is off; set [cast] enabled = true");
+        }
+        let selected = self.file_lines.get(self.file_selected);
+        let (Some(torrent), Some(file)) = (self.files_torrent, selected) else {
+            bail!("No file selected");
+        };
+        if !file.included || file.have < file.len {
+            bail!("{} isn't fully downloaded yet", file.path);
+        }
+        let name = file.path.rsplit('/').next().unwrap_or(&file.path);
+        let mime = mime_guess::from_path(name)
+            .first_raw()
+            .filter(|mime| cast::is_media(mime))
+            .ok_or_else(|| anyhow!("{name} isn't a video, audio or image file"))?;
+        Ok(CastFile {
+            torrent,
+            file: file.index,
+            name: name.to_string(),
+            mime: mime.to_string(),
+        })
+    }
+
+    pub(super) async fn discover_casts(&mut self, file: CastFile) -> Result<()> {
+        let wait = Duration::from_secs(self.config.cast.discover_secs);
+        let devices = cancellable(&self.effect_cancel.token(), async {
+            Ok(cast::discover(wait).await)
+        })
+        .await?;
+        // Multicast answers get lost, so an empty round is worth another try.
+        if devices.is_empty() {
+            return Err(anyhow!("no DLNA renderers or Chromecasts answered on the LAN"))
+                .map_err(NetworkError::wrap("cast discovery"));
+        }
+        self.cast = Some(CastPicker {
+            file,
+            devices,
+            selected: 0,
+        });
+        self.dialog = Dialog::Cast;
+        Ok(())
+    }
+
+    pub(super) fn move_cast_selection(&mut self, delta: isize) {
+        if let Some(picker) = &mut self.cast {
+            picker.selected = picker
+                .selected
+                .saturating_add_signed(delta)
+                .min(picker.devices.len().saturating_sub(1));
+        }
+    }
+
+    pub(super) fn close_cast(&mut self) {
+        self.cast = None;
+        if self.dialog == Dialog::Cast {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    /// Plays the picked file on the picked device through the stream server, addressed so
+    /// the device can reach it.
+    pub(super) async fn cast_selected(&mut self) -> Result<()> {
+        let Some(picker) = self.cast.take() else {
+            return Ok(());
+        };
+        let Some(device) = picker.devices.get(picker.selected).cloned() else {
+            return Ok(());
+        };
+        let (addr, token) = self.ensure_stream_server().await?;
+        let host = self
+            .config
+            .stream
+            .host
+            .clone()
+            .or_else(|| cast::local_ip_for(&device).map(crate::stream::host_of));
+        let file = picker.file;
+        let media = CastMedia {
+            url: crate::stream::url(
+                addr,
+                host.as_deref(),
+                file.torrent,
+                file.file,
+                &file.name,
+                token.as_deref(),
+            ),
+            mime: file.mime,
+            title: file.name.clone(),
+        };
+        let secs = self.config.cast.timeout_secs;
+        cancellable(&self.effect_cancel.token(), async {
+            with_timeout(secs, cast::play(&device, &media)).await
+        })
+        .await
+        .map_err(|err| err.context(format!("failed to cast to {}", device.name)))?;
+        self.activity.push(format!(
+            "Cast {} to {} ({})",
+            file.name,
+            device.name,
+            device.kind.label()
+        ));
+        self.show_toast(format!("Playing {} on {}", file.name, device.name));
+        Ok(())
+    }
+}
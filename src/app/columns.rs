@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+/// Torrent table headers, in display order.
+pub const COLUMN_NAMES: [&str; COLUMNS] =
+    ["NAME", "STATUS", "PROG%", "DOWN", "UP", "PEERS", "SIZE", "RATIO", "VERIFIED"];
+
+pub const COLUMNS: usize = 9;
+
+/// The name column's entry is its minimum; it gets whatever the others leave over.
+const DEFAULT_WIDTHS: [usize; COLUMNS] = [24, 10, 8, 15, 13, 9, 10, 8, 10];
+const MIN_WIDTH: usize = 4;
+const MAX_WIDTH: usize = 120;
+
+/// Resize mode: the highlighted column and the widths to restore on cancel.
+#[derive(Debug, Clone)]
+pub struct ColumnResize {
+    pub column: usize,
+    pub original: [usize; COLUMNS],
+}
+
+/// Column widths chosen in resize mode, persisted as `columns.json` in the data dir.
+#[derive(Debug)]
+pub struct ColumnWidths {
+    widths: [usize; COLUMNS],
+    path: Option<PathBuf>,
+}
+
+impl ColumnWidths {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let saved: Vec<usize> = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        // Columns added since the file was saved keep their defaults; a hand-edited file
+        // gets the same bounds as resizing.
+        let mut widths = DEFAULT_WIDTHS;
+        for (width, saved) in widths.iter_mut().zip(saved) {
+            *width = saved.clamp(MIN_WIDTH, MAX_WIDTH);
+        }
+        Self { widths, path }
+    }
+
+    pub fn widths(&self) -> [usize; COLUMNS] {
+        self.widths
+    }
+
+    pub fn set(&mut self, widths: [usize; COLUMNS]) {
+        self.widths = widths;
+    }
+
+    pub fn resize(&mut self, column: usize, delta: isize) {
+        if let Some(width) = self.widths.get_mut(column) {
+            *width = width.saturating_add_signed(delta).clamp(MIN_WIDTH, MAX_WIDTH);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.widths = DEFAULT_WIDTHS;
+    }
+
+    /// Keeps what was loaded but stops writing changes back.
+    pub fn read_only(&mut self) {
+        self.path = None;
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("failed to create data dir")?;
+        }
+        let text = serde_json::to_string_pretty(&self.widths)?;
+        std::fs::write(path, text).context("failed to save column widths")
+    }
+}
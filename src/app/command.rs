@@ -0,0 +1,198 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::config::{Density, QuitConfirm};
+
+use super::{
+    action::Action,
+    activity::ActivityFilter,
+    effect::Effect,
+    maintenance::MaintenanceKind,
+    state::{App, ExportRequest},
+};
+
+/// Smallest interval accepted by `:set` and `[ui]`, to keep a typo from pinning a core.
+pub(super) const MIN_INTERVAL: Duration = Duration::from_millis(50);
+
+impl App {
+    /// Runs a `:` command line such as `set refresh 2s`.
+    pub(super) fn run_command(&mut self, line: &str) -> Result<Vec<Action>> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("set") => {
+                let key = words.next().ok_or_else(|| {
+                    anyhow!(
+                        "usage: set <tick|refresh> <interval>, set quit <dialog|twice>, \
+                         set density <comfortable|compact>"
+                    )
+                })?;
+                if key == "quit" {
+                    self.quit_confirm = match words.next() {
+                        Some("dialog") => QuitConfirm::Dialog,
+                        Some("twice") => QuitConfirm::Twice,
+                        _ => bail!("usage: set quit <dialog|twice>"),
+                    };
+                    self.status = match self.quit_confirm {
+                        QuitConfirm::Dialog => "q asks in a dialog before quitting".to_string(),
+                        QuitConfirm::Twice => "Press q twice within a second to quit".to_string(),
+                    };
+                    return Ok(Vec::new());
+                }
+                if key == "density" {
+                    self.density = match words.next() {
+                        Some("comfortable") => Density::Comfortable,
+                        Some("compact") => Density::Compact,
+                        _ => bail!("usage: set density <comfortable|compact>"),
+                    };
+                    self.status = match self.density {
+                        Density::Comfortable => "Comfortable density".to_string(),
+                        Density::Compact => "Compact density".to_string(),
+                    };
+                    return Ok(Vec::new());
+                }
+                let value = words
+                    .next()
+                    .ok_or_else(|| anyhow!("usage: set {key} <interval>"))?;
+                let interval = parse_interval(value)?;
+                match key {
+                    "tick" => self.tick_interval = interval,
+                    "refresh" => self.refresh_interval = interval,
+                    other => bail!("unknown setting: {other}"),
+                }
+                self.status = format!("{key} = {}ms", interval.as_millis());
+                Ok(Vec::new())
+            }
+            Some("cancel-adds") => {
+                match self.cancel_adds() {
+                    0 => bail!("no adds in progress"),
+                    count => self.show_toast(format!("Cancelled {count} adds")),
+                }
+                Ok(Vec::new())
+            }
+            Some("tasks") => Ok(vec![Action::TasksOpen]),
+            Some("lock") => Ok(vec![Action::Lock]),
+            Some("offload") => Ok(vec![Action::RunEffect(Effect::Offload)]),
+            Some("archive") => Ok(vec![Action::ArchiveOpen]),
+            Some("test-rules") => Ok(vec![Action::RuleTesterOpen]),
+            Some("schedule") => Ok(vec![Action::ScheduleOpen]),
+            Some("activity") => {
+                let filter = match words.next() {
+                    Some(word) => ActivityFilter::parse(word).ok_or_else(|| {
+                        anyhow!(
+                            "unknown filter {word}; use all, automation, failed, hook, budget, \
+                             move, permissions or library"
+                        )
+                    })?,
+                    None => ActivityFilter::All,
+                };
+                Ok(vec![Action::ActivityOpen(filter)])
+            }
+            Some("duplicates") => {
+                self.find_duplicates()?;
+                Ok(Vec::new())
+            }
+            Some("pause-all") => Ok(vec![Action::RunEffect(Effect::PauseAll)]),
+            Some("reannounce-all") => {
+                self.ask_maintenance(MaintenanceKind::Reannounce)?;
+                Ok(Vec::new())
+            }
+            Some("debug-announce") => {
+                self.debug_announce()?;
+                Ok(Vec::new())
+            }
+            Some("recheck-all") => {
+                self.ask_maintenance(MaintenanceKind::Recheck)?;
+                Ok(Vec::new())
+            }
+            Some("source") => {
+                let query = words.collect::<Vec<_>>().join(" ").to_lowercase();
+                self.source_filter = (!query.is_empty()).then_some(query);
+                self.ensure_selection_for_filter();
+                self.status = match self.source_filter.as_deref() {
+                    Some(query) => format!("Showing torrents added by {query}"),
+                    None => "Source filter cleared".to_string(),
+                };
+                Ok(Vec::new())
+            }
+            Some("import") => {
+                let path = words.collect::<Vec<_>>().join(" ");
+                if path.is_empty() {
+                    bail!("usage: import <file>");
+                }
+                self.start_import(Path::new(&path))?;
+                Ok(Vec::new())
+            }
+            Some("inspect") => {
+                let path = words.collect::<Vec<_>>().join(" ");
+                if path.is_empty() {
+                    bail!("usage: inspect <file.torrent>");
+                }
+                self.inspect_torrent_file(PathBuf::from(path))?;
+                Ok(Vec::new())
+            }
+            Some("backup") => {
+                let path = words.collect::<Vec<_>>().join(" ");
+                if path.is_empty() {
+                    bail!("usage: backup <file>");
+                }
+                let summary = crate::backup::backup(Path::new(&path))?;
+                self.status = format!("Backed up {} files to {path}", summary.files);
+                Ok(Vec::new())
+            }
+            Some("export") => {
+                let mut request = ExportRequest {
+                    path: PathBuf::new(),
+                    full_list: false,
+                    ansi: false,
+                };
+                let mut path = Vec::new();
+                for word in words {
+                    match word {
+                        "--all" => request.full_list = true,
+                        "--ansi" => request.ansi = true,
+                        _ => path.push(word),
+                    }
+                }
+                if path.is_empty() {
+                    bail!("usage: export [--all] [--ansi] <file>");
+                }
+                request.path = PathBuf::from(path.join(" "));
+                self.pending_export = Some(request);
+                Ok(Vec::new())
+            }
+            Some("reveal") => {
+                self.reveal_secrets = !self.reveal_secrets;
+                self.status = if self.reveal_secrets {
+                    "Showing tracker passkeys".to_string()
+                } else {
+                    "Masking tracker passkeys".to_string()
+                };
+                Ok(Vec::new())
+            }
+            Some("resume-all") => Ok(vec![Action::RunEffect(Effect::ResumeAll)]),
+            Some(other) => bail!("unknown command: {other}"),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Accepts `250ms`, `2s`, or a bare number of milliseconds.
+fn parse_interval(value: &str) -> Result<Duration> {
+    let invalid = || anyhow!("invalid interval: {value} (use e.g. 250ms or 2s)");
+    let interval = if let Some(ms) = value.strip_suffix("ms") {
+        Duration::from_millis(ms.parse().map_err(|_| invalid())?)
+    } else if let Some(secs) = value.strip_suffix('s') {
+        Duration::try_from_secs_f64(secs.parse().map_err(|_| invalid())?)
+            .map_err(|_| invalid())?
+    } else {
+        Duration::from_millis(value.parse().map_err(|_| invalid())?)
+    };
+    if interval < MIN_INTERVAL {
+        bail!("interval must be at least {}ms", MIN_INTERVAL.as_millis());
+    }
+    Ok(interval)
+}
@@ -0,0 +1,209 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use librqbit::TorrentStatsState;
+
+use super::{
+    LabelTarget, StorageKind, View,
+    action::Action,
+    effect::Effect,
+    state::{App, Dialog, TorrentRow},
+    util::open_with_system,
+};
+
+/// Something the context menu can do with the selected torrent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    Files,
+    Pause,
+    Resume,
+    ForceStart,
+    Recheck,
+    Locate,
+    Label,
+    Rename,
+    Notes,
+    OpenFolder,
+    CopyMagnet,
+    CopyInfoHash,
+    Log,
+    Inspect,
+    Delete,
+}
+
+impl MenuItem {
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuItem::Files => "Files",
+            MenuItem::Pause => "Pause",
+            MenuItem::Resume => "Resume",
+            MenuItem::ForceStart => "Force start",
+            MenuItem::Recheck => "Recheck data",
+            MenuItem::Locate => "Locate data...",
+            MenuItem::Label => "Set label...",
+            MenuItem::Rename => "Rename...",
+            MenuItem::Notes => "Notes...",
+            MenuItem::OpenFolder => "Open folder",
+            MenuItem::CopyMagnet => "Copy magnet link",
+            MenuItem::CopyInfoHash => "Copy info hash",
+            MenuItem::Log => "Event log",
+            MenuItem::Inspect => "Inspect JSON",
+            MenuItem::Delete => "Delete...",
+        }
+    }
+
+    /// The key that does the same outside the menu, if there is one.
+    pub fn key(self) -> Option<&'static str> {
+        Some(match self {
+            MenuItem::Files => "o",
+            MenuItem::Pause | MenuItem::Resume => "p",
+            MenuItem::ForceStart => "F",
+            MenuItem::Locate => "m",
+            MenuItem::Label => "L",
+            MenuItem::Rename => "n",
+            MenuItem::Notes => "N",
+            MenuItem::Log => "l",
+            MenuItem::Inspect => "J",
+            MenuItem::Delete => "d",
+            MenuItem::Recheck
+            | MenuItem::OpenFolder
+            | MenuItem::CopyMagnet
+            | MenuItem::CopyInfoHash => return None,
+        })
+    }
+}
+
+/// The actions that apply to the torrent it was opened on, with one highlighted.
+#[derive(Debug, Clone)]
+pub struct ContextMenu {
+    pub torrent_id: usize,
+    pub name: String,
+    pub items: Vec<MenuItem>,
+    pub selected: usize,
+}
+
+impl App {
+    pub(super) fn open_context_menu(&mut self) {
+        if self.selected_group().is_some() {
+            return;
+        }
+        let Some(t) = self.selected_torrent() else {
+            return;
+        };
+        let items = self.menu_items(t);
+        self.context_menu = Some(ContextMenu {
+            torrent_id: t.id,
+            name: t.name.clone(),
+            items,
+            selected: 0,
+        });
+        self.dialog = Dialog::ContextMenu;
+    }
+
+    fn menu_items(&self, t: &TorrentRow) -> Vec<MenuItem> {
+        let running = t.stats.as_ref().is_some_and(|s| {
+            matches!(s.state, TorrentStatsState::Live | TorrentStatsState::Initializing)
+        });
+        let on_disk = self.storage_of(t) != StorageKind::Memory && !self.is_missing_files(t);
+        let mut items = vec![MenuItem::Files];
+        items.push(if running { MenuItem::Pause } else { MenuItem::Resume });
+        if !self.is_forced(t) {
+            items.push(MenuItem::ForceStart);
+        }
+        if on_disk && t.info_hash.is_some() {
+            items.push(MenuItem::Recheck);
+        }
+        items.extend([
+            MenuItem::Locate,
+            MenuItem::Label,
+            MenuItem::Rename,
+            MenuItem::Notes,
+        ]);
+        if on_disk {
+            items.push(MenuItem::OpenFolder);
+        }
+        if t.info_hash.is_some() {
+            items.extend([MenuItem::CopyMagnet, MenuItem::CopyInfoHash]);
+        }
+        items.extend([MenuItem::Log, MenuItem::Inspect, MenuItem::Delete]);
+        items
+    }
+
+    pub(super) fn close_context_menu(&mut self) {
+        self.context_menu = None;
+        if self.dialog == Dialog::ContextMenu {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    pub(super) fn move_context_menu(&mut self, delta: isize) {
+        if let Some(menu) = &mut self.context_menu {
+            let count = menu.items.len() as isize;
+            menu.selected = (menu.selected as isize + delta).rem_euclid(count.max(1)) as usize;
+        }
+    }
+
+    /// Closes the menu and runs the highlighted item, on the torrent it was opened on.
+    pub(super) fn context_menu_actions(&mut self) -> Result<Vec<Action>> {
+        let Some(menu) = self.context_menu.take() else {
+            return Ok(Vec::new());
+        };
+        self.close_context_menu();
+        let Some(item) = menu.items.get(menu.selected).copied() else {
+            return Ok(Vec::new());
+        };
+        let Some(t) = self.torrents.iter().find(|t| t.id == menu.torrent_id) else {
+            bail!("{} is gone", menu.name);
+        };
+        let hash = t.info_hash.clone();
+        Ok(match item {
+            MenuItem::Files => vec![Action::FilesOpen],
+            MenuItem::Pause | MenuItem::Resume => vec![Action::TogglePause],
+            MenuItem::ForceStart => vec![Action::ForceStart],
+            MenuItem::Recheck => match hash {
+                Some(hash) => vec![Action::RunEffect(Effect::Recheck(hash))],
+                None => Vec::new(),
+            },
+            MenuItem::Locate => vec![Action::StartLocate],
+            MenuItem::Label => vec![Action::StartLabelEdit(LabelTarget::Selected)],
+            MenuItem::Rename => vec![Action::StartRename],
+            MenuItem::Notes => vec![Action::NotesOpen],
+            MenuItem::OpenFolder => {
+                open_with_system(Path::new(&t.output_folder))?;
+                self.show_toast(format!("Opening {}", t.output_folder));
+                Vec::new()
+            }
+            MenuItem::CopyMagnet => {
+                let link = magnet_link(t);
+                crate::tui::copy_to_clipboard(&link)?;
+                self.show_toast("Copied magnet link".to_string());
+                Vec::new()
+            }
+            MenuItem::CopyInfoHash => vec![Action::CopyInfoHash],
+            MenuItem::Log => vec![Action::ViewSet(View::Log)],
+            MenuItem::Inspect => vec![Action::InspectorOpen],
+            MenuItem::Delete => vec![Action::ConfirmDeleteOpen],
+        })
+    }
+
+    pub fn context_menu(&self) -> Option<&ContextMenu> {
+        self.context_menu.as_ref()
+    }
+}
+
+/// A magnet link with the torrent's name and trackers, for sharing it without the file.
+fn magnet_link(t: &TorrentRow) -> String {
+    let encode = |text: &str| -> String {
+        url::form_urlencoded::byte_serialize(text.as_bytes()).collect()
+    };
+    let mut link = format!(
+        "magnet:?xt=urn:btih:{}&dn={}",
+        t.info_hash.as_deref().unwrap_or_default(),
+        encode(&t.original_name)
+    );
+    for tracker in &t.trackers {
+        link.push_str("&tr=");
+        link.push_str(&encode(tracker));
+    }
+    link
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+/// Timings shown in the debug overlay. Collected unconditionally; each sample is just
+/// an `Instant` pair, so this stays cheap when the overlay is hidden.
+#[derive(Debug, Default)]
+pub struct DebugStats {
+    pub visible: bool,
+    pub frames: u64,
+    pub draw_last: Duration,
+    pub draw_avg: Duration,
+    pub draw_max: Duration,
+    pub refresh_last: Duration,
+    pub effect_last: Option<(&'static str, Duration)>,
+    pub effect_max: Duration,
+    pub queue_peak: usize,
+    pub event_backlog: usize,
+    /// Resident set size in bytes, sampled on refresh while the overlay is open.
+    pub rss: Option<u64>,
+}
+
+impl DebugStats {
+    pub fn record_draw(&mut self, elapsed: Duration) {
+        self.frames += 1;
+        self.draw_last = elapsed;
+        self.draw_max = self.draw_max.max(elapsed);
+        // Exponential moving average with a 1/8 weight for the new sample.
+        self.draw_avg = if self.frames == 1 {
+            elapsed
+        } else {
+            (self.draw_avg * 7 + elapsed) / 8
+        };
+    }
+
+    pub fn record_effect(&mut self, name: &'static str, elapsed: Duration) {
+        self.effect_last = Some((name, elapsed));
+        self.effect_max = self.effect_max.max(elapsed);
+    }
+
+    pub fn record_queue_depth(&mut self, depth: usize) {
+        self.queue_peak = self.queue_peak.max(depth);
+    }
+}
+
+/// Current resident set size from `/proc/self/status` (Linux only).
+pub fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
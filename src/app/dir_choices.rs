@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use super::{disk::available_space, state::App};
+
+/// A download directory offered in the directory prompt.
+#[derive(Debug, Clone)]
+pub struct DirChoice {
+    pub path: PathBuf,
+    /// Free bytes on its filesystem, when that could be read.
+    pub free: Option<u64>,
+}
+
+impl App {
+    /// The default download directory and `download_dirs`, with their free space read now.
+    pub(super) fn load_dir_choices(&mut self) {
+        let mut paths = vec![self.download_dir.clone()];
+        for dir in &self.config.download_dirs {
+            if !paths.contains(dir) {
+                paths.push(dir.clone());
+            }
+        }
+        self.dir_choices = paths
+            .into_iter()
+            .map(|path| DirChoice {
+                free: available_space(&path),
+                path,
+            })
+            .collect();
+    }
+
+    /// Fills the prompt with the next or previous directory, starting from the one typed.
+    pub(super) fn move_dir_choice(&mut self, delta: isize) {
+        let count = self.dir_choices.len();
+        if count == 0 {
+            return;
+        }
+        let typed = PathBuf::from(self.input.trim());
+        let next = match self.dir_choices.iter().position(|c| c.path == typed) {
+            Some(idx) => (idx as isize + delta).rem_euclid(count as isize) as usize,
+            None if delta < 0 => count - 1,
+            None => 0,
+        };
+        self.input = self.dir_choices[next].path.to_string_lossy().into_owned();
+        self.input_cursor = self.input.chars().count();
+    }
+
+    pub fn dir_choices(&self) -> &[DirChoice] {
+        &self.dir_choices
+    }
+
+    /// Index of the offered directory with the most free space, when there's a choice.
+    pub fn roomiest_dir_choice(&self) -> Option<usize> {
+        if self.dir_choices.len() < 2 {
+            return None;
+        }
+        self.dir_choices
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, c)| Some((idx, c.free?)))
+            // The first of several on the same filesystem.
+            .max_by(|(a_idx, a), (b_idx, b)| a.cmp(b).then(b_idx.cmp(a_idx)))
+            .map(|(idx, _)| idx)
+    }
+}
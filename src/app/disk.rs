@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+/// Free space available to the current user on the filesystem holding `path`.
+/// Walks up to the nearest existing ancestor so not-yet-created folders can be probed.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = nearest_existing(path)?;
+    fs4::available_space(existing).ok()
+}
+
+pub fn nearest_existing(path: &Path) -> Option<PathBuf> {
+    let mut current = Some(path);
+    while let Some(p) = current {
+        if p.exists() {
+            return Some(p.to_path_buf());
+        }
+        current = p.parent();
+    }
+    None
+}
+
+/// Whether two paths live on the same filesystem. Assumes they do when it can't tell.
+pub fn same_filesystem(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let dev = |p: &Path| {
+            nearest_existing(p)
+                .and_then(|p| std::fs::metadata(p).ok())
+                .map(|m| m.dev())
+        };
+        match (dev(a), dev(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let root = |p: &Path| p.components().next().map(|c| c.as_os_str().to_owned());
+        root(a) == root(b)
+    }
+}
+
+/// The filesystem holding `path`, as an id to group by and its mount point. On Unix the
+/// mount point is the topmost ancestor still on the same device.
+pub fn filesystem_of(path: &Path) -> Option<(u64, PathBuf)> {
+    let existing = nearest_existing(path)?;
+    let existing = existing.canonicalize().unwrap_or(existing);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let dev = std::fs::metadata(&existing).ok()?.dev();
+        let mut mount = existing.as_path();
+        while let Some(parent) = mount.parent()
+            && std::fs::metadata(parent).is_ok_and(|m| m.dev() == dev)
+        {
+            mount = parent;
+        }
+        Some((dev, mount.to_path_buf()))
+    }
+    #[cfg(not(unix))]
+    {
+        let root: PathBuf = existing.components().take(2).collect();
+        Some((0, root))
+    }
+}
+
+/// Filesystems where sparse files and preallocation are slow or unreliable.
+const NETWORK_FILESYSTEMS: [&str; 10] = [
+    "nfs", "nfs4", "cifs", "smb3", "smbfs", "9p", "ceph", "glusterfs", "fuse.sshfs", "afs",
+];
+
+/// Type of the network filesystem holding `path`, e.g. `nfs4`; None for local disks or
+/// when it can't tell. Reads the mount table, so Linux only.
+pub fn network_filesystem(path: &Path) -> Option<String> {
+    let existing = nearest_existing(path)?;
+    let existing = existing.canonicalize().unwrap_or(existing);
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    let (_, fstype) = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let target = fields.nth(1)?.replace("\\040", " ");
+            let fstype = fields.next()?;
+            Some((PathBuf::from(target), fstype))
+        })
+        .filter(|(target, _)| existing.starts_with(target))
+        .max_by_key(|(target, _)| target.components().count())?;
+    NETWORK_FILESYSTEMS
+        .contains(&fstype)
+        .then(|| fstype.to_string())
+}
@@ -0,0 +1,93 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use super::{
+    disk::{available_space, filesystem_of},
+    meta::StorageKind,
+    state::App,
+};
+
+/// How often folders are looked up again and free space is read; rates follow every refresh.
+const CHECK_EVERY: Duration = Duration::from_secs(30);
+
+/// Torrents on one filesystem, for the Disks section of the sidebar.
+#[derive(Debug, Clone)]
+pub struct DiskStats {
+    pub mount: PathBuf,
+    pub torrents: usize,
+    /// Selected bytes of those torrents.
+    pub payload: u64,
+    pub free: Option<u64>,
+    /// Bytes per second downloaded into and uploaded from the filesystem.
+    pub write_rate: f64,
+    pub read_rate: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct Disks {
+    /// Filesystem of each output folder, None where it couldn't be found.
+    folders: HashMap<String, Option<(u64, PathBuf)>>,
+    free: HashMap<u64, Option<u64>>,
+    checked_at: Option<Instant>,
+    stats: Vec<DiskStats>,
+}
+
+impl App {
+    /// Regroups the torrents kept on disk by filesystem.
+    pub(super) fn sample_disks(&mut self) {
+        let due = self
+            .disks
+            .checked_at
+            .is_none_or(|at| at.elapsed() >= CHECK_EVERY);
+        if due {
+            self.disks.checked_at = Some(Instant::now());
+            self.disks.folders.clear();
+            self.disks.free.clear();
+        }
+        let mut by_dev: HashMap<u64, DiskStats> = HashMap::new();
+        for t in &self.torrents {
+            if self.storage_of(t) == StorageKind::Memory {
+                continue;
+            }
+            let disks = &mut self.disks;
+            let fs = disks
+                .folders
+                .entry(t.output_folder.clone())
+                .or_insert_with(|| filesystem_of(Path::new(&t.output_folder)));
+            let Some((dev, mount)) = fs.clone() else {
+                continue;
+            };
+            let free = *disks
+                .free
+                .entry(dev)
+                .or_insert_with(|| available_space(&mount));
+            let disk = by_dev.entry(dev).or_insert_with(|| DiskStats {
+                mount,
+                torrents: 0,
+                payload: 0,
+                free,
+                write_rate: 0.0,
+                read_rate: 0.0,
+            });
+            disk.torrents += 1;
+            let Some(stats) = t.stats.as_ref() else {
+                continue;
+            };
+            disk.payload += stats.total_bytes;
+            if let Some(live) = stats.live.as_ref() {
+                disk.write_rate += live.download_speed.mbps * 1024.0 * 1024.0;
+                disk.read_rate += live.upload_speed.mbps * 1024.0 * 1024.0;
+            }
+        }
+        let mut stats: Vec<DiskStats> = by_dev.into_values().collect();
+        stats.sort_by(|a, b| a.mount.cmp(&b.mount));
+        self.disks.stats = stats;
+    }
+
+    pub fn disk_stats(&self) -> &[DiskStats] {
+        &self.disks.stats
+    }
+}
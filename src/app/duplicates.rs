@@ -0,0 +1,223 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Result, bail};
+
+use super::{
+    disk::same_filesystem,
+    state::{App, Dialog},
+};
+
+/// Files smaller than this aren't worth listing (subtitles, .nfo files), though they
+/// still count when deciding whether a whole torrent is redundant.
+const MIN_LISTED_BYTES: u64 = 1024 * 1024;
+
+/// One torrent's copy of a duplicated file.
+#[derive(Debug, Clone)]
+pub struct DuplicateCopy {
+    pub torrent: String,
+    pub path: PathBuf,
+    pub finished: bool,
+    /// (device, inode), when the file is on disk.
+    inode: Option<(u64, u64)>,
+}
+
+/// The same content in several torrents, oldest torrent first.
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    pub len: u64,
+    pub copies: Vec<DuplicateCopy>,
+}
+
+impl DuplicateGroup {
+    /// Bytes held more than once on disk; hard-linked copies only count once.
+    pub fn wasted(&self) -> u64 {
+        let mut seen = Vec::new();
+        let mut distinct = 0u64;
+        for copy in &self.copies {
+            match copy.inode {
+                Some(inode) if seen.contains(&inode) => {}
+                Some(inode) => {
+                    seen.push(inode);
+                    distinct += 1;
+                }
+                None => distinct += 1,
+            }
+        }
+        self.len * distinct.saturating_sub(1)
+    }
+
+    /// How to reclaim the space of each copy after the first.
+    pub fn suggestions(&self) -> Vec<String> {
+        let Some((keep, others)) = self.copies.split_first() else {
+            return Vec::new();
+        };
+        others
+            .iter()
+            .map(|copy| {
+                if keep.inode.is_some() && copy.inode == keep.inode {
+                    "already hard-linked".to_string()
+                } else if !(keep.finished && copy.finished) {
+                    "wait for both downloads to finish".to_string()
+                } else if same_filesystem(&keep.path, &copy.path) {
+                    format!(
+                        "ln -f {} {}",
+                        shell_quote(&keep.path),
+                        shell_quote(&copy.path)
+                    )
+                } else {
+                    "on another filesystem; can't hard-link".to_string()
+                }
+            })
+            .collect()
+    }
+}
+
+/// A torrent whose every file is also in `covered_by`, so it can go without losing data.
+#[derive(Debug, Clone)]
+pub struct RedundantTorrent {
+    pub torrent: String,
+    pub covered_by: String,
+    pub bytes: u64,
+}
+
+/// Result of `:duplicates`, shown until closed.
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateReport {
+    pub groups: Vec<DuplicateGroup>,
+    pub redundant: Vec<RedundantTorrent>,
+    /// Torrents whose files couldn't all be fingerprinted (no metadata yet, or files that
+    /// share pieces); they may hold more duplicates.
+    pub unknown: usize,
+    pub scroll: u16,
+}
+
+impl DuplicateReport {
+    pub fn wasted(&self) -> u64 {
+        self.groups.iter().map(DuplicateGroup::wasted).sum()
+    }
+}
+
+struct Scanned {
+    name: String,
+    /// (digest, len, path) of every wanted file with a fingerprint.
+    files: Vec<(Vec<u8>, u64, PathBuf)>,
+    finished: bool,
+    complete: bool,
+}
+
+impl App {
+    /// Compares the fingerprints of every wanted file across the session.
+    pub(super) fn find_duplicates(&mut self) -> Result<()> {
+        if self.torrents.len() < 2 {
+            bail!("need at least two torrents to compare");
+        }
+        let mut scanned = Vec::new();
+        for t in &self.torrents {
+            let wanted: Vec<bool> = self
+                .backend
+                .torrent_details(t.id)
+                .ok()
+                .and_then(|d| d.files)
+                .map(|files| files.iter().map(|f| f.included && f.length > 0).collect())
+                .unwrap_or_default();
+            let prints = self.backend.file_fingerprints(t.id);
+            let files: Vec<_> = prints
+                .into_iter()
+                .filter(|f| wanted.get(f.index).copied().unwrap_or(false))
+                .map(|f| (f.digest, f.len, Path::new(&t.output_folder).join(f.path)))
+                .collect();
+            scanned.push(Scanned {
+                name: t.name.clone(),
+                complete: !wanted.is_empty()
+                    && files.len() == wanted.iter().filter(|w| **w).count(),
+                files,
+                finished: t.stats.as_ref().is_some_and(|s| s.finished),
+            });
+        }
+
+        let mut by_digest: HashMap<&[u8], Vec<(usize, u64, &PathBuf)>> = HashMap::new();
+        for (idx, s) in scanned.iter().enumerate() {
+            for (digest, len, path) in &s.files {
+                by_digest.entry(digest).or_default().push((idx, *len, path));
+            }
+        }
+        let mut groups: Vec<DuplicateGroup> = by_digest
+            .values()
+            .filter(|copies| copies.len() > 1 && copies[0].1 >= MIN_LISTED_BYTES)
+            .map(|copies| {
+                let mut copies: Vec<_> = copies.clone();
+                copies.sort_by_key(|(idx, _, path)| (*idx, (*path).clone()));
+                DuplicateGroup {
+                    len: copies[0].1,
+                    copies: copies
+                        .into_iter()
+                        .map(|(idx, _, path)| DuplicateCopy {
+                            torrent: scanned[idx].name.clone(),
+                            path: path.clone(),
+                            finished: scanned[idx].finished,
+                            inode: inode(path),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        groups.sort_by_key(|g| std::cmp::Reverse(g.wasted()));
+
+        let mut redundant = Vec::new();
+        for (idx, s) in scanned.iter().enumerate() {
+            if !s.complete || s.files.is_empty() {
+                continue;
+            }
+            let covered_by = scanned.iter().enumerate().find(|(other, o)| {
+                *other != idx
+                    && !redundant.iter().any(|r: &RedundantTorrent| r.torrent == o.name)
+                    && s.files.iter().all(|(digest, ..)| {
+                        o.files.iter().any(|(other_digest, ..)| other_digest == digest)
+                    })
+            });
+            if let Some((_, o)) = covered_by {
+                redundant.push(RedundantTorrent {
+                    torrent: s.name.clone(),
+                    covered_by: o.name.clone(),
+                    bytes: s.files.iter().map(|(_, len, _)| len).sum(),
+                });
+            }
+        }
+
+        self.duplicates = Some(DuplicateReport {
+            groups,
+            redundant,
+            unknown: scanned.iter().filter(|s| !s.complete).count(),
+            scroll: 0,
+        });
+        self.dialog = Dialog::Duplicates;
+        Ok(())
+    }
+
+    pub(super) fn close_duplicates(&mut self) {
+        self.duplicates = None;
+        if self.dialog == Dialog::Duplicates {
+            self.dialog = Dialog::None;
+        }
+    }
+}
+
+fn inode(path: &Path) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
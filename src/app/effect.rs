@@ -1,17 +1,160 @@
+use std::path::PathBuf;
+
+use crate::config::CollisionStrategy;
+
+use super::{
+    cast::CastFile,
+    meta::{AddSource, StorageKind},
+};
+
 #[derive(Debug, Clone)]
 pub enum Effect {
     Refresh,
     TogglePause,
-    StopSelected,
-    DeleteSelectedFiles,
-    PreflightAdd {
-        magnet: String,
-    },
+    ForceStart,
+    /// Drops a torrent from the session, and its data with `delete_files`.
+    Remove { id: usize, delete_files: bool },
     StartFilePicker { magnet: String, output_folder: String },
     StartDownload {
         magnet: String,
         output_folder: String,
         only_files: Vec<usize>,
+        label: Option<String>,
+        source: AddSource,
+        storage: StorageKind,
+    },
+    StartPendingAdd,
+    /// Adds a magnet with every file selected, as imports do.
+    AddMagnet {
+        magnet: String,
+        label: Option<String>,
+        dir: Option<PathBuf>,
+        source: AddSource,
+    },
+    ImportNext,
+    /// Adds the next torrent an `[[rss.rules]]` entry matched.
+    RssNext,
+    MaintenanceNext,
+    ResolveCollision(CollisionStrategy),
+    ResolvePathReview {
+        sanitize: bool,
+    },
+    InspectSelected,
+    PowerPause {
+        reason: String,
+    },
+    PowerResume,
+    PauseAll,
+    ResumeAll,
+    StartTorrents(Vec<usize>),
+    PauseTorrents(Vec<usize>),
+    SetLabel {
+        info_hash: String,
+        label: Option<String>,
+    },
+    SetDisplayName {
+        info_hash: String,
+        name: Option<String>,
     },
+    SetNotes {
+        info_hash: String,
+        notes: Option<String>,
+    },
+    Relocate {
+        id: usize,
+        output_folder: String,
+        paused: bool,
+    },
+    /// Moves a finished torrent's folder to `output_folder` under a `move_to` template.
+    MoveCompleted {
+        id: usize,
+        output_folder: String,
+    },
+    /// Moves the selected torrent's data to `[archive] dir` and drops it from the session.
+    Offload,
+    /// Scheduled recheck of a seeding torrent, by info hash.
+    Scrub(String),
+    /// Recheck of one torrent asked for from its context menu.
+    Recheck(String),
+    CopyStreamUrl,
+    /// Looks for cast devices to play the file on, then opens the picker.
+    DiscoverCasts(CastFile),
+    CastSelected,
 }
 
+
+impl Effect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Effect::Refresh => "Refresh",
+            Effect::TogglePause => "TogglePause",
+            Effect::ForceStart => "ForceStart",
+            Effect::Remove { .. } => "Remove",
+            Effect::StartFilePicker { .. } => "StartFilePicker",
+            Effect::StartDownload { .. } => "StartDownload",
+            Effect::StartPendingAdd => "StartPendingAdd",
+            Effect::AddMagnet { .. } => "AddMagnet",
+            Effect::ImportNext => "ImportNext",
+            Effect::RssNext => "RssNext",
+            Effect::MaintenanceNext => "MaintenanceNext",
+            Effect::ResolveCollision(_) => "ResolveCollision",
+            Effect::ResolvePathReview { .. } => "ResolvePathReview",
+            Effect::InspectSelected => "InspectSelected",
+            Effect::PowerPause { .. } => "PowerPause",
+            Effect::PowerResume => "PowerResume",
+            Effect::PauseAll => "PauseAll",
+            Effect::ResumeAll => "ResumeAll",
+            Effect::StartTorrents(_) => "StartTorrents",
+            Effect::PauseTorrents(_) => "PauseTorrents",
+            Effect::SetLabel { .. } => "SetLabel",
+            Effect::SetDisplayName { .. } => "SetDisplayName",
+            Effect::SetNotes { .. } => "SetNotes",
+            Effect::Relocate { .. } => "Relocate",
+            Effect::MoveCompleted { .. } => "MoveCompleted",
+            Effect::Offload => "Offload",
+            Effect::Scrub(_) => "Scrub",
+            Effect::Recheck(_) => "Recheck",
+            Effect::CopyStreamUrl => "CopyStreamUrl",
+            Effect::DiscoverCasts(_) => "DiscoverCasts",
+            Effect::CastSelected => "CastSelected",
+        }
+    }
+
+    /// Effects that go out to the network, retried with backoff when that fails.
+    pub fn retries(&self) -> bool {
+        matches!(
+            self,
+            Effect::StartFilePicker { .. }
+                | Effect::StartDownload { .. }
+                | Effect::AddMagnet { .. }
+                | Effect::DiscoverCasts(_)
+        )
+    }
+
+    /// Effects that can be aborted with Esc while they run. Maintenance isn't one: a
+    /// recheck or re-announce cut short would leave its torrent removed or paused.
+    pub fn cancellable(&self) -> bool {
+        matches!(
+            self,
+            Effect::StartFilePicker { .. }
+                | Effect::StartDownload { .. }
+                | Effect::AddMagnet { .. }
+                | Effect::ImportNext
+                | Effect::RssNext
+                | Effect::DiscoverCasts(_)
+                | Effect::CastSelected
+        )
+    }
+
+    /// What the busy dialog says while this effect runs; None for effects whose progress
+    /// already has a dialog.
+    pub fn busy_label(&self) -> Option<&'static str> {
+        match self {
+            Effect::StartFilePicker { .. } => Some("Fetching metadata"),
+            Effect::StartDownload { .. } | Effect::AddMagnet { .. } => Some("Adding torrent"),
+            Effect::DiscoverCasts(_) => Some("Looking for cast devices"),
+            Effect::CastSelected => Some("Starting playback"),
+            _ => None,
+        }
+    }
+}
@@ -1,16 +1,46 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use librqbit::{
+    AddTorrentOptions,
+    api::ApiAddTorrentResponse,
+    storage::{StorageFactoryExt, filesystem::FilesystemStorageFactory},
+};
+use tokio::task::JoinHandle;
 
-use anyhow::{Context, Result, anyhow};
-use librqbit::{AddTorrentOptions, api::ApiTorrentListOpts};
-use tokio::time::sleep;
+use crate::{
+    backend::{MemoryStorageFactory, SyncedStorageFactory, ThrottledStorageFactory},
+    config::CollisionStrategy,
+};
 
 use super::{
+    PendingAdd,
     action::Action,
+    activity::Automation,
+    budget::BudgetEvictions,
+    bus::SessionEvent,
+    cancel::cancellable,
+    disk::{available_space, network_filesystem, same_filesystem},
     effect::Effect,
-    state::{App, Dialog, FilePickerState, TorrentRow},
+    import::ImportItem,
+    inspector::JsonInspector,
+    meta::{AddSource, StorageKind},
+    paths,
+    retry::{NetworkError, with_timeout},
+    save_path::{SavePathTarget, SavePathVars},
+    state::{App, Dialog, FilePickerState, PathReview, PendingPlacement, TorrentRow},
     util::{build_add_torrent, build_picker, derive_folder_suffix, sanitize_path_component, to_row},
 };
 
+/// Largest selection that can be previewed in memory.
+const MAX_PREVIEW_BYTES: u64 = 256 * 1024 * 1024;
+/// How long a queued add that failed to start waits before it's tried again.
+const PENDING_RETRY: Duration = Duration::from_secs(5 * 60);
+
 impl App {
     pub async fn run_effect(&mut self, effect: Effect) -> Result<Vec<Action>> {
         match effect {
@@ -20,15 +50,11 @@ impl App {
             Effect::TogglePause => {
                 self.toggle_pause().await?;
             }
-            Effect::StopSelected => {
-                self.stop_selected().await?;
-            }
-            Effect::DeleteSelectedFiles => {
-                self.delete_selected_files().await?;
+            Effect::ForceStart => {
+                self.force_start_selected().await?;
             }
-            Effect::PreflightAdd { magnet } => {
-                let next = self.preflight_add(magnet).await?;
-                return Ok(next);
+            Effect::Remove { id, delete_files } => {
+                self.remove_torrent(id, delete_files).await?;
             }
             Effect::StartFilePicker {
                 magnet,
@@ -40,58 +66,380 @@ impl App {
                 magnet,
                 output_folder,
                 only_files,
+                label,
+                source,
+                storage,
             } => {
+                let bytes = self.selected_bytes(&only_files);
+                if storage == StorageKind::Memory && bytes > MAX_PREVIEW_BYTES {
+                    return Err(anyhow!(
+                        "Too large to preview in memory ({} MiB selected, limit {} MiB)",
+                        bytes / (1024 * 1024),
+                        MAX_PREVIEW_BYTES / (1024 * 1024)
+                    ));
+                }
+                // The budget's removals wait until the add went through, so a queued,
+                // failed or retried add never deletes anything.
+                let evictions = match storage {
+                    StorageKind::Disk => self.plan_label_budget(label.as_deref(), bytes, None)?,
+                    StorageKind::Memory => BudgetEvictions::default(),
+                };
+                let folder = Path::new(&output_folder);
+                let needed = bytes.saturating_sub(evictions.freed_on(folder));
+                if storage == StorageKind::Disk
+                    && let Some(free) = self.space_shortfall(folder, needed)
+                {
+                    let name = Path::new(&output_folder)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| output_folder.clone());
+                    self.file_picker = None;
+                    self.mode = super::Mode::Normal;
+                    self.dialog = Dialog::None;
+                    return Ok(vec![Action::ConfirmSpaceOpen {
+                        add: PendingAdd {
+                            magnet,
+                            name,
+                            output_folder,
+                            only_files,
+                            label,
+                            source,
+                            bytes,
+                            queued_at: chrono::Local::now(),
+                        },
+                        free,
+                    }]);
+                }
                 self.status = "Starting download...".to_string();
                 self.last_error = None;
-                self.start_download(magnet, output_folder, only_files).await?;
+                self.start_download(magnet, output_folder, only_files, label, source, storage)
+                    .await?;
                 self.file_picker = None;
                 self.mode = super::Mode::Normal;
                 self.dialog = Dialog::None;
+                self.evict_after_add(evictions).await;
+            }
+            Effect::StartPendingAdd => {
+                // The add stays queued until it started, so a failed start is tried again.
+                if let Some(add) = self.pending_adds.items().first().cloned() {
+                    self.activity
+                        .push(format!("Space available, starting queued add: {}", add.name));
+                    if let Err(err) = self.start_pending_add(add).await {
+                        self.pending_adds.hold(PENDING_RETRY);
+                        return Err(err.context(format!(
+                            "queued add failed, trying again in {} minutes",
+                            PENDING_RETRY.as_secs() / 60
+                        )));
+                    }
+                }
+            }
+            Effect::AddMagnet {
+                magnet,
+                label,
+                dir,
+                source,
+            } => {
+                let item = ImportItem { magnet, label, dir };
+                if !self.import_one(item, source).await? {
+                    self.status = "Already in the session".to_string();
+                }
+            }
+            Effect::ImportNext => {
+                self.import_next().await;
+                return Ok(vec![self.import_progress()]);
+            }
+            Effect::RssNext => self.rss_next().await,
+            Effect::MaintenanceNext => {
+                self.maintenance_next().await;
+                return Ok(vec![self.maintenance_progress()]);
+            }
+            Effect::ResolvePathReview { sanitize } => {
+                if let Some(review) = self.path_review.take() {
+                    let mut placement = review.placement;
+                    if sanitize {
+                        placement.base = review
+                            .sanitized
+                            .parent()
+                            .map(PathBuf::from)
+                            .unwrap_or(placement.base);
+                        placement.folder_name = review
+                            .sanitized
+                            .file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or(placement.folder_name);
+                    }
+                    self.place_download(placement)?;
+                }
+            }
+            Effect::ResolveCollision(strategy) => {
+                if let Some(collision) = self.collision.take() {
+                    self.resolve_collision(collision, strategy)?;
+                }
+            }
+            Effect::InspectSelected => {
+                self.inspect_selected()?;
+            }
+            Effect::PowerPause { reason } => {
+                self.power_pause_all(reason).await?;
+            }
+            Effect::PowerResume => {
+                self.power_resume();
+            }
+            Effect::PauseAll => {
+                let live = self.active_ids();
+                let paused = self.pause_ids(&live).await?;
+                self.status = format!("Paused {paused} torrents");
+            }
+            Effect::ResumeAll => {
+                let paused: Vec<usize> = self
+                    .torrents
+                    .iter()
+                    .filter(|t| {
+                        t.stats
+                            .as_ref()
+                            .is_some_and(|s| matches!(s.state, librqbit::TorrentStatsState::Paused))
+                    })
+                    .map(|t| t.id)
+                    .collect();
+                self.status = match self.config.announce.jitter_secs {
+                    0 => format!("Resuming {} torrents", paused.len()),
+                    secs => format!("Resuming {} torrents over {secs}s", paused.len()),
+                };
+                self.schedule_starts(paused);
+            }
+            Effect::PauseTorrents(ids) => {
+                self.pause_ids(&ids).await?;
+            }
+            Effect::StartTorrents(ids) => {
+                for id in ids {
+                    // Torrents removed while queued are simply skipped.
+                    if self.torrents.iter().any(|t| t.id == id) {
+                        self.backend
+                            .start(id)
+                            .await
+                            .context("error resuming torrent")?;
+                    }
+                }
+            }
+            Effect::SetDisplayName { info_hash, name } => {
+                for t in &mut self.torrents {
+                    if t.info_hash.as_deref() == Some(info_hash.as_str()) {
+                        t.name = name.clone().unwrap_or_else(|| t.original_name.clone());
+                    }
+                }
+                self.status = match name.as_deref() {
+                    Some(name) => format!("Renamed to {name}"),
+                    None => "Name reset".to_string(),
+                };
+                self.meta.update(&info_hash, |m| m.display_name = name)?;
+            }
+            Effect::Relocate {
+                id,
+                output_folder,
+                paused,
+            } => {
+                self.relocate(id, output_folder, paused).await?;
+            }
+            Effect::MoveCompleted { id, output_folder } => {
+                if let Err(err) = self.move_completed(id, PathBuf::from(output_folder)).await {
+                    let torrent = self.torrents.iter().find(|t| t.id == id);
+                    let rule = self.move_rule(torrent.and_then(|t| self.label_of(t)));
+                    self.activity
+                        .record(Automation::Move, rule, false, format!("Move: {err:#}"));
+                    return Err(err);
+                }
+            }
+            Effect::Offload => {
+                self.offload_selected().await?;
+            }
+            Effect::Scrub(hash) => {
+                self.scrub_one(&hash).await?;
+            }
+            Effect::Recheck(hash) => {
+                if !self.recheck_one(&hash).await? {
+                    bail!("can't recheck: the data is in memory or its folder is missing");
+                }
+                self.status = "Rechecking".to_string();
+            }
+            Effect::CopyStreamUrl => {
+                self.copy_stream_url().await?;
+            }
+            Effect::DiscoverCasts(file) => {
+                self.discover_casts(file).await?;
+            }
+            Effect::CastSelected => {
+                self.cast_selected().await?;
+            }
+            Effect::SetNotes { info_hash, notes } => {
+                self.status = if notes.is_some() {
+                    "Notes saved".to_string()
+                } else {
+                    "Notes cleared".to_string()
+                };
+                self.meta.update(&info_hash, |m| m.notes = notes)?;
+            }
+            Effect::SetLabel { info_hash, label } => {
+                // The new label is saved first so the budget is planned against it; a
+                // budget that can't fit the torrent puts the old one back.
+                let previous = self.meta.label(&info_hash).map(str::to_string);
+                self.meta.update(&info_hash, |m| m.label = label.clone())?;
+                if let Some(label) = label.as_deref() {
+                    let bytes = self
+                        .torrents
+                        .iter()
+                        .find(|t| t.info_hash.as_deref() == Some(info_hash.as_str()))
+                        .and_then(|t| t.stats.as_ref())
+                        .map(|s| s.progress_bytes)
+                        .unwrap_or(0);
+                    let planned = self.plan_label_budget(Some(label), bytes, Some(&info_hash));
+                    let evictions = match planned {
+                        Ok(evictions) => evictions,
+                        Err(err) => {
+                            self.meta.update(&info_hash, |m| m.label = previous)?;
+                            return Err(err);
+                        }
+                    };
+                    self.evict_for_budget(evictions).await?;
+                }
+                self.status = match label.as_deref() {
+                    Some(label) => format!("Label set: {label}"),
+                    None => "Label cleared".to_string(),
+                };
             }
         }
         Ok(Vec::new())
     }
 
-    async fn preflight_add(&mut self, magnet: String) -> Result<Vec<Action>> {
-        let add = build_add_torrent(&magnet)?;
-        let response = self
-            .api
-            .api_add_torrent(
+    fn selected_bytes(&self, only_files: &[usize]) -> u64 {
+        let Some(picker) = self.file_picker.as_ref() else {
+            return 0;
+        };
+        only_files
+            .iter()
+            .filter_map(|&idx| picker.files.get(idx))
+            .map(|f| f.length)
+            .sum()
+    }
+
+    /// Bytes that incomplete torrents on the same filesystem as `folder` still have to write.
+    fn outstanding_bytes(&self, folder: &Path) -> u64 {
+        self.torrents
+            .iter()
+            .filter(|t| same_filesystem(Path::new(&t.output_folder), folder))
+            .filter_map(|t| t.stats.as_ref())
+            .filter(|s| !s.finished)
+            .map(|s| s.total_bytes.saturating_sub(s.progress_bytes))
+            .sum()
+    }
+
+    /// Returns the free space on `folder`'s filesystem if it can't fit `bytes` on top of
+    /// the torrents still downloading there.
+    pub(super) fn space_shortfall(&self, folder: &Path, bytes: u64) -> Option<u64> {
+        let free = available_space(folder)?;
+        let needed = bytes.saturating_add(self.outstanding_bytes(folder));
+        if needed > free { Some(free) } else { None }
+    }
+
+    async fn start_pending_add(&mut self, add: PendingAdd) -> Result<()> {
+        let evictions = self.plan_label_budget(add.label.as_deref(), add.bytes, None)?;
+        self.start_download(
+            add.magnet,
+            add.output_folder,
+            add.only_files,
+            add.label,
+            add.source,
+            StorageKind::Disk,
+        )
+        .await?;
+        self.pending_adds.remove(0)?;
+        self.evict_after_add(evictions).await;
+        Ok(())
+    }
+
+    pub(super) fn pending_add_ready(&self) -> bool {
+        if self.pending_adds.held() {
+            return false;
+        }
+        self.pending_adds
+            .items()
+            .first()
+            .map(|add| {
+                self.space_shortfall(Path::new(&add.output_folder), add.bytes)
+                    .is_none()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Resolves `magnet` to its file list without adding it, within `timeouts.metadata_secs`.
+    pub(super) async fn list_files(
+        &self,
+        magnet: &str,
+        output_folder: String,
+    ) -> Result<ApiAddTorrentResponse> {
+        let add = build_add_torrent(magnet)?;
+        let list = self.backend.add_torrent(
+            add,
+            AddTorrentOptions {
+                list_only: true,
+                output_folder: Some(output_folder),
+                ..Default::default()
+            },
+        );
+        let secs = self.config.timeouts.metadata_secs;
+        cancellable(&self.effect_cancel.token(), async {
+            with_timeout(secs, list)
+                .await
+                .map_err(NetworkError::wrap("error listing files"))
+        })
+        .await
+    }
+
+    /// [`Self::list_files`] as a background task, for adds that shouldn't hold up the tick.
+    pub(super) fn spawn_list_files(
+        &self,
+        magnet: &str,
+        output_folder: String,
+    ) -> Result<JoinHandle<Result<ApiAddTorrentResponse>>> {
+        let add = build_add_torrent(magnet)?;
+        let backend = self.backend.clone();
+        let secs = self.config.timeouts.metadata_secs;
+        Ok(tokio::spawn(async move {
+            let list = backend.add_torrent(
                 add,
-                Some(AddTorrentOptions {
+                AddTorrentOptions {
                     list_only: true,
-                    output_folder: Some(self.download_dir.to_string_lossy().into_owned()),
+                    output_folder: Some(output_folder),
                     ..Default::default()
-                }),
-            )
-            .await
-            .context("error listing files")?;
-        let info_hash = response.details.info_hash.as_str();
-        let existing = self
-            .api
-            .api_torrent_list_ext(ApiTorrentListOpts { with_stats: false })
-            .torrents
-            .iter()
-            .any(|t| t.info_hash == info_hash);
-        if existing {
-            return Err(anyhow!(
-                "Torrent already added; duplicate locations are not supported"
-            ));
-        }
-        Ok(vec![Action::PreflightAddResult { magnet }])
+                },
+            );
+            with_timeout(secs, list)
+                .await
+                .map_err(NetworkError::wrap("error listing files"))
+        }))
     }
 
     pub fn refresh(&mut self) {
+        let started = Instant::now();
+        self.last_refresh = Some(started);
         let selected_id = self.selected_torrent().map(|t| t.id);
-        self.session_stats = Some(self.api.api_session_stats());
-        let list = self
-            .api
-            .api_torrent_list_ext(ApiTorrentListOpts { with_stats: true });
-        let rows: Vec<TorrentRow> = list
-            .torrents
+        self.session_stats = Some(self.backend.session_stats());
+        self.record_speeds();
+        let list = self.backend.torrent_list(true);
+        let mut rows: Vec<TorrentRow> = list
             .into_iter()
             .filter_map(|t| to_row(t).ok())
             .collect();
+        for row in &mut rows {
+            row.trackers = self.backend.trackers(row.id);
+            if let Some(name) = row
+                .info_hash
+                .as_deref()
+                .and_then(|h| self.meta.get(h))
+                .and_then(|m| m.display_name.clone())
+            {
+                row.name = name;
+            }
+        }
         if rows.is_empty() {
             self.selected = 0;
         } else if let Some(id) = selected_id {
@@ -104,7 +452,52 @@ impl App {
             self.selected = self.selected.min(rows.len().saturating_sub(1));
         }
         self.torrents = rows;
+        self.rates.update(&self.torrents, started);
         self.ensure_selection_for_filter();
+        self.selected_pieces = self
+            .selected_torrent()
+            .and_then(|t| Some((t.id, self.backend.piece_progress(t.id)?)));
+        self.log_state_changes();
+        self.check_missing_files();
+        for idx in self.detect_completions() {
+            self.on_torrent_completed(idx);
+        }
+        self.flush_notifications();
+        self.publish_torrent_events();
+        // Until the startup policy has run, the stored states still describe last shutdown.
+        if !self.startup_pending {
+            self.record_torrent_states();
+        }
+        self.track_uploads();
+        self.track_verified();
+        self.sync_links();
+        self.sample_disk_writes(started);
+        self.sample_disks();
+        self.sample_peers(started);
+        self.sample_files();
+        self.sample_info();
+        self.summarize_filters();
+        self.apply_start_label();
+        self.check_alerts();
+        self.remember_ui_state();
+        if self.debug.visible {
+            self.debug.rss = super::debug::resident_bytes();
+        }
+        self.debug.refresh_last = started.elapsed();
+    }
+
+    fn sample_disk_writes(&mut self, now: Instant) {
+        let Some(writes) = self.disk_writes.as_ref() else {
+            return;
+        };
+        let written = writes.written();
+        if let Some((at, before)) = self.disk_write_sample {
+            let secs = now.duration_since(at).as_secs_f64();
+            if secs > 0.0 {
+                self.disk_write_rate = Some(written.saturating_sub(before) as f64 / secs);
+            }
+        }
+        self.disk_write_sample = Some((now, written));
     }
 
     async fn start_file_picker_with_dir(
@@ -112,58 +505,120 @@ impl App {
         magnet: String,
         output_folder: String,
     ) -> Result<()> {
-        let response = {
-            let mut resp = None;
-            for attempt in 0..3 {
-                let add = build_add_torrent(&magnet)?;
-                match self
-                    .api
-                    .api_add_torrent(
-                        add,
-                        Some(AddTorrentOptions {
-                            list_only: true,
-                            output_folder: Some(output_folder.clone()),
-                            ..Default::default()
-                        }),
-                    )
-                    .await
-                {
-                    Ok(ok) => {
-                        resp = Some(ok);
-                        break;
-                    }
-                    Err(err) => {
-                        if attempt < 2 {
-                            sleep(std::time::Duration::from_millis(500)).await;
-                        } else {
-                            return Err(anyhow!("error listing files: {err}"));
-                        }
-                    }
-                }
-            }
-            resp.ok_or_else(|| anyhow!("error listing files: unknown"))?
-        };
+        let response = self.list_files(&magnet, output_folder.clone()).await?;
         let info_hash = response.details.info_hash.as_str();
         let suffix = derive_folder_suffix(&response);
-        let base = PathBuf::from(&output_folder);
-        let mut folder_name = sanitize_path_component(&suffix);
-        let mut final_output = base.join(&folder_name);
+        let save_path = SavePathTarget {
+            base: PathBuf::from(&output_folder),
+            vars: SavePathVars::for_add(&magnet, &suffix, info_hash, None),
+        };
+        let final_output = self.save_path(&save_path.base, &save_path.vars)?;
         if self.has_same_destination(info_hash, final_output.to_string_lossy().as_ref()) {
             return Err(anyhow!(
                 "Torrent already added for this download directory"
             ));
         }
-        if final_output.exists() {
-            let short_hash: String = info_hash.chars().take(8).collect();
-            folder_name = format!("{folder_name}-{short_hash}");
-            final_output = base.join(&folder_name);
-            if final_output.exists() {
-                return Err(anyhow!("Destination folder already exists"));
+        let base = final_output
+            .parent()
+            .map_or_else(|| save_path.base.clone(), PathBuf::from);
+        let folder_name = final_output
+            .file_name()
+            .map_or_else(|| sanitize_path_component(&suffix), |n| n.to_string_lossy().into_owned());
+        let placement = PendingPlacement {
+            magnet,
+            base,
+            folder_name,
+            response,
+            save_path,
+        };
+        if self.config.checks_windows_paths()
+            && let Some((issues, sanitized)) = paths::review(&placement)
+        {
+            self.path_review = Some(PathReview {
+                placement,
+                issues,
+                sanitized,
+            });
+            self.path_review_choice = true;
+            self.mode = super::Mode::Normal;
+            self.status = "Path won't work on Windows".to_string();
+            self.dialog = Dialog::PathReview;
+            return Ok(());
+        }
+        self.place_download(placement)
+    }
+
+    fn place_download(&mut self, placement: PendingPlacement) -> Result<()> {
+        let final_output = placement.base.join(&placement.folder_name);
+        if self.backend.is_simulated() || !final_output.exists() {
+            return self.open_file_picker(
+                placement.magnet,
+                final_output,
+                placement.response,
+                placement.save_path,
+            );
+        }
+        let collision = placement;
+        match self.config.collision {
+            CollisionStrategy::Prompt => {
+                self.collision = Some(collision);
+                self.collision_choice = 0;
+                self.mode = super::Mode::Normal;
+                self.status = "Destination folder already exists".to_string();
+                self.dialog = Dialog::Collision;
+                Ok(())
             }
+            strategy => self.resolve_collision(collision, strategy),
+        }
+    }
+
+    fn resolve_collision(
+        &mut self,
+        collision: PendingPlacement,
+        strategy: CollisionStrategy,
+    ) -> Result<()> {
+        let PendingPlacement {
+            magnet,
+            base,
+            folder_name,
+            response,
+            save_path,
+        } = collision;
+        let final_output =
+            collision_path(&base, &folder_name, &response.details.info_hash, strategy)?;
+        let final_str = final_output.to_string_lossy();
+        if self.has_same_destination(&response.details.info_hash, final_str.as_ref()) {
+            return Err(anyhow!(
+                "Torrent already added for this download directory"
+            ));
         }
-        std::fs::create_dir_all(&final_output).context("failed to create download folder")?;
+        self.open_file_picker(magnet, final_output, response, save_path)
+    }
+
+    fn open_file_picker(
+        &mut self,
+        magnet: String,
+        final_output: PathBuf,
+        response: ApiAddTorrentResponse,
+        save_path: SavePathTarget,
+    ) -> Result<()> {
         let output_folder = final_output.to_string_lossy().into_owned();
-        let picker: FilePickerState = build_picker(magnet, output_folder, response)?;
+        let flagged = if self.config.checks_windows_paths() {
+            paths::flagged_files(&final_output, &response)
+        } else {
+            HashSet::new()
+        };
+        let mut picker: FilePickerState = build_picker(magnet, output_folder, response)?;
+        for idx in flagged {
+            if let Some(file) = picker.files.get_mut(idx) {
+                file.path_issue = true;
+            }
+        }
+        picker.save_path = Some(save_path);
+        self.mark_risky_files(&mut picker);
+        if !self.backend.is_simulated() {
+            picker.network_fs = network_filesystem(Path::new(&picker.output_folder));
+        }
         self.file_picker = Some(picker);
         self.mode = super::Mode::FilePicker;
         self.status = "Select files and press Enter".to_string();
@@ -171,62 +626,245 @@ impl App {
         Ok(())
     }
 
-    async fn start_download(
+    pub(super) async fn start_download(
         &mut self,
         magnet: String,
         output_folder: String,
         only_files: Vec<usize>,
+        label: Option<String>,
+        source: AddSource,
+        storage: StorageKind,
     ) -> Result<()> {
         if only_files.is_empty() {
             return Err(anyhow!("No files selected"));
         }
+        if !self.backend.is_simulated() {
+            std::fs::create_dir_all(&output_folder).context("failed to create download folder")?;
+        }
         let expected: HashSet<usize> = only_files.iter().copied().collect();
+        let network = (storage == StorageKind::Disk && !self.backend.is_simulated())
+            .then(|| network_filesystem(Path::new(&output_folder)))
+            .flatten();
+        if let Some(fstype) = network.as_deref()
+            && self.config.disk.refuse_network
+        {
+            return Err(anyhow!(
+                "{output_folder} is on a network filesystem ({fstype}) and disk.refuse_network \
+                 is set"
+            ));
+        }
+        let storage_factory = match storage {
+            StorageKind::Memory => Some(MemoryStorageFactory.boxed()),
+            StorageKind::Disk if network.is_some() && self.config.disk.fsync_on_network => {
+                let synced = SyncedStorageFactory::new(
+                    FilesystemStorageFactory::default(),
+                    PathBuf::from(&output_folder),
+                );
+                Some(match self.disk_writes.clone() {
+                    Some(writes) => ThrottledStorageFactory::new(synced, writes).boxed(),
+                    None => synced.boxed(),
+                })
+            }
+            StorageKind::Disk => None,
+        };
         let add = build_add_torrent(&magnet)?;
-        let response = self
-            .api
-            .api_add_torrent(
-                add,
-                Some(AddTorrentOptions {
-                    paused: true,
-                    only_files: Some(only_files),
-                    output_folder: Some(output_folder),
-                    overwrite: true,
-                    ..Default::default()
-                }),
-            )
-            .await
-            .context("error adding torrent")?;
-        if response.id.is_none() {
+        let adding = self.backend.add_torrent(
+            add,
+            AddTorrentOptions {
+                paused: true,
+                only_files: Some(only_files),
+                output_folder: Some(output_folder),
+                overwrite: true,
+                storage_factory,
+                ..Default::default()
+            },
+        );
+        let secs = self.config.timeouts.add_secs;
+        let response = cancellable(&self.effect_cancel.token(), async {
+            with_timeout(secs, adding)
+                .await
+                .map_err(NetworkError::wrap("error adding torrent"))
+        })
+        .await?;
+        let Some(id) = response.id else {
             return Err(anyhow!("torrent was not added"));
+        };
+        let details = self
+            .backend
+            .torrent_details(id)
+            .context("error verifying file selection")?;
+        let files = details
+            .files
+            .ok_or_else(|| anyhow!("torrent details missing files"))?;
+        let actual: HashSet<usize> = files
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, file)| if file.included { Some(idx) } else { None })
+            .collect();
+        if actual != expected {
+            let _ = self.backend.delete(id).await;
+            return Err(anyhow!(
+                "File selection was not honored; torrent was removed"
+            ));
         }
-        if let Some(id) = response.id {
-            let details = self
-                .api
-                .api_torrent_details(id.into())
-                .context("error verifying file selection")?;
-            let files = details
-                .files
-                .ok_or_else(|| anyhow!("torrent details missing files"))?;
-            let actual: HashSet<usize> = files
-                .into_iter()
-                .enumerate()
-                .filter_map(|(idx, file)| if file.included { Some(idx) } else { None })
-                .collect();
-            if actual != expected {
-                let _ = self.api.api_torrent_action_delete(id.into()).await;
-                return Err(anyhow!(
-                    "File selection was not honored; torrent was removed"
-                ));
-            }
-            self.api
-                .api_torrent_action_start(id.into())
-                .await
-                .context("error starting torrent")?;
+        // Only a verified add gets metadata, a log entry and an Added event.
+        let added = format!(
+            "Added to {} ({source}, {})",
+            response.details.output_folder,
+            storage.label()
+        );
+        let network_warning = network.map(|fstype| {
+            format!(
+                "{} is on {fstype}; sparse/preallocated writes may be slow or unreliable",
+                response.details.output_folder
+            )
+        });
+        let source_label = source.to_string();
+        self.meta.update(&response.details.info_hash, |m| {
+            m.label = label;
+            m.added_at = Some(chrono::Local::now());
+            m.source = Some(source);
+            m.storage = storage;
+        })?;
+        self.torrent_logs.push(&response.details.info_hash, added);
+        self.bus.publish(SessionEvent::Added {
+            info_hash: response.details.info_hash.clone(),
+            name: response
+                .details
+                .name
+                .clone()
+                .unwrap_or_else(|| response.details.info_hash.clone()),
+            source: source_label,
+        });
+        self.backend
+            .start(id)
+            .await
+            .context("error starting torrent")?;
+        self.status = match network_warning {
+            Some(warning) => {
+                self.activity.push(format!("Network storage: {warning}"));
+                format!("Torrent added; {warning}")
+            }
+            None => "Torrent added".to_string(),
+        };
+        Ok(())
+    }
+
+    fn inspect_selected(&mut self) -> Result<()> {
+        let Some(t) = self.selected_torrent() else {
+            return Ok(());
+        };
+        let details = self
+            .backend
+            .torrent_details(t.id)
+            .context("error fetching torrent details")?;
+        let meta = t.info_hash.as_deref().and_then(|h| self.meta.get(h));
+        let root = serde_json::json!({
+            "details": details,
+            "stats": t.stats,
+            "local": meta,
+        });
+        let name = t.name.clone();
+        match &mut self.inspector {
+            Some(inspector) => inspector.set_root(root),
+            None => self.inspector = Some(JsonInspector::new(name, root)),
         }
-        self.status = "Torrent added".to_string();
+        self.dialog = Dialog::Inspector;
         Ok(())
     }
 
+    /// Drops in-memory previews so the persisted session never reloads them onto disk.
+    /// Returns how many there were.
+    pub async fn forget_previews(&mut self) -> usize {
+        let previews: Vec<usize> = self
+            .torrents
+            .iter()
+            .filter(|t| self.storage_of(t) == StorageKind::Memory)
+            .map(|t| t.id)
+            .collect();
+        for &id in &previews {
+            let _ = self.backend.forget(id).await;
+        }
+        previews.len()
+    }
+
+    /// Torrents that can be paused right now; librqbit refuses while initializing.
+    /// Force-started torrents are left alone.
+    fn active_ids(&self) -> Vec<usize> {
+        self.torrents
+            .iter()
+            .filter(|t| {
+                t.stats
+                    .as_ref()
+                    .is_some_and(|s| matches!(s.state, librqbit::TorrentStatsState::Live))
+                    && !self.is_forced(t)
+            })
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Pauses every one of `ids`, going on past failures, which are reported together.
+    /// Returns how many paused.
+    async fn pause_ids(&self, ids: &[usize]) -> Result<usize> {
+        let mut paused = 0;
+        let mut failed = Vec::new();
+        for &id in ids {
+            match self.backend.pause(id).await {
+                Ok(()) => paused += 1,
+                Err(err) => failed.push(err),
+            }
+        }
+        match failed.first() {
+            Some(err) => bail!(
+                "error pausing {} of {} torrents: {err:#}",
+                failed.len(),
+                ids.len()
+            ),
+            None => Ok(paused),
+        }
+    }
+
+    /// Pauses what is running. The ones that paused are remembered for the resume even
+    /// when another fails, so they aren't left paused for good.
+    async fn power_pause_all(&mut self, reason: String) -> Result<()> {
+        let mut paused = Vec::new();
+        let mut failed = None;
+        for id in self.active_ids() {
+            match self.backend.pause(id).await {
+                Ok(()) => paused.push(id),
+                Err(err) => {
+                    failed.get_or_insert(err);
+                }
+            }
+        }
+        self.activity
+            .push(format!("Paused {} torrents: {reason}", paused.len()));
+        for &id in &paused {
+            self.log_torrent(id, format!("Paused: {reason}"));
+        }
+        // Staggered starts that haven't fired yet resume with everything else.
+        paused.extend(self.start_queue.drain(..).map(|(_, id)| id));
+        self.power_paused_ids = paused;
+        self.power_pause = Some(reason);
+        match failed {
+            Some(err) => Err(err).context("error pausing torrent"),
+            None => Ok(()),
+        }
+    }
+
+    fn power_resume(&mut self) {
+        let ids = std::mem::take(&mut self.power_paused_ids);
+        self.power_pause = None;
+        self.activity.push(format!(
+            "Resuming {} torrents: pause conditions cleared",
+            ids.len()
+        ));
+        for &id in &ids {
+            self.log_torrent(id, "Resuming: pause conditions cleared");
+        }
+        self.schedule_starts(ids);
+    }
+
     async fn toggle_pause(&mut self) -> Result<()> {
         let Some(t) = self.selected_torrent() else {
             return Ok(());
@@ -236,17 +874,23 @@ impl App {
         };
         match stats.state {
             librqbit::TorrentStatsState::Paused => {
-                self.api
-                    .api_torrent_action_start(t.id.into())
+                self.backend
+                    .start(t.id)
                     .await
                     .context("error resuming torrent")?;
                 self.status = "Resumed".to_string();
             }
             librqbit::TorrentStatsState::Live | librqbit::TorrentStatsState::Initializing => {
-                self.api
-                    .api_torrent_action_pause(t.id.into())
+                self.backend
+                    .pause(t.id)
                     .await
                     .context("error pausing torrent")?;
+                // Pausing by hand hands the torrent back to the usual rules.
+                if self.is_forced(t)
+                    && let Some(hash) = t.info_hash.clone()
+                {
+                    self.meta.update(&hash, |m| m.force_started = false)?;
+                }
                 self.status = "Paused".to_string();
             }
             librqbit::TorrentStatsState::Error => {
@@ -256,27 +900,82 @@ impl App {
         Ok(())
     }
 
-    async fn stop_selected(&mut self) -> Result<()> {
+    /// Starts the selected torrent now, skipping any staggered start slot, and keeps it
+    /// running through pause-all, power and load pauses. Pressing it again on a forced
+    /// torrent drops the flag and leaves it running.
+    async fn force_start_selected(&mut self) -> Result<()> {
         let Some(t) = self.selected_torrent() else {
             return Ok(());
         };
-        self.api
-            .api_torrent_action_forget(t.id.into())
-            .await
-            .context("error stopping torrent")?;
-        self.status = "Stopped (forgotten)".to_string();
+        let (id, forced, paused) = (
+            t.id,
+            self.is_forced(t),
+            t.stats
+                .as_ref()
+                .is_some_and(|s| matches!(s.state, librqbit::TorrentStatsState::Paused)),
+        );
+        let Some(hash) = t.info_hash.clone() else {
+            self.show_toast("Cannot force start: metadata not loaded yet".to_string());
+            return Ok(());
+        };
+        if forced {
+            self.meta.update(&hash, |m| m.force_started = false)?;
+            self.log_torrent(id, "Force start cleared");
+            self.show_toast("Force start cleared".to_string());
+            return Ok(());
+        }
+        self.start_queue.retain(|&(_, queued)| queued != id);
+        self.power_paused_ids.retain(|&held| held != id);
+        if paused {
+            self.backend
+                .start(id)
+                .await
+                .context("error force starting torrent")?;
+        }
+        self.meta.update(&hash, |m| m.force_started = true)?;
+        self.log_torrent(id, "Force started");
+        self.show_toast("Force started".to_string());
         Ok(())
     }
 
-    async fn delete_selected_files(&mut self) -> Result<()> {
-        let Some(t) = self.selected_torrent() else {
-            return Ok(());
-        };
-        self.api
-            .api_torrent_action_delete(t.id.into())
-            .await
-            .context("error deleting torrent and files")?;
-        self.status = "Deleted torrent and files".to_string();
+    async fn remove_torrent(&mut self, id: usize, delete_files: bool) -> Result<()> {
+        if delete_files {
+            self.backend
+                .delete(id)
+                .await
+                .context("error deleting torrent and files")?;
+            self.status = "Deleted torrent and files".to_string();
+        } else {
+            self.backend
+                .forget(id)
+                .await
+                .context("error stopping torrent")?;
+            self.status = "Stopped (forgotten)".to_string();
+        }
         Ok(())
     }
 }
+
+/// Where `folder_name` under `base` ends up when it already exists, per `strategy`.
+pub(super) fn collision_path(
+    base: &Path,
+    folder_name: &str,
+    info_hash: &str,
+    strategy: CollisionStrategy,
+) -> Result<PathBuf> {
+    match strategy {
+        CollisionStrategy::Reuse | CollisionStrategy::Prompt => Ok(base.join(folder_name)),
+        CollisionStrategy::AppendHash => {
+            let short_hash: String = info_hash.chars().take(8).collect();
+            let path = base.join(format!("{folder_name}-{short_hash}"));
+            if path.exists() {
+                return Err(anyhow!("Destination folder already exists"));
+            }
+            Ok(path)
+        }
+        CollisionStrategy::AppendNumber => (2..1000)
+            .map(|n| base.join(format!("{folder_name} ({n})")))
+            .find(|path| !path.exists())
+            .ok_or_else(|| anyhow!("Destination folder already exists")),
+    }
+}
@@ -0,0 +1,28 @@
+use librqbit::TorrentStatsState;
+
+use super::state::TorrentRow;
+
+/// Whether a running download is down to its last pieces, where librqbit re-requests
+/// slow in-flight pieces from faster peers ("steals") and cancels the originals.
+///
+/// Piece size is estimated from what this run has verified so far, and end-game starts
+/// once every remaining piece could be in flight with a live peer.
+pub fn in_end_game(t: &TorrentRow) -> bool {
+    let Some(stats) = t.stats.as_ref() else {
+        return false;
+    };
+    let Some(live) = stats.live.as_ref() else {
+        return false;
+    };
+    if !matches!(stats.state, TorrentStatsState::Live) || stats.finished {
+        return false;
+    }
+    let snapshot = &live.snapshot;
+    if snapshot.downloaded_and_checked_pieces == 0 {
+        return false;
+    }
+    let piece = (snapshot.downloaded_and_checked_bytes / snapshot.downloaded_and_checked_pieces)
+        .max(1);
+    let remaining = stats.total_bytes.saturating_sub(stats.progress_bytes);
+    remaining.div_ceil(piece) <= snapshot.peer_stats.live as u64
+}
@@ -0,0 +1,95 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use librqbit::TorrentStatsState;
+
+use super::state::{App, TorrentRow};
+
+/// Weight of the newest sample in the smoothed download rate.
+const SMOOTHING: f64 = 0.2;
+
+/// Exponentially smoothed download rate per torrent id, fed from refreshes.
+#[derive(Debug, Default)]
+pub struct RateTracker {
+    /// Last sample time, progress bytes at that time, smoothed bytes per second.
+    samples: HashMap<usize, (Instant, u64, f64)>,
+}
+
+impl RateTracker {
+    pub fn update(&mut self, rows: &[TorrentRow], now: Instant) {
+        let mut next = HashMap::with_capacity(rows.len());
+        for t in rows {
+            let Some(stats) = t.stats.as_ref() else {
+                continue;
+            };
+            let rate = match self.samples.get(&t.id) {
+                Some(&(at, bytes, rate)) => {
+                    let secs = now.duration_since(at).as_secs_f64();
+                    if secs <= 0.0 {
+                        continue;
+                    }
+                    let sample = stats.progress_bytes.saturating_sub(bytes) as f64 / secs;
+                    rate + SMOOTHING * (sample - rate)
+                }
+                // Seed from librqbit's own estimate so the first ETA isn't empty.
+                None => stats
+                    .live
+                    .as_ref()
+                    .map_or(0.0, |l| l.download_speed.mbps * 1024.0 * 1024.0),
+            };
+            next.insert(t.id, (now, stats.progress_bytes, rate));
+        }
+        self.samples = next;
+    }
+
+    pub fn rate(&self, id: usize) -> Option<f64> {
+        self.samples.get(&id).map(|&(_, _, rate)| rate)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    pub name: String,
+    pub remaining: u64,
+    /// Smoothed bytes per second.
+    pub rate: f64,
+    /// None while stalled.
+    pub eta: Option<Duration>,
+}
+
+impl App {
+    /// Running downloads sorted by projected finish (stalled ones last), plus when all of
+    /// them should be done assuming the combined rate holds as bandwidth shifts to the rest.
+    pub fn timeline(&self) -> (Vec<TimelineEntry>, Option<Duration>) {
+        let mut entries: Vec<TimelineEntry> = self
+            .torrents
+            .iter()
+            .filter_map(|t| {
+                let stats = t.stats.as_ref()?;
+                if !matches!(stats.state, TorrentStatsState::Live) || stats.finished {
+                    return None;
+                }
+                let remaining = stats.total_bytes.saturating_sub(stats.progress_bytes);
+                if remaining == 0 {
+                    return None;
+                }
+                let rate = self.rates.rate(t.id).unwrap_or(0.0);
+                let eta = (rate >= 1.0).then(|| Duration::from_secs_f64(remaining as f64 / rate));
+                Some(TimelineEntry {
+                    name: t.name.clone(),
+                    remaining,
+                    rate,
+                    eta,
+                })
+            })
+            .collect();
+        entries.sort_by_key(|e| (e.eta.is_none(), e.eta));
+        let remaining: u64 = entries.iter().map(|e| e.remaining).sum();
+        let rate: f64 = entries.iter().map(|e| e.rate).sum();
+        let all_done = (!entries.is_empty() && entries.iter().all(|e| e.eta.is_some()))
+            .then(|| Duration::from_secs_f64(remaining as f64 / rate));
+        (entries, all_done)
+    }
+}
@@ -0,0 +1,212 @@
+use std::process::Command;
+
+use anyhow::{Context, Result, anyhow, bail};
+use tokio::task::JoinHandle;
+
+use crate::config::HookEvent;
+
+use super::{
+    activity::Automation,
+    bus::{SessionEvent, StampedEvent},
+    state::App,
+};
+
+/// A `[[hooks]]` command or email started for an event.
+#[derive(Debug)]
+pub struct HookRun {
+    /// What was started, for the log: the command, or who is mailed.
+    action: String,
+    /// Position of the rule in `[[hooks]]`, counting from 1.
+    rule: usize,
+    event: &'static str,
+    info_hash: Option<String>,
+    task: JoinHandle<Result<()>>,
+}
+
+/// Which hook event `event` is, for matching against a rule's `on` list.
+fn hook_event(event: &SessionEvent) -> Option<HookEvent> {
+    Some(match event {
+        SessionEvent::Added { .. } => HookEvent::Added,
+        SessionEvent::Progress { percent: 25, .. } => HookEvent::Progress25,
+        SessionEvent::Progress { percent: 50, .. } => HookEvent::Progress50,
+        SessionEvent::Progress { percent: 75, .. } => HookEvent::Progress75,
+        SessionEvent::Progress { .. } => return None,
+        SessionEvent::Completed { .. } => HookEvent::Completed,
+        SessionEvent::FirstPeer { .. } => HookEvent::FirstPeer,
+        SessionEvent::Error { .. } => HookEvent::Error,
+        SessionEvent::Alert { .. } => HookEvent::Alert,
+    })
+}
+
+fn event_name(event: HookEvent) -> &'static str {
+    match event {
+        HookEvent::Added => "added",
+        HookEvent::Progress25 => "progress_25",
+        HookEvent::Progress50 => "progress_50",
+        HookEvent::Progress75 => "progress_75",
+        HookEvent::Completed => "completed",
+        HookEvent::FirstPeer => "first_peer",
+        HookEvent::Error => "error",
+        HookEvent::Alert => "alert",
+    }
+}
+
+impl App {
+    /// Starts the hooks for events published since the last tick and records how the finished
+    /// ones went.
+    pub(super) async fn poll_event_hooks(&mut self) {
+        let events: Vec<StampedEvent> = self.bus.drain_hooks().collect();
+        for stamped in events {
+            let Some(kind) = hook_event(&stamped.event) else {
+                continue;
+            };
+            let info_hash = match &stamped.event {
+                SessionEvent::Added { info_hash, .. }
+                | SessionEvent::Progress { info_hash, .. }
+                | SessionEvent::Completed { info_hash, .. }
+                | SessionEvent::FirstPeer { info_hash, .. } => Some(info_hash.clone()),
+                SessionEvent::Error { info_hash, .. } => info_hash.clone(),
+                SessionEvent::Alert { .. } => None,
+            };
+            let env = event_env(&stamped);
+            let rules = self.config.hooks.iter().enumerate();
+            for (idx, rule) in rules.filter(|(_, r)| r.on.contains(&kind)) {
+                if let Some(command) = rule.command.clone() {
+                    let env = env.clone();
+                    self.hook_runs.push(HookRun {
+                        action: command.clone(),
+                        rule: idx + 1,
+                        event: event_name(kind),
+                        info_hash: info_hash.clone(),
+                        task: tokio::task::spawn_blocking(move || run_shell(&command, &env)),
+                    });
+                }
+                if rule.email {
+                    let email = self.config.email.clone();
+                    let (subject, body) = (subject(&stamped.event), mail_body(&stamped));
+                    self.hook_runs.push(HookRun {
+                        action: format!("email to {}", email.to.join(", ")),
+                        rule: idx + 1,
+                        event: event_name(kind),
+                        info_hash: info_hash.clone(),
+                        task: tokio::spawn(async move {
+                            crate::smtp::send(&email, &subject, &body).await
+                        }),
+                    });
+                }
+                for &service in &rule.chat {
+                    let chat = self.config.chat.clone();
+                    let text = summary(&stamped.event);
+                    self.hook_runs.push(HookRun {
+                        action: format!("{} message", crate::chat::service_name(service)),
+                        rule: idx + 1,
+                        event: event_name(kind),
+                        info_hash: info_hash.clone(),
+                        task: tokio::spawn(async move {
+                            crate::chat::send(&chat, service, &text).await
+                        }),
+                    });
+                }
+            }
+        }
+        if self.hook_runs.iter().all(|r| !r.task.is_finished()) {
+            return;
+        }
+        let (done, running): (Vec<_>, Vec<_>) = std::mem::take(&mut self.hook_runs)
+            .into_iter()
+            .partition(|r| r.task.is_finished());
+        self.hook_runs = running;
+        for run in done {
+            let result = match run.task.await {
+                Ok(result) => result,
+                Err(err) => Err(anyhow!("hook stopped: {err}")),
+            };
+            let message = match &result {
+                Ok(()) => format!("Hook ({}): {} done", run.event, run.action),
+                Err(err) => format!("Hook ({}): {} failed: {err:#}", run.event, run.action),
+            };
+            let rule = format!("hooks #{}", run.rule);
+            self.activity.record(Automation::Hook, rule, result.is_ok(), message.clone());
+            if let Some(hash) = run.info_hash {
+                self.torrent_logs.push(&hash, message);
+            }
+        }
+    }
+}
+
+fn subject(event: &SessionEvent) -> String {
+    match event {
+        SessionEvent::Added { name, .. } => format!("Added: {name}"),
+        SessionEvent::Progress { name, percent, .. } => format!("{percent}%: {name}"),
+        SessionEvent::Completed { name, .. } => format!("Completed: {name}"),
+        SessionEvent::FirstPeer { name, .. } => format!("First peer: {name}"),
+        SessionEvent::Error { name: Some(name), .. } => format!("Error: {name}"),
+        SessionEvent::Error { .. } => "Error".to_string(),
+        SessionEvent::Alert { name, .. } => format!("Alert: {name}"),
+    }
+}
+
+/// The subject with an error's message, for a one-line chat post.
+fn summary(event: &SessionEvent) -> String {
+    match event {
+        SessionEvent::Error { message, .. } => format!("{}: {message}", subject(event)),
+        SessionEvent::Alert { value, .. } => format!("{} ({value})", subject(event)),
+        _ => subject(event),
+    }
+}
+
+/// One `key: value` line per field of the event.
+fn mail_body(stamped: &StampedEvent) -> String {
+    event_env(stamped)
+        .into_iter()
+        .filter(|(key, _)| key != "IB_EVENT_JSON")
+        .map(|(key, value)| format!("{}: {value}\n", key["IB_".len()..].to_lowercase()))
+        .collect()
+}
+
+/// The event's fields as `IB_*` variables (`IB_EVENT`, `IB_NAME`, `IB_INFO_HASH`,
+/// `IB_PERCENT`, ...), plus the whole event as `IB_EVENT_JSON`.
+fn event_env(stamped: &StampedEvent) -> Vec<(String, String)> {
+    let mut env = Vec::new();
+    if let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(stamped) {
+        for (key, value) in fields {
+            let value = match value {
+                serde_json::Value::String(text) => text,
+                other => other.to_string(),
+            };
+            env.push((format!("IB_{}", key.to_uppercase()), value));
+        }
+    }
+    if let Ok(json) = serde_json::to_string(stamped) {
+        env.push(("IB_EVENT_JSON".to_string(), json));
+    }
+    env
+}
+
+fn run_shell(command: &str, env: &[(String, String)]) -> Result<()> {
+    #[cfg(unix)]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    #[cfg(not(unix))]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+    let output = shell
+        .arg(command)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .output()
+        .context("failed to start")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.trim().lines().last() {
+            Some(line) => bail!("{}: {line}", output.status),
+            None => bail!("{}", output.status),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use super::{
+    state::{App, View},
+    util::open_with_system,
+};
+
+/// One file of the torrent shown in the Files view.
+#[derive(Debug, Clone)]
+pub struct FileLine {
+    /// Index in the torrent's file list.
+    pub index: usize,
+    pub path: String,
+    pub len: u64,
+    pub have: u64,
+    pub included: bool,
+}
+
+impl App {
+    /// Reads the selected torrent's files while the Files view is open, keeping the cursor
+    /// on the same torrent between refreshes.
+    pub(super) fn sample_files(&mut self) {
+        let selected = self
+            .selected_torrent()
+            .map(|t| (t.id, t.stats.as_ref().map(|s| s.file_progress.clone())));
+        let Some((id, have)) = selected.filter(|_| self.view == View::Files) else {
+            self.files_torrent = None;
+            self.files_pick_largest = false;
+            self.file_lines.clear();
+            return;
+        };
+        if self.files_torrent != Some(id) {
+            self.files_torrent = Some(id);
+            self.file_selected = 0;
+        }
+        let Ok(details) = self.backend.torrent_details(id) else {
+            self.file_lines.clear();
+            return;
+        };
+        let have = have.unwrap_or_default();
+        self.file_lines = details
+            .files
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, f)| !f.attributes.padding)
+            .map(|(index, f)| FileLine {
+                index,
+                path: f.components.join("/"),
+                len: f.length,
+                have: have.get(index).copied().unwrap_or(0),
+                included: f.included,
+            })
+            .collect();
+        if self.files_pick_largest && !self.file_lines.is_empty() {
+            self.files_pick_largest = false;
+            self.file_selected = (0..self.file_lines.len())
+                .max_by_key(|&idx| self.file_lines[idx].len)
+                .unwrap_or(0);
+        }
+        self.move_file_selection(0);
+    }
+
+    /// Switches to the Files view of the selected torrent with its largest file
+    /// highlighted, usually the one to watch or listen to.
+    pub(super) fn open_files_view(&mut self) {
+        if self.selected_group().is_some() || self.selected_torrent().is_none() {
+            return;
+        }
+        self.view = View::Files;
+        self.files_torrent = None;
+        self.files_pick_largest = true;
+        self.sample_files();
+    }
+
+    /// Opens the highlighted file with the system's default application, once it has
+    /// finished downloading.
+    pub(super) fn open_selected_file(&mut self) -> Result<()> {
+        let (Some(id), Some(file)) = (self.files_torrent, self.file_lines.get(self.file_selected))
+        else {
+            bail!("no file selected");
+        };
+        if !file.included || file.have < file.len {
+            bail!("{} hasn't finished downloading", file.path);
+        }
+        let Some(t) = self.torrents.iter().find(|t| t.id == id) else {
+            bail!("torrent {id} is gone");
+        };
+        let path = Path::new(&t.output_folder).join(&file.path);
+        open_with_system(&path)?;
+        self.show_toast(format!("Opening {}", file.path));
+        Ok(())
+    }
+
+    pub(super) fn move_file_selection(&mut self, delta: isize) {
+        self.file_selected = self
+            .file_selected
+            .saturating_add_signed(delta)
+            .min(self.file_lines.len().saturating_sub(1));
+    }
+
+    /// Copies the stream URL of the highlighted file, starting the stream server first if
+    /// this is the first one.
+    pub(super) async fn copy_stream_url(&mut self) -> Result<()> {
+        let (Some(id), Some(file)) = (self.files_torrent, self.file_lines.get(self.file_selected))
+        else {
+            bail!("no file selected");
+        };
+        let (index, path, included) = (file.index, file.path.clone(), file.included);
+        if !included {
+            bail!("{path} is not selected for download");
+        }
+        let (addr, token) = self.ensure_stream_server().await?;
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        let url = crate::stream::url(
+            addr,
+            self.config.stream.host.as_deref(),
+            id,
+            index,
+            name,
+            token.as_deref(),
+        );
+        crate::tui::copy_to_clipboard(&url)?;
+        self.show_toast(format!("Copied {}", self.masked(&url)));
+        Ok(())
+    }
+
+    /// The stream server's address, starting it on first use, and the token its URLs
+    /// need when `[auth] stream` is on or it listens beyond loopback.
+    pub(super) async fn ensure_stream_server(
+        &mut self,
+    ) -> Result<(std::net::SocketAddr, Option<String>)> {
+        let listen = &self.config.stream.listen;
+        let token = if self.config.auth.stream || !crate::stream::is_loopback(listen) {
+            Some(crate::auth::load_or_create_token(&self.config.auth)?.0)
+        } else {
+            None
+        };
+        if let Some(addr) = self.stream_addr {
+            return Ok((addr, token));
+        }
+        let addr = crate::stream::serve(
+            &self.config.stream.listen,
+            self.backend.clone(),
+            token.clone(),
+        )
+        .await
+        .context("failed to start the stream server")?;
+        self.activity.push(format!("Stream server listening on {addr}"));
+        self.stream_addr = Some(addr);
+        Ok((addr, token))
+    }
+}
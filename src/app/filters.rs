@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+use super::state::{App, FILTERS, FilterKind, TorrentRow};
+
+/// How many torrents each sidebar filter holds, worked out once per refresh.
+#[derive(Debug, Clone, Default)]
+pub struct FilterSummary {
+    /// One count per entry of [`FILTERS`], in the same order.
+    pub kinds: [usize; FILTERS.len()],
+    /// Labels in use or configured, by name, with how many torrents carry each.
+    pub labels: Vec<(String, usize)>,
+}
+
+impl FilterSummary {
+    pub fn count(&self, kind: FilterKind) -> usize {
+        FILTERS
+            .iter()
+            .position(|&k| k == kind)
+            .map_or(0, |idx| self.kinds[idx])
+    }
+}
+
+impl App {
+    pub(super) fn summarize_filters(&mut self) {
+        let mut summary = FilterSummary::default();
+        let mut labels: BTreeMap<String, usize> = self
+            .config
+            .labels
+            .keys()
+            .map(|name| (name.clone(), 0))
+            .collect();
+        for t in &self.torrents {
+            for (idx, &kind) in FILTERS.iter().enumerate() {
+                if self.matches_kind(t, kind) {
+                    summary.kinds[idx] += 1;
+                }
+            }
+            if let Some(label) = self.label_of(t) {
+                *labels.entry(label.to_string()).or_default() += 1;
+            }
+        }
+        summary.labels = labels.into_iter().collect();
+        self.filter_summary = summary;
+        if self.filter_index >= self.filter_rows() {
+            self.filter_index = 0;
+        }
+    }
+
+    /// Whether `t` belongs under `kind`, leaving out the source filter. Torrents without stats
+    /// are the stopped ones: the session no longer runs them.
+    pub(super) fn matches_kind(&self, t: &TorrentRow, kind: FilterKind) -> bool {
+        use FilterKind::*;
+        use librqbit::TorrentStatsState as S;
+        let Some(stats) = t.stats.as_ref() else {
+            return matches!(kind, All | Stopped);
+        };
+        let is_seeding = stats.finished
+            || (stats.total_bytes > 0
+                && stats.progress_bytes >= stats.total_bytes
+                && matches!(stats.state, S::Live));
+        match kind {
+            All => true,
+            Downloading => matches!(stats.state, S::Live) && !stats.finished,
+            Seeding => is_seeding,
+            Paused => matches!(stats.state, S::Paused),
+            Stopped => false,
+            Error => matches!(stats.state, S::Error),
+            Idle => self.is_idle_seed(t),
+        }
+    }
+
+    /// The label picked in the sidebar, when the cursor is past the state filters.
+    pub fn selected_label_filter(&self) -> Option<&str> {
+        let idx = self.filter_index.checked_sub(FILTERS.len())?;
+        self.filter_summary.labels.get(idx).map(|(name, _)| name.as_str())
+    }
+
+    /// How many rows the sidebar's filter list has: the state filters, then the labels.
+    pub(super) fn filter_rows(&self) -> usize {
+        FILTERS.len() + self.filter_summary.labels.len()
+    }
+
+    pub fn filter_summary(&self) -> &FilterSummary {
+        &self.filter_summary
+    }
+}
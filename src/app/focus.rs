@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use super::state::App;
+
+impl App {
+    /// Follows terminal focus reports. Coming back refreshes right away, so the screen
+    /// doesn't show stats from the slow background rate.
+    pub(super) fn set_focused(&mut self, focused: bool) {
+        if focused && !self.focused {
+            self.last_refresh = None;
+        }
+        self.focused = focused;
+    }
+
+    /// `[ui] unfocused_ms` while the terminal reports it lost focus, when that's slower.
+    fn background_interval(&self, interval: Duration) -> Duration {
+        let floor = self.config.ui.unfocused_ms;
+        if self.focused || floor == 0 {
+            interval
+        } else {
+            interval.max(Duration::from_millis(floor))
+        }
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        self.background_interval(self.tick_interval)
+    }
+
+    pub(super) fn refresh_interval(&self) -> Duration {
+        self.background_interval(self.refresh_interval)
+    }
+
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+}
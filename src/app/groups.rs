@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use librqbit::TorrentStatsState;
+
+use super::state::{App, TorrentRow};
+
+/// How the torrent table is split into sections.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    #[default]
+    Off,
+    State,
+    Label,
+    Tracker,
+}
+
+impl GroupBy {
+    pub fn next(self) -> Self {
+        match self {
+            GroupBy::Off => GroupBy::State,
+            GroupBy::State => GroupBy::Label,
+            GroupBy::Label => GroupBy::Tracker,
+            GroupBy::Tracker => GroupBy::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupBy::Off => "off",
+            GroupBy::State => "state",
+            GroupBy::Label => "label",
+            GroupBy::Tracker => "tracker",
+        }
+    }
+}
+
+/// State sections in display order.
+const STATE_GROUPS: [&str; 6] = [
+    "Downloading",
+    "Seeding",
+    "Checking",
+    "Paused",
+    "Error",
+    "Stopped",
+];
+
+/// One line of the torrent table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListEntry {
+    Header {
+        name: String,
+        count: usize,
+        collapsed: bool,
+    },
+    /// Index into `torrents()`.
+    Torrent(usize),
+}
+
+impl App {
+    pub fn group_by(&self) -> GroupBy {
+        self.group_by
+    }
+
+    /// Section header under the cursor, when one is selected instead of a torrent.
+    pub fn selected_group(&self) -> Option<&str> {
+        self.selected_group.as_deref()
+    }
+
+    /// Rows of the torrent table in the current filter: just the torrents when ungrouped,
+    /// otherwise section headers each followed by their torrents unless collapsed.
+    pub fn list_entries(&self) -> Vec<ListEntry> {
+        let indices = self.filtered_indices();
+        if self.group_by == GroupBy::Off {
+            return indices.into_iter().map(ListEntry::Torrent).collect();
+        }
+        let mut groups: BTreeMap<(usize, String), Vec<usize>> = BTreeMap::new();
+        for idx in indices {
+            let name = self.group_of(&self.torrents[idx]);
+            let rank = match self.group_by {
+                GroupBy::State => STATE_GROUPS.iter().position(|g| *g == name).unwrap_or(0),
+                // Ungrouped torrents go last.
+                _ => usize::from(name.starts_with('(')),
+            };
+            groups.entry((rank, name)).or_default().push(idx);
+        }
+        let mut entries = Vec::new();
+        for ((_, name), members) in groups {
+            let collapsed = self.collapsed_groups.contains(&name);
+            entries.push(ListEntry::Header {
+                name,
+                count: members.len(),
+                collapsed,
+            });
+            if !collapsed {
+                entries.extend(members.into_iter().map(ListEntry::Torrent));
+            }
+        }
+        entries
+    }
+
+    pub(super) fn cycle_group_by(&mut self) {
+        self.group_by = self.group_by.next();
+        self.collapsed_groups.clear();
+        self.selected_group = None;
+        self.ensure_selection_for_filter();
+        self.status = format!("Grouping: {}", self.group_by.label());
+    }
+
+    /// Collapses or expands the section under the cursor; collapsing from one of its torrents
+    /// moves the cursor onto the header.
+    pub(super) fn toggle_group(&mut self) {
+        if self.group_by == GroupBy::Off {
+            return;
+        }
+        let name = match self.selected_group.clone() {
+            Some(name) => name,
+            None => match self.selected_torrent() {
+                Some(t) => self.group_of(t),
+                None => return,
+            },
+        };
+        if !self.collapsed_groups.remove(&name) {
+            self.collapsed_groups.insert(name.clone());
+            self.selected_group = Some(name);
+        }
+    }
+
+    pub(super) fn move_grouped_selection(&mut self, delta: isize) {
+        let entries = self.list_entries();
+        if entries.is_empty() {
+            return;
+        }
+        let current = self.cursor_position(&entries).unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, entries.len() as isize - 1) as usize;
+        self.set_cursor(&entries[next]);
+    }
+
+    /// Keeps the cursor on a visible row after the list or the collapsed set changes.
+    pub(super) fn ensure_group_cursor(&mut self) {
+        if self.group_by == GroupBy::Off {
+            self.selected_group = None;
+            return;
+        }
+        let entries = self.list_entries();
+        if self.cursor_position(&entries).is_some() {
+            return;
+        }
+        // The selected torrent's section is collapsed, or its header is gone.
+        let fallback = self
+            .torrents
+            .get(self.selected)
+            .map(|t| self.group_of(t))
+            .and_then(|name| {
+                entries
+                    .iter()
+                    .find(|e| matches!(e, ListEntry::Header { name: n, .. } if *n == name))
+            })
+            .or(entries.first())
+            .cloned();
+        match fallback {
+            Some(entry) => self.set_cursor(&entry),
+            None => self.selected_group = None,
+        }
+    }
+
+    fn cursor_position(&self, entries: &[ListEntry]) -> Option<usize> {
+        entries.iter().position(|e| match (e, self.selected_group.as_deref()) {
+            (ListEntry::Header { name, .. }, Some(selected)) => name == selected,
+            (ListEntry::Torrent(idx), None) => *idx == self.selected,
+            _ => false,
+        })
+    }
+
+    fn set_cursor(&mut self, entry: &ListEntry) {
+        match entry {
+            ListEntry::Header { name, .. } => self.selected_group = Some(name.clone()),
+            ListEntry::Torrent(idx) => {
+                self.selected = *idx;
+                self.selected_group = None;
+            }
+        }
+    }
+
+    pub(super) fn group_of(&self, t: &TorrentRow) -> String {
+        match self.group_by {
+            GroupBy::Off => String::new(),
+            GroupBy::State => state_group(t).to_string(),
+            GroupBy::Label => self
+                .label_of(t)
+                .map_or_else(|| "(no label)".to_string(), str::to_string),
+            GroupBy::Tracker => t
+                .trackers
+                .iter()
+                .filter_map(|url| tracker_host(url))
+                .min()
+                .map_or_else(|| "(no tracker)".to_string(), str::to_string),
+        }
+    }
+}
+
+fn state_group(t: &TorrentRow) -> &'static str {
+    let Some(stats) = t.stats.as_ref() else {
+        return "Stopped";
+    };
+    match stats.state {
+        TorrentStatsState::Error => "Error",
+        TorrentStatsState::Paused => "Paused",
+        TorrentStatsState::Initializing => "Checking",
+        TorrentStatsState::Live
+            if stats.finished
+                || (stats.total_bytes > 0 && stats.progress_bytes >= stats.total_bytes) =>
+        {
+            "Seeding"
+        }
+        TorrentStatsState::Live => "Downloading",
+    }
+}
+
+/// `tracker.example.org` from `udp://tracker.example.org:1337/announce`.
+pub(super) fn tracker_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?']).next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
@@ -0,0 +1,287 @@
+use std::path::{Path, PathBuf};
+
+use tokio::task::JoinHandle;
+
+use crate::config::CompletionConfig;
+
+use super::{activity::Automation, bus::SessionEvent, state::App};
+
+pub(super) const MAX_LOGGED_ERRORS: usize = 5;
+
+/// A finished torrent's permission changes and library links, walked on a blocking thread
+/// so a large tree doesn't hold up the UI. Its `move_to` waits until they're done.
+#[derive(Debug)]
+pub struct CompletionTask {
+    id: usize,
+    name: String,
+    move_to: Option<String>,
+    task: JoinHandle<CompletionReport>,
+}
+
+#[derive(Debug, Default)]
+struct CompletionReport {
+    /// Paths adjusted and the errors, when permissions were adjusted.
+    permissions: Option<(usize, Vec<String>)>,
+    /// Where the files were linked, how many and the errors, with `library_dir` set.
+    library: Option<(PathBuf, usize, Vec<String>)>,
+}
+
+impl App {
+    /// Returns indices into `self.torrents` of torrents that turned finished since the
+    /// last refresh. Torrents seen for the first time are only recorded, so nothing fires
+    /// for payloads that were already complete at startup.
+    pub(super) fn detect_completions(&mut self) -> Vec<usize> {
+        let mut completed = Vec::new();
+        for (idx, t) in self.torrents.iter().enumerate() {
+            let (Some(hash), Some(stats)) = (t.info_hash.as_ref(), t.stats.as_ref()) else {
+                continue;
+            };
+            let previous = self.finished_state.insert(hash.clone(), stats.finished);
+            if previous == Some(false) && stats.finished {
+                completed.push(idx);
+            }
+        }
+        completed
+    }
+
+    pub(super) fn on_torrent_completed(&mut self, idx: usize) {
+        let Some(t) = self.torrents.get(idx) else {
+            return;
+        };
+        let id = t.id;
+        let name = t.name.clone();
+        let output_folder = t.output_folder.clone();
+        let hash = t.info_hash.clone();
+        let move_rule = self.move_rule(self.label_of(t));
+        if let Some(hash) = hash.as_deref()
+            && let Err(err) = self
+                .meta
+                .update(hash, |m| m.completed_at = Some(chrono::Local::now()))
+        {
+            self.activity.push(format!("Metadata: {err:#}"));
+        }
+        self.activity.push(format!("Completed: {name}"));
+        self.notify_completed(&name);
+        self.log_torrent(id, "Completed");
+        if let Some(info_hash) = hash {
+            self.bus.publish(SessionEvent::Completed {
+                info_hash,
+                name: name.clone(),
+            });
+        }
+        let move_to = match self.completed_move_target(idx) {
+            Ok(target) => target.map(|target| target.to_string_lossy().into_owned()),
+            Err(err) => {
+                self.activity
+                    .record(Automation::Move, move_rule, false, format!("Move: {err:#}"));
+                self.log_torrent(id, format!("Move: {err:#}"));
+                None
+            }
+        };
+        let simulated = self.backend.is_simulated();
+        let permissions = (!simulated && self.config.completion.adjusts_permissions())
+            .then(|| self.config.completion.clone());
+        let root = PathBuf::from(&output_folder);
+        let library = self
+            .config
+            .completion
+            .library_dir
+            .clone()
+            .filter(|_| !simulated)
+            .map(|library| {
+                let folder = root
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| name.clone().into());
+                library.join(folder)
+            });
+        if permissions.is_none() && library.is_none() {
+            self.completed_moves.extend(move_to.map(|target| (id, target)));
+        } else {
+            let task = tokio::task::spawn_blocking(move || CompletionReport {
+                permissions: permissions.map(|cfg| adjust_permissions(&root, &cfg)),
+                library: library.map(|target| {
+                    let (linked, errors) = hard_link_tree(&root, &target);
+                    (target, linked, errors)
+                }),
+            });
+            self.completion_tasks.push(CompletionTask {
+                id,
+                name: name.clone(),
+                move_to,
+                task,
+            });
+        }
+    }
+
+    /// Reports finished completion tasks and queues the moves that waited on them.
+    pub(super) async fn poll_completions(&mut self) {
+        if self.completion_tasks.iter().all(|c| !c.task.is_finished()) {
+            return;
+        }
+        let (done, running): (Vec<_>, Vec<_>) = std::mem::take(&mut self.completion_tasks)
+            .into_iter()
+            .partition(|c| c.task.is_finished());
+        self.completion_tasks = running;
+        for completion in done {
+            let CompletionTask {
+                id,
+                name,
+                move_to,
+                task,
+            } = completion;
+            let report = task.await.unwrap_or_default();
+            if let Some((adjusted, errors)) = report.permissions {
+                self.activity.record(
+                    Automation::Permissions,
+                    "completion permissions",
+                    errors.is_empty(),
+                    format!(
+                        "Permissions: adjusted {adjusted} paths for {name} ({} errors)",
+                        errors.len()
+                    ),
+                );
+                self.log_torrent(
+                    id,
+                    format!("Permissions: adjusted {adjusted} paths ({} errors)", errors.len()),
+                );
+                for err in errors.iter().take(MAX_LOGGED_ERRORS) {
+                    self.activity.record(
+                        Automation::Permissions,
+                        "completion permissions",
+                        false,
+                        format!("Permissions: {err}"),
+                    );
+                    self.log_torrent(id, format!("Permissions: {err}"));
+                }
+            }
+            if let Some((target, linked, errors)) = report.library {
+                self.activity.record(
+                    Automation::Library,
+                    "completion library_dir",
+                    errors.is_empty(),
+                    format!(
+                        "Library: linked {linked} files for {name} into {} ({} errors)",
+                        target.display(),
+                        errors.len()
+                    ),
+                );
+                self.log_torrent(
+                    id,
+                    format!(
+                        "Library: linked {linked} files into {} ({} errors)",
+                        target.display(),
+                        errors.len()
+                    ),
+                );
+                for err in errors.iter().take(MAX_LOGGED_ERRORS) {
+                    self.activity.record(
+                        Automation::Library,
+                        "completion library_dir",
+                        false,
+                        format!("Library: {err}"),
+                    );
+                    self.log_torrent(id, format!("Library: {err}"));
+                }
+            }
+            self.completed_moves.extend(move_to.map(|target| (id, target)));
+        }
+    }
+}
+
+/// Mirrors the directory structure under `root` into `target`, hard-linking every file.
+/// Files that already exist in `target` are left untouched.
+fn hard_link_tree(root: &Path, target: &Path) -> (usize, Vec<String>) {
+    let mut linked = 0;
+    let mut errors = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(path) = stack.pop() {
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let dest = target.join(relative);
+        let meta = match std::fs::symlink_metadata(&path) {
+            Ok(meta) => meta,
+            Err(err) => {
+                errors.push(format!("{}: {err}", path.display()));
+                continue;
+            }
+        };
+        if meta.is_dir() {
+            if let Err(err) = std::fs::create_dir_all(&dest) {
+                errors.push(format!("mkdir {}: {err}", dest.display()));
+                continue;
+            }
+            match std::fs::read_dir(&path) {
+                Ok(entries) => stack.extend(entries.filter_map(|e| e.ok()).map(|e| e.path())),
+                Err(err) => errors.push(format!("{}: {err}", path.display())),
+            }
+        } else if meta.is_file() {
+            if dest.exists() {
+                continue;
+            }
+            match std::fs::hard_link(&path, &dest) {
+                Ok(()) => linked += 1,
+                Err(err) => errors.push(format!("link {}: {err}", dest.display())),
+            }
+        }
+    }
+    (linked, errors)
+}
+
+#[cfg(unix)]
+fn adjust_permissions(root: &Path, cfg: &CompletionConfig) -> (usize, Vec<String>) {
+    use std::os::unix::fs::{PermissionsExt, chown};
+
+    let mut adjusted = 0;
+    let mut errors = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(path) = stack.pop() {
+        let meta = match std::fs::symlink_metadata(&path) {
+            Ok(meta) => meta,
+            Err(err) => {
+                errors.push(format!("{}: {err}", path.display()));
+                continue;
+            }
+        };
+        if meta.file_type().is_symlink() {
+            continue;
+        }
+        let mode = if meta.is_dir() {
+            cfg.dir_mode
+        } else {
+            cfg.file_mode
+        };
+        let mut ok = true;
+        if let Some(mode) = mode
+            && let Err(err) = std::fs::set_permissions(&path, PermissionsExt::from_mode(mode))
+        {
+            errors.push(format!("chmod {}: {err}", path.display()));
+            ok = false;
+        }
+        if (cfg.owner.is_some() || cfg.group.is_some())
+            && let Err(err) = chown(&path, cfg.owner, cfg.group)
+        {
+            errors.push(format!("chown {}: {err}", path.display()));
+            ok = false;
+        }
+        if ok {
+            adjusted += 1;
+        }
+        if meta.is_dir() {
+            match std::fs::read_dir(&path) {
+                Ok(entries) => stack.extend(entries.filter_map(|e| e.ok()).map(|e| e.path())),
+                Err(err) => errors.push(format!("{}: {err}", path.display())),
+            }
+        }
+    }
+    (adjusted, errors)
+}
+
+#[cfg(not(unix))]
+fn adjust_permissions(_root: &Path, _cfg: &CompletionConfig) -> (usize, Vec<String>) {
+    (
+        0,
+        vec!["permission adjustment is only supported on Unix".to_string()],
+    )
+}
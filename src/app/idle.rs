@@ -0,0 +1,48 @@
+use chrono::{DateTime, Local};
+
+use super::state::{App, TorrentRow};
+
+/// Don't rewrite metadata.json more than once a minute per uploading torrent.
+const UPLOAD_STAMP_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+impl App {
+    /// Stamps `last_upload_at` for torrents whose uploaded byte count grew since the last
+    /// refresh. Seeds with no stamp yet start counting from completion (or now).
+    pub(super) fn track_uploads(&mut self) {
+        let now = Local::now();
+        let mut stamps = Vec::new();
+        for t in &self.torrents {
+            let (Some(hash), Some(stats)) = (t.info_hash.as_ref(), t.stats.as_ref()) else {
+                continue;
+            };
+            let before = self.uploads_seen.insert(t.id, stats.uploaded_bytes);
+            let meta = self.meta.get(hash);
+            let last = meta.and_then(|m| m.last_upload_at);
+            let uploaded = before.is_some_and(|b| stats.uploaded_bytes > b);
+            if uploaded && last.is_none_or(|at| now - at >= UPLOAD_STAMP_INTERVAL) {
+                stamps.push((hash.clone(), now));
+            } else if last.is_none() && stats.finished {
+                stamps.push((hash.clone(), meta.and_then(|m| m.completed_at).unwrap_or(now)));
+            }
+        }
+        for (hash, at) in stamps {
+            if let Err(err) = self.meta.update(&hash, |m| m.last_upload_at = Some(at)) {
+                self.activity.push(format!("Metadata: {err:#}"));
+            }
+        }
+    }
+
+    pub fn last_upload_of(&self, t: &TorrentRow) -> Option<DateTime<Local>> {
+        t.info_hash
+            .as_deref()
+            .and_then(|h| self.meta.get(h))
+            .and_then(|m| m.last_upload_at)
+    }
+
+    /// Finished torrents that haven't uploaded anything within `seeding.idle_days`.
+    pub fn is_idle_seed(&self, t: &TorrentRow) -> bool {
+        let finished = t.stats.as_ref().is_some_and(|s| s.finished);
+        let cutoff = chrono::TimeDelta::days(self.config.seeding.idle_days as i64);
+        finished && self.last_upload_of(t).is_some_and(|at| Local::now() - at >= cutoff)
+    }
+}
@@ -0,0 +1,381 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use librqbit::api::ApiAddTorrentResponse;
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use super::{
+    cancel::Cancelled,
+    effects::collision_path,
+    meta::{AddSource, StorageKind},
+    retry::FetchRetry,
+    save_path::SavePathVars,
+    state::{App, Dialog},
+    util::derive_folder_suffix,
+};
+
+/// One line (or object) of an import file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportItem {
+    #[serde(alias = "url", alias = "uri")]
+    pub magnet: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Base directory; the torrent still gets its own subfolder inside it.
+    #[serde(default, alias = "directory")]
+    pub dir: Option<PathBuf>,
+}
+
+/// An item whose metadata is fetched in the background, so a slow magnet doesn't hold up
+/// the tick. It's added on a later tick, once the fetch finished.
+pub struct ImportFetch {
+    pub item: ImportItem,
+    /// Set when an earlier fetch of the item failed on the network.
+    pub retry: Option<FetchRetry>,
+    task: JoinHandle<Result<ApiAddTorrentResponse>>,
+}
+
+impl std::fmt::Debug for ImportFetch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportFetch")
+            .field("item", &self.item)
+            .field("retry", &self.retry)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ImportFetch {
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+/// A bulk add worked through one torrent per tick so the UI stays responsive.
+#[derive(Debug)]
+pub struct ImportJob {
+    /// Import file name, recorded as each torrent's source.
+    pub file: String,
+    pub queue: VecDeque<ImportItem>,
+    /// The item taken off the queue, while its metadata is fetched.
+    pub fetch: Option<ImportFetch>,
+    /// An item whose add failed on the network, fetched again once its backoff is over.
+    pub waiting: Option<(ImportItem, FetchRetry)>,
+    pub total: usize,
+    pub added: usize,
+    pub duplicates: usize,
+    /// (magnet, error) for every item that couldn't be added.
+    pub failures: Vec<(String, String)>,
+    pub cancelled: bool,
+}
+
+impl ImportJob {
+    pub fn done(&self) -> usize {
+        self.added + self.duplicates + self.failures.len()
+    }
+
+    /// Cancelling lets the item being fetched finish, and drops one waiting to retry.
+    pub fn finished(&self) -> bool {
+        (self.cancelled || (self.queue.is_empty() && self.waiting.is_none()))
+            && self.fetch.is_none()
+    }
+}
+
+/// Reads `.json` (array of strings or `{magnet, label, dir}` objects), `.csv` (columns
+/// `magnet,label,dir`, header optional), or plain text with one magnet per line.
+pub fn parse_import(path: &Path) -> Result<Vec<ImportItem>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let items = match ext.as_str() {
+        "json" => parse_json(&text)?,
+        "csv" => parse_csv(&text),
+        _ => text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| ImportItem {
+                magnet: line.to_string(),
+                label: None,
+                dir: None,
+            })
+            .collect(),
+    };
+    if items.is_empty() {
+        bail!("no magnets found in {}", path.display());
+    }
+    Ok(items)
+}
+
+fn parse_json(text: &str) -> Result<Vec<ImportItem>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Magnet(String),
+        Item(ImportItem),
+    }
+    let entries: Vec<Entry> = serde_json::from_str(text).context("invalid import JSON")?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| match entry {
+            Entry::Magnet(magnet) => ImportItem {
+                magnet,
+                label: None,
+                dir: None,
+            },
+            Entry::Item(item) => item,
+        })
+        .collect())
+}
+
+fn parse_csv(text: &str) -> Vec<ImportItem> {
+    let mut rows = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(split_csv_line)
+        .peekable();
+    // Default column order, replaced by the header when there is one.
+    let mut columns = vec!["magnet".to_string(), "label".to_string(), "dir".to_string()];
+    if let Some(first) = rows.peek()
+        && first
+            .iter()
+            .any(|f| matches!(f.to_lowercase().as_str(), "magnet" | "url" | "uri"))
+    {
+        columns = first.iter().map(|f| f.to_lowercase()).collect();
+        rows.next();
+    }
+    let field = |row: &[String], names: &[&str]| {
+        columns
+            .iter()
+            .position(|c| names.contains(&c.as_str()))
+            .and_then(|idx| row.get(idx))
+            .filter(|value| !value.is_empty())
+            .cloned()
+    };
+    rows.filter_map(|row| {
+        Some(ImportItem {
+            magnet: field(&row, &["magnet", "url", "uri"])?,
+            label: field(&row, &["label"]),
+            dir: field(&row, &["dir", "directory"]).map(PathBuf::from),
+        })
+    })
+    .collect()
+}
+
+/// Comma-separated fields with optional double quotes (`""` inside quotes is a quote).
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+impl App {
+    pub fn start_import(&mut self, path: &Path) -> Result<()> {
+        if self.import.as_ref().is_some_and(|job| !job.finished()) {
+            bail!("an import is already running");
+        }
+        let items = parse_import(path)?;
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        self.activity
+            .push(format!("Importing {} torrents from {file}", items.len()));
+        self.import = Some(ImportJob {
+            file,
+            total: items.len(),
+            queue: items.into(),
+            fetch: None,
+            waiting: None,
+            added: 0,
+            duplicates: 0,
+            failures: Vec::new(),
+            cancelled: false,
+        });
+        self.dialog = Dialog::Import;
+        Ok(())
+    }
+
+    pub(super) fn import_pending(&self) -> bool {
+        self.import.as_ref().is_some_and(|job| !job.finished())
+    }
+
+    /// Starts fetching the next queued item, or adds the one whose fetch finished,
+    /// recording the outcome instead of failing the import.
+    pub(super) async fn import_next(&mut self) {
+        let Some(job) = self.import.as_mut() else {
+            return;
+        };
+        if job.fetch.as_ref().is_some_and(|fetch| !fetch.is_finished()) {
+            return;
+        }
+        let source = AddSource::Import(job.file.clone());
+        let (magnet, outcome) = match job.fetch.take() {
+            Some(fetch) => {
+                let (item, retry) = (fetch.item.clone(), fetch.retry.clone());
+                match self.finish_import(fetch, source).await {
+                    Err(err) if !err.is::<Cancelled>() => {
+                        match FetchRetry::schedule(retry.as_ref(), err) {
+                            Ok(retry) => {
+                                if let Some(job) = self.import.as_mut() {
+                                    job.waiting = Some((item, retry));
+                                }
+                                return;
+                            }
+                            Err(err) => (item.magnet, Err(err)),
+                        }
+                    }
+                    outcome => (item.magnet, outcome),
+                }
+            }
+            None => {
+                if job.cancelled {
+                    return;
+                }
+                let (item, retry) = match job.waiting.take() {
+                    Some((item, retry)) if retry.due() => (item, Some(retry)),
+                    Some(waiting) => {
+                        job.waiting = Some(waiting);
+                        return;
+                    }
+                    None => match job.queue.pop_front() {
+                        Some(item) => (item, None),
+                        None => return,
+                    },
+                };
+                let magnet = item.magnet.clone();
+                match self.begin_import(item) {
+                    Ok(mut fetch) => {
+                        fetch.retry = retry;
+                        if let Some(job) = self.import.as_mut() {
+                            job.fetch = Some(fetch);
+                        }
+                        return;
+                    }
+                    Err(err) => (magnet, Err(err)),
+                }
+            }
+        };
+        let Some(job) = self.import.as_mut() else {
+            return;
+        };
+        match outcome {
+            Ok(true) => job.added += 1,
+            Ok(false) => job.duplicates += 1,
+            // Esc while an item was being added stops the whole import.
+            Err(err) if err.is::<Cancelled>() => job.cancelled = true,
+            Err(err) => job.failures.push((magnet, format!("{err:#}"))),
+        }
+        if job.finished() {
+            self.activity.push(format!(
+                "Import of {} finished: {} added, {} duplicates, {} failed",
+                job.file,
+                job.added,
+                job.duplicates,
+                job.failures.len()
+            ));
+        }
+    }
+
+    /// Starts fetching `item`'s metadata in the background.
+    pub(super) fn begin_import(&self, item: ImportItem) -> Result<ImportFetch> {
+        let base = item.dir.clone().unwrap_or_else(|| self.download_dir.clone());
+        let task = self.spawn_list_files(&item.magnet, base.to_string_lossy().into_owned())?;
+        Ok(ImportFetch {
+            item,
+            retry: None,
+            task,
+        })
+    }
+
+    /// Adds an item whose fetch finished. Ok(false) when it's already in the session.
+    pub(super) async fn finish_import(
+        &mut self,
+        fetch: ImportFetch,
+        source: AddSource,
+    ) -> Result<bool> {
+        let response = fetch
+            .task
+            .await
+            .unwrap_or_else(|err| Err(anyhow!("metadata fetch stopped: {err}")))?;
+        self.add_fetched(fetch.item, response, source).await
+    }
+
+    /// Ok(false) when the torrent is already in the session.
+    pub(super) async fn import_one(&mut self, item: ImportItem, source: AddSource) -> Result<bool> {
+        let base = item.dir.clone().unwrap_or_else(|| self.download_dir.clone());
+        let response = self
+            .list_files(&item.magnet, base.to_string_lossy().into_owned())
+            .await?;
+        self.add_fetched(item, response, source).await
+    }
+
+    async fn add_fetched(
+        &mut self,
+        item: ImportItem,
+        response: ApiAddTorrentResponse,
+        source: AddSource,
+    ) -> Result<bool> {
+        let base = item.dir.unwrap_or_else(|| self.download_dir.clone());
+        let info_hash = response.details.info_hash.clone();
+        if self.backend.torrent_list(false).iter().any(|t| t.info_hash == info_hash) {
+            return Ok(false);
+        }
+        let vars = SavePathVars::for_add(
+            &item.magnet,
+            &derive_folder_suffix(&response),
+            &info_hash,
+            item.label.as_deref(),
+        );
+        let mut output = self.save_path(&base, &vars)?;
+        let files = response.details.files.unwrap_or_default();
+        let bytes: u64 = files.iter().map(|f| f.length).sum();
+        if output.exists()
+            && let (Some(parent), Some(folder_name)) = (output.parent(), output.file_name())
+        {
+            // Nobody to ask mid-import, so "prompt" falls back to reusing the folder.
+            let folder_name = folder_name.to_string_lossy().into_owned();
+            output = collision_path(parent, &folder_name, &info_hash, self.config.collision)?;
+        }
+        // As for a single add, budget removals wait until this one went through.
+        let evictions = self.plan_label_budget(item.label.as_deref(), bytes, None)?;
+        let needed = bytes.saturating_sub(evictions.freed_on(&base));
+        if let Some(free) = self.space_shortfall(&base, needed) {
+            return Err(anyhow!(
+                "not enough free space ({} MiB free)",
+                free / (1024 * 1024)
+            ));
+        }
+        self.start_download(
+            item.magnet,
+            output.to_string_lossy().into_owned(),
+            (0..files.len()).collect(),
+            item.label,
+            source,
+            StorageKind::Disk,
+        )
+        .await?;
+        self.evict_after_add(evictions).await;
+        Ok(true)
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Local};
+
+use crate::metainfo::{self, TorrentInfo};
+
+use super::state::{App, TorrentRow, View};
+
+/// Metainfo of the torrent the Info view shows, decoded once when it is first shown.
+#[derive(Debug)]
+pub struct InfoDetails {
+    torrent_id: usize,
+    /// Why there is none, for magnets still fetching metadata and the demo.
+    metainfo: Result<TorrentInfo, String>,
+}
+
+impl App {
+    /// Decodes the selected torrent's metainfo while the Info view is open; other views
+    /// and torrents don't pay for it.
+    pub(super) fn sample_info(&mut self) {
+        let id = self.selected_torrent().map(|t| t.id);
+        let Some(id) = id.filter(|_| self.view == View::Info) else {
+            self.info_details = None;
+            return;
+        };
+        let cached = self.info_details.as_ref().filter(|d| d.torrent_id == id);
+        if cached.is_some_and(|d| d.metainfo.is_ok()) {
+            return;
+        }
+        let metainfo = self
+            .backend
+            .torrent_bytes(id)
+            .and_then(|bytes| metainfo::decode(&bytes))
+            .map_err(|err| format!("{err:#}"));
+        self.info_details = Some(InfoDetails {
+            torrent_id: id,
+            metainfo,
+        });
+    }
+
+    /// The decoded metainfo of `t`, or why it isn't available; None until it is sampled.
+    pub fn info_metainfo(&self, t: &TorrentRow) -> Option<Result<&TorrentInfo, &str>> {
+        self.info_details
+            .as_ref()
+            .filter(|d| d.torrent_id == t.id)
+            .map(|d| d.metainfo.as_ref().map_err(String::as_str))
+    }
+
+    pub fn added_at_of(&self, t: &TorrentRow) -> Option<DateTime<Local>> {
+        t.info_hash
+            .as_deref()
+            .and_then(|h| self.meta.get(h))
+            .and_then(|m| m.added_at)
+    }
+
+    pub fn completed_at_of(&self, t: &TorrentRow) -> Option<DateTime<Local>> {
+        t.info_hash
+            .as_deref()
+            .and_then(|h| self.meta.get(h))
+            .and_then(|m| m.completed_at)
+    }
+
+    pub(super) fn copy_info_hash(&mut self) -> Result<()> {
+        let Some(hash) = self.selected_torrent().and_then(|t| t.info_hash.clone()) else {
+            bail!("no info hash for the selected torrent");
+        };
+        crate::tui::copy_to_clipboard(&hash)?;
+        self.show_toast(format!("Copied {hash}"));
+        Ok(())
+    }
+}
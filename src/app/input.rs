@@ -1,31 +1,61 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 
-use super::{FocusPanel, Mode, View, action::Action, state::App};
+use super::{
+    FocusPanel, LabelTarget, LimitKind, Mode, NotesEdit, RuleTesterEdit, ScheduleEdit, View,
+    action::Action,
+    state::{App, Dialog},
+};
 
 impl App {
     pub fn actions_from_event(&mut self, ev: Event) -> Vec<Action> {
         match ev {
             Event::Key(key) => self.actions_from_key(key),
+            Event::Paste(_) if self.locked => Vec::new(),
             Event::Paste(text) => vec![Action::Paste(text)],
             Event::Resize(_, _) => Vec::new(),
+            Event::FocusGained => vec![Action::Focus(true)],
+            Event::FocusLost => vec![Action::Focus(false)],
+            Event::Mouse(mouse) if !self.locked => self.actions_from_mouse(mouse),
             _ => Vec::new(),
         }
     }
 
+    /// Right-click opens the context menu of the highlighted torrent; anything else
+    /// closes it.
+    fn actions_from_mouse(&self, mouse: MouseEvent) -> Vec<Action> {
+        let MouseEventKind::Down(button) = mouse.kind else {
+            return Vec::new();
+        };
+        if self.context_menu.is_some() {
+            return vec![Action::ContextMenuClose];
+        }
+        let idle = matches!(self.mode, Mode::Normal) && self.dialog == Dialog::None;
+        if button == MouseButton::Right && idle && self.view == View::Torrents {
+            return vec![Action::ContextMenuOpen];
+        }
+        Vec::new()
+    }
+
     pub fn actions_from_key(&mut self, key: KeyEvent) -> Vec<Action> {
         if key.kind == KeyEventKind::Repeat {
             let repeat_ok = matches!(
                 key.code,
                 KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
             );
-            let input_repeat_ok = matches!(self.mode, Mode::EnterMagnet | Mode::EnterTorrentDir)
-                && matches!(key.code, KeyCode::Char(_));
+            let editing = self.notes_editor.is_some() || self.rule_tester.is_some();
+            let input_repeat_ok = (self.mode.is_text_input() || editing)
+                && matches!(key.code, KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete);
             if !repeat_ok && !input_repeat_ok {
                 return Vec::new();
             }
         } else if key.kind != KeyEventKind::Press {
             return Vec::new();
         }
+        if self.locked {
+            return self.locked_actions(key);
+        }
         if matches!(self.mode, Mode::Normal) {
             match key.code {
                 KeyCode::Char(c) => {
@@ -39,6 +69,9 @@ impl App {
                 }
             }
         }
+        if key.code == KeyCode::F(12) {
+            return vec![Action::DebugToggle];
+        }
         if self.show_help {
             return match key.code {
                 KeyCode::Char('?') | KeyCode::Char('x') | KeyCode::Esc => {
@@ -81,26 +114,361 @@ impl App {
                 _ => Vec::new(),
             };
         }
+        if self.confirm_space.is_some() {
+            return match key.code {
+                KeyCode::Left | KeyCode::Char('h') => vec![Action::ConfirmSpaceSelect(true)],
+                KeyCode::Right | KeyCode::Char('l') => vec![Action::ConfirmSpaceSelect(false)],
+                KeyCode::Char('y') | KeyCode::Char('Y') => vec![Action::ConfirmSpaceSelect(true)],
+                KeyCode::Char('n') | KeyCode::Char('N') => vec![Action::ConfirmSpaceSelect(false)],
+                KeyCode::Esc => vec![Action::ConfirmSpaceCancel],
+                KeyCode::Enter => vec![Action::ConfirmSpaceConfirm],
+                _ => Vec::new(),
+            };
+        }
+        if self.mode == Mode::FilePicker
+            && self.file_picker.as_ref().is_some_and(|p| p.risky_choice.is_some())
+        {
+            return match key.code {
+                KeyCode::Left | KeyCode::Char('h') => vec![Action::RiskyFilesSelect(true)],
+                KeyCode::Right | KeyCode::Char('l') => vec![Action::RiskyFilesSelect(false)],
+                KeyCode::Char('y') | KeyCode::Char('Y') => vec![Action::RiskyFilesSelect(true)],
+                KeyCode::Char('n') | KeyCode::Char('N') => vec![Action::RiskyFilesSelect(false)],
+                KeyCode::Esc => vec![Action::RiskyFilesCancel],
+                KeyCode::Enter => vec![Action::RiskyFilesConfirm],
+                _ => Vec::new(),
+            };
+        }
+        if self.notes_editor.is_some() {
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            return match key.code {
+                KeyCode::Char('s') if ctrl => vec![Action::NotesSave],
+                KeyCode::Esc => vec![Action::NotesCancel],
+                KeyCode::Enter => vec![Action::NotesEdit(NotesEdit::Newline)],
+                KeyCode::Backspace => vec![Action::NotesEdit(NotesEdit::Backspace)],
+                KeyCode::Delete => vec![Action::NotesEdit(NotesEdit::Delete)],
+                KeyCode::Left => vec![Action::NotesEdit(NotesEdit::Left)],
+                KeyCode::Right => vec![Action::NotesEdit(NotesEdit::Right)],
+                KeyCode::Up => vec![Action::NotesEdit(NotesEdit::Up)],
+                KeyCode::Down => vec![Action::NotesEdit(NotesEdit::Down)],
+                KeyCode::Home => vec![Action::NotesEdit(NotesEdit::Home)],
+                KeyCode::End => vec![Action::NotesEdit(NotesEdit::End)],
+                KeyCode::Char(c) if !ctrl => vec![Action::NotesEdit(NotesEdit::Char(c))],
+                _ => Vec::new(),
+            };
+        }
+        if self.rule_tester.is_some() {
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let edit = match key.code {
+                KeyCode::Esc => return vec![Action::RuleTesterClose],
+                KeyCode::Tab | KeyCode::Down | KeyCode::Enter => RuleTesterEdit::Next,
+                KeyCode::BackTab | KeyCode::Up => RuleTesterEdit::Previous,
+                KeyCode::Backspace => RuleTesterEdit::Backspace,
+                KeyCode::Char('u') if ctrl => RuleTesterEdit::Clear,
+                KeyCode::Char(c) if !ctrl => RuleTesterEdit::Char(c),
+                _ => return Vec::new(),
+            };
+            return vec![Action::RuleTesterEdit(edit)];
+        }
+        if self.schedule_editor.is_some() {
+            let edit = match key.code {
+                KeyCode::Esc => return vec![Action::ScheduleClose],
+                KeyCode::Enter => return vec![Action::ScheduleSave],
+                KeyCode::Left => ScheduleEdit::Left,
+                KeyCode::Right => ScheduleEdit::Right,
+                KeyCode::Up => ScheduleEdit::Up,
+                KeyCode::Down => ScheduleEdit::Down,
+                KeyCode::Char(c) => ScheduleEdit::Set(c),
+                _ => return Vec::new(),
+            };
+            return vec![Action::ScheduleEdit(edit)];
+        }
+        if self.magnet_preview.is_some() {
+            return match key.code {
+                KeyCode::Enter | KeyCode::Char('y') => vec![Action::MagnetPreviewFetch],
+                KeyCode::Char('e') => vec![Action::MagnetPreviewEdit],
+                KeyCode::Esc | KeyCode::Char('n') => vec![Action::MagnetPreviewReject],
+                _ => Vec::new(),
+            };
+        }
+        if self.path_review.is_some() {
+            return match key.code {
+                KeyCode::Left | KeyCode::Char('h') => vec![Action::PathReviewSelect(true)],
+                KeyCode::Right | KeyCode::Char('l') => vec![Action::PathReviewSelect(false)],
+                KeyCode::Char('s') | KeyCode::Char('S') => vec![Action::PathReviewSelect(true)],
+                KeyCode::Char('k') | KeyCode::Char('K') => vec![Action::PathReviewSelect(false)],
+                KeyCode::Esc => vec![Action::PathReviewCancel],
+                KeyCode::Enter => vec![Action::PathReviewConfirm],
+                _ => Vec::new(),
+            };
+        }
+        if let Some(job) = &self.import {
+            return match key.code {
+                KeyCode::Esc if !job.finished() => vec![Action::ImportCancel],
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('x') => vec![Action::ImportClose],
+                _ => Vec::new(),
+            };
+        }
+        if self.busy.is_some() {
+            return match key.code {
+                KeyCode::Esc => vec![Action::BusyCancel],
+                _ => Vec::new(),
+            };
+        }
+        if self.retry.is_some() {
+            return match key.code {
+                KeyCode::Esc => vec![Action::RetryCancel],
+                KeyCode::Enter | KeyCode::Char('r') => vec![Action::RetryNow],
+                _ => Vec::new(),
+            };
+        }
+        if self.confirm_maintenance.is_some() {
+            return match key.code {
+                KeyCode::Left | KeyCode::Char('h') => vec![Action::MaintenanceConfirmSelect(true)],
+                KeyCode::Right | KeyCode::Char('l') => {
+                    vec![Action::MaintenanceConfirmSelect(false)]
+                }
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    vec![Action::MaintenanceConfirmSelect(true)]
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => {
+                    vec![Action::MaintenanceConfirmSelect(false)]
+                }
+                KeyCode::Esc => vec![Action::MaintenanceConfirmCancel],
+                KeyCode::Enter => vec![Action::MaintenanceConfirm],
+                _ => Vec::new(),
+            };
+        }
+        if let Some(job) = &self.maintenance {
+            return match key.code {
+                KeyCode::Esc if !job.finished() => vec![Action::MaintenanceCancel],
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('x') => {
+                    vec![Action::MaintenanceClose]
+                }
+                _ => Vec::new(),
+            };
+        }
+        if self.collision.is_some() {
+            return match key.code {
+                KeyCode::Left | KeyCode::Char('h') => vec![Action::CollisionSelect(-1)],
+                KeyCode::Right | KeyCode::Char('l') => vec![Action::CollisionSelect(1)],
+                KeyCode::Char('1') => vec![Action::CollisionConfirm(Some(0))],
+                KeyCode::Char('2') => vec![Action::CollisionConfirm(Some(1))],
+                KeyCode::Char('3') => vec![Action::CollisionConfirm(Some(2))],
+                KeyCode::Esc => vec![Action::CollisionCancel],
+                KeyCode::Enter => vec![Action::CollisionConfirm(None)],
+                _ => Vec::new(),
+            };
+        }
+        if self.column_resize.is_some() {
+            return match key.code {
+                KeyCode::Tab | KeyCode::Char('\t') => vec![Action::ColumnResizeSelect(1)],
+                KeyCode::BackTab => vec![Action::ColumnResizeSelect(-1)],
+                KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('-') => {
+                    vec![Action::ColumnResize(-1)]
+                }
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('+') => {
+                    vec![Action::ColumnResize(1)]
+                }
+                KeyCode::Char('r') => vec![Action::ColumnResizeReset],
+                KeyCode::Enter => vec![Action::ColumnResizeSave],
+                KeyCode::Esc => vec![Action::ColumnResizeCancel],
+                _ => Vec::new(),
+            };
+        }
+        if self.cast.is_some() && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') => vec![Action::CastClose],
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::CastMove(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::CastMove(1)],
+                KeyCode::Enter => vec![Action::CastConfirm],
+                KeyCode::Char('r') => vec![Action::CastRefresh],
+                _ => Vec::new(),
+            };
+        }
+        if self.duplicates.is_some() && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') => vec![Action::DuplicatesClose],
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::DuplicatesScroll(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::DuplicatesScroll(1)],
+                KeyCode::PageUp => vec![Action::DuplicatesScroll(-10)],
+                KeyCode::PageDown => vec![Action::DuplicatesScroll(10)],
+                _ => Vec::new(),
+            };
+        }
+        if self.torrent_file.is_some() && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') => vec![Action::TorrentFileClose],
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::TorrentFileScroll(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::TorrentFileScroll(1)],
+                KeyCode::PageUp => vec![Action::TorrentFileScroll(-10)],
+                KeyCode::PageDown => vec![Action::TorrentFileScroll(10)],
+                KeyCode::Char('a') => vec![Action::TorrentFileAdd],
+                _ => Vec::new(),
+            };
+        }
+        if self.archive_open && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') => vec![Action::ArchiveClose],
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::ArchiveMove(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::ArchiveMove(1)],
+                KeyCode::Enter | KeyCode::Char('r') => vec![Action::ArchiveRestore],
+                _ => Vec::new(),
+            };
+        }
+        if self.context_menu.is_some() && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') => vec![Action::ContextMenuClose],
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::ContextMenuMove(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::ContextMenuMove(1)],
+                KeyCode::Enter => vec![Action::ContextMenuConfirm],
+                _ => Vec::new(),
+            };
+        }
+        if self.activity_view.is_some() && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') => vec![Action::ActivityClose],
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::ActivityScroll(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::ActivityScroll(1)],
+                KeyCode::PageUp => vec![Action::ActivityScroll(-10)],
+                KeyCode::PageDown => vec![Action::ActivityScroll(10)],
+                KeyCode::Char('f') | KeyCode::Tab => vec![Action::ActivityFilterNext],
+                _ => Vec::new(),
+            };
+        }
+        if self.talkers_open && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') | KeyCode::Char('b') => {
+                    vec![Action::TalkersClose]
+                }
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::TalkersMove(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::TalkersMove(1)],
+                KeyCode::Char('s') | KeyCode::Tab => vec![Action::TalkersSort],
+                KeyCode::Enter => vec![Action::TalkersJump],
+                _ => Vec::new(),
+            };
+        }
+        if self.tasks_open && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') | KeyCode::Char('T') => {
+                    vec![Action::TasksClose]
+                }
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::TasksMove(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::TasksMove(1)],
+                KeyCode::Char('c') | KeyCode::Delete => vec![Action::TasksCancel],
+                _ => Vec::new(),
+            };
+        }
+        if self.inspector.is_some() && matches!(self.mode, Mode::Normal) {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Char('x') | KeyCode::Char('J') => {
+                    vec![Action::InspectorClose]
+                }
+                KeyCode::Up | KeyCode::Char('k') => vec![Action::InspectorMove(-1)],
+                KeyCode::Down | KeyCode::Char('j') => vec![Action::InspectorMove(1)],
+                KeyCode::PageUp => vec![Action::InspectorMove(-10)],
+                KeyCode::PageDown => vec![Action::InspectorMove(10)],
+                KeyCode::Enter | KeyCode::Char(' ') => vec![Action::InspectorToggleFold],
+                KeyCode::Char('c') => vec![Action::InspectorFoldAll(true)],
+                KeyCode::Char('e') => vec![Action::InspectorFoldAll(false)],
+                KeyCode::Char('/') => vec![Action::InspectorSearchStart],
+                KeyCode::Char('n') => vec![Action::InspectorFind(true)],
+                KeyCode::Char('N') => vec![Action::InspectorFind(false)],
+                KeyCode::Char('r') => vec![Action::InspectorOpen],
+                _ => Vec::new(),
+            };
+        }
+        if matches!(self.mode, Mode::Normal)
+            && self.view == View::Files
+            && self.focus == FocusPanel::Torrents
+        {
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') => return vec![Action::FilesMove(1)],
+                KeyCode::Up | KeyCode::Char('k') => return vec![Action::FilesMove(-1)],
+                KeyCode::PageDown => return vec![Action::FilesMove(10)],
+                KeyCode::PageUp => return vec![Action::FilesMove(-10)],
+                KeyCode::Char('y') => return vec![Action::CopyStreamUrl],
+                KeyCode::Char('c') => return vec![Action::CastOpen],
+                KeyCode::Char('o') => return vec![Action::FileOpenSystem],
+                _ => {}
+            }
+        }
+        if matches!(self.mode, Mode::Normal)
+            && self.view == View::Peers
+            && self.focus == FocusPanel::Torrents
+        {
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') => return vec![Action::PeersMove(1)],
+                KeyCode::Up | KeyCode::Char('k') => return vec![Action::PeersMove(-1)],
+                KeyCode::PageDown => return vec![Action::PeersMove(10)],
+                KeyCode::PageUp => return vec![Action::PeersMove(-10)],
+                KeyCode::Char('y') => return vec![Action::CopyPeerAddress],
+                _ => {}
+            }
+        }
+        if matches!(self.mode, Mode::Normal)
+            && self.view == View::Info
+            && self.focus == FocusPanel::Torrents
+        {
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') => return vec![Action::InfoScroll(1)],
+                KeyCode::Up | KeyCode::Char('k') => return vec![Action::InfoScroll(-1)],
+                KeyCode::PageDown => return vec![Action::InfoScroll(10)],
+                KeyCode::PageUp => return vec![Action::InfoScroll(-10)],
+                KeyCode::Home => return vec![Action::InfoTop],
+                KeyCode::Char('y') => return vec![Action::CopyInfoHash],
+                _ => {}
+            }
+        }
         if matches!(self.mode, Mode::Normal) {
             return match key.code {
                 KeyCode::Char('f') => vec![Action::ViewSet(View::Torrents)],
+                KeyCode::Char('o') => vec![Action::ViewSet(View::Files)],
                 KeyCode::Char('i') => vec![Action::ViewSet(View::Info)],
                 KeyCode::Char('v') => vec![Action::ViewSet(View::Peers)],
+                KeyCode::Char('u') => vec![Action::ViewSet(View::Trackers)],
+                KeyCode::Char('e') => vec![Action::ViewSet(View::Timeline)],
+                KeyCode::Char('R') => vec![Action::ViewSet(View::Rss)],
+                KeyCode::Char('l') => vec![Action::ViewSet(View::Log)],
                 KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('\t') => vec![Action::FocusToggle],
                 KeyCode::Char('?') => vec![Action::HelpOpen],
                 KeyCode::Char('t') => vec![Action::FocusSet(FocusPanel::Torrents)],
                 KeyCode::Char('g') => vec![Action::FocusSet(FocusPanel::Filters)],
                 KeyCode::Char('p') => vec![Action::TogglePause],
+                KeyCode::Char('F') => vec![Action::ForceStart],
                 KeyCode::Char('a') => vec![Action::StartAdd],
                 KeyCode::Char('d') => vec![Action::ConfirmDeleteOpen],
-                KeyCode::Char('q') => vec![Action::ConfirmQuitOpen],
+                KeyCode::Char('L') => vec![Action::StartLabelEdit(LabelTarget::Selected)],
+                KeyCode::Char('J') => vec![Action::InspectorOpen],
+                KeyCode::Char('T') => vec![Action::TasksOpen],
+                KeyCode::Char('b') => vec![Action::TalkersOpen],
+                KeyCode::Char('n') => vec![Action::StartRename],
+                KeyCode::Char('m') => vec![Action::StartLocate],
+                KeyCode::Char('N') => vec![Action::NotesOpen],
+                KeyCode::Char('w') => vec![Action::ColumnResizeOpen],
+                KeyCode::Char('G') => vec![Action::GroupCycle],
+                KeyCode::Char('+') => vec![Action::RateLimitBump(LimitKind::Download, 1)],
+                KeyCode::Char('-') => vec![Action::RateLimitBump(LimitKind::Download, -1)],
+                KeyCode::Char(']') => vec![Action::RateLimitBump(LimitKind::Upload, 1)],
+                KeyCode::Char('[') => vec![Action::RateLimitBump(LimitKind::Upload, -1)],
+                KeyCode::Char('=') => vec![Action::RateLimitsClear],
+                KeyCode::Char('z') | KeyCode::Char(' ') => vec![Action::GroupToggle],
+                KeyCode::Char(':') => vec![Action::StartCommand],
+                KeyCode::Char('q') => vec![Action::QuitPressed],
+                KeyCode::Char('K') => vec![Action::Lock],
+                KeyCode::Char('C') => vec![Action::CycleTheme],
                 KeyCode::Char('1') => vec![Action::SetFilter(0)],
                 KeyCode::Char('2') => vec![Action::SetFilter(1)],
                 KeyCode::Char('3') => vec![Action::SetFilter(2)],
                 KeyCode::Char('4') => vec![Action::SetFilter(3)],
                 KeyCode::Char('5') => vec![Action::SetFilter(4)],
                 KeyCode::Char('6') => vec![Action::SetFilter(5)],
+                KeyCode::Char('7') => vec![Action::SetFilter(6)],
                 KeyCode::Char('r') => vec![Action::Refresh],
+                KeyCode::Enter
+                    if self.view == View::Torrents && self.focus == FocusPanel::Torrents =>
+                {
+                    vec![Action::ContextMenuOpen]
+                }
                 KeyCode::Down | KeyCode::Char('j') => match self.focus {
                     FocusPanel::Torrents => vec![Action::MoveSelection(1)],
                     FocusPanel::Filters => vec![Action::MoveFilter(1)],
@@ -113,7 +481,9 @@ impl App {
             };
         }
         match self.mode {
-            Mode::EnterMagnet | Mode::EnterTorrentDir => match key.code {
+            Mode::EnterTorrentDir if key.code == KeyCode::Up => vec![Action::DirChoiceMove(-1)],
+            Mode::EnterTorrentDir if key.code == KeyCode::Down => vec![Action::DirChoiceMove(1)],
+            mode if mode.is_text_input() => match key.code {
                 KeyCode::Esc => vec![Action::InputCancel],
                 KeyCode::Enter => vec![Action::InputEnter],
                 KeyCode::Backspace => vec![Action::InputBackspace],
@@ -132,10 +502,13 @@ impl App {
                 KeyCode::Char(' ') => vec![Action::FilePickerToggle],
                 KeyCode::Char('a') => vec![Action::FilePickerAll],
                 KeyCode::Char('n') => vec![Action::FilePickerNone],
+                KeyCode::Char('s') => vec![Action::FilePickerSmart],
+                KeyCode::Char('m') => vec![Action::FilePickerToggleStorage],
+                KeyCode::Char('l') => vec![Action::StartLabelEdit(LabelTarget::Picker)],
                 KeyCode::Enter => vec![Action::FilePickerConfirm],
                 _ => Vec::new(),
             },
-            Mode::Normal => Vec::new(),
+            _ => Vec::new(),
         }
     }
 }
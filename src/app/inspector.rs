@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+/// Foldable, searchable view over a JSON document, used for raw torrent details.
+#[derive(Debug, Clone)]
+pub struct JsonInspector {
+    pub title: String,
+    root: Value,
+    collapsed: HashSet<String>,
+    cursor: usize,
+    query: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InspectorLine {
+    pub depth: usize,
+    /// JSON pointer of the node, e.g. `/stats/live/download_speed`.
+    pub path: String,
+    pub key: Option<String>,
+    pub value: String,
+    pub foldable: bool,
+    pub collapsed: bool,
+}
+
+impl JsonInspector {
+    pub fn new(title: String, root: Value) -> Self {
+        Self {
+            title,
+            root,
+            collapsed: HashSet::new(),
+            cursor: 0,
+            query: None,
+        }
+    }
+
+    /// Swaps in fresh data, keeping folds, query and (clamped) cursor.
+    pub fn set_root(&mut self, root: Value) {
+        self.root = root;
+        self.move_cursor(0);
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Lines currently visible, honouring folds.
+    pub fn lines(&self) -> Vec<InspectorLine> {
+        let mut out = Vec::new();
+        self.flatten(&self.root, None, String::new(), 0, true, &mut out);
+        out
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let len = self.lines().len();
+        if len == 0 {
+            self.cursor = 0;
+            return;
+        }
+        self.cursor = (self.cursor as isize + delta).clamp(0, len as isize - 1) as usize;
+    }
+
+    pub fn toggle_fold(&mut self) {
+        let Some(line) = self.lines().into_iter().nth(self.cursor) else {
+            return;
+        };
+        if !line.foldable {
+            return;
+        }
+        if !self.collapsed.remove(&line.path) {
+            self.collapsed.insert(line.path);
+        }
+    }
+
+    pub fn set_all_collapsed(&mut self, collapsed: bool) {
+        self.collapsed.clear();
+        if collapsed {
+            let mut all = Vec::new();
+            self.flatten(&self.root, None, String::new(), 0, false, &mut all);
+            // Keep the root open so there's something to navigate.
+            self.collapsed
+                .extend(all.into_iter().filter(|l| l.foldable && l.depth > 0).map(|l| l.path));
+        }
+        self.cursor = 0;
+    }
+
+    pub fn set_query(&mut self, query: Option<String>) {
+        self.query = query;
+    }
+
+    /// Jumps to the next (or previous) line whose key or value contains the query,
+    /// unfolding its ancestors. Returns false when nothing matches.
+    pub fn find(&mut self, forward: bool) -> bool {
+        let Some(query) = self.query.as_ref().map(|q| q.to_lowercase()) else {
+            return false;
+        };
+        let mut all = Vec::new();
+        self.flatten(&self.root, None, String::new(), 0, false, &mut all);
+        let current = self
+            .lines()
+            .get(self.cursor)
+            .map(|l| l.path.clone())
+            .unwrap_or_default();
+        let start = all.iter().position(|l| l.path == current).unwrap_or(0);
+        let len = all.len();
+        let found = (1..=len)
+            .map(|step| {
+                if forward {
+                    (start + step) % len
+                } else {
+                    (start + len - step % len) % len
+                }
+            })
+            .find(|&idx| line_matches(&all[idx], &query));
+        let Some(idx) = found else {
+            return false;
+        };
+        let target = all[idx].path.clone();
+        for (pos, _) in target.match_indices('/') {
+            self.collapsed.remove(&target[..pos]);
+        }
+        if let Some(pos) = self.lines().iter().position(|l| l.path == target) {
+            self.cursor = pos;
+        }
+        true
+    }
+
+    pub fn is_match(&self, line: &InspectorLine) -> bool {
+        self.query
+            .as_ref()
+            .is_some_and(|q| line_matches(line, &q.to_lowercase()))
+    }
+
+    fn flatten(
+        &self,
+        value: &Value,
+        key: Option<String>,
+        path: String,
+        depth: usize,
+        respect_folds: bool,
+        out: &mut Vec<InspectorLine>,
+    ) {
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(idx, v)| (idx.to_string(), v))
+                .collect(),
+            _ => {
+                out.push(InspectorLine {
+                    depth,
+                    path,
+                    key,
+                    value: value.to_string(),
+                    foldable: false,
+                    collapsed: false,
+                });
+                return;
+            }
+        };
+        let collapsed = self.collapsed.contains(&path);
+        let summary = match value {
+            Value::Object(_) => format!("{{{} keys}}", children.len()),
+            _ => format!("[{} items]", children.len()),
+        };
+        out.push(InspectorLine {
+            depth,
+            path: path.clone(),
+            key,
+            value: summary,
+            foldable: !children.is_empty(),
+            collapsed,
+        });
+        if collapsed && respect_folds {
+            return;
+        }
+        for (child_key, child) in children {
+            let child_path = format!("{path}/{}", child_key.replace('~', "~0").replace('/', "~1"));
+            self.flatten(child, Some(child_key), child_path, depth + 1, respect_folds, out);
+        }
+    }
+}
+
+fn line_matches(line: &InspectorLine, query: &str) -> bool {
+    line.key
+        .as_ref()
+        .is_some_and(|k| k.to_lowercase().contains(query))
+        || (!line.foldable && line.value.to_lowercase().contains(query))
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use super::{hooks::MAX_LOGGED_ERRORS, state::App, util::sanitize_path_component};
+
+impl App {
+    /// Keeps `links_dir` holding one symlink per torrent, named after the torrent and
+    /// pointing at its output folder. Only symlinks it created itself, or ones pointing at
+    /// a torrent's folder, are ever removed from the directory.
+    pub(super) fn sync_links(&mut self) {
+        let Some(dir) = self.config.links_dir.clone() else {
+            return;
+        };
+        if self.backend.is_simulated() {
+            return;
+        }
+        let mut desired: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for t in &self.torrents {
+            let mut name = sanitize_path_component(&t.name);
+            if name.is_empty() || name == "." || name == ".." {
+                continue;
+            }
+            if desired.contains_key(&name) {
+                let short_hash: String =
+                    t.info_hash.as_deref().unwrap_or_default().chars().take(8).collect();
+                name = format!("{name}-{short_hash}");
+            }
+            desired.insert(name, PathBuf::from(&t.output_folder));
+        }
+        if self.links_synced.as_ref() == Some(&desired) {
+            return;
+        }
+        let errors = sync_link_dir(&dir, &desired);
+        for err in errors.iter().take(MAX_LOGGED_ERRORS) {
+            self.activity.push(format!("Links: {err}"));
+        }
+        self.links_synced = Some(desired);
+    }
+}
+
+/// Names of the links ittybitty made, kept in the links directory so other links there
+/// are left alone.
+const MANIFEST: &str = ".ittybitty-links";
+
+fn sync_link_dir(dir: &Path, desired: &BTreeMap<String, PathBuf>) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Err(err) = std::fs::create_dir_all(dir) {
+        errors.push(format!("mkdir {}: {err}", dir.display()));
+        return errors;
+    }
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            errors.push(format!("{}: {err}", dir.display()));
+            return errors;
+        }
+    };
+    let manifest = dir.join(MANIFEST);
+    let mut owned: HashSet<String> = std::fs::read_to_string(&manifest)
+        .map(|text| text.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+    let targets: HashSet<&PathBuf> = desired.values().collect();
+    let mut present = HashMap::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(target) = std::fs::read_link(&path) else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if desired.get(&name) == Some(&target) {
+            present.insert(name, target);
+            continue;
+        }
+        // Links someone else made to something else stay.
+        if !owned.contains(&name) && !targets.contains(&target) {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                owned.remove(&name);
+            }
+            Err(err) => errors.push(format!("remove {}: {err}", path.display())),
+        }
+    }
+    for (name, target) in desired {
+        if present.contains_key(name) {
+            owned.insert(name.clone());
+            continue;
+        }
+        let link = dir.join(name);
+        if link.symlink_metadata().is_ok() {
+            errors.push(format!("{} exists and is not a link", link.display()));
+            continue;
+        }
+        match symlink_dir(target, &link) {
+            Ok(()) => {
+                owned.insert(name.clone());
+            }
+            Err(err) => errors.push(format!("link {}: {err}", link.display())),
+        }
+    }
+    // Links deleted by hand are forgotten.
+    owned.retain(|name| dir.join(name).symlink_metadata().is_ok());
+    let mut names: Vec<&str> = owned.iter().map(String::as_str).collect();
+    names.sort_unstable();
+    let text = names.iter().map(|name| format!("{name}\n")).collect::<String>();
+    if let Err(err) = std::fs::write(&manifest, text) {
+        errors.push(format!("{}: {err}", manifest.display()));
+    }
+    errors
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
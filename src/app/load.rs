@@ -0,0 +1,48 @@
+use crate::config::LoadConfig;
+
+/// Why the load guard wants the session paused, if it does.
+pub fn pause_reason(config: &LoadConfig) -> Option<String> {
+    if let Some(threshold) = config.pause_above_load
+        && let Some(load) = load_per_core()
+        && load > threshold
+    {
+        return Some(format!("high load ({load:.2}/core)"));
+    }
+    if let Some(threshold) = config.pause_above_io_pressure
+        && let Some(pressure) = io_pressure()
+        && pressure > threshold
+    {
+        return Some(format!("IO pressure ({pressure:.0}%)"));
+    }
+    None
+}
+
+/// One-minute load average divided by the number of cores.
+#[cfg(target_os = "linux")]
+fn load_per_core() -> Option<f64> {
+    let text = std::fs::read_to_string("/proc/loadavg").ok()?;
+    let load: f64 = text.split_whitespace().next()?.parse().ok()?;
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    Some(load / cores as f64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn load_per_core() -> Option<f64> {
+    None
+}
+
+/// Share of the last 10 seconds in which some task was stalled on IO (PSI `some avg10`).
+#[cfg(target_os = "linux")]
+fn io_pressure() -> Option<f64> {
+    let text = std::fs::read_to_string("/proc/pressure/io").ok()?;
+    let some = text.lines().find(|line| line.starts_with("some "))?;
+    some.split_whitespace()
+        .find_map(|field| field.strip_prefix("avg10="))?
+        .parse()
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn io_pressure() -> Option<f64> {
+    None
+}
@@ -0,0 +1,58 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::{FocusPanel, action::Action, state::App};
+
+impl App {
+    pub(super) fn lock_input(&mut self) {
+        self.locked = true;
+        self.unlock_typed.clear();
+        self.show_toast("Keyboard locked".to_string());
+    }
+
+    pub(super) fn unlock_input(&mut self) {
+        self.locked = false;
+        self.unlock_typed.clear();
+        self.show_toast("Keyboard unlocked".to_string());
+    }
+
+    /// While locked, keys can only move around the list; typed characters go toward the
+    /// unlock sequence instead of their usual actions.
+    pub(super) fn locked_actions(&mut self, key: KeyEvent) -> Vec<Action> {
+        match key.code {
+            KeyCode::Char(c) => {
+                let sequence = self.config.ui.unlock_sequence();
+                self.unlock_typed.push(c);
+                if self.unlock_typed.ends_with(sequence) {
+                    return vec![Action::Unlock];
+                }
+                // Only the tail can still turn into the sequence.
+                let keep = sequence.chars().count() - 1;
+                let excess = self.unlock_typed.chars().count().saturating_sub(keep);
+                let cut = match self.unlock_typed.char_indices().nth(excess) {
+                    Some((idx, _)) => idx,
+                    None => self.unlock_typed.len(),
+                };
+                self.unlock_typed.drain(..cut);
+                Vec::new()
+            }
+            KeyCode::Esc => {
+                self.unlock_typed.clear();
+                Vec::new()
+            }
+            KeyCode::Down => match self.focus {
+                FocusPanel::Torrents => vec![Action::MoveSelection(1)],
+                FocusPanel::Filters => vec![Action::MoveFilter(1)],
+            },
+            KeyCode::Up => match self.focus {
+                FocusPanel::Torrents => vec![Action::MoveSelection(-1)],
+                FocusPanel::Filters => vec![Action::MoveFilter(-1)],
+            },
+            KeyCode::Tab | KeyCode::BackTab => vec![Action::FocusToggle],
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+
+use anyhow::{Context, Result, bail};
+use librqbit::TorrentStatsState;
+
+use super::{
+    meta::StorageKind,
+    retry::with_timeout,
+    state::{App, Dialog},
+};
+
+/// Session-wide chores run over every torrent, one at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceKind {
+    /// Re-add each torrent in place so librqbit verifies its data.
+    Recheck,
+    /// Restart each running torrent so it announces to its trackers again.
+    Reannounce,
+}
+
+impl MaintenanceKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            MaintenanceKind::Recheck => "Recheck",
+            MaintenanceKind::Reannounce => "Re-announce",
+        }
+    }
+
+    pub fn verb(self) -> &'static str {
+        match self {
+            MaintenanceKind::Recheck => "Rechecking",
+            MaintenanceKind::Reannounce => "Re-announcing",
+        }
+    }
+}
+
+/// A recheck or re-announce of the whole session, worked through one torrent per tick.
+#[derive(Debug)]
+pub struct MaintenanceJob {
+    pub kind: MaintenanceKind,
+    /// Info hashes still to go; ids can change when a recheck re-adds a torrent.
+    pub queue: VecDeque<String>,
+    /// Torrent handled last; the next one waits until it has finished initializing.
+    pub current: Option<String>,
+    pub total: usize,
+    pub done: usize,
+    pub skipped: usize,
+    /// (name, error) for every torrent that couldn't be handled.
+    pub failures: Vec<(String, String)>,
+    pub cancelled: bool,
+}
+
+impl MaintenanceJob {
+    pub fn finished(&self) -> bool {
+        self.current.is_none() && (self.cancelled || self.queue.is_empty())
+    }
+
+    pub fn handled(&self) -> usize {
+        self.done + self.skipped + self.failures.len()
+    }
+}
+
+impl App {
+    /// Asks before starting `kind` over every torrent.
+    pub(super) fn ask_maintenance(&mut self, kind: MaintenanceKind) -> Result<()> {
+        if self.maintenance.as_ref().is_some_and(|job| !job.finished()) {
+            bail!("a recheck or re-announce is already running");
+        }
+        if self.torrents.is_empty() {
+            bail!("no torrents to {}", kind.label().to_lowercase());
+        }
+        self.maintenance = None;
+        self.confirm_maintenance = Some(kind);
+        self.maintenance_choice = false;
+        self.dialog = Dialog::ConfirmMaintenance;
+        Ok(())
+    }
+
+    pub(super) fn start_maintenance(&mut self, kind: MaintenanceKind) {
+        let queue: VecDeque<String> = self
+            .torrents
+            .iter()
+            .filter_map(|t| t.info_hash.clone())
+            .collect();
+        self.activity
+            .push(format!("{} {} torrents", kind.verb(), queue.len()));
+        self.maintenance = Some(MaintenanceJob {
+            kind,
+            total: queue.len(),
+            queue,
+            current: None,
+            done: 0,
+            skipped: 0,
+            failures: Vec::new(),
+            cancelled: false,
+        });
+        self.dialog = Dialog::Maintenance;
+    }
+
+    pub(super) fn maintenance_pending(&self) -> bool {
+        self.maintenance.as_ref().is_some_and(|job| !job.finished())
+    }
+
+    /// Moves on to the next torrent once the previous one has settled, recording the
+    /// outcome instead of failing the whole run.
+    pub(super) async fn maintenance_next(&mut self) {
+        let Some(job) = self.maintenance.as_mut() else {
+            return;
+        };
+        if let Some(hash) = job.current.as_deref() {
+            let checking = self.torrents.iter().any(|t| {
+                t.info_hash.as_deref() == Some(hash)
+                    && t.stats
+                        .as_ref()
+                        .is_some_and(|s| matches!(s.state, TorrentStatsState::Initializing))
+            });
+            if checking {
+                return;
+            }
+            job.current = None;
+        }
+        let next = if job.cancelled {
+            None
+        } else {
+            job.queue.pop_front()
+        };
+        let kind = job.kind;
+        if let Some(hash) = next {
+            let name = self
+                .torrents
+                .iter()
+                .find(|t| t.info_hash.as_deref() == Some(hash.as_str()))
+                .map(|t| t.name.clone())
+                .unwrap_or_else(|| hash.clone());
+            let outcome = match kind {
+                MaintenanceKind::Recheck => self.recheck_one(&hash).await,
+                MaintenanceKind::Reannounce => self.reannounce_one(&hash).await,
+            };
+            let Some(job) = self.maintenance.as_mut() else {
+                return;
+            };
+            match outcome {
+                Ok(true) => {
+                    job.done += 1;
+                    job.current = Some(hash);
+                }
+                Ok(false) => job.skipped += 1,
+                Err(err) => job.failures.push((name, format!("{err:#}"))),
+            }
+        }
+        let Some(job) = self.maintenance.as_ref() else {
+            return;
+        };
+        if job.finished() {
+            self.activity.push(format!(
+                "{} finished: {} done, {} skipped, {} failed",
+                job.kind.label(),
+                job.done,
+                job.skipped,
+                job.failures.len()
+            ));
+        }
+    }
+
+    /// Ok(false) when the torrent is gone, held in memory, or its folder is missing.
+    pub(super) async fn recheck_one(&mut self, hash: &str) -> Result<bool> {
+        let Some(t) = self
+            .torrents
+            .iter()
+            .find(|t| t.info_hash.as_deref() == Some(hash))
+        else {
+            return Ok(false);
+        };
+        if self.storage_of(t) == StorageKind::Memory || self.is_missing_files(t) {
+            return Ok(false);
+        }
+        let (id, folder) = (t.id, t.output_folder.clone());
+        let paused = !t.stats.as_ref().is_some_and(|s| {
+            matches!(s.state, TorrentStatsState::Live | TorrentStatsState::Initializing)
+        });
+        let new_id = self
+            .backend
+            .relocate(id, folder, paused)
+            .await
+            .context("error rechecking torrent")?;
+        // Queued starts and power pauses hold ids, which change on re-add.
+        for (_, queued) in self.start_queue.iter_mut().filter(|(_, q)| *q == id) {
+            *queued = new_id;
+        }
+        for held in self.power_paused_ids.iter_mut().filter(|held| **held == id) {
+            *held = new_id;
+        }
+        for held in self.seed_held_ids.iter_mut().filter(|held| **held == id) {
+            *held = new_id;
+        }
+        self.torrent_logs.push(hash, "Rechecking data");
+        self.verifying.insert(hash.to_string());
+        self.refresh();
+        Ok(true)
+    }
+
+    /// Pausing and starting a running torrent sends `stopped` then `started` to its
+    /// trackers. Ok(false) for torrents that aren't running.
+    async fn reannounce_one(&mut self, hash: &str) -> Result<bool> {
+        let Some(t) = self
+            .torrents
+            .iter()
+            .find(|t| t.info_hash.as_deref() == Some(hash))
+        else {
+            return Ok(false);
+        };
+        let live = t
+            .stats
+            .as_ref()
+            .is_some_and(|s| matches!(s.state, TorrentStatsState::Live));
+        if !live {
+            return Ok(false);
+        }
+        let id = t.id;
+        let restart = async {
+            self.backend
+                .pause(id)
+                .await
+                .context("error pausing torrent")?;
+            self.backend
+                .start(id)
+                .await
+                .context("error resuming torrent")
+        };
+        with_timeout(self.config.timeouts.announce_secs, restart).await?;
+        self.torrent_logs.push(hash, "Re-announced to trackers");
+        Ok(true)
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Where an add came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum AddSource {
+    #[default]
+    Manual,
+    /// Picked up from the given watch folder.
+    WatchFolder(String),
+    /// Added by the named RSS rule.
+    Rss(String),
+    /// Added through the API by the named client.
+    Api(String),
+    /// Bulk-imported from the named file.
+    Import(String),
+}
+
+impl std::fmt::Display for AddSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddSource::Manual => write!(f, "manual"),
+            AddSource::WatchFolder(dir) => write!(f, "watch:{dir}"),
+            AddSource::Rss(rule) => write!(f, "rss:{rule}"),
+            AddSource::Api(client) => write!(f, "api:{client}"),
+            AddSource::Import(file) => write!(f, "import:{file}"),
+        }
+    }
+}
+
+/// Where a torrent's data is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKind {
+    #[default]
+    Disk,
+    /// Held in RAM for a quick preview and dropped on quit.
+    Memory,
+}
+
+impl StorageKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            StorageKind::Disk => "disk",
+            StorageKind::Memory => "memory (preview)",
+        }
+    }
+}
+
+/// Locally kept per-torrent data that librqbit doesn't track.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TorrentMeta {
+    pub label: Option<String>,
+    /// Shown instead of the metainfo name.
+    pub display_name: Option<String>,
+    pub notes: Option<String>,
+    /// Unknown for torrents added before sources were tracked.
+    pub source: Option<AddSource>,
+    pub storage: StorageKind,
+    pub added_at: Option<DateTime<Local>>,
+    pub completed_at: Option<DateTime<Local>>,
+    /// Last recheck started by ittybitty (recheck-all or a scrub) that found no errors.
+    pub verified_at: Option<DateTime<Local>>,
+    /// Last refresh that saw the upload count grow, or completion if nothing since.
+    pub last_upload_at: Option<DateTime<Local>>,
+    /// Running (or about to be) at the last refresh; drives the `restore` startup policy.
+    pub was_active: Option<bool>,
+    /// Started with force start: kept running through pause-all, power and load pauses,
+    /// and startup policy until paused by hand.
+    pub force_started: bool,
+}
+
+/// Metadata keyed by info hash, persisted as `metadata.json` in the data dir.
+#[derive(Debug, Default)]
+pub struct MetaStore {
+    items: HashMap<String, TorrentMeta>,
+    path: Option<PathBuf>,
+}
+
+impl MetaStore {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let items = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { items, path }
+    }
+
+    pub fn get(&self, info_hash: &str) -> Option<&TorrentMeta> {
+        self.items.get(info_hash)
+    }
+
+    pub fn label(&self, info_hash: &str) -> Option<&str> {
+        self.get(info_hash).and_then(|m| m.label.as_deref())
+    }
+
+    pub fn notes(&self, info_hash: &str) -> Option<&str> {
+        self.get(info_hash).and_then(|m| m.notes.as_deref())
+    }
+
+    pub fn update(&mut self, info_hash: &str, f: impl FnOnce(&mut TorrentMeta)) -> Result<()> {
+        f(self.items.entry(info_hash.to_string()).or_default());
+        self.save()
+    }
+
+    /// Keeps what was loaded but stops writing changes back.
+    pub fn read_only(&mut self) {
+        self.path = None;
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("failed to create data dir")?;
+        }
+        let text = serde_json::to_string_pretty(&self.items)?;
+        std::fs::write(path, text).context("failed to save torrent metadata")
+    }
+}
@@ -1,10 +1,117 @@
 mod action;
+mod alerts;
+mod adding;
+mod announce_debug;
+mod api;
+mod activity;
+mod archive;
+mod budget;
+mod bus;
+mod cancel;
+mod cast;
+mod columns;
+mod command;
+mod context_menu;
+mod debug;
+mod dir_choices;
+mod duplicates;
 mod effect;
+mod files;
+mod filters;
+mod eta;
+mod speed;
+mod stagger;
+mod startup;
+mod talkers;
+mod tasks;
 mod state;
+mod torrent_file;
+mod torrent_log;
+mod trackers;
+mod rss;
 mod input;
+mod inspector;
+mod links;
+mod load;
+mod lock;
+mod maintenance;
+mod meta;
+mod notes;
+mod notify;
+mod progress;
 mod reducer;
+mod retry;
+mod risky;
+mod rule_tester;
+mod safe_mode;
+mod save_path;
+mod screensaver;
+mod schedule;
+mod scrub;
+mod seeding;
+mod smart_select;
+mod disk;
+mod disk_stats;
 mod effects;
+mod event_hooks;
+mod focus;
+mod info;
+mod groups;
+mod endgame;
+mod hooks;
+mod idle;
+mod import;
+mod paths;
+mod peers;
+mod pending;
+mod power;
+mod problems;
+mod redact;
+mod ui_state;
 mod util;
 
-pub use state::{App, FileEntry, FilePickerState, FilterKind, FocusPanel, Mode, TorrentRow, View};
+pub use alerts::FiringAlert;
+pub use activity::{ActivityEntry, ActivityFilter, ActivityView, Automation, AutomationRun};
+pub use archive::{ArchiveDirection, ArchivedTorrent};
+pub use bus::{EventBus, SessionEvent, StampedEvent};
+pub use cancel::{BusyEffect, EffectCancel};
+pub use cast::{CastFile, CastPicker};
+pub use context_menu::{ContextMenu, MenuItem};
+pub use columns::{COLUMN_NAMES, COLUMNS, ColumnResize};
+pub use debug::DebugStats;
+pub use dir_choices::DirChoice;
+pub use duplicates::{DuplicateCopy, DuplicateGroup, DuplicateReport, RedundantTorrent};
+pub use disk::available_space;
+pub use disk_stats::DiskStats;
+pub use inspector::{InspectorLine, JsonInspector};
+pub use endgame::in_end_game;
+pub use eta::TimelineEntry;
+pub use files::FileLine;
+pub use filters::FilterSummary;
+pub use groups::{GroupBy, ListEntry};
+pub use import::ImportJob;
+pub use info::InfoDetails;
+pub use adding::{AddOperation, AddStage, MagnetPreview};
+pub use maintenance::{MaintenanceJob, MaintenanceKind};
+pub use progress::{ProgressBoard, ProgressEntry};
+pub use retry::PendingRetry;
+pub use rule_tester::{RULE_TESTER_FIELDS, RuleCheck, RuleTester, RuleTesterEdit};
+pub use schedule::{
+    KEEP_LIMITS, SCHEDULE_DAYS, SCHEDULE_HOURS, ScheduleEdit, ScheduleEditor, Week,
+};
+pub use talkers::Talker;
+pub use tasks::{Task, TaskId};
+pub use torrent_file::TorrentFileView;
+pub use trackers::{TrackerLine, TrackerState};
+pub use rss::{FeedStatus, RssMatch, RssOutcome, RuleStatus};
+pub use meta::{AddSource, StorageKind};
+pub use notes::{NotesEdit, NotesEditor};
+pub use peers::PeerLine;
+pub use pending::PendingAdd;
+pub use redact::redact;
+pub use speed::{LimitKind, RateLimits, format_limit};
+pub use state::{
+    App, ExportRequest, FILTERS, FileEntry, FilePickerState, FilterKind, FocusPanel, LabelTarget,
+    Mode, PathReview, PendingPlacement, SessionTotals, TorrentRow, View,
+};
 
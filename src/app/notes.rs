@@ -0,0 +1,107 @@
+/// Multi-line editor state for a torrent's notes. The cursor is a char index into `text`.
+#[derive(Debug, Clone)]
+pub struct NotesEditor {
+    pub info_hash: String,
+    pub title: String,
+    text: String,
+    cursor: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum NotesEdit {
+    Char(char),
+    Newline,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+}
+
+impl NotesEditor {
+    pub fn new(info_hash: String, title: String, text: String) -> Self {
+        let cursor = text.chars().count();
+        Self {
+            info_hash,
+            title,
+            text,
+            cursor,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Zero-based (line, column) of the cursor, in chars.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let before: String = self.text.chars().take(self.cursor).collect();
+        let line = before.matches('\n').count();
+        let col = before.rsplit('\n').next().map_or(0, |l| l.chars().count());
+        (line, col)
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        for c in s.chars().filter(|c| *c == '\n' || !c.is_control()) {
+            self.apply(NotesEdit::Char(c));
+        }
+    }
+
+    pub fn apply(&mut self, edit: NotesEdit) {
+        let len = self.text.chars().count();
+        match edit {
+            NotesEdit::Char(c) => {
+                let idx = self.byte_index(self.cursor);
+                self.text.insert(idx, c);
+                self.cursor += 1;
+            }
+            NotesEdit::Newline => self.apply(NotesEdit::Char('\n')),
+            NotesEdit::Backspace => {
+                if self.cursor > 0 {
+                    let start = self.byte_index(self.cursor - 1);
+                    let end = self.byte_index(self.cursor);
+                    self.text.replace_range(start..end, "");
+                    self.cursor -= 1;
+                }
+            }
+            NotesEdit::Delete => {
+                if self.cursor < len {
+                    let start = self.byte_index(self.cursor);
+                    let end = self.byte_index(self.cursor + 1);
+                    self.text.replace_range(start..end, "");
+                }
+            }
+            NotesEdit::Left => self.cursor = self.cursor.saturating_sub(1),
+            NotesEdit::Right => self.cursor = (self.cursor + 1).min(len),
+            NotesEdit::Up | NotesEdit::Down => {
+                let (line, col) = self.cursor_position();
+                let target = match edit {
+                    NotesEdit::Up if line == 0 => return,
+                    NotesEdit::Up => line - 1,
+                    _ => line + 1,
+                };
+                let lines: Vec<&str> = self.text.split('\n').collect();
+                let Some(target_line) = lines.get(target) else {
+                    return;
+                };
+                let start: usize = lines[..target].iter().map(|l| l.chars().count() + 1).sum();
+                self.cursor = start + col.min(target_line.chars().count());
+            }
+            NotesEdit::Home => {
+                let (_, col) = self.cursor_position();
+                self.cursor -= col;
+            }
+            NotesEdit::End => {
+                let rest = self.text.chars().skip(self.cursor).take_while(|c| *c != '\n');
+                self.cursor += rest.count();
+            }
+        }
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        super::util::cursor_to_byte_index(&self.text, cursor)
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+use super::{state::App, util::desktop_notification};
+
+/// Completions waiting for the next desktop notification.
+#[derive(Debug, Default)]
+pub struct Notifier {
+    pending: Vec<String>,
+    last_sent: Option<Instant>,
+}
+
+impl App {
+    /// Queues a completion for the desktop when `[notifications] desktop` is on.
+    pub(super) fn notify_completed(&mut self, name: &str) {
+        if !self.config.notifications.desktop {
+            return;
+        }
+        // A torrent finishing again (say after a recheck) within the window is one event.
+        if !self.notifier.pending.iter().any(|n| n == name) {
+            self.notifier.pending.push(name.to_string());
+        }
+    }
+
+    /// Sends what is queued unless one went out less than `throttle_secs` ago.
+    pub(super) fn flush_notifications(&mut self) {
+        if self.notifier.pending.is_empty() {
+            return;
+        }
+        let throttle = Duration::from_secs(self.config.notifications.throttle_secs);
+        if self.notifier.last_sent.is_some_and(|at| at.elapsed() < throttle) {
+            return;
+        }
+        let names = std::mem::take(&mut self.notifier.pending);
+        let (title, body) = match names.as_slice() {
+            [name] => ("Download finished".to_string(), name.clone()),
+            _ => (format!("{} downloads finished", names.len()), names.join("\n")),
+        };
+        self.notifier.last_sent = Some(Instant::now());
+        if let Err(err) = desktop_notification(&title, &body) {
+            self.activity.push(format!("Notification: {err:#}"));
+        }
+    }
+}
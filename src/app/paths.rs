@@ -0,0 +1,126 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use librqbit::api::ApiAddTorrentResponse;
+
+use super::state::PendingPlacement;
+
+/// Classic Win32 limit, counting the drive and the terminating NUL.
+pub const MAX_PATH: usize = 259;
+
+const ILLEGAL: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+const RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Reasons Windows would refuse (or silently mangle) a single path component.
+pub fn component_issues(name: &str) -> Vec<String> {
+    let mut issues = Vec::new();
+    if let Some(ch) = name.chars().find(|c| ILLEGAL.contains(c) || c.is_control()) {
+        issues.push(format!("illegal character {ch:?} in {name:?}"));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        issues.push(format!("trailing dot or space in {name:?}"));
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        issues.push(format!("reserved device name {name:?}"));
+    }
+    issues
+}
+
+/// A Windows-safe version of `name`: illegal characters become `-`, trailing dots and
+/// spaces are dropped, and reserved device names get a `_` suffix.
+pub fn sanitize_component(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| if ILLEGAL.contains(&c) || c.is_control() { '-' } else { c })
+        .collect();
+    let mut out = replaced.trim_end_matches(['.', ' ']).to_string();
+    let stem = out.split('.').next().unwrap_or_default().trim_end().to_string();
+    if RESERVED.iter().any(|r| r.eq_ignore_ascii_case(&stem)) {
+        out.insert(stem.len(), '_');
+    }
+    if out.is_empty() {
+        out.push_str("download");
+    }
+    out
+}
+
+/// Length of the longest file path a torrent would create under `folder`.
+pub fn longest_path(folder: &Path, files: &[Vec<String>]) -> usize {
+    files
+        .iter()
+        .map(|components| {
+            components
+                .iter()
+                .fold(folder.to_path_buf(), |path, c| path.join(c))
+                .as_os_str()
+                .len()
+        })
+        .max()
+        .unwrap_or(folder.as_os_str().len())
+}
+
+/// Extended-length (`\\?\`) form of an absolute path, which lifts the MAX_PATH limit.
+pub fn extended_length(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if text.starts_with(r"\\?\") || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match text.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{unc}")),
+        None => PathBuf::from(format!(r"\\?\{text}")),
+    }
+}
+
+/// Problems with an add's destination, plus a sanitized destination to offer instead.
+/// None when everything is fine.
+pub fn review(placement: &PendingPlacement) -> Option<(Vec<String>, PathBuf)> {
+    let mut issues = component_issues(&placement.folder_name);
+    let original = placement.base.join(&placement.folder_name);
+    let bad_files = flagged_files(&original, &placement.response);
+    if !bad_files.is_empty() {
+        issues.push(format!(
+            "{} files have names Windows can't create (marked with ! in the picker)",
+            bad_files.len()
+        ));
+    }
+    let sanitized_name = sanitize_component(&placement.folder_name);
+    let mut sanitized = placement.base.join(&sanitized_name);
+    let components = file_components(&placement.response);
+    let longest = longest_path(&sanitized, &components);
+    if longest > MAX_PATH {
+        issues.push(format!("longest path is {longest} characters (limit {MAX_PATH})"));
+        sanitized = extended_length(&sanitized);
+    }
+    (!issues.is_empty()).then_some((issues, sanitized))
+}
+
+/// Indices of torrent files whose own path components break Windows rules.
+pub fn flagged_files(folder: &Path, response: &ApiAddTorrentResponse) -> HashSet<usize> {
+    let extended = folder.to_string_lossy().starts_with(r"\\?\");
+    file_components(response)
+        .iter()
+        .enumerate()
+        .filter(|(_, components)| {
+            let too_long = longest_path(folder, std::slice::from_ref(components)) > MAX_PATH;
+            components.iter().any(|c| !component_issues(c).is_empty()) || (!extended && too_long)
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+fn file_components(response: &ApiAddTorrentResponse) -> Vec<Vec<String>> {
+    response
+        .details
+        .files
+        .iter()
+        .flatten()
+        .map(|f| f.components.clone())
+        .collect()
+}
@@ -0,0 +1,121 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+use anyhow::{Result, bail};
+
+use super::state::{App, View};
+
+/// Rate samples kept per peer for its sparkline.
+const HISTORY: usize = 30;
+
+/// Per-peer download history of the torrent shown in the Peers view.
+#[derive(Debug, Default)]
+pub struct PeerHistory {
+    torrent_id: Option<usize>,
+    peers: HashMap<String, PeerTrack>,
+    /// Highlighted peer, by address so it stays put when the order changes.
+    selected: Option<String>,
+}
+
+#[derive(Debug)]
+struct PeerTrack {
+    fetched: u64,
+    at: Instant,
+    rates: VecDeque<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerLine {
+    pub addr: String,
+    /// Bytes per second over the last refresh.
+    pub rate: f64,
+    pub fetched: u64,
+    /// Oldest first, at most `HISTORY` samples.
+    pub history: Vec<f64>,
+}
+
+impl App {
+    /// Samples the selected torrent's live peers while the Peers view is open; other
+    /// torrents and views don't pay for the per-peer snapshot.
+    pub(super) fn sample_peers(&mut self, now: Instant) {
+        let id = self.selected_torrent().map(|t| t.id);
+        let Some(id) = id.filter(|_| self.view == View::Peers && !self.config.low_memory) else {
+            self.peer_history = PeerHistory::default();
+            return;
+        };
+        if self.peer_history.torrent_id != Some(id) {
+            self.peer_history = PeerHistory {
+                torrent_id: Some(id),
+                ..PeerHistory::default()
+            };
+        }
+        let samples = self.backend.peer_stats(id).unwrap_or_default();
+        let mut peers = HashMap::with_capacity(samples.len());
+        for sample in samples {
+            let mut track = self.peer_history.peers.remove(&sample.addr).unwrap_or(PeerTrack {
+                fetched: sample.fetched_bytes,
+                at: now,
+                rates: VecDeque::new(),
+            });
+            let secs = now.duration_since(track.at).as_secs_f64();
+            if secs > 0.0 {
+                let bytes = sample.fetched_bytes.saturating_sub(track.fetched);
+                track.rates.push_back(bytes as f64 / secs);
+                if track.rates.len() > HISTORY {
+                    track.rates.pop_front();
+                }
+            }
+            track.fetched = sample.fetched_bytes;
+            track.at = now;
+            peers.insert(sample.addr, track);
+        }
+        self.peer_history.peers = peers;
+    }
+
+    /// Live peers of the selected torrent, fastest first.
+    pub fn peer_lines(&self) -> Vec<PeerLine> {
+        let mut lines: Vec<PeerLine> = self
+            .peer_history
+            .peers
+            .iter()
+            .map(|(addr, track)| PeerLine {
+                addr: addr.clone(),
+                rate: track.rates.back().copied().unwrap_or(0.0),
+                fetched: track.fetched,
+                history: track.rates.iter().copied().collect(),
+            })
+            .collect();
+        lines.sort_by(|a, b| b.rate.total_cmp(&a.rate).then_with(|| a.addr.cmp(&b.addr)));
+        lines
+    }
+
+    /// Row of the highlighted peer in `peer_lines`; the first when it has gone.
+    pub fn peer_selected(&self, lines: &[PeerLine]) -> usize {
+        let selected = self.peer_history.selected.as_deref();
+        lines
+            .iter()
+            .position(|line| Some(line.addr.as_str()) == selected)
+            .unwrap_or(0)
+    }
+
+    pub(super) fn move_peer_selection(&mut self, delta: isize) {
+        let lines = self.peer_lines();
+        let row = self
+            .peer_selected(&lines)
+            .saturating_add_signed(delta)
+            .min(lines.len().saturating_sub(1));
+        self.peer_history.selected = lines.get(row).map(|line| line.addr.clone());
+    }
+
+    pub(super) fn copy_peer_address(&mut self) -> Result<()> {
+        let lines = self.peer_lines();
+        let Some(line) = lines.get(self.peer_selected(&lines)) else {
+            bail!("no peer selected");
+        };
+        crate::tui::copy_to_clipboard(&line.addr)?;
+        self.show_toast(format!("Copied {}", line.addr));
+        Ok(())
+    }
+}
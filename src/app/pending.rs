@@ -0,0 +1,91 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::meta::AddSource;
+
+/// A fully configured add that is waiting for enough free disk space.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAdd {
+    pub magnet: String,
+    pub name: String,
+    pub output_folder: String,
+    pub only_files: Vec<usize>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub source: AddSource,
+    pub bytes: u64,
+    pub queued_at: DateTime<Local>,
+}
+
+/// FIFO of pending adds persisted as `pending_adds.json` in the data dir.
+#[derive(Debug, Default)]
+pub struct PendingAdds {
+    items: Vec<PendingAdd>,
+    path: Option<PathBuf>,
+    /// Set when the head failed to start, so it isn't tried again every tick.
+    held_until: Option<Instant>,
+}
+
+impl PendingAdds {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let items = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            items,
+            path,
+            held_until: None,
+        }
+    }
+
+    pub fn items(&self) -> &[PendingAdd] {
+        &self.items
+    }
+
+    pub fn push(&mut self, add: PendingAdd) -> Result<()> {
+        self.items.push(add);
+        self.save()
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Result<Option<PendingAdd>> {
+        if idx >= self.items.len() {
+            return Ok(None);
+        }
+        let add = self.items.remove(idx);
+        self.save()?;
+        Ok(Some(add))
+    }
+
+    pub fn hold(&mut self, delay: Duration) {
+        self.held_until = Some(Instant::now() + delay);
+    }
+
+    pub fn held(&self) -> bool {
+        self.held_until.is_some_and(|at| at > Instant::now())
+    }
+
+    /// Keeps what was loaded but stops writing changes back.
+    pub fn read_only(&mut self) {
+        self.path = None;
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("failed to create data dir")?;
+        }
+        let text = serde_json::to_string_pretty(&self.items)?;
+        std::fs::write(path, text).context("failed to save pending adds")
+    }
+}
@@ -0,0 +1,66 @@
+use crate::config::PowerConfig;
+
+/// Why the power watcher wants the session paused, if it does.
+pub fn pause_reason(config: &PowerConfig) -> Option<String> {
+    if let Some(threshold) = config.pause_on_battery_below
+        && let Some(percent) = battery_discharging_percent()
+        && percent < threshold
+    {
+        return Some(format!("on battery ({percent}%)"));
+    }
+    if config.pause_on_metered
+        && let Some(iface) = default_route_interface()
+        && config
+            .metered_interfaces
+            .iter()
+            .any(|pattern| interface_matches(pattern, &iface))
+    {
+        return Some(format!("metered connection ({iface})"));
+    }
+    None
+}
+
+/// `wwan*` style prefix patterns, or exact names.
+fn interface_matches(pattern: &str, iface: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => iface.starts_with(prefix),
+        None => pattern == iface,
+    }
+}
+
+/// Lowest charge among batteries that are currently discharging; None when on AC
+/// or when no battery is found.
+#[cfg(target_os = "linux")]
+fn battery_discharging_percent() -> Option<u8> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok();
+    std::fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| read(dir.join("type")).is_some_and(|t| t.trim() == "Battery"))
+        .filter(|dir| read(dir.join("status")).is_some_and(|s| s.trim() == "Discharging"))
+        .filter_map(|dir| read(dir.join("capacity"))?.trim().parse::<u8>().ok())
+        .min()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn battery_discharging_percent() -> Option<u8> {
+    None
+}
+
+/// Interface carrying the IPv4 default route.
+#[cfg(target_os = "linux")]
+fn default_route_interface() -> Option<String> {
+    let table = std::fs::read_to_string("/proc/net/route").ok()?;
+    table.lines().skip(1).find_map(|line| {
+        let mut cols = line.split_whitespace();
+        let iface = cols.next()?;
+        let destination = cols.next()?;
+        (destination == "00000000").then(|| iface.to_string())
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn default_route_interface() -> Option<String> {
+    None
+}
@@ -0,0 +1,98 @@
+use std::{collections::HashSet, path::Path};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::config::ReturnPolicy;
+
+use super::{
+    meta::StorageKind,
+    state::{App, TorrentRow},
+};
+
+impl App {
+    /// Notes torrents whose download folder vanished after data was written, e.g. an
+    /// unmounted drive or files moved away by hand.
+    pub(super) fn check_missing_files(&mut self) {
+        if self.backend.is_simulated() {
+            return;
+        }
+        let missing: HashSet<usize> = self
+            .torrents
+            .iter()
+            .filter(|t| t.stats.as_ref().is_some_and(|s| s.progress_bytes > 0))
+            .filter(|t| self.storage_of(t) == StorageKind::Disk)
+            .filter(|t| !Path::new(&t.output_folder).exists())
+            .map(|t| t.id)
+            .collect();
+        let newly: Vec<usize> = missing.difference(&self.missing_files).copied().collect();
+        for id in newly {
+            self.log_torrent(id, "Download folder missing");
+        }
+        let returned: Vec<usize> = self.missing_files.difference(&missing).copied().collect();
+        for id in returned {
+            let Some(t) = self.torrents.iter().find(|t| t.id == id) else {
+                continue;
+            };
+            let policy = self.config.return_policy(Path::new(&t.output_folder));
+            let message = match policy {
+                ReturnPolicy::Ignore => "Download folder is back",
+                ReturnPolicy::Recheck => "Download folder is back; rechecking",
+                ReturnPolicy::Resume => "Download folder is back; rechecking and resuming",
+            };
+            self.log_torrent(id, message);
+            match policy {
+                ReturnPolicy::Ignore => {}
+                ReturnPolicy::Recheck => self.storage_returned.push((id, true)),
+                ReturnPolicy::Resume => self.storage_returned.push((id, false)),
+            }
+        }
+        self.missing_files = missing;
+    }
+
+    pub fn is_missing_files(&self, t: &TorrentRow) -> bool {
+        self.missing_files.contains(&t.id)
+    }
+
+    /// Points the torrent at data that moved to `output_folder` and rechecks it there.
+    pub(super) async fn relocate(
+        &mut self,
+        id: usize,
+        output_folder: String,
+        paused: bool,
+    ) -> Result<()> {
+        if !self.backend.is_simulated() && !Path::new(&output_folder).is_dir() {
+            return Err(anyhow!("{output_folder} is not a folder"));
+        }
+        let hash = self
+            .torrents
+            .iter()
+            .find(|t| t.id == id)
+            .and_then(|t| t.info_hash.clone());
+        self.backend
+            .relocate(id, output_folder.clone(), paused)
+            .await
+            .context("error relocating torrent")?;
+        self.missing_files.remove(&id);
+        if let Some(hash) = hash {
+            self.torrent_logs
+                .push(&hash, format!("Relocated to {output_folder}, rechecking"));
+        }
+        self.status = "Data located; rechecking".to_string();
+        self.refresh();
+        Ok(())
+    }
+
+    /// Short description of what's wrong with `t`, if anything; rows with one get a badge.
+    pub fn problem_of(&self, t: &TorrentRow) -> Option<String> {
+        if let Some(error) = t.stats.as_ref().and_then(|s| s.error.as_ref()) {
+            return Some(error.clone());
+        }
+        if self.is_missing_files(t) {
+            return Some(format!(
+                "download folder {} is missing; [m] to locate the data",
+                t.output_folder
+            ));
+        }
+        None
+    }
+}
@@ -0,0 +1,113 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use librqbit::TorrentStatsState;
+
+use super::{action::Action, state::App};
+
+/// Progress of one long-running operation, as last reported with [`Action::Progress`].
+#[derive(Debug, Clone)]
+pub struct ProgressEntry {
+    /// 0-100; None while there's nothing to measure yet.
+    pub pct: Option<u8>,
+    pub msg: String,
+    pub started: Instant,
+}
+
+/// Operations in progress by id, e.g. `import`, `maintenance` or `check:<info hash>`.
+#[derive(Debug, Default)]
+pub struct ProgressBoard {
+    entries: BTreeMap<String, ProgressEntry>,
+}
+
+impl ProgressBoard {
+    pub fn report(&mut self, id: String, pct: Option<u8>, msg: String) {
+        let started = self.entries.get(&id).map_or_else(Instant::now, |e| e.started);
+        self.entries.insert(
+            id,
+            ProgressEntry {
+                pct: pct.map(|p| p.min(100)),
+                msg,
+                started,
+            },
+        );
+    }
+
+    pub fn finish(&mut self, id: &str) {
+        self.entries.remove(id);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ProgressEntry> {
+        self.entries.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ProgressEntry)> {
+        self.entries.iter().map(|(id, entry)| (id.as_str(), entry))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// `done` of `total` in percent, rounding down so 100 means finished.
+pub fn percent(done: u64, total: u64) -> u8 {
+    done.saturating_mul(100)
+        .checked_div(total)
+        .map_or(0, |p| p.min(100) as u8)
+}
+
+impl App {
+    /// Progress of the running import, or its removal once it has finished.
+    pub(super) fn import_progress(&self) -> Action {
+        match self.import.as_ref().filter(|job| !job.finished()) {
+            Some(job) => Action::Progress {
+                id: "import".to_string(),
+                pct: Some(percent(job.done() as u64, job.total as u64)),
+                msg: format!("Importing {}: {} of {}", job.file, job.done(), job.total),
+            },
+            None => Action::ProgressDone {
+                id: "import".to_string(),
+            },
+        }
+    }
+
+    pub(super) fn maintenance_progress(&self) -> Action {
+        match self.maintenance.as_ref().filter(|job| !job.finished()) {
+            Some(job) => Action::Progress {
+                id: "maintenance".to_string(),
+                pct: Some(percent(job.handled() as u64, job.total as u64)),
+                msg: format!("{}: {} of {}", job.kind.verb(), job.handled(), job.total),
+            },
+            None => Action::ProgressDone {
+                id: "maintenance".to_string(),
+            },
+        }
+    }
+
+    /// Progress of every torrent checking its data, and removal of checks that are over.
+    pub(super) fn check_progress(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let mut checking = Vec::new();
+        for t in &self.torrents {
+            let (Some(hash), Some(stats)) = (t.info_hash.as_ref(), t.stats.as_ref()) else {
+                continue;
+            };
+            if !matches!(stats.state, TorrentStatsState::Initializing) {
+                continue;
+            }
+            let id = format!("check:{hash}");
+            checking.push(id.clone());
+            actions.push(Action::Progress {
+                id,
+                pct: Some(percent(stats.progress_bytes, stats.total_bytes)),
+                msg: format!("Checking {}", t.name),
+            });
+        }
+        for (id, _) in self.progress.iter() {
+            if id.starts_with("check:") && !checking.iter().any(|c| c == id) {
+                actions.push(Action::ProgressDone { id: id.to_string() });
+            }
+        }
+        actions
+    }
+}
@@ -0,0 +1,114 @@
+use std::{borrow::Cow, ops::Range};
+
+/// Query parameters trackers use for per-user keys.
+const SECRET_PARAMS: [&str; 6] = ["passkey", "authkey", "torrent_pass", "apikey", "token", "pk"];
+
+/// Path segments of announce URLs at least this long that mix letters and digits look
+/// like passkeys, e.g. `/announce/<key>` or `/<key>/announce`.
+const MIN_KEY_SEGMENT: usize = 16;
+
+const MASK: &str = "***";
+
+/// Masks tracker passkeys in any URLs within `text`, including percent-encoded announce
+/// URLs inside magnet links.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let lower = text.to_ascii_lowercase();
+    let mut masks = secret_params(&lower);
+    let mut offset = 0;
+    for token in lower.split_inclusive(char::is_whitespace) {
+        if token.contains("announce") {
+            let shifted = key_segments(token).into_iter();
+            masks.extend(shifted.map(|m| m.start + offset..m.end + offset));
+        }
+        offset += token.len();
+    }
+    if masks.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    masks.sort_by_key(|m| m.start);
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    for mask in masks {
+        if mask.start < pos {
+            continue;
+        }
+        out.push_str(&text[pos..mask.start]);
+        out.push_str(MASK);
+        pos = mask.end;
+    }
+    out.push_str(&text[pos..]);
+    Cow::Owned(out)
+}
+
+fn secret_params(lower: &str) -> Vec<Range<usize>> {
+    let mut masks = Vec::new();
+    for name in SECRET_PARAMS {
+        for (pos, _) in lower.match_indices(name) {
+            let before = &lower[..pos];
+            let starts_param = before
+                .bytes()
+                .last()
+                .is_none_or(|b| !b.is_ascii_alphanumeric() && b != b'_')
+                || before.ends_with("%26")
+                || before.ends_with("%3f");
+            if !starts_param {
+                continue;
+            }
+            let rest = &lower[pos + name.len()..];
+            let skip = if rest.starts_with('=') {
+                1
+            } else if rest.starts_with("%3d") {
+                3
+            } else {
+                continue;
+            };
+            let start = pos + name.len() + skip;
+            let end = start + value_len(&lower[start..]);
+            if end > start {
+                masks.push(start..end);
+            }
+        }
+    }
+    masks
+}
+
+fn key_segments(lower: &str) -> Vec<Range<usize>> {
+    let bytes = lower.as_bytes();
+    let mut masks = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if !bytes[idx].is_ascii_alphanumeric() {
+            idx += 1;
+            continue;
+        }
+        let mut start = idx;
+        while idx < bytes.len() && bytes[idx].is_ascii_alphanumeric() {
+            idx += 1;
+        }
+        // An encoded slash (`%2F`) runs straight into the segment after it.
+        let encoded_slash =
+            start > 0 && bytes[start - 1] == b'%' && lower[start..].starts_with("2f");
+        if encoded_slash {
+            start += 2;
+        }
+        let after_slash = encoded_slash || lower[..start].ends_with('/');
+        let run = &lower[start..idx];
+        // A trailing dot means a host name or file name, not a key.
+        let ends_segment = bytes.get(idx).is_none_or(|&b| b != b'.');
+        if after_slash
+            && ends_segment
+            && run.len() >= MIN_KEY_SEGMENT
+            && run.bytes().any(|b| b.is_ascii_digit())
+            && run.bytes().any(|b| b.is_ascii_alphabetic())
+        {
+            masks.push(start..idx);
+        }
+    }
+    masks
+}
+
+fn value_len(rest: &str) -> usize {
+    rest.bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+        .count()
+}
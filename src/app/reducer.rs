@@ -1,20 +1,156 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Result, anyhow};
 
 use super::{
     action::Action,
+    cancel::Cancelled,
     effect::Effect,
     state::Dialog,
     FocusPanel,
+    LabelTarget,
     Mode,
 };
-use super::state::App;
+use super::{
+    View,
+    columns::{COLUMNS, ColumnResize},
+    meta::StorageKind,
+    notes::NotesEditor,
+    state::App,
+};
+use crate::config::{CollisionStrategy, QuitConfirm};
+
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a first `q` waits for the second with `quit = "twice"`.
+const QUIT_WINDOW: Duration = Duration::from_secs(1);
 
 impl App {
     pub async fn handle_event(&mut self, ev: crossterm::event::Event) -> Result<bool> {
-        let mut queue: VecDeque<Action> = self.actions_from_event(ev).into();
+        if self.note_input(&ev) {
+            return Ok(false);
+        }
+        let actions = self.actions_from_event(ev);
+        self.dispatch(actions).await
+    }
+
+    pub async fn tick(&mut self) -> Result<bool> {
+        let refresh_due = self
+            .last_refresh
+            .is_none_or(|at| at.elapsed() >= self.refresh_interval());
+        if refresh_due {
+            self.refresh();
+        }
+        self.poll_adds().await;
+        self.poll_trackers().await;
+        self.poll_announce_debug().await;
+        self.poll_archive().await;
+        self.poll_completions().await;
+        self.poll_event_hooks().await;
+        self.poll_rss().await;
+        self.poll_api().await;
+        self.check_idle();
+        self.apply_speed_schedule();
+        if self.quit_pending_at.is_some_and(|at| at.elapsed() >= QUIT_WINDOW) {
+            self.quit_pending_at = None;
+            self.toast = None;
+        }
+        let mut actions = Vec::new();
+        if self.startup_pending && self.session_settled() {
+            self.startup_pending = false;
+            actions.extend(self.startup_actions());
+        }
+        actions.extend(self.scheduled_actions());
+        actions.extend(self.api_actions());
+        actions.extend(self.power_actions());
+        actions.extend(self.seeding_actions());
+        self.dispatch(actions).await
+    }
+
+    fn scheduled_actions(&mut self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let due = self.due_starts();
+        if !due.is_empty() {
+            actions.push(Action::RunEffect(Effect::StartTorrents(due)));
+        }
+        if self.pending_add_ready() {
+            actions.push(Action::RunEffect(Effect::StartPendingAdd));
+        }
+        if self.import_pending() {
+            actions.push(Action::RunEffect(Effect::ImportNext));
+        }
+        if self.rss_pending() {
+            actions.push(Action::RunEffect(Effect::RssNext));
+        }
+        if self.maintenance_pending() {
+            actions.push(Action::RunEffect(Effect::MaintenanceNext));
+        }
+        actions.extend(self.check_progress());
+        if let Some(effect) = self.due_retry() {
+            actions.push(Action::RunEffect(effect));
+        }
+        if let Some(hash) = self.scrub_due() {
+            actions.push(Action::RunEffect(Effect::Scrub(hash)));
+        }
+        if let Some(effect) = self.due_busy() {
+            actions.push(Action::RunEffect(effect));
+        }
+        for (id, paused) in std::mem::take(&mut self.storage_returned) {
+            if let Some(t) = self.torrents.iter().find(|t| t.id == id) {
+                actions.push(Action::RunEffect(Effect::Relocate {
+                    id,
+                    output_folder: t.output_folder.clone(),
+                    paused,
+                }));
+            }
+        }
+        for (id, output_folder) in std::mem::take(&mut self.completed_moves) {
+            actions.push(Action::RunEffect(Effect::MoveCompleted { id, output_folder }));
+        }
+        actions
+    }
+
+    fn power_actions(&mut self) -> Vec<Action> {
+        if !(self.config.power.enabled() || self.config.load.enabled())
+            || self
+                .last_power_check
+                .is_some_and(|at| at.elapsed() < POWER_CHECK_INTERVAL)
+        {
+            return Vec::new();
+        }
+        self.last_power_check = Some(Instant::now());
+        let reason = super::power::pause_reason(&self.config.power)
+            .or_else(|| super::load::pause_reason(&self.config.load));
+        if reason.is_some() {
+            self.pause_clear_since = None;
+        }
+        match (reason, &self.power_pause) {
+            (Some(reason), None) => vec![Action::RunEffect(Effect::PowerPause { reason })],
+            (None, Some(_)) => {
+                // Load averages dip between bursts, so only resume once they've stayed low.
+                let clear_since = *self.pause_clear_since.get_or_insert_with(Instant::now);
+                let settle = if self.config.load.enabled() {
+                    Duration::from_secs(self.config.load.resume_after_secs)
+                } else {
+                    Duration::ZERO
+                };
+                if clear_since.elapsed() >= settle {
+                    self.pause_clear_since = None;
+                    vec![Action::RunEffect(Effect::PowerResume)]
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    async fn dispatch(&mut self, actions: Vec<Action>) -> Result<bool> {
+        let mut queue: VecDeque<Action> = actions.into();
         while let Some(action) = queue.pop_front() {
+            self.debug.record_queue_depth(queue.len() + 1);
             if let Some(quit) = self.apply_action(action, &mut queue).await? {
                 return Ok(quit);
             }
@@ -29,7 +165,11 @@ impl App {
     ) -> Result<Option<bool>> {
         match action {
             Action::Paste(text) => {
-                if matches!(self.mode, Mode::EnterMagnet | Mode::EnterTorrentDir) {
+                if let Some(editor) = &mut self.notes_editor {
+                    editor.insert_str(&text);
+                } else if let Some(tester) = &mut self.rule_tester {
+                    tester.insert_str(&text);
+                } else if self.mode.is_text_input() {
                     self.input = text;
                     self.input_cursor = self.input.chars().count();
                 } else {
@@ -37,6 +177,9 @@ impl App {
                     self.status = "Paste ignored".to_string();
                 }
             }
+            Action::DebugToggle => {
+                self.debug.visible = !self.debug.visible;
+            }
             Action::HelpOpen => {
                 self.show_help = true;
                 self.help_scroll = 0;
@@ -68,16 +211,13 @@ impl App {
                 self.delete_choice = choice;
             }
             Action::ConfirmDeleteConfirm => {
-                if self.delete_choice {
-                    self.confirm_delete = false;
-                    self.delete_choice = false;
-                    self.dialog = Dialog::None;
-                    queue.push_back(Action::RunEffect(Effect::DeleteSelectedFiles));
-                } else {
-                    self.confirm_delete = false;
-                    self.delete_choice = false;
-                    self.dialog = Dialog::None;
-                    queue.push_back(Action::RunEffect(Effect::StopSelected));
+                let delete_files = self.delete_choice;
+                self.confirm_delete = false;
+                self.delete_choice = false;
+                self.dialog = Dialog::None;
+                if let Some(t) = self.selected_torrent() {
+                    let id = t.id;
+                    queue.push_back(Action::RunEffect(Effect::Remove { id, delete_files }));
                 }
             }
             Action::ConfirmDeleteCancel => {
@@ -86,6 +226,21 @@ impl App {
                 self.status = "Delete cancelled".to_string();
                 self.dialog = Dialog::None;
             }
+            Action::Lock => self.lock_input(),
+            Action::CycleTheme => self.cycle_theme(),
+            Action::Unlock => self.unlock_input(),
+            Action::Focus(focused) => self.set_focused(focused),
+            Action::QuitPressed => match self.quit_confirm {
+                QuitConfirm::Dialog => queue.push_back(Action::ConfirmQuitOpen),
+                QuitConfirm::Twice => {
+                    if self.quit_pending_at.is_some_and(|at| at.elapsed() < QUIT_WINDOW) {
+                        self.forget_previews().await;
+                        return Ok(Some(true));
+                    }
+                    self.quit_pending_at = Some(Instant::now());
+                    self.show_toast("Press q again to quit".to_string());
+                }
+            },
             Action::ConfirmQuitOpen => {
                 self.confirm_quit = true;
                 self.quit_choice = false;
@@ -96,6 +251,7 @@ impl App {
             }
             Action::ConfirmQuitConfirm => {
                 if self.quit_choice {
+                    self.forget_previews().await;
                     return Ok(Some(true));
                 }
                 self.confirm_quit = false;
@@ -109,8 +265,271 @@ impl App {
                 self.status = "Quit cancelled".to_string();
                 self.dialog = Dialog::None;
             }
+            Action::ConfirmSpaceOpen { add, free } => {
+                self.confirm_space = Some(add);
+                self.space_free = free;
+                self.space_choice = true;
+                self.dialog = Dialog::ConfirmSpace;
+            }
+            Action::ConfirmSpaceSelect(choice) => {
+                self.space_choice = choice;
+            }
+            Action::ConfirmSpaceConfirm => {
+                let add = self.confirm_space.take();
+                self.dialog = Dialog::None;
+                match add {
+                    Some(add) if self.space_choice => {
+                        let name = add.name.clone();
+                        self.pending_adds.push(add)?;
+                        self.activity
+                            .push(format!("Queued add waiting for space: {name}"));
+                        self.status = "Queued: waiting for free space".to_string();
+                    }
+                    _ => {
+                        self.status = "Add cancelled: not enough free space".to_string();
+                    }
+                }
+            }
+            Action::ConfirmSpaceCancel => {
+                self.confirm_space = None;
+                self.status = "Add cancelled: not enough free space".to_string();
+                self.dialog = Dialog::None;
+            }
+            Action::CollisionSelect(delta) => {
+                let last = CollisionStrategy::CHOICES.len() as isize - 1;
+                self.collision_choice =
+                    (self.collision_choice as isize + delta).clamp(0, last) as usize;
+            }
+            Action::CollisionConfirm(choice) => {
+                let idx = choice.unwrap_or(self.collision_choice);
+                if let Some(&strategy) = CollisionStrategy::CHOICES.get(idx) {
+                    self.dialog = Dialog::None;
+                    queue.push_back(Action::RunEffect(Effect::ResolveCollision(strategy)));
+                }
+            }
+            Action::ImportCancel => {
+                if let Some(job) = &mut self.import {
+                    job.cancelled = true;
+                    self.status = format!("Import cancelled after {} of {}", job.done(), job.total);
+                }
+                queue.push_back(self.import_progress());
+            }
+            Action::ImportClose => {
+                if self.import.as_ref().is_some_and(|job| job.finished()) {
+                    self.import = None;
+                    self.dialog = Dialog::None;
+                }
+            }
+            Action::MaintenanceConfirmSelect(choice) => {
+                self.maintenance_choice = choice;
+            }
+            Action::MaintenanceConfirm => {
+                let kind = self.confirm_maintenance.take();
+                self.dialog = Dialog::None;
+                match kind {
+                    Some(kind) if self.maintenance_choice => self.start_maintenance(kind),
+                    Some(kind) => self.status = format!("{} cancelled", kind.label()),
+                    None => {}
+                }
+            }
+            Action::MaintenanceConfirmCancel => {
+                if let Some(kind) = self.confirm_maintenance.take() {
+                    self.status = format!("{} cancelled", kind.label());
+                }
+                self.dialog = Dialog::None;
+            }
+            Action::MaintenanceCancel => {
+                if let Some(job) = &mut self.maintenance {
+                    job.cancelled = true;
+                    self.status = format!(
+                        "{} stopped after {} of {}",
+                        job.kind.label(),
+                        job.handled(),
+                        job.total
+                    );
+                }
+            }
+            Action::MaintenanceClose => {
+                if self.maintenance.as_ref().is_some_and(|job| job.finished()) {
+                    self.maintenance = None;
+                    self.dialog = Dialog::None;
+                }
+            }
+            Action::Progress { id, pct, msg } => {
+                self.progress.report(id, pct, msg);
+            }
+            Action::ProgressDone { id } => {
+                self.progress.finish(&id);
+            }
+            Action::RetryNow => {
+                self.retry_now();
+            }
+            Action::RetryCancel => {
+                self.cancel_retry();
+            }
+            Action::TalkersOpen => self.open_talkers(),
+            Action::TalkersClose => self.close_talkers(),
+            Action::TalkersMove(delta) => self.move_talker_selection(delta),
+            Action::TalkersSort => self.toggle_talker_sort(),
+            Action::TalkersJump => self.jump_to_talker(),
+            Action::TasksOpen => self.open_tasks(),
+            Action::TasksClose => self.close_tasks(),
+            Action::TasksMove(delta) => self.move_task_selection(delta),
+            Action::TasksCancel => {
+                self.cancel_selected_task();
+                queue.push_back(self.import_progress());
+                queue.push_back(self.maintenance_progress());
+            }
+            Action::DuplicatesClose => self.close_duplicates(),
+            Action::DuplicatesScroll(delta) => {
+                if let Some(report) = &mut self.duplicates {
+                    report.scroll = report.scroll.saturating_add_signed(delta as i16);
+                }
+            }
+            Action::DirChoiceMove(delta) => self.move_dir_choice(delta),
+            Action::TorrentFileClose => self.close_torrent_file(),
+            Action::TorrentFileScroll(delta) => self.scroll_torrent_file(delta),
+            Action::TorrentFileAdd => {
+                if let Err(err) = self.add_torrent_file() {
+                    self.set_error(err);
+                }
+            }
+            Action::ArchiveOpen => {
+                if let Err(err) = self.open_archive() {
+                    self.show_toast(format!("{err:#}"));
+                }
+            }
+            Action::ArchiveClose => self.close_archive(),
+            Action::ArchiveMove(delta) => self.move_archive_selection(delta),
+            Action::ArchiveRestore => {
+                if let Err(err) = self.restore_archived() {
+                    self.show_toast(format!("{err:#}"));
+                }
+            }
+            Action::RuleTesterOpen => self.open_rule_tester(),
+            Action::RuleTesterEdit(edit) => {
+                if let Some(tester) = &mut self.rule_tester {
+                    tester.apply(edit);
+                }
+            }
+            Action::RuleTesterClose => self.close_rule_tester(),
+            Action::ActivityOpen(filter) => self.open_activity(filter),
+            Action::ActivityFilterNext => self.cycle_activity_filter(),
+            Action::ActivityScroll(delta) => self.scroll_activity(delta),
+            Action::ActivityClose => self.close_activity(),
+            Action::BusyCancel => {
+                self.busy = None;
+                self.retry = None;
+                self.effect_cancelled();
+            }
+            Action::CollisionCancel => {
+                self.collision = None;
+                self.status = "Add cancelled: folder exists".to_string();
+                self.dialog = Dialog::None;
+            }
+            Action::MagnetPreviewFetch => {
+                if let Err(err) = self.fetch_magnet_preview() {
+                    self.set_error(err);
+                }
+            }
+            Action::MagnetPreviewEdit => self.edit_magnet_preview(),
+            Action::MagnetPreviewReject => self.reject_magnet_preview(),
+            Action::PathReviewSelect(sanitize) => {
+                self.path_review_choice = sanitize;
+            }
+            Action::PathReviewConfirm => {
+                self.dialog = Dialog::None;
+                queue.push_back(Action::RunEffect(Effect::ResolvePathReview {
+                    sanitize: self.path_review_choice,
+                }));
+            }
+            Action::PathReviewCancel => {
+                self.path_review = None;
+                self.status = "Add cancelled".to_string();
+                self.dialog = Dialog::None;
+            }
+            Action::InspectorOpen => {
+                queue.push_back(Action::RunEffect(Effect::InspectSelected));
+            }
+            Action::InspectorClose => {
+                self.inspector = None;
+                self.dialog = Dialog::None;
+            }
+            Action::InspectorMove(delta) => {
+                if let Some(inspector) = &mut self.inspector {
+                    inspector.move_cursor(delta);
+                }
+            }
+            Action::InspectorToggleFold => {
+                if let Some(inspector) = &mut self.inspector {
+                    inspector.toggle_fold();
+                }
+            }
+            Action::InspectorFoldAll(collapsed) => {
+                if let Some(inspector) = &mut self.inspector {
+                    inspector.set_all_collapsed(collapsed);
+                }
+            }
+            Action::InspectorSearchStart => {
+                self.mode = Mode::EnterSearch;
+                self.input = self
+                    .inspector
+                    .as_ref()
+                    .and_then(|i| i.query())
+                    .unwrap_or_default()
+                    .to_string();
+                self.input_cursor = self.input.chars().count();
+                self.status = "Search keys and values".to_string();
+            }
+            Action::InspectorFind(forward) => {
+                if let Some(inspector) = &mut self.inspector
+                    && inspector.query().is_some()
+                    && !inspector.find(forward)
+                {
+                    self.status = "No matches".to_string();
+                }
+            }
+            Action::ColumnResizeOpen => {
+                self.view = View::Torrents;
+                self.column_resize = Some(ColumnResize {
+                    column: 0,
+                    original: self.columns.widths(),
+                });
+                self.status = "Resizing columns (Enter saves, Esc cancels)".to_string();
+                self.dialog = Dialog::ColumnResize;
+            }
+            Action::ColumnResizeSelect(delta) => {
+                if let Some(resize) = &mut self.column_resize {
+                    resize.column = (resize.column as isize + delta).rem_euclid(COLUMNS as isize)
+                        as usize;
+                }
+            }
+            Action::ColumnResize(delta) => {
+                if let Some(resize) = &self.column_resize {
+                    self.columns.resize(resize.column, delta);
+                }
+            }
+            Action::ColumnResizeReset => {
+                self.columns.reset();
+            }
+            Action::ColumnResizeSave => {
+                self.column_resize = None;
+                self.dialog = Dialog::None;
+                self.columns.save()?;
+                self.status = "Column widths saved".to_string();
+            }
+            Action::ColumnResizeCancel => {
+                if let Some(resize) = self.column_resize.take() {
+                    self.columns.set(resize.original);
+                }
+                self.status = "Column widths unchanged".to_string();
+                self.dialog = Dialog::None;
+            }
             Action::ViewSet(view) => {
                 self.view = view;
+                self.info_scroll = 0;
+                self.sample_files();
+                self.sample_info();
             }
             Action::FocusToggle => {
                 self.focus = match self.focus {
@@ -124,22 +543,75 @@ impl App {
             Action::MoveSelection(delta) => {
                 self.move_selection(delta);
             }
+            Action::FilesMove(delta) => {
+                self.move_file_selection(delta);
+            }
+            Action::FilesOpen => self.open_files_view(),
+            Action::ContextMenuOpen => self.open_context_menu(),
+            Action::ContextMenuMove(delta) => self.move_context_menu(delta),
+            Action::ContextMenuConfirm => queue.extend(self.context_menu_actions()?),
+            Action::ContextMenuClose => self.close_context_menu(),
+            Action::ScheduleOpen => self.open_schedule()?,
+            Action::ScheduleEdit(edit) => self.edit_schedule(edit),
+            Action::ScheduleSave => self.save_schedule()?,
+            Action::ScheduleClose => self.close_schedule(),
+            Action::FileOpenSystem => self.open_selected_file()?,
+            Action::CopyStreamUrl => {
+                queue.push_back(Action::RunEffect(Effect::CopyStreamUrl));
+            }
+            Action::PeersMove(delta) => self.move_peer_selection(delta),
+            Action::CopyPeerAddress => self.copy_peer_address()?,
+            Action::InfoScroll(delta) => {
+                self.info_scroll = self.info_scroll.saturating_add_signed(delta);
+            }
+            Action::InfoTop => self.info_scroll = 0,
+            Action::CopyInfoHash => self.copy_info_hash()?,
+            Action::CastOpen => match self.cast_file() {
+                Ok(file) => queue.push_back(Action::RunEffect(Effect::DiscoverCasts(file))),
+                Err(err) => self.show_toast(format!("{err:#}")),
+            },
+            Action::CastMove(delta) => self.move_cast_selection(delta),
+            Action::CastConfirm => {
+                queue.push_back(Action::RunEffect(Effect::CastSelected));
+            }
+            Action::CastRefresh => {
+                if let Some(picker) = &self.cast {
+                    let file = picker.file.clone();
+                    queue.push_back(Action::RunEffect(Effect::DiscoverCasts(file)));
+                }
+            }
+            Action::CastClose => self.close_cast(),
+            Action::GroupCycle => {
+                self.cycle_group_by();
+            }
+            Action::GroupToggle => {
+                self.toggle_group();
+            }
             Action::MoveFilter(delta) => {
                 if delta.is_negative() {
                     self.filter_index = self.filter_index.saturating_sub(delta.unsigned_abs());
                 } else {
                     self.filter_index =
-                        (self.filter_index + delta as usize).min(super::state::FILTERS.len() - 1);
+                        (self.filter_index + delta as usize).min(self.filter_rows() - 1);
                 }
                 self.ensure_selection_for_filter();
             }
             Action::SetFilter(index) => {
-                self.filter_index = index.min(super::state::FILTERS.len() - 1);
+                self.filter_index = index.min(self.filter_rows() - 1);
                 self.ensure_selection_for_filter();
             }
+            Action::RateLimitBump(kind, steps) => {
+                self.bump_rate_limit(kind, steps);
+            }
+            Action::RateLimitsClear => {
+                self.clear_rate_limits();
+            }
             Action::TogglePause => {
                 queue.push_back(Action::RunEffect(Effect::TogglePause));
             }
+            Action::ForceStart => {
+                queue.push_back(Action::RunEffect(Effect::ForceStart));
+            }
             Action::StartAdd => {
                 self.mode = Mode::EnterMagnet;
                 self.input.clear();
@@ -147,6 +619,82 @@ impl App {
                 self.status = "Paste magnet/URL/path and press Enter".to_string();
                 self.dialog = Dialog::AddTorrent;
             }
+            Action::StartCommand => {
+                self.mode = Mode::EnterCommand;
+                self.input.clear();
+                self.input_cursor = 0;
+                self.status = "Enter a command, e.g. set refresh 2s".to_string();
+                self.dialog = Dialog::AddTorrent;
+            }
+            Action::StartRename => {
+                let Some(t) = self.selected_torrent() else {
+                    return Ok(None);
+                };
+                self.input = t.name.clone();
+                self.input_cursor = self.input.chars().count();
+                self.mode = Mode::EnterName;
+                self.status = "Enter a display name (empty restores the original)".to_string();
+                self.dialog = Dialog::AddTorrent;
+            }
+            Action::StartLocate => {
+                let Some(t) = self.selected_torrent() else {
+                    return Ok(None);
+                };
+                self.input = t.output_folder.clone();
+                self.input_cursor = self.input.chars().count();
+                self.mode = Mode::EnterLocation;
+                self.status = "Enter the folder that now holds this torrent's data".to_string();
+                self.dialog = Dialog::AddTorrent;
+            }
+            Action::NotesOpen => {
+                let Some(t) = self.selected_torrent() else {
+                    return Ok(None);
+                };
+                let Some(info_hash) = t.info_hash.clone() else {
+                    return Ok(None);
+                };
+                let text = self.notes_of(t).unwrap_or_default().to_string();
+                self.notes_editor = Some(NotesEditor::new(info_hash, t.name.clone(), text));
+                self.status = "Editing notes (Ctrl+S to save, Esc to discard)".to_string();
+                self.dialog = Dialog::Notes;
+            }
+            Action::NotesEdit(edit) => {
+                if let Some(editor) = &mut self.notes_editor {
+                    editor.apply(edit);
+                }
+            }
+            Action::NotesSave => {
+                if let Some(editor) = self.notes_editor.take() {
+                    let text = editor.text().trim_end().to_string();
+                    self.dialog = Dialog::None;
+                    queue.push_back(Action::RunEffect(Effect::SetNotes {
+                        info_hash: editor.info_hash,
+                        notes: (!text.is_empty()).then_some(text),
+                    }));
+                }
+            }
+            Action::NotesCancel => {
+                self.notes_editor = None;
+                self.status = "Notes discarded".to_string();
+                self.dialog = Dialog::None;
+            }
+            Action::StartLabelEdit(target) => {
+                let current = match target {
+                    LabelTarget::Selected => {
+                        let Some(t) = self.selected_torrent() else {
+                            return Ok(None);
+                        };
+                        self.label_of(t).map(str::to_string)
+                    }
+                    LabelTarget::Picker => self.file_picker.as_ref().and_then(|p| p.label.clone()),
+                };
+                self.label_target = target;
+                self.mode = Mode::EnterLabel;
+                self.input = current.unwrap_or_default();
+                self.input_cursor = self.input.chars().count();
+                self.status = "Enter a label (empty clears it)".to_string();
+                self.dialog = Dialog::AddTorrent;
+            }
             Action::InputChar(c) => {
                 self.insert_char(c);
             }
@@ -182,18 +730,80 @@ impl App {
                         } else if let Err(err) = super::util::build_add_torrent(&value) {
                             self.set_error(err);
                         } else {
-                            self.status = "Checking torrent...".to_string();
                             self.dialog = Dialog::None;
-                            queue.push_back(Action::RunEffect(Effect::PreflightAdd {
-                                magnet: value,
+                            if let Err(err) = self.submit_add(value) {
+                                self.set_error(err);
+                            }
+                        }
+                    }
+                    Mode::EnterLabel => {
+                        let label = (!value.is_empty()).then_some(value);
+                        match self.label_target {
+                            LabelTarget::Picker => {
+                                if let Err(err) = self.set_picker_label(label) {
+                                    self.show_toast(format!("Label not set: {err:#}"));
+                                }
+                                self.mode = Mode::FilePicker;
+                                self.status = "Select files and press Enter".to_string();
+                                self.dialog = Dialog::FilePicker;
+                            }
+                            LabelTarget::Selected => {
+                                self.dialog = Dialog::None;
+                                if let Some(info_hash) =
+                                    self.selected_torrent().and_then(|t| t.info_hash.clone())
+                                {
+                                    queue.push_back(Action::RunEffect(Effect::SetLabel {
+                                        info_hash,
+                                        label,
+                                    }));
+                                }
+                            }
+                        }
+                    }
+                    Mode::EnterName => {
+                        self.dialog = Dialog::None;
+                        if let Some(t) = self.selected_torrent()
+                            && let Some(info_hash) = t.info_hash.clone()
+                        {
+                            let name = (!value.is_empty() && value != t.original_name)
+                                .then_some(value);
+                            queue.push_back(Action::RunEffect(Effect::SetDisplayName {
+                                info_hash,
+                                name,
                             }));
                         }
                     }
+                    Mode::EnterLocation => {
+                        self.dialog = Dialog::None;
+                        if value.is_empty() {
+                            self.set_error("Folder cannot be empty");
+                        } else if let Some(t) = self.selected_torrent() {
+                            queue.push_back(Action::RunEffect(Effect::Relocate {
+                                id: t.id,
+                                output_folder: value,
+                                paused: false,
+                            }));
+                        }
+                    }
+                    Mode::EnterCommand => {
+                        self.dialog = Dialog::None;
+                        queue.extend(self.run_command(&value)?);
+                    }
+                    Mode::EnterSearch => {
+                        if let Some(inspector) = &mut self.inspector {
+                            inspector.set_query((!value.is_empty()).then_some(value));
+                            if inspector.query().is_some() && !inspector.find(true) {
+                                self.status = "No matches".to_string();
+                            } else {
+                                self.status = "Ready".to_string();
+                            }
+                        }
+                    }
                     Mode::EnterTorrentDir => {
                         let add_input = self
-                            .pending_add_input
-                            .take()
-                            .ok_or_else(|| anyhow!("missing pending torrent input"))?;
+                            .take_prompting_add()
+                            .ok_or_else(|| anyhow!("missing pending torrent input"))?
+                            .input;
                         let output_folder = if value.is_empty() {
                             self.download_dir.to_string_lossy().into_owned()
                         } else {
@@ -213,20 +823,35 @@ impl App {
                 }
             }
             Action::InputCancel => {
-                if self.mode == Mode::EnterTorrentDir {
-                    if let Some(add_input) = self.pending_add_input.take() {
-                        let output_folder = self.download_dir.to_string_lossy().into_owned();
-                        self.input.clear();
-                        self.input_cursor = 0;
-                        self.status = "Fetching metadata...".to_string();
-                        self.last_error = None;
-                        self.dialog = Dialog::AddTorrent;
-                        queue.push_back(Action::RunEffect(Effect::StartFilePicker {
-                            magnet: add_input,
-                            output_folder,
-                        }));
-                        return Ok(None);
-                    }
+                if self.mode == Mode::EnterTorrentDir
+                    && let Some(add_input) = self.take_prompting_add().map(|op| op.input)
+                {
+                    let output_folder = self.download_dir.to_string_lossy().into_owned();
+                    self.input.clear();
+                    self.input_cursor = 0;
+                    self.status = "Fetching metadata...".to_string();
+                    self.last_error = None;
+                    self.dialog = Dialog::AddTorrent;
+                    queue.push_back(Action::RunEffect(Effect::StartFilePicker {
+                        magnet: add_input,
+                        output_folder,
+                    }));
+                    return Ok(None);
+                }
+                if self.mode == Mode::EnterLabel && self.label_target == LabelTarget::Picker {
+                    self.mode = Mode::FilePicker;
+                    self.input.clear();
+                    self.input_cursor = 0;
+                    self.status = "Select files and press Enter".to_string();
+                    self.dialog = Dialog::FilePicker;
+                    return Ok(None);
+                }
+                if self.mode == Mode::EnterSearch {
+                    self.mode = Mode::Normal;
+                    self.input.clear();
+                    self.input_cursor = 0;
+                    self.status = "Ready".to_string();
+                    return Ok(None);
                 }
                 self.mode = Mode::Normal;
                 self.input.clear();
@@ -246,17 +871,17 @@ impl App {
                 }
             }
             Action::FilePickerDown => {
-                if let Some(picker) = &mut self.file_picker {
-                    if !picker.files.is_empty() {
-                        picker.cursor = (picker.cursor + 1).min(picker.files.len() - 1);
-                    }
+                if let Some(picker) = &mut self.file_picker
+                    && !picker.files.is_empty()
+                {
+                    picker.cursor = (picker.cursor + 1).min(picker.files.len() - 1);
                 }
             }
             Action::FilePickerToggle => {
-                if let Some(picker) = &mut self.file_picker {
-                    if let Some(file) = picker.files.get_mut(picker.cursor) {
-                        file.included = !file.included;
-                    }
+                if let Some(picker) = &mut self.file_picker
+                    && let Some(file) = picker.files.get_mut(picker.cursor)
+                {
+                    file.included = !file.included;
                 }
             }
             Action::FilePickerAll => {
@@ -273,40 +898,51 @@ impl App {
                     }
                 }
             }
-            Action::FilePickerConfirm => {
-                if let Some(picker) = &self.file_picker {
-                    let magnet = picker.magnet.clone();
-                    let output_folder = picker.output_folder.clone();
-                    let only_files: Vec<usize> = picker
-                        .files
-                        .iter()
-                        .enumerate()
-                        .filter_map(|(idx, file)| if file.included { Some(idx) } else { None })
-                        .collect();
-                    queue.push_back(Action::RunEffect(Effect::StartDownload {
-                        magnet,
-                        output_folder,
-                        only_files,
-                    }));
+            Action::FilePickerSmart => self.smart_select_files(),
+            Action::FilePickerToggleStorage => {
+                if let Some(picker) = &mut self.file_picker {
+                    picker.storage = match picker.storage {
+                        StorageKind::Disk => StorageKind::Memory,
+                        StorageKind::Memory => StorageKind::Disk,
+                    };
                 }
             }
+            Action::FilePickerConfirm => queue.extend(self.confirm_file_picker()),
+            Action::RiskyFilesSelect(choice) => self.select_risky_choice(choice),
+            Action::RiskyFilesConfirm => queue.extend(self.answer_risky_files()),
+            Action::RiskyFilesCancel => self.cancel_risky_files(),
             Action::Refresh => {
                 queue.push_back(Action::RunEffect(Effect::Refresh));
             }
             Action::RunEffect(effect) => {
-                let next = self.run_effect(effect).await?;
+                if self.defer_busy(&effect) {
+                    return Ok(None);
+                }
+                let name = effect.name();
+                let retryable = effect.retries().then(|| effect.clone());
+                if effect.cancellable() {
+                    self.effect_cancel.arm();
+                }
+                let started = Instant::now();
+                let next = self.run_effect(effect).await;
+                self.effect_cancel.disarm();
+                self.finish_busy();
+                self.debug.record_effect(name, started.elapsed());
+                let next = match next {
+                    Err(err) if err.is::<Cancelled>() => {
+                        self.effect_cancelled();
+                        Ok(Vec::new())
+                    }
+                    next => next,
+                };
+                let next = match retryable {
+                    Some(effect) => self.retry_outcome(effect, next)?,
+                    None => next?,
+                };
                 for action in next {
                     queue.push_back(action);
                 }
             }
-            Action::PreflightAddResult { magnet } => {
-                self.pending_add_input = Some(magnet);
-                self.mode = Mode::EnterTorrentDir;
-                self.input = self.download_dir.to_string_lossy().into_owned();
-                self.input_cursor = self.input.chars().count();
-                self.status = "Set download dir for this torrent".to_string();
-                self.dialog = Dialog::AddTorrent;
-            }
         }
         Ok(None)
     }
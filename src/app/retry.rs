@@ -0,0 +1,260 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Result, anyhow};
+
+use super::{
+    action::Action,
+    effect::Effect,
+    state::{App, Dialog, Mode},
+};
+
+/// Exponential backoff for effects whose network calls fail.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub initial: Duration,
+    pub max_delay: Duration,
+    /// No retry is scheduled past this long after the first failure.
+    pub max_elapsed: Duration,
+}
+
+impl RetryPolicy {
+    pub const NETWORK: RetryPolicy = RetryPolicy {
+        initial: Duration::from_millis(500),
+        max_delay: Duration::from_secs(10),
+        max_elapsed: Duration::from_secs(60),
+    };
+
+    /// Wait before retry number `attempt` (from 1): doubling from `initial`, capped at
+    /// `max_delay`, then jittered down by up to half so retries don't line up.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let doubled = self
+            .initial
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(self.max_delay);
+        let jitter = getrandom::u32().map_or(0.0, |r| r as f64 / u32::MAX as f64);
+        doubled.mul_f64(1.0 - jitter / 2.0)
+    }
+}
+
+/// A failed network call, which is worth retrying; anything else an effect returns (bad
+/// input, duplicates, no space) fails straight away.
+#[derive(Debug)]
+pub struct NetworkError {
+    context: &'static str,
+    source: anyhow::Error,
+}
+
+impl NetworkError {
+    pub fn wrap(context: &'static str) -> impl FnOnce(anyhow::Error) -> anyhow::Error {
+        move |source| NetworkError { context, source }.into()
+    }
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.source)
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+/// Runs `op`, failing once `secs` have passed so a hung fetch can't block the app; 0 waits
+/// forever.
+pub(super) async fn with_timeout<T>(secs: u64, op: impl Future<Output = Result<T>>) -> Result<T> {
+    if secs == 0 {
+        return op.await;
+    }
+    tokio::time::timeout(Duration::from_secs(secs), op)
+        .await
+        .unwrap_or_else(|_| Err(anyhow!("timed out after {secs}s")))
+}
+
+/// A background fetch that failed on the network and runs again at `next_at`, for work
+/// that adds one item at a time (imports, RSS) instead of through an effect.
+#[derive(Debug, Clone)]
+pub struct FetchRetry {
+    /// Failed attempts so far.
+    pub attempts: u32,
+    first_failed: Instant,
+    next_at: Instant,
+    pub last_error: String,
+}
+
+impl FetchRetry {
+    /// The retry after `err`, following `previous`; the error itself when it wasn't a
+    /// network failure or the policy gives up.
+    pub fn schedule(previous: Option<&FetchRetry>, err: anyhow::Error) -> Result<FetchRetry> {
+        if err.downcast_ref::<NetworkError>().is_none() {
+            return Err(err);
+        }
+        let policy = RetryPolicy::NETWORK;
+        let now = Instant::now();
+        let (attempts, first_failed) = match previous {
+            Some(retry) => (retry.attempts + 1, retry.first_failed),
+            None => (1, now),
+        };
+        let next_at = now + policy.delay(attempts);
+        let last_error = brief(&err.to_string());
+        if next_at > first_failed + policy.max_elapsed {
+            return Err(anyhow!("gave up after {attempts} attempts: {last_error}"));
+        }
+        Ok(FetchRetry {
+            attempts,
+            first_failed,
+            next_at,
+            last_error,
+        })
+    }
+
+    pub fn due(&self) -> bool {
+        self.next_at <= Instant::now()
+    }
+
+    pub fn wait(&self) -> Duration {
+        self.next_at.saturating_duration_since(Instant::now())
+    }
+}
+
+/// A network effect that failed and will run again at `next_at`.
+#[derive(Debug)]
+pub struct PendingRetry {
+    pub effect: Effect,
+    /// Failed attempts so far.
+    pub attempts: u32,
+    pub first_failed: Instant,
+    /// None while the retry is running.
+    pub next_at: Option<Instant>,
+    pub last_error: String,
+    pub deadline: Instant,
+}
+
+impl PendingRetry {
+    pub fn label(&self) -> &'static str {
+        match self.effect {
+            Effect::StartDownload { .. } | Effect::AddMagnet { .. } => "Adding torrent",
+            Effect::DiscoverCasts(_) => "Looking for cast devices",
+            _ => "Fetching torrent info",
+        }
+    }
+
+    pub fn wait(&self) -> Option<Duration> {
+        self.next_at
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn give_up_in(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+}
+
+impl App {
+    /// The pending retry's effect once its wait is over.
+    pub(super) fn due_retry(&mut self) -> Option<Effect> {
+        let retry = self.retry.as_mut()?;
+        if retry.next_at.is_none_or(|at| at > Instant::now()) {
+            return None;
+        }
+        retry.next_at = None;
+        Some(retry.effect.clone())
+    }
+
+    /// Handles the outcome of a retryable effect: network failures are scheduled again
+    /// until the policy gives up, everything else clears the retry. There's one retry at a
+    /// time, so an effect failing while another's is pending fails straight away.
+    pub(super) fn retry_outcome(
+        &mut self,
+        effect: Effect,
+        outcome: Result<Vec<Action>>,
+    ) -> Result<Vec<Action>> {
+        let own = self
+            .retry
+            .as_ref()
+            .is_none_or(|retry| retry.effect.name() == effect.name());
+        let err = match outcome {
+            Ok(next) => {
+                if own && self.retry.take().is_some() && self.dialog == Dialog::Retry {
+                    self.dialog = Dialog::None;
+                }
+                return Ok(next);
+            }
+            Err(err) if !own => return Err(err),
+            Err(err) => err,
+        };
+        let previous = self.retry.take();
+        if err.downcast_ref::<NetworkError>().is_none() {
+            return Err(err);
+        }
+        let policy = RetryPolicy::NETWORK;
+        let now = Instant::now();
+        let (attempts, first_failed) = match previous {
+            Some(retry) => (retry.attempts + 1, retry.first_failed),
+            None => (1, now),
+        };
+        let deadline = first_failed + policy.max_elapsed;
+        let next_at = now + policy.delay(attempts);
+        let last_error = brief(&err.to_string());
+        if next_at > deadline {
+            return Err(anyhow!("gave up after {attempts} attempts: {last_error}"));
+        }
+        self.retry = Some(PendingRetry {
+            effect,
+            attempts,
+            first_failed,
+            next_at: Some(next_at),
+            last_error,
+            deadline,
+        });
+        self.dialog = Dialog::Retry;
+        Ok(Vec::new())
+    }
+
+    /// Drops the pending retry along with the add it belonged to.
+    pub(super) fn cancel_retry(&mut self) {
+        let Some(retry) = self.retry.take() else {
+            return;
+        };
+        if matches!(
+            retry.effect,
+            Effect::StartFilePicker { .. } | Effect::StartDownload { .. }
+        ) {
+            self.file_picker = None;
+            self.mode = Mode::Normal;
+        }
+        self.dialog = Dialog::None;
+        self.status = "Ready".to_string();
+        self.show_toast(format!("{} cancelled", retry.label()));
+    }
+
+    /// Runs the pending retry on the next tick instead of waiting out the backoff.
+    pub(super) fn retry_now(&mut self) {
+        if let Some(retry) = self.retry.as_mut()
+            && retry.next_at.is_some()
+        {
+            retry.next_at = Some(Instant::now());
+        }
+    }
+}
+
+/// librqbit renders its errors with `{:?}`, a multi-line cause list and maybe a backtrace;
+/// keeps the first line and the innermost cause so it fits the dialog.
+pub(super) fn brief(error: &str) -> String {
+    let mut lines = error
+        .lines()
+        .take_while(|line| !line.starts_with("Stack backtrace:"))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && *line != "Caused by:");
+    let first = lines.next().unwrap_or_default();
+    match lines.last() {
+        Some(cause) => {
+            let cause = cause
+                .split_once(": ")
+                .filter(|(n, _)| n.chars().all(|c| c.is_ascii_digit()))
+                .map_or(cause, |(_, rest)| rest);
+            format!("{first}: {cause}")
+        }
+        None => first.to_string(),
+    }
+}
@@ -0,0 +1,74 @@
+use super::{
+    action::Action,
+    effect::Effect,
+    meta::AddSource,
+    state::{App, FilePickerState},
+};
+
+impl App {
+    /// Flags the picker's files whose type is listed under `[risky_files]`.
+    pub(super) fn mark_risky_files(&self, picker: &mut FilePickerState) {
+        let extensions = &self.config.risky_files.extensions;
+        for file in &mut picker.files {
+            file.risky = file
+                .name
+                .rsplit(['/', '\\'])
+                .next()
+                .and_then(|name| name.rsplit_once('.'))
+                .is_some_and(|(_, ext)| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)));
+        }
+    }
+
+    /// Enter in the file picker: asks first when risky files are selected, otherwise
+    /// starts the download.
+    pub(super) fn confirm_file_picker(&mut self) -> Option<Action> {
+        let picker = self.file_picker.as_mut()?;
+        if picker.risky_choice.is_none() && picker.files.iter().any(|f| f.included && f.risky) {
+            picker.risky_choice = Some(false);
+            return None;
+        }
+        self.picker_download()
+    }
+
+    pub(super) fn select_risky_choice(&mut self, choice: bool) {
+        if let Some(picker) = &mut self.file_picker
+            && picker.risky_choice.is_some()
+        {
+            picker.risky_choice = Some(choice);
+        }
+    }
+
+    /// Answers the risky-files question; No goes back to the picker to change the selection.
+    pub(super) fn answer_risky_files(&mut self) -> Option<Action> {
+        let picker = self.file_picker.as_mut()?;
+        if picker.risky_choice.take()? {
+            return self.picker_download();
+        }
+        self.show_toast("Not started; deselect the files marked * to skip them".to_string());
+        None
+    }
+
+    pub(super) fn cancel_risky_files(&mut self) {
+        if let Some(picker) = &mut self.file_picker {
+            picker.risky_choice = None;
+        }
+    }
+
+    fn picker_download(&self) -> Option<Action> {
+        let picker = self.file_picker.as_ref()?;
+        let only_files: Vec<usize> = picker
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, file)| if file.included { Some(idx) } else { None })
+            .collect();
+        Some(Action::RunEffect(Effect::StartDownload {
+            magnet: picker.magnet.clone(),
+            output_folder: picker.output_folder.clone(),
+            only_files,
+            label: picker.label.clone(),
+            source: AddSource::Manual,
+            storage: picker.storage,
+        }))
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use tokio::task::JoinHandle;
+
+use crate::{
+    config::{RssFeed, RssRule},
+    rss::FeedItem,
+};
+
+use super::{
+    cancel::Cancelled,
+    import::{ImportFetch, ImportItem},
+    meta::AddSource,
+    retry::FetchRetry,
+    state::App,
+};
+
+/// Matches listed in the RSS view, newest first.
+const RECENT_MATCHES: usize = 50;
+/// Links remembered as added, so a feed's older entries aren't added again.
+const SEEN_LIMIT: usize = 5000;
+/// Failed adds of a link before it's kept as seen and no longer tried.
+const MAX_LINK_FAILURES: u32 = 3;
+
+type FetchResults = Vec<(usize, Result<Vec<FeedItem>>)>;
+
+/// Feed fetches and rule matches for `[rss]`.
+#[derive(Debug, Default)]
+pub struct RssState {
+    /// One per `[[rss.feeds]]` entry, in the same order.
+    feeds: Vec<FeedStatus>,
+    /// One per `[[rss.rules]]` entry, in the same order.
+    rules: Vec<RuleStatus>,
+    fetch: Option<JoinHandle<FetchResults>>,
+    fetched_at: Option<Instant>,
+    /// Matches waiting to be added, one at a time.
+    queue: VecDeque<RssMatch>,
+    /// The match taken off the queue, while its metadata is fetched.
+    adding: Option<(RssMatch, ImportFetch)>,
+    /// A match whose add failed on the network, fetched again once its backoff is over.
+    waiting: Option<(RssMatch, FetchRetry)>,
+    recent: VecDeque<RssMatch>,
+    /// Failed adds so far of links not yet given up on.
+    failures: HashMap<String, u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FeedStatus {
+    pub items: usize,
+    pub error: Option<String>,
+    pub checked: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleStatus {
+    pub matched: usize,
+    pub added: usize,
+    pub failed: usize,
+    /// Title of the last entry it matched.
+    pub last: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RssMatch {
+    pub rule: usize,
+    pub title: String,
+    pub link: String,
+    pub at: chrono::DateTime<chrono::Local>,
+    pub outcome: RssOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RssOutcome {
+    Queued,
+    /// Failed on the network; tried again after a backoff.
+    Retrying(String),
+    Added,
+    /// Already in the session.
+    Duplicate,
+    Failed(String),
+}
+
+/// Links already added or found in the session, persisted as `rss_seen.json` in the data
+/// dir. Failed ones are tried again on later fetches until `MAX_LINK_FAILURES`, then kept
+/// too.
+#[derive(Debug, Default)]
+pub struct RssSeen {
+    links: VecDeque<String>,
+    path: Option<PathBuf>,
+}
+
+impl RssSeen {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let links = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { links, path }
+    }
+
+    /// Keeps what was loaded but stops writing changes back.
+    pub fn read_only(&mut self) {
+        self.path = None;
+    }
+
+    fn contains(&self, link: &str) -> bool {
+        self.links.iter().any(|l| l == link)
+    }
+
+    fn insert(&mut self, link: String) {
+        if self.links.len() == SEEN_LIMIT {
+            self.links.pop_front();
+        }
+        self.links.push_back(link);
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("failed to create data dir")?;
+        }
+        let text = serde_json::to_string(&self.links)?;
+        std::fs::write(path, text).context("failed to save seen RSS links")
+    }
+}
+
+impl App {
+    /// Collects a finished round of feed fetches and starts the next one every
+    /// `[rss] interval_mins`.
+    pub(super) async fn poll_rss(&mut self) {
+        let feeds = &self.config.rss.feeds;
+        // The demo backend stays off the network.
+        if feeds.is_empty() || self.backend.is_simulated() {
+            return;
+        }
+        self.rss.feeds.resize_with(feeds.len(), FeedStatus::default);
+        self.rss.rules.resize_with(self.config.rss.rules.len(), RuleStatus::default);
+        if let Some(fetch) = self.rss.fetch.take_if(|fetch| fetch.is_finished()) {
+            for (feed, result) in fetch.await.unwrap_or_default() {
+                self.collect_feed(feed, result);
+            }
+        }
+        let every = Duration::from_secs(self.config.rss.interval_mins.max(1) * 60);
+        let due = self.rss.fetch.is_none()
+            && self.rss.fetched_at.is_none_or(|at| at.elapsed() >= every);
+        if !due {
+            return;
+        }
+        self.rss.fetched_at = Some(Instant::now());
+        let urls: Vec<String> = self.config.rss.feeds.iter().map(|f| f.url.clone()).collect();
+        self.rss.fetch = Some(tokio::spawn(async move {
+            let fetches: Vec<_> = urls
+                .into_iter()
+                .map(|url| tokio::spawn(async move { crate::rss::fetch(&url).await }))
+                .collect();
+            let mut results = Vec::with_capacity(fetches.len());
+            for (feed, fetch) in fetches.into_iter().enumerate() {
+                // A fetch that panicked leaves its feed as it was.
+                if let Ok(result) = fetch.await {
+                    results.push((feed, result));
+                }
+            }
+            results
+        }));
+    }
+
+    /// Queues the entries of `feed` that a rule picks and that weren't added before. The
+    /// first rule that matches an entry takes it.
+    fn collect_feed(&mut self, feed: usize, result: Result<Vec<FeedItem>>) {
+        let name = self.config.rss.feeds[feed].name.clone();
+        let status = &mut self.rss.feeds[feed];
+        status.checked = Some(Instant::now());
+        let items = match result {
+            Ok(items) => {
+                status.items = items.len();
+                status.error = None;
+                items
+            }
+            Err(err) => {
+                let err = format!("{err:#}");
+                if status.error.as_ref() != Some(&err) {
+                    self.activity.push(format!("RSS: {name}: {err}"));
+                }
+                status.error = Some(err);
+                return;
+            }
+        };
+        for item in items {
+            if self.rss_seen.contains(&item.link) || self.rss_queued(&item.link) {
+                continue;
+            }
+            let Some(rule) = self
+                .config
+                .rss
+                .rules
+                .iter()
+                .position(|r| r.watches(&name) && r.pattern.0.is_match(&item.title))
+            else {
+                continue;
+            };
+            let status = &mut self.rss.rules[rule];
+            status.matched += 1;
+            status.last = Some(item.title.clone());
+            self.rss.queue.push_back(RssMatch {
+                rule,
+                title: item.title,
+                link: item.link,
+                at: chrono::Local::now(),
+                outcome: RssOutcome::Queued,
+            });
+        }
+    }
+
+    pub(super) fn rss_pending(&self) -> bool {
+        !self.rss.queue.is_empty() || self.rss.adding.is_some() || self.rss.waiting.is_some()
+    }
+
+    fn rss_queued(&self, link: &str) -> bool {
+        let adding = self.rss.adding.iter().map(|(found, _)| found);
+        let waiting = self.rss.waiting.iter().map(|(found, _)| found);
+        self.rss.queue.iter().chain(adding).chain(waiting).any(|found| found.link == link)
+    }
+
+    /// Starts fetching the next queued match, or adds the one whose fetch finished into
+    /// the rule's directory, recording the outcome.
+    pub(super) async fn rss_next(&mut self) {
+        if self.rss.adding.as_ref().is_some_and(|(_, fetch)| !fetch.is_finished()) {
+            return;
+        }
+        let (mut found, outcome) = match self.rss.adding.take() {
+            Some((mut found, fetch)) => {
+                let source = AddSource::Rss(self.rss_rule_name(found.rule).to_string());
+                let retry = fetch.retry.clone();
+                match self.finish_import(fetch, source).await {
+                    Err(err) if !err.is::<Cancelled>() => {
+                        match FetchRetry::schedule(retry.as_ref(), err) {
+                            Ok(retry) => {
+                                found.outcome = RssOutcome::Retrying(retry.last_error.clone());
+                                self.rss.waiting = Some((found, retry));
+                                return;
+                            }
+                            Err(err) => (found, Err(err)),
+                        }
+                    }
+                    outcome => (found, outcome),
+                }
+            }
+            None => {
+                let (found, retry) = match self.rss.waiting.take() {
+                    Some((found, retry)) if retry.due() => (found, Some(retry)),
+                    Some(waiting) => {
+                        self.rss.waiting = Some(waiting);
+                        return;
+                    }
+                    None => match self.rss.queue.pop_front() {
+                        Some(found) => (found, None),
+                        None => return,
+                    },
+                };
+                let rule = &self.config.rss.rules[found.rule];
+                let item = ImportItem {
+                    magnet: found.link.clone(),
+                    label: rule.label.clone(),
+                    dir: rule.dir.clone(),
+                };
+                match self.begin_import(item) {
+                    Ok(mut fetch) => {
+                        fetch.retry = retry;
+                        self.rss.adding = Some((found, fetch));
+                        return;
+                    }
+                    Err(err) => (found, Err(err)),
+                }
+            }
+        };
+        let rule_name = self.rss_rule_name(found.rule).to_string();
+        // Added, or failed too often to try again.
+        let seen = match &outcome {
+            Ok(_) => {
+                self.rss.failures.remove(&found.link);
+                true
+            }
+            Err(err) if err.is::<Cancelled>() => false,
+            Err(_) => {
+                let failures = self.rss.failures.entry(found.link.clone()).or_default();
+                *failures += 1;
+                let give_up = *failures >= MAX_LINK_FAILURES;
+                if give_up {
+                    self.rss.failures.remove(&found.link);
+                    self.activity.push(format!(
+                        "RSS: giving up on {} ({rule_name}) after {MAX_LINK_FAILURES} failed \
+                         adds",
+                        found.title
+                    ));
+                }
+                give_up
+            }
+        };
+        if seen {
+            self.rss_seen.insert(found.link.clone());
+            if let Err(err) = self.rss_seen.save() {
+                self.activity.push(format!("RSS: {err:#}"));
+            }
+        }
+        let status = &mut self.rss.rules[found.rule];
+        found.outcome = match outcome {
+            Ok(true) => {
+                status.added += 1;
+                self.activity
+                    .push(format!("RSS: added {} ({rule_name})", found.title));
+                RssOutcome::Added
+            }
+            Ok(false) => RssOutcome::Duplicate,
+            Err(err) => {
+                status.failed += 1;
+                let err = if err.is::<Cancelled>() {
+                    "cancelled".to_string()
+                } else {
+                    format!("{err:#}")
+                };
+                self.activity
+                    .push(format!("RSS: {} ({rule_name}): {err}", found.title));
+                RssOutcome::Failed(err)
+            }
+        };
+        if self.rss.recent.len() == RECENT_MATCHES {
+            self.rss.recent.pop_back();
+        }
+        self.rss.recent.push_front(found);
+    }
+
+    pub fn rss_feeds(&self) -> impl Iterator<Item = (&RssFeed, Option<&FeedStatus>)> {
+        let status = &self.rss.feeds;
+        self.config
+            .rss
+            .feeds
+            .iter()
+            .enumerate()
+            .map(|(idx, feed)| (feed, status.get(idx)))
+    }
+
+    pub fn rss_rules(&self) -> impl Iterator<Item = (&RssRule, Option<&RuleStatus>)> {
+        let status = &self.rss.rules;
+        self.config
+            .rss
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(idx, rule)| (rule, status.get(idx)))
+    }
+
+    /// Matches newest first: the ones still waiting, the one being added or retried, then
+    /// those already added or failed.
+    pub fn rss_matches(&self) -> impl Iterator<Item = &RssMatch> {
+        let adding = self.rss.adding.iter().map(|(found, _)| found);
+        let waiting = self.rss.waiting.iter().map(|(found, _)| found);
+        let queue = self.rss.queue.iter().rev();
+        queue.chain(adding).chain(waiting).chain(self.rss.recent.iter())
+    }
+
+    pub fn rss_rule_name(&self, rule: usize) -> &str {
+        self.config.rss.rules.get(rule).map_or("", |r| r.name.as_str())
+    }
+
+    pub fn rss_fetching(&self) -> bool {
+        self.rss.fetch.is_some()
+    }
+}
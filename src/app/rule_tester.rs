@@ -0,0 +1,246 @@
+use crate::config::BudgetPolicy;
+
+use super::{
+    groups::tracker_host,
+    paths::component_issues,
+    save_path::{SavePathVars, render},
+    seeding::preferred_tracker,
+    state::{App, Dialog},
+};
+
+pub const RULE_TESTER_FIELDS: [&str; 3] = ["Name", "Tracker", "Label"];
+
+const GIB: f64 = (1024 * 1024 * 1024) as f64;
+
+/// A made-up torrent typed into `:test-rules`; the cursor is always at the end of `field`.
+#[derive(Debug, Clone, Default)]
+pub struct RuleTester {
+    pub fields: [String; 3],
+    pub field: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum RuleTesterEdit {
+    Char(char),
+    Backspace,
+    Clear,
+    Next,
+    Previous,
+}
+
+/// What one kind of automation would do with the tested torrent.
+#[derive(Debug, Clone)]
+pub struct RuleCheck {
+    pub rule: &'static str,
+    pub outcome: String,
+    /// Whether a configured rule applies, rather than the default.
+    pub matched: bool,
+}
+
+impl RuleTester {
+    pub fn apply(&mut self, edit: RuleTesterEdit) {
+        let count = self.fields.len();
+        match edit {
+            RuleTesterEdit::Char(c) => self.fields[self.field].push(c),
+            RuleTesterEdit::Backspace => {
+                self.fields[self.field].pop();
+            }
+            RuleTesterEdit::Clear => self.fields[self.field].clear(),
+            RuleTesterEdit::Next => self.field = (self.field + 1) % count,
+            RuleTesterEdit::Previous => self.field = (self.field + count - 1) % count,
+        }
+    }
+
+    /// Pastes the first line of `s` into the current field.
+    pub fn insert_str(&mut self, s: &str) {
+        let line = s.lines().next().unwrap_or_default();
+        self.fields[self.field].extend(line.chars().filter(|c| !c.is_control()));
+    }
+
+    fn value(&self, idx: usize) -> Option<&str> {
+        Some(self.fields[idx].trim()).filter(|v| !v.is_empty())
+    }
+}
+
+impl App {
+    pub(super) fn open_rule_tester(&mut self) {
+        self.rule_tester = Some(RuleTester::default());
+        self.dialog = Dialog::RuleTester;
+    }
+
+    pub(super) fn close_rule_tester(&mut self) {
+        self.rule_tester = None;
+        if self.dialog == Dialog::RuleTester {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    pub fn rule_tester(&self) -> Option<&RuleTester> {
+        self.rule_tester.as_ref()
+    }
+
+    /// What labels, save-path and move templates, seeding priority and label budgets
+    /// would do with the torrent in the tester; empty until it has a name.
+    pub fn rule_checks(&self) -> Vec<RuleCheck> {
+        let Some(tester) = &self.rule_tester else {
+            return Vec::new();
+        };
+        let Some(name) = tester.value(0) else {
+            return Vec::new();
+        };
+        let host = tester.value(1).and_then(tracker_host);
+        let label = tester.value(2);
+        let label_config = label.and_then(|l| self.config.labels.get(l));
+        let vars = SavePathVars {
+            name: name.to_string(),
+            label: label.map(str::to_string),
+            tracker: host.map(str::to_string),
+            info_hash: "0123456789abcdef".to_string(),
+        };
+        let whose = |own: bool| if own { "the label's" } else { "the global" };
+        let mut checks = Vec::new();
+
+        checks.push(match (label, label_config) {
+            (None, _) => RuleCheck {
+                rule: "Label",
+                outcome: "None given".to_string(),
+                matched: false,
+            },
+            (Some(label), None) => RuleCheck {
+                rule: "Label",
+                outcome: format!("{label} has no [labels.{label}] settings"),
+                matched: false,
+            },
+            (Some(label), Some(_)) => RuleCheck {
+                rule: "Label",
+                outcome: format!("[labels.{label}] applies"),
+                matched: true,
+            },
+        });
+
+        checks.push(match self.config.save_path_template(label) {
+            Some(template) => RuleCheck {
+                rule: "Save path",
+                outcome: match render(&self.download_dir, template, &vars) {
+                    Ok(path) => format!(
+                        "{} ({} template {template:?})",
+                        path.display(),
+                        whose(label_config.is_some_and(|l| l.save_path.is_some()))
+                    ),
+                    Err(err) => format!("{err:#}"),
+                },
+                matched: true,
+            },
+            None => RuleCheck {
+                rule: "Save path",
+                outcome: match self.save_path(&self.download_dir, &vars) {
+                    Ok(path) => format!("{} (no template)", path.display()),
+                    Err(err) => format!("{err:#}"),
+                },
+                matched: false,
+            },
+        });
+
+        checks.push(match self.config.move_to_template(label) {
+            Some(template) => RuleCheck {
+                rule: "Move when done",
+                outcome: match render(&self.download_dir, template, &vars) {
+                    Ok(path) => format!(
+                        "{} ({} move_to {template:?})",
+                        path.display(),
+                        whose(label_config.is_some_and(|l| l.move_to.is_some()))
+                    ),
+                    Err(err) => format!("{err:#}"),
+                },
+                matched: true,
+            },
+            None => RuleCheck {
+                rule: "Move when done",
+                outcome: "Stays where it was downloaded (no move_to)".to_string(),
+                matched: false,
+            },
+        });
+
+        let own_goal = label_config.is_some_and(|l| l.ratio_goal.is_some());
+        checks.push(RuleCheck {
+            rule: "Ratio goal",
+            outcome: format!(
+                "{} ({})",
+                self.ratio_goal(label),
+                if own_goal { "label" } else { "[seeding]" }
+            ),
+            matched: own_goal,
+        });
+
+        let seeding = &self.config.seeding;
+        checks.push(match host {
+            None => RuleCheck {
+                rule: "Preferred tracker",
+                outcome: "No tracker given".to_string(),
+                matched: false,
+            },
+            Some(host) => match preferred_tracker(seeding, host) {
+                Some(entry) => RuleCheck {
+                    rule: "Preferred tracker",
+                    outcome: format!(
+                        "{host} matches {entry:?}: +{} seeding priority",
+                        seeding.tracker_weight
+                    ),
+                    matched: true,
+                },
+                None => RuleCheck {
+                    rule: "Preferred tracker",
+                    outcome: format!("{host} isn't in preferred_trackers"),
+                    matched: false,
+                },
+            },
+        });
+
+        checks.push(match (label, label_config.and_then(|l| l.budget_gb)) {
+            (Some(label), Some(budget)) => {
+                let used = self.label_usage(label, None) as f64 / GIB;
+                let policy = match label_config.map(|l| l.budget_policy) {
+                    Some(BudgetPolicy::RemoveOldest) => {
+                        "the oldest completed torrents under it are deleted to fit"
+                    }
+                    _ => "adds that don't fit are refused",
+                };
+                RuleCheck {
+                    rule: "Budget",
+                    outcome: format!("{used:.1} of {budget} GiB used; over it, {policy}"),
+                    matched: true,
+                }
+            }
+            _ => RuleCheck {
+                rule: "Budget",
+                outcome: "No size budget".to_string(),
+                matched: false,
+            },
+        });
+
+        if self.config.checks_windows_paths() {
+            let issues = component_issues(name);
+            checks.push(RuleCheck {
+                rule: "Windows paths",
+                matched: !issues.is_empty(),
+                outcome: if issues.is_empty() {
+                    "Name is fine".to_string()
+                } else {
+                    format!("Asks to sanitize: {}", issues.join(", "))
+                },
+            });
+        }
+
+        if !self.config.hooks.is_empty() {
+            checks.push(RuleCheck {
+                rule: "Hooks",
+                outcome: format!(
+                    "{} [[hooks]] run for every torrent's events",
+                    self.config.hooks.len()
+                ),
+                matched: true,
+            });
+        }
+        checks
+    }
+}
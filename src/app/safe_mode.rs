@@ -0,0 +1,23 @@
+use super::state::App;
+
+impl App {
+    /// For `--safe-mode`: everything loaded from the data directory stays in memory, but
+    /// nothing is written back, so a bad session can be inspected without changing it.
+    /// The librqbit session itself runs from a scratch copy (see main.rs).
+    pub fn enter_safe_mode(&mut self) {
+        self.safe_mode = true;
+        self.meta.read_only();
+        self.pending_adds.read_only();
+        self.archive.read_only();
+        self.columns.read_only();
+        self.ui_state.read_only();
+        self.rss_seen.read_only();
+        self.schedule.read_only();
+        self.activity.read_only();
+        self.status = "Safe mode: default config, no hooks, local data isn't saved".to_string();
+    }
+
+    pub fn safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+}
@@ -0,0 +1,227 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use librqbit::TorrentStatsState;
+
+use crate::metainfo;
+
+use super::{
+    activity::Automation,
+    disk::network_filesystem,
+    groups::tracker_host,
+    meta::StorageKind,
+    state::App,
+    util::{parse_magnet, sanitize_path_component},
+};
+
+/// What a save-path template's placeholders stand for.
+#[derive(Debug, Clone)]
+pub struct SavePathVars {
+    pub name: String,
+    pub label: Option<String>,
+    /// Host name of the torrent's first tracker.
+    pub tracker: Option<String>,
+    pub info_hash: String,
+}
+
+/// The directory picked for a new torrent and what its template is filled with, kept so
+/// the folder follows a label picked afterwards.
+#[derive(Debug, Clone)]
+pub struct SavePathTarget {
+    pub base: PathBuf,
+    pub vars: SavePathVars,
+}
+
+impl SavePathVars {
+    /// Vars for an add of `input`; the tracker comes from a magnet's `tr` or a local
+    /// `.torrent` file, since nothing else is known before the torrent is in the session.
+    pub fn for_add(input: &str, name: &str, info_hash: &str, label: Option<&str>) -> Self {
+        let first = if input.starts_with("magnet:") {
+            parse_magnet(input).ok().and_then(|link| link.trackers.into_iter().next())
+        } else if Path::new(input).is_file() {
+            metainfo::read(Path::new(input))
+                .ok()
+                .and_then(|info| info.trackers.into_iter().flatten().next())
+        } else {
+            None
+        };
+        Self {
+            name: name.to_string(),
+            label: label.map(str::to_string),
+            tracker: first.as_deref().and_then(tracker_host).map(str::to_string),
+            info_hash: info_hash.to_string(),
+        }
+    }
+}
+
+/// Expands `{name}`, `{label}`, `{tracker}` and `{hash}` (its first 8 characters) in
+/// `template` and joins the result onto `base`. Each value is one folder name; folders
+/// that come out empty, like `{label}` for an unlabelled torrent, are left out.
+pub fn render(base: &Path, template: &str, vars: &SavePathVars) -> Result<PathBuf> {
+    let mut path = PathBuf::new();
+    if template.starts_with(['/', '\\']) {
+        path.push(std::path::MAIN_SEPARATOR_STR);
+    }
+    let mut named = false;
+    for part in template.split(['/', '\\']) {
+        let part = expand(part, vars)
+            .with_context(|| format!("invalid save path template {template:?}"))?;
+        let part = part.trim();
+        if part.is_empty() || part == "." {
+            continue;
+        }
+        path.push(part);
+        named = true;
+    }
+    if !named {
+        path.push(sanitize_path_component(&vars.name));
+    }
+    Ok(base.join(path))
+}
+
+fn expand(part: &str, vars: &SavePathVars) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = part;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            bail!("unclosed {{");
+        };
+        let value = match &after[..close] {
+            "name" => Some(vars.name.as_str()),
+            "label" => vars.label.as_deref(),
+            "tracker" => vars.tracker.as_deref(),
+            "hash" => vars.info_hash.get(..8),
+            other => bail!("unknown placeholder {{{other}}}"),
+        };
+        out.push_str(&value.map(sanitize_path_component).unwrap_or_default());
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+impl App {
+    /// Where a new torrent with `vars` goes under `base`: its label's or the global
+    /// template, else `base/<name>`.
+    pub(super) fn save_path(&self, base: &Path, vars: &SavePathVars) -> Result<PathBuf> {
+        match self.config.save_path_template(vars.label.as_deref()) {
+            Some(template) => render(base, template, vars),
+            None => Ok(base.join(sanitize_path_component(&vars.name))),
+        }
+    }
+
+    /// Sets the picker's label, moving its folder along when the label changes which
+    /// template applies or what it expands to.
+    pub(super) fn set_picker_label(&mut self, label: Option<String>) -> Result<()> {
+        let target = self.file_picker.as_ref().and_then(|p| p.save_path.clone());
+        let mut moved = None;
+        if let Some(target) = target {
+            let before = self.save_path(&target.base, &target.vars)?;
+            let vars = SavePathVars {
+                label: label.clone(),
+                ..target.vars
+            };
+            let after = self.save_path(&target.base, &vars)?;
+            moved = (after != before).then_some(after);
+        }
+        let network_fs = moved
+            .as_deref()
+            .filter(|_| !self.backend.is_simulated())
+            .and_then(network_filesystem);
+        let Some(picker) = &mut self.file_picker else {
+            return Ok(());
+        };
+        if let Some(target) = &mut picker.save_path {
+            target.vars.label = label.clone();
+        }
+        picker.label = label;
+        if let Some(after) = moved {
+            picker.output_folder = after.to_string_lossy().into_owned();
+            picker.network_fs = network_fs;
+        }
+        Ok(())
+    }
+
+    /// Where the finished torrent at `idx` in `self.torrents` belongs under its label's or
+    /// the global `move_to`, when that's somewhere else.
+    pub(super) fn completed_move_target(&self, idx: usize) -> Result<Option<PathBuf>> {
+        let Some(t) = self.torrents.get(idx) else {
+            return Ok(None);
+        };
+        let label = self.label_of(t);
+        let Some(template) = self.config.move_to_template(label) else {
+            return Ok(None);
+        };
+        if self.storage_of(t) == StorageKind::Memory {
+            return Ok(None);
+        }
+        let folder = Path::new(&t.output_folder);
+        // The folder's own name keeps any suffix a collision gave it.
+        let name = folder
+            .file_name()
+            .map_or_else(|| t.name.clone(), |n| n.to_string_lossy().into_owned());
+        let vars = SavePathVars {
+            name,
+            label: label.map(str::to_string),
+            tracker: t.trackers.first().and_then(|url| tracker_host(url)).map(str::to_string),
+            info_hash: t.info_hash.clone().unwrap_or_default(),
+        };
+        let target = render(&self.download_dir, template, &vars)?;
+        Ok((target != folder).then_some(target))
+    }
+
+    /// Which `move_to` applies under `label`, naming it for the activity log.
+    pub(super) fn move_rule(&self, label: Option<&str>) -> String {
+        match label {
+            Some(label) if self.config.labels.get(label).is_some_and(|l| l.move_to.is_some()) => {
+                format!("labels.{label} move_to")
+            }
+            _ => "completion move_to".to_string(),
+        }
+    }
+
+    /// Renames a finished torrent's folder to `target` and re-adds the torrent there, which
+    /// rechecks it. A rename can't cross filesystems, so neither can `move_to`.
+    pub(super) async fn move_completed(&mut self, id: usize, target: PathBuf) -> Result<()> {
+        let Some(t) = self.torrents.iter().find(|t| t.id == id) else {
+            return Ok(());
+        };
+        let (name, hash) = (t.name.clone(), t.info_hash.clone());
+        let from = PathBuf::from(&t.output_folder);
+        let paused = !t.stats.as_ref().is_some_and(|s| {
+            matches!(s.state, TorrentStatsState::Live | TorrentStatsState::Initializing)
+        });
+        let simulated = self.backend.is_simulated();
+        if !simulated {
+            if target.exists() {
+                bail!("{} already exists", target.display());
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            std::fs::rename(&from, &target).with_context(|| {
+                format!("failed to move {} to {}", from.display(), target.display())
+            })?;
+        }
+        let output_folder = target.to_string_lossy().into_owned();
+        if let Err(err) = self.backend.relocate(id, output_folder.clone(), paused).await {
+            if !simulated {
+                let _ = std::fs::rename(&target, &from);
+            }
+            return Err(err.context(format!("error moving {name}")));
+        }
+        let rule = self.move_rule(hash.as_deref().and_then(|h| self.meta.label(h)));
+        self.activity
+            .record(Automation::Move, rule, true, format!("Moved {name} to {output_folder}"));
+        if let Some(hash) = hash {
+            self.torrent_logs
+                .push(&hash, format!("Moved to {output_folder}, rechecking"));
+        }
+        self.refresh();
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use chrono::{Datelike, Timelike};
+
+use crate::config::SpeedProfile;
+
+use super::{
+    speed::{RateLimits, format_limit},
+    state::{App, Dialog},
+};
+
+pub const SCHEDULE_DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+pub const SCHEDULE_HOURS: usize = 24;
+/// A cell that leaves the limits as they are.
+pub const KEEP_LIMITS: char = '.';
+
+pub type Week = [[char; SCHEDULE_HOURS]; 7];
+
+/// The grid edited in `:schedule`, persisted as `schedule.json` in the data dir. Once saved
+/// it takes the place of `[speed.schedule] week`.
+#[derive(Debug)]
+pub struct ScheduleFile {
+    week: Option<Vec<String>>,
+    path: Option<PathBuf>,
+}
+
+impl ScheduleFile {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let week = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok());
+        Self { week, path }
+    }
+
+    /// Keeps what was loaded but stops writing changes back.
+    pub fn read_only(&mut self) {
+        self.path = None;
+    }
+
+    fn save(&mut self, week: &Week) -> Result<()> {
+        let rows: Vec<String> = week.iter().map(|day| day.iter().collect()).collect();
+        if let Some(path) = self.path.as_ref() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).context("failed to create data dir")?;
+            }
+            let text = serde_json::to_string_pretty(&rows)?;
+            std::fs::write(path, text).context("failed to save the schedule")?;
+        }
+        self.week = Some(rows);
+        Ok(())
+    }
+}
+
+/// The week grid open in `:schedule`, with the highlighted hour.
+#[derive(Debug, Clone)]
+pub struct ScheduleEditor {
+    pub week: Week,
+    pub day: usize,
+    pub hour: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ScheduleEdit {
+    Left,
+    Right,
+    Up,
+    Down,
+    /// Puts a profile key (or `.`) in the highlighted hour and moves on to the next.
+    Set(char),
+}
+
+impl ScheduleEditor {
+    fn step(&mut self) {
+        self.hour += 1;
+        if self.hour == SCHEDULE_HOURS {
+            self.hour = 0;
+            self.day = (self.day + 1) % SCHEDULE_DAYS.len();
+        }
+    }
+}
+
+impl App {
+    /// The grid in effect: the one saved from `:schedule`, else `[speed.schedule] week`.
+    /// Hours naming a profile that doesn't exist leave the limits alone.
+    pub fn schedule_week(&self) -> Week {
+        let rows = self.schedule.week.as_ref().unwrap_or(&self.config.speed.schedule.week);
+        let mut week = [[KEEP_LIMITS; SCHEDULE_HOURS]; 7];
+        for (day, row) in week.iter_mut().zip(rows) {
+            for (cell, key) in day.iter_mut().zip(row.chars()) {
+                if self.schedule_profile(key).is_some() {
+                    *cell = key;
+                }
+            }
+        }
+        week
+    }
+
+    fn schedule_profile(&self, key: char) -> Option<&SpeedProfile> {
+        self.config.speed.schedule.profiles.get(key.to_string().as_str())
+    }
+
+    /// Profiles by key, for the dialog's legend.
+    pub fn schedule_profiles(&self) -> Vec<(char, &SpeedProfile)> {
+        let mut profiles: Vec<(char, &SpeedProfile)> = self
+            .config
+            .speed
+            .schedule
+            .profiles
+            .iter()
+            .filter_map(|(key, profile)| {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c != KEEP_LIMITS => Some((c, profile)),
+                    _ => None,
+                }
+            })
+            .collect();
+        profiles.sort_by_key(|(key, _)| *key);
+        profiles
+    }
+
+    /// Switches the limits when the clock enters an hour with a different profile. Limits
+    /// changed by hand stay until then.
+    pub(super) fn apply_speed_schedule(&mut self) {
+        if self.config.speed.schedule.profiles.is_empty() {
+            return;
+        }
+        let now = chrono::Local::now();
+        let day = now.weekday().num_days_from_monday() as usize;
+        let key = self.schedule_week()[day][now.hour() as usize];
+        if self.schedule_slot == Some(key) {
+            return;
+        }
+        self.schedule_slot = Some(key);
+        let Some(profile) = self.schedule_profile(key) else {
+            return;
+        };
+        let name = profile.name.clone().unwrap_or_else(|| key.to_string());
+        self.rate_limits = RateLimits {
+            download: profile.download_kib.map(|kib| kib * 1024),
+            upload: profile.upload_kib.map(|kib| kib * 1024),
+        };
+        self.apply_rate_limits();
+        self.activity.push(format!(
+            "Schedule: {name}: down {}, up {}",
+            format_limit(self.rate_limits.download),
+            format_limit(self.rate_limits.upload)
+        ));
+    }
+
+    pub(super) fn open_schedule(&mut self) -> Result<()> {
+        if self.schedule_profiles().is_empty() {
+            bail!("no [speed.schedule.profiles] to schedule");
+        }
+        let now = chrono::Local::now();
+        self.schedule_editor = Some(ScheduleEditor {
+            week: self.schedule_week(),
+            day: now.weekday().num_days_from_monday() as usize,
+            hour: now.hour() as usize,
+        });
+        self.dialog = Dialog::Schedule;
+        Ok(())
+    }
+
+    pub(super) fn close_schedule(&mut self) {
+        self.schedule_editor = None;
+        if self.dialog == Dialog::Schedule {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    pub(super) fn edit_schedule(&mut self, edit: ScheduleEdit) {
+        let known = matches!(edit, ScheduleEdit::Set(key)
+            if key == KEEP_LIMITS || self.schedule_profile(key).is_some());
+        let Some(editor) = &mut self.schedule_editor else {
+            return;
+        };
+        let days = SCHEDULE_DAYS.len();
+        match edit {
+            ScheduleEdit::Left => editor.hour = (editor.hour + SCHEDULE_HOURS - 1) % SCHEDULE_HOURS,
+            ScheduleEdit::Right => editor.hour = (editor.hour + 1) % SCHEDULE_HOURS,
+            ScheduleEdit::Up => editor.day = (editor.day + days - 1) % days,
+            ScheduleEdit::Down => editor.day = (editor.day + 1) % days,
+            ScheduleEdit::Set(key) if known => {
+                editor.week[editor.day][editor.hour] = key;
+                editor.step();
+            }
+            ScheduleEdit::Set(_) => {}
+        }
+    }
+
+    /// Saves the edited grid and applies the current hour's profile right away.
+    pub(super) fn save_schedule(&mut self) -> Result<()> {
+        let Some(editor) = self.schedule_editor.take() else {
+            return Ok(());
+        };
+        self.close_schedule();
+        self.schedule.save(&editor.week)?;
+        self.schedule_slot = None;
+        self.apply_speed_schedule();
+        self.show_toast("Schedule saved".to_string());
+        Ok(())
+    }
+
+    pub fn schedule_editor(&self) -> Option<&ScheduleEditor> {
+        self.schedule_editor.as_ref()
+    }
+}
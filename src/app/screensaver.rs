@@ -0,0 +1,61 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyEventKind};
+
+use super::state::{App, Dialog, Mode};
+
+/// Refreshes of session speed kept for the screensaver graph.
+const HISTORY: usize = 120;
+
+impl App {
+    /// Samples session download and upload speed (MiB/s) on each refresh.
+    pub(super) fn record_speeds(&mut self) {
+        let Some(stats) = self.session_stats.as_ref() else {
+            return;
+        };
+        if self.speed_history.len() >= HISTORY {
+            self.speed_history.pop_front();
+        }
+        self.speed_history
+            .push_back((stats.download_speed.mbps, stats.upload_speed.mbps));
+    }
+
+    /// Switches to the screensaver once `[ui] screensaver_secs` pass without a key press,
+    /// unless a dialog or prompt is open.
+    pub(super) fn check_idle(&mut self) {
+        let secs = self.config.ui.screensaver_secs;
+        if secs == 0 || self.screensaver {
+            return;
+        }
+        if !matches!(self.mode, Mode::Normal) || self.dialog != Dialog::None {
+            return;
+        }
+        if self.last_input_at.elapsed() >= Duration::from_secs(secs) {
+            self.screensaver = true;
+        }
+    }
+
+    /// Notes a key press; true when it only woke the screen and should do nothing else.
+    pub(super) fn note_input(&mut self, ev: &Event) -> bool {
+        let Event::Key(key) = ev else {
+            return false;
+        };
+        if key.kind != KeyEventKind::Press {
+            return self.screensaver;
+        }
+        self.last_input_at = Instant::now();
+        std::mem::take(&mut self.screensaver)
+    }
+
+    pub fn screensaver(&self) -> bool {
+        self.screensaver
+    }
+
+    /// Session (download, upload) speeds in MiB/s, oldest first.
+    pub fn speed_history(&self) -> &VecDeque<(f64, f64)> {
+        &self.speed_history
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use librqbit::TorrentStatsState;
+
+use super::state::{App, TorrentRow};
+
+impl App {
+    /// Stamps `verified_at` on torrents whose recheck (from recheck-all or a scrub) has
+    /// finished without an error.
+    pub(super) fn track_verified(&mut self) {
+        let mut done = Vec::new();
+        for hash in &self.verifying {
+            let Some(t) = self
+                .torrents
+                .iter()
+                .find(|t| t.info_hash.as_deref() == Some(hash.as_str()))
+            else {
+                // Gone from the session; nothing left to stamp.
+                done.push((hash.clone(), false));
+                continue;
+            };
+            match t.stats.as_ref().map(|s| &s.state) {
+                Some(TorrentStatsState::Initializing) | None => {}
+                Some(TorrentStatsState::Error) => done.push((hash.clone(), false)),
+                Some(_) => done.push((hash.clone(), true)),
+            }
+        }
+        let now = Local::now();
+        for (hash, verified) in done {
+            self.verifying.remove(&hash);
+            if verified && let Err(err) = self.meta.update(&hash, |m| m.verified_at = Some(now))
+            {
+                self.activity.push(format!("Metadata: {err:#}"));
+            }
+        }
+    }
+
+    pub fn verified_at_of(&self, t: &TorrentRow) -> Option<DateTime<Local>> {
+        t.info_hash
+            .as_deref()
+            .and_then(|h| self.meta.get(h))
+            .and_then(|m| m.verified_at)
+    }
+
+    /// The seeding torrent to scrub next, once `scrub.per_week` spacing has passed since
+    /// the last scrub and the last verification of any torrent. Waits while other checks
+    /// run so scrubs never pile onto a busy disk.
+    pub(super) fn scrub_due(&self) -> Option<String> {
+        let scrub = &self.config.scrub;
+        if !scrub.enabled() || self.startup_pending || !self.verifying.is_empty() {
+            return None;
+        }
+        let interval = scrub.interval();
+        if self.last_scrub.is_some_and(|at| at.elapsed() < interval)
+            || self.maintenance.as_ref().is_some_and(|job| !job.finished())
+        {
+            return None;
+        }
+        let checking = self.torrents.iter().any(|t| {
+            t.stats
+                .as_ref()
+                .is_some_and(|s| matches!(s.state, TorrentStatsState::Initializing))
+        });
+        let newest = self.torrents.iter().filter_map(|t| self.verified_at_of(t)).max();
+        let spacing = chrono::TimeDelta::from_std(interval).unwrap_or(chrono::TimeDelta::MAX);
+        if checking || newest.is_some_and(|at| Local::now() - at < spacing) {
+            return None;
+        }
+        self.torrents
+            .iter()
+            .filter(|t| {
+                t.stats.as_ref().is_some_and(|s| {
+                    s.finished && matches!(s.state, TorrentStatsState::Live)
+                }) && !self.is_missing_files(t)
+                    && self.storage_of(t) == super::StorageKind::Disk
+            })
+            .min_by_key(|t| (self.verified_at_of(t), t.id))
+            .and_then(|t| t.info_hash.clone())
+    }
+
+    pub(super) async fn scrub_one(&mut self, hash: &str) -> Result<()> {
+        self.last_scrub = Some(Instant::now());
+        let name = self
+            .torrents
+            .iter()
+            .find(|t| t.info_hash.as_deref() == Some(hash))
+            .map(|t| t.name.clone())
+            .unwrap_or_else(|| hash.to_string());
+        if self.recheck_one(hash).await? {
+            self.activity.push(format!("Scrub: rechecking {name}"));
+        }
+        Ok(())
+    }
+}
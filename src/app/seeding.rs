@@ -0,0 +1,144 @@
+use std::time::{Duration, Instant};
+
+use librqbit::TorrentStatsState;
+
+use crate::config::SeedingConfig;
+
+use super::{
+    action::Action,
+    effect::Effect,
+    groups::tracker_host,
+    state::{App, TorrentRow},
+};
+
+/// Re-ranking restarts torrents, which re-announces them, so it isn't done often.
+const BALANCE_INTERVAL: Duration = Duration::from_secs(300);
+
+struct Seed {
+    id: usize,
+    score: f64,
+    ratio: f64,
+    live: bool,
+}
+
+impl App {
+    /// Hands the `[seeding] upload_slots` out to the finished torrents with the highest
+    /// priority while an upload limit is set, pausing the others; once the limit or the
+    /// slots are gone, the held torrents are resumed. Force-started torrents are left
+    /// alone and don't take a slot.
+    pub(super) fn seeding_actions(&mut self) -> Vec<Action> {
+        if self.startup_pending
+            || self.power_pause.is_some()
+            || self
+                .last_seed_balance
+                .is_some_and(|at| at.elapsed() < BALANCE_INTERVAL)
+        {
+            return Vec::new();
+        }
+        self.last_seed_balance = Some(Instant::now());
+        let known: Vec<usize> = self.torrents.iter().map(|t| t.id).collect();
+        self.seed_held_ids.retain(|id| known.contains(id));
+        let slots = match (self.config.seeding.upload_slots, self.rate_limits.upload) {
+            (Some(slots), Some(_)) => slots,
+            _ => {
+                if self.seed_held_ids.is_empty() {
+                    return Vec::new();
+                }
+                let ids = std::mem::take(&mut self.seed_held_ids);
+                self.activity
+                    .push(format!("Resuming {} seeds: upload slots lifted", ids.len()));
+                for &id in &ids {
+                    self.log_torrent(id, "Resuming: upload slots lifted");
+                }
+                return vec![Action::RunEffect(Effect::StartTorrents(ids))];
+            }
+        };
+
+        let mut ranked: Vec<Seed> = self
+            .torrents
+            .iter()
+            .filter_map(|t| {
+                let stats = t.stats.as_ref().filter(|s| s.finished)?;
+                let live = matches!(stats.state, TorrentStatsState::Live);
+                let held = self.seed_held_ids.contains(&t.id);
+                if !((live && !self.is_forced(t)) || held) {
+                    return None;
+                }
+                let ratio = stats.uploaded_bytes as f64 / stats.total_bytes.max(1) as f64;
+                Some(Seed {
+                    id: t.id,
+                    score: self.seed_priority(t, ratio),
+                    ratio,
+                    live,
+                })
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.ratio.total_cmp(&b.ratio)));
+        let (keep, rest) = ranked.split_at(slots.min(ranked.len()));
+        let start: Vec<usize> = keep.iter().filter(|s| !s.live).map(|s| s.id).collect();
+        let pause: Vec<usize> = rest.iter().filter(|s| s.live).map(|s| s.id).collect();
+        self.seed_held_ids = rest.iter().map(|s| s.id).collect();
+        if start.is_empty() && pause.is_empty() {
+            return Vec::new();
+        }
+        self.activity.push(format!(
+            "Upload slots: {} seeding, paused {}, resumed {}",
+            keep.len(),
+            pause.len(),
+            start.len()
+        ));
+        for &id in &pause {
+            self.log_torrent(id, "Paused: lost its upload slot to higher-priority seeds");
+        }
+        for &id in &start {
+            self.log_torrent(id, "Resuming: got an upload slot");
+        }
+        let mut actions = Vec::new();
+        if !pause.is_empty() {
+            actions.push(Action::RunEffect(Effect::PauseTorrents(pause)));
+        }
+        if !start.is_empty() {
+            actions.push(Action::RunEffect(Effect::StartTorrents(start)));
+        }
+        actions
+    }
+
+    /// Weighted score: how far below its ratio goal the torrent is (0 at or above it, 1
+    /// with nothing uploaded) plus the bonus for a preferred tracker.
+    fn seed_priority(&self, t: &TorrentRow, ratio: f64) -> f64 {
+        let cfg = &self.config.seeding;
+        let goal = self.ratio_goal(self.label_of(t));
+        let below = if goal > 0.0 {
+            (1.0 - ratio / goal).max(0.0)
+        } else {
+            0.0
+        };
+        let preferred = t
+            .trackers
+            .iter()
+            .filter_map(|url| tracker_host(url))
+            .any(|host| preferred_tracker(cfg, host).is_some());
+        let tracker = if preferred { cfg.tracker_weight } else { 0.0 };
+        cfg.below_goal_weight * below + tracker
+    }
+
+    /// The label's ratio goal, else `[seeding] ratio_goal`.
+    pub(super) fn ratio_goal(&self, label: Option<&str>) -> f64 {
+        label
+            .and_then(|label| self.config.labels.get(label))
+            .and_then(|label| label.ratio_goal)
+            .unwrap_or(self.config.seeding.ratio_goal)
+    }
+}
+
+/// The `preferred_trackers` entry that `host` matches, by suffix.
+pub(super) fn preferred_tracker<'a>(cfg: &'a SeedingConfig, host: &str) -> Option<&'a str> {
+    let host = host.to_ascii_lowercase();
+    cfg.preferred_trackers
+        .iter()
+        .find(|p| {
+            let p = p.to_ascii_lowercase();
+            host == p || host.ends_with(&format!(".{p}"))
+        })
+        .map(String::as_str)
+}
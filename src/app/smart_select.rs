@@ -0,0 +1,84 @@
+use crate::config::SmartSelectConfig;
+
+use super::state::{App, FileEntry};
+
+impl App {
+    /// Selects the picker's main media files and the subtitles that go with them, leaving
+    /// everything else (samples, proofs, executables, extras) out.
+    pub(super) fn smart_select_files(&mut self) {
+        let Some(picker) = &mut self.file_picker else {
+            return;
+        };
+        let Some(keep) = smart_selection(&picker.files, &self.config.smart_select) else {
+            self.show_toast("No media files found; selection unchanged".to_string());
+            return;
+        };
+        let (count, total) = (keep.iter().filter(|k| **k).count(), keep.len());
+        for (file, keep) in picker.files.iter_mut().zip(keep) {
+            file.included = keep;
+        }
+        self.show_toast(format!("Smart select: {count} of {total} files"));
+    }
+}
+
+/// Which files to keep, or `None` when the torrent has no main media to anchor on.
+fn smart_selection(files: &[FileEntry], config: &SmartSelectConfig) -> Option<Vec<bool>> {
+    let media: Vec<bool> = files
+        .iter()
+        .map(|f| {
+            has_extension(&f.name, &config.media_extensions)
+                && !has_word(&f.name, &config.skip_words)
+        })
+        .collect();
+    if !media.contains(&true) {
+        return None;
+    }
+    let stems: Vec<String> = files
+        .iter()
+        .zip(&media)
+        .filter(|(_, media)| **media)
+        .map(|(f, _)| stem(file_name(&f.name)).to_lowercase())
+        .collect();
+    let keep = files
+        .iter()
+        .zip(media)
+        .map(|(f, media)| {
+            media
+                || (has_extension(&f.name, &config.subtitle_extensions)
+                    && !has_word(&f.name, &config.skip_words)
+                    && subtitle_matches(&f.name, &stems, &config.subtitle_folders))
+        })
+        .collect();
+    Some(keep)
+}
+
+/// Named after a kept media file (`Movie.en.srt` for `Movie.mkv`), or in a subtitles folder.
+fn subtitle_matches(path: &str, stems: &[String], folders: &[String]) -> bool {
+    let name = file_name(path).to_lowercase();
+    if stems.iter().any(|stem| name.starts_with(stem.as_str())) {
+        return true;
+    }
+    let mut dirs = path.split(['/', '\\']).rev().skip(1);
+    dirs.any(|dir| folders.iter().any(|f| f.eq_ignore_ascii_case(dir)))
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+fn stem(name: &str) -> &str {
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+fn has_extension(path: &str, extensions: &[String]) -> bool {
+    file_name(path)
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
+
+/// Whole-word match, so `sample` skips `Sample/x.mkv` and `x-sample.mkv` but not
+/// `Samples of Sound.flac`.
+fn has_word(path: &str, words: &[String]) -> bool {
+    path.split(|c: char| !c.is_alphanumeric())
+        .any(|part| words.iter().any(|w| w.eq_ignore_ascii_case(part)))
+}
@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+use super::state::App;
+
+/// How long a toast stays on screen.
+const TOAST_FOR: Duration = Duration::from_secs(3);
+
+/// Which global limit a key adjusts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    Download,
+    Upload,
+}
+
+/// Global network limits in bytes per second; `None` is unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    pub download: Option<u64>,
+    pub upload: Option<u64>,
+}
+
+impl App {
+    pub fn rate_limits(&self) -> RateLimits {
+        self.rate_limits
+    }
+
+    /// A short-lived notice drawn over the UI, e.g. after a limit change.
+    pub fn toast(&self) -> Option<&str> {
+        self.toast
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < TOAST_FOR)
+            .map(|(message, _)| message.as_str())
+    }
+
+    pub(super) fn show_toast(&mut self, message: String) {
+        self.status = message.clone();
+        self.toast = Some((message, Instant::now()));
+    }
+
+    /// Moves a limit by `steps` configured steps. Lowering an unlimited rate starts from the
+    /// current session speed, so one press always slows things down.
+    pub(super) fn bump_rate_limit(&mut self, kind: LimitKind, steps: i64) {
+        let step = self.config.speed.step_kib.max(1) * 1024;
+        let current_speed = self.session_stats.as_ref().map_or(0.0, |s| match kind {
+            LimitKind::Download => s.download_speed.mbps,
+            LimitKind::Upload => s.upload_speed.mbps,
+        }) * 1024.0
+            * 1024.0;
+        let limit = match kind {
+            LimitKind::Download => &mut self.rate_limits.download,
+            LimitKind::Upload => &mut self.rate_limits.upload,
+        };
+        let next = match *limit {
+            // Raising an unlimited rate has nowhere to go.
+            None if steps > 0 => None,
+            None => Some((current_speed as u64 / step) * step),
+            Some(bps) => Some(bps.saturating_add_signed(steps * step as i64)),
+        };
+        *limit = next.map(|bps| bps.max(step));
+        self.apply_rate_limits();
+    }
+
+    pub(super) fn clear_rate_limits(&mut self) {
+        self.rate_limits = RateLimits::default();
+        self.apply_rate_limits();
+    }
+
+    pub(super) fn apply_rate_limits(&mut self) {
+        let limits = self.rate_limits;
+        self.backend.set_rate_limits(limits.download, limits.upload);
+        // Upload slots only apply under an upload limit, so rebalance on the next tick.
+        self.last_seed_balance = None;
+        self.show_toast(format!(
+            "Limits: down {}, up {}",
+            format_limit(limits.download),
+            format_limit(limits.upload)
+        ));
+    }
+}
+
+pub fn format_limit(bps: Option<u64>) -> String {
+    match bps {
+        None => "unlimited".to_string(),
+        Some(bps) if bps >= 1024 * 1024 => format!("{:.1} MiB/s", bps as f64 / 1024.0 / 1024.0),
+        Some(bps) => format!("{} KiB/s", bps / 1024),
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::{Duration, Instant};
+
+use super::state::App;
+
+impl App {
+    /// Queues bulk starts so their tracker announces are spread evenly over the configured
+    /// jitter window instead of all firing at once.
+    pub(super) fn schedule_starts(&mut self, ids: Vec<usize>) {
+        let now = Instant::now();
+        let window = Duration::from_secs(self.config.announce.jitter_secs);
+        let count = ids.len().max(1) as u32;
+        for (idx, id) in ids.into_iter().enumerate() {
+            self.start_queue
+                .push_back((now + window * idx as u32 / count, id));
+        }
+    }
+
+    /// Removes and returns queued starts whose slot has come up.
+    pub(super) fn due_starts(&mut self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut due = Vec::new();
+        while let Some(&(at, id)) = self.start_queue.front() {
+            if at > now {
+                break;
+            }
+            self.start_queue.pop_front();
+            due.push(id);
+        }
+        due
+    }
+}
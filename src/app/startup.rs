@@ -0,0 +1,84 @@
+use librqbit::TorrentStatsState;
+
+use crate::config::StartupPolicy;
+
+use super::{action::Action, effect::Effect, state::App};
+
+fn is_active(state: &TorrentStatsState) -> bool {
+    matches!(state, TorrentStatsState::Live | TorrentStatsState::Initializing)
+}
+
+impl App {
+    /// Whether every torrent finished initializing, so start/pause requests will stick.
+    pub(super) fn session_settled(&self) -> bool {
+        !self.torrents.iter().any(|t| {
+            t.stats
+                .as_ref()
+                .is_some_and(|s| matches!(s.state, TorrentStatsState::Initializing))
+        })
+    }
+
+    /// Start/pause effects that bring the freshly loaded session in line with the
+    /// configured startup policy.
+    pub(super) fn startup_actions(&mut self) -> Vec<Action> {
+        let mut pause = Vec::new();
+        let mut start = Vec::new();
+        for t in &self.torrents {
+            let Some(stats) = t.stats.as_ref() else {
+                continue;
+            };
+            let active = matches!(stats.state, TorrentStatsState::Live);
+            let paused = matches!(stats.state, TorrentStatsState::Paused);
+            let want_active = match self.config.startup {
+                _ if self.is_forced(t) => true,
+                StartupPolicy::ResumeAll => true,
+                StartupPolicy::Paused => false,
+                StartupPolicy::Restore => {
+                    let last = t
+                        .info_hash
+                        .as_deref()
+                        .and_then(|h| self.meta.get(h))
+                        .and_then(|m| m.was_active);
+                    // Nothing recorded yet: leave it as the session loaded it.
+                    last.unwrap_or(active)
+                }
+            };
+            if active && !want_active {
+                pause.push(t.id);
+            } else if paused && want_active {
+                start.push(t.id);
+            }
+        }
+        let mut actions = Vec::new();
+        if !pause.is_empty() {
+            actions.push(Action::RunEffect(Effect::PauseTorrents(pause)));
+        }
+        if !start.is_empty() {
+            self.schedule_starts(start);
+        }
+        actions
+    }
+
+    /// Remembers which torrents are running so `StartupPolicy::Restore` can bring them
+    /// back. Torrents paused by the app itself (queued starts, power pause) count as active.
+    pub(super) fn record_torrent_states(&mut self) {
+        let mut changes = Vec::new();
+        for t in &self.torrents {
+            let (Some(hash), Some(stats)) = (t.info_hash.as_ref(), t.stats.as_ref()) else {
+                continue;
+            };
+            let active = is_active(&stats.state)
+                || self.start_queue.iter().any(|&(_, id)| id == t.id)
+                || self.power_paused_ids.contains(&t.id);
+            if self.meta.get(hash).and_then(|m| m.was_active) != Some(active) {
+                changes.push((hash.clone(), active));
+            }
+        }
+        for (hash, active) in changes {
+            if let Err(err) = self.meta.update(&hash, |m| m.was_active = Some(active)) {
+                self.activity.push(format!("Metadata: {err:#}"));
+                break;
+            }
+        }
+    }
+}
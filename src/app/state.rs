@@ -1,27 +1,133 @@
-use std::{path::PathBuf, time::{Duration, Instant}};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use librqbit::{
-    api::Api,
+    api::ApiAddTorrentResponse,
     session_stats::snapshot::SessionStatsSnapshot,
     TorrentStats,
 };
 
+use crate::{
+    backend::{DiskWrites, PieceProgress, TorrentBackend},
+    config::{Config, Density, QuitConfirm, ThemeName},
+};
+
+use super::{
+    activity::{ActivityLog, ActivityView},
+    archive::{Archive, ArchiveMove},
+    adding::{AddOperation, AddStage, MagnetPreview},
+    bus::{EventBus, SessionEvent},
+    cancel::{BusyEffect, EffectCancel},
+    cast::CastPicker,
+    columns::{COLUMNS, ColumnResize, ColumnWidths},
+    command::MIN_INTERVAL,
+    groups::GroupBy,
+    speed::RateLimits,
+    debug::DebugStats,
+    duplicates::DuplicateReport,
+    event_hooks::HookRun,
+    hooks::CompletionTask,
+    alerts::FiringAlert,
+    filters::FilterSummary,
+    notify::Notifier,
+    api::ApiState,
+    ui_state::UiStateFile,
+    rss::{RssSeen, RssState},
+    info::InfoDetails,
+    context_menu::ContextMenu,
+    files::FileLine,
+    eta::RateTracker,
+    import::ImportJob,
+    maintenance::{MaintenanceJob, MaintenanceKind},
+    inspector::JsonInspector,
+    notes::NotesEditor,
+    meta::{AddSource, MetaStore, StorageKind},
+    peers::PeerHistory,
+    trackers::TrackerHealth,
+    disk_stats::Disks,
+    dir_choices::DirChoice,
+    save_path::SavePathTarget,
+    announce_debug::AnnounceDebug,
+    pending::{PendingAdd, PendingAdds},
+    progress::ProgressBoard,
+    redact::redact,
+    retry::PendingRetry,
+    rule_tester::RuleTester,
+    schedule::{ScheduleEditor, ScheduleFile},
+    torrent_file::TorrentFileView,
+    torrent_log::TorrentLogs,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Mode {
     Normal,
     EnterMagnet,
     EnterTorrentDir,
+    EnterLabel,
+    EnterName,
+    EnterLocation,
+    EnterSearch,
+    EnterCommand,
     FilePicker,
 }
 
+impl Mode {
+    /// Modes that edit the shared single-line input.
+    pub fn is_text_input(self) -> bool {
+        matches!(
+            self,
+            Mode::EnterMagnet
+                | Mode::EnterTorrentDir
+                | Mode::EnterLabel
+                | Mode::EnterName
+                | Mode::EnterLocation
+                | Mode::EnterSearch
+                | Mode::EnterCommand
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelTarget {
+    Selected,
+    Picker,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Dialog {
     None,
     AddTorrent,
     ConfirmDelete,
     ConfirmQuit,
+    ConfirmSpace,
+    Collision,
+    PathReview,
+    MagnetPreview,
     Help,
     FilePicker,
+    Inspector,
+    Notes,
+    ColumnResize,
+    Import,
+    ConfirmMaintenance,
+    Maintenance,
+    Retry,
+    Busy,
+    Tasks,
+    TopTalkers,
+    Duplicates,
+    TorrentFile,
+    Archive,
+    RuleTester,
+    Activity,
+    ContextMenu,
+    Schedule,
+    Cast,
     Error,
 }
 
@@ -30,6 +136,11 @@ pub enum View {
     Torrents,
     Peers,
     Info,
+    Files,
+    Trackers,
+    Timeline,
+    Log,
+    Rss,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,31 +157,65 @@ pub enum FilterKind {
     Paused,
     Stopped,
     Error,
+    Idle,
 }
 
-pub const FILTERS: [FilterKind; 6] = [
+pub const FILTERS: [FilterKind; 7] = [
     FilterKind::All,
     FilterKind::Downloading,
     FilterKind::Seeding,
     FilterKind::Paused,
     FilterKind::Stopped,
     FilterKind::Error,
+    FilterKind::Idle,
 ];
 
 #[derive(Debug)]
 pub struct TorrentRow {
     pub id: usize,
+    /// Display name: the local override if one is set, else the metainfo name.
     pub name: String,
+    pub original_name: String,
     pub info_hash: Option<String>,
     pub output_folder: String,
+    /// Announce URLs from the metainfo or magnet.
+    pub trackers: Vec<String>,
     pub stats: Option<TorrentStats>,
 }
 
+/// Transfer since launch, from counters recorded when the app started.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionTotals {
+    pub uptime: Duration,
+    pub downloaded: u64,
+    pub uploaded: u64,
+}
+
+impl SessionTotals {
+    pub fn ratio(&self) -> Option<f64> {
+        (self.downloaded > 0).then(|| self.uploaded as f64 / self.downloaded as f64)
+    }
+}
+
+/// A `:export` waiting for the next frame, written by the UI.
+#[derive(Debug, Clone)]
+pub struct ExportRequest {
+    pub path: PathBuf,
+    /// Every torrent in the current filter rather than just what fits on screen.
+    pub full_list: bool,
+    /// Keep colours as ANSI escapes instead of plain text.
+    pub ansi: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct FileEntry {
     pub name: String,
     pub length: u64,
     pub included: bool,
+    /// The file's name or path can't be created on Windows as-is.
+    pub path_issue: bool,
+    /// Executable or script type from `[risky_files]`.
+    pub risky: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -79,10 +224,37 @@ pub struct FilePickerState {
     pub output_folder: String,
     pub files: Vec<FileEntry>,
     pub cursor: usize,
+    pub label: Option<String>,
+    pub storage: StorageKind,
+    /// Network filesystem type of the output folder, shown as a warning.
+    pub network_fs: Option<String>,
+    /// Set while asking whether to start with risky files selected; true is Yes.
+    pub risky_choice: Option<bool>,
+    pub save_path: Option<SavePathTarget>,
 }
 
+/// An add whose destination folder is waiting on a user decision.
+pub struct PendingPlacement {
+    pub magnet: String,
+    pub base: PathBuf,
+    pub folder_name: String,
+    pub response: ApiAddTorrentResponse,
+    pub save_path: SavePathTarget,
+}
+
+/// Windows path problems found for an add, with a sanitized alternative.
+pub struct PathReview {
+    pub placement: PendingPlacement,
+    pub issues: Vec<String>,
+    pub sanitized: PathBuf,
+}
+
+/// Floors for the tick and refresh intervals under `low_memory`.
+const LOW_MEMORY_TICK: Duration = Duration::from_secs(1);
+const LOW_MEMORY_REFRESH: Duration = Duration::from_secs(2);
+
 pub struct App {
-    pub api: Api,
+    pub backend: Arc<dyn TorrentBackend>,
     pub torrents: Vec<TorrentRow>,
     pub selected: usize,
     pub mode: Mode,
@@ -90,6 +262,8 @@ pub struct App {
     pub input_cursor: usize,
     pub last_char_at: Option<Instant>,
     pub download_dir: PathBuf,
+    /// Directories listed under the download directory prompt.
+    pub dir_choices: Vec<DirChoice>,
     pub status: String,
     pub last_error: Option<String>,
     pub file_picker: Option<FilePickerState>,
@@ -99,18 +273,204 @@ pub struct App {
     pub delete_choice: bool,
     pub confirm_quit: bool,
     pub quit_choice: bool,
+    pub quit_confirm: QuitConfirm,
+    /// From `[ui] theme`; `C` switches it.
+    pub theme: ThemeName,
+    pub density: Density,
+    /// When `q` was pressed with `quit = "twice"`, until the window to press it again ends.
+    pub quit_pending_at: Option<Instant>,
+    /// Set by `K` / `:lock`: input can't change anything until the unlock sequence is typed.
+    pub locked: bool,
+    /// The last characters typed while locked, as long as the unlock sequence.
+    pub unlock_typed: String,
+    pub last_input_at: Instant,
+    pub screensaver: bool,
+    pub speed_history: VecDeque<(f64, f64)>,
+    /// False while the terminal reports it lost focus; see `[ui] unfocused_ms`.
+    pub focused: bool,
     pub focus: FocusPanel,
     pub filter_index: usize,
-    pub pending_add_input: Option<String>,
+    pub(super) filter_summary: FilterSummary,
+    /// Label filter from `[ui]` to pick once the labels are counted.
+    pub(super) start_label: Option<String>,
+    pub(super) ui_state: UiStateFile,
+    pub(super) rss: RssState,
+    pub(super) rss_seen: RssSeen,
+    /// `[[alerts]]` rules holding as of the last refresh, by position in the config.
+    pub(super) alerts: Vec<(usize, FiringAlert)>,
+    pub(super) notifier: Notifier,
+    pub(super) api: ApiState,
+    pub(super) schedule: ScheduleFile,
+    /// Grid cell applied last, so limits only switch when the hour's profile changes.
+    pub(super) schedule_slot: Option<char>,
+    /// Adds whose metadata is being fetched or that wait for their download dir prompt.
+    pub adds: Vec<AddOperation>,
     pub show_help: bool,
     pub help_scroll: u16,
     pub dialog: Dialog,
+    pub config: Config,
+    pub activity: ActivityLog,
+    /// Started with `--safe-mode`; see `enter_safe_mode`.
+    pub safe_mode: bool,
+    pub finished_state: HashMap<String, bool>,
+    pub pending_adds: PendingAdds,
+    pub confirm_space: Option<PendingAdd>,
+    pub space_free: u64,
+    pub space_choice: bool,
+    pub meta: MetaStore,
+    pub label_target: LabelTarget,
+    pub inspector: Option<JsonInspector>,
+    pub debug: DebugStats,
+    pub tick_interval: Duration,
+    pub refresh_interval: Duration,
+    pub last_refresh: Option<Instant>,
+    pub power_pause: Option<String>,
+    pub power_paused_ids: Vec<usize>,
+    pub last_power_check: Option<Instant>,
+    /// Finished torrents paused because they lost their upload slot.
+    pub seed_held_ids: Vec<usize>,
+    pub last_seed_balance: Option<Instant>,
+    /// When pause conditions were first seen clear while paused; resumes wait on it.
+    pub pause_clear_since: Option<Instant>,
+    pub start_queue: VecDeque<(Instant, usize)>,
+    pub startup_pending: bool,
+    pub links_synced: Option<BTreeMap<String, PathBuf>>,
+    pub collision: Option<PendingPlacement>,
+    pub collision_choice: usize,
+    pub path_review: Option<PathReview>,
+    pub magnet_preview: Option<MagnetPreview>,
+    pub path_review_choice: bool,
+    pub notes_editor: Option<NotesEditor>,
+    /// Write pacing and byte count of the real session's storage; None in demo mode.
+    pub disk_writes: Option<Arc<DiskWrites>>,
+    /// Session events for `--json-events`; only collected once enabled.
+    pub bus: EventBus,
+    pub disk_write_sample: Option<(Instant, u64)>,
+    /// Bytes per second written between the last two refreshes.
+    pub disk_write_rate: Option<f64>,
+    pub disks: Disks,
+    pub rates: RateTracker,
+    /// Uploaded bytes per torrent id at the previous refresh.
+    pub uploads_seen: HashMap<usize, u64>,
+    /// Info hashes rechecked by recheck-all or a scrub, stamped once their check is over.
+    pub verifying: HashSet<String>,
+    pub last_scrub: Option<Instant>,
+    pub peer_history: PeerHistory,
+    pub tracker_health: TrackerHealth,
+    pub announce_debug: AnnounceDebug,
+    /// Torrent whose files the Files view shows, with its files and the highlighted one.
+    pub files_torrent: Option<usize>,
+    pub file_lines: Vec<FileLine>,
+    pub file_selected: usize,
+    /// Set by Enter on a torrent: highlight its largest file once the list is read.
+    pub files_pick_largest: bool,
+    /// First line of the Info view shown, for torrents whose details don't fit.
+    pub info_scroll: u16,
+    pub info_details: Option<InfoDetails>,
+    /// Where the stream server listens, once the first stream URL has started it.
+    pub stream_addr: Option<std::net::SocketAddr>,
+    pub cast: Option<CastPicker>,
+    pub torrent_logs: TorrentLogs,
+    /// Ids of torrents whose download folder was missing at the last refresh.
+    pub missing_files: HashSet<usize>,
+    /// Torrents whose folder came back, to recheck (paused or not) on the next tick.
+    pub storage_returned: Vec<(usize, bool)>,
+    /// Finished torrents waiting to be moved under `move_to`, with where to.
+    pub completed_moves: Vec<(usize, String)>,
+    /// Completion work on finished torrents' files, run off the UI thread.
+    pub completion_tasks: Vec<CompletionTask>,
+    /// Running or just-finished bulk import; kept until its summary is dismissed.
+    pub import: Option<ImportJob>,
+    /// Recheck-all or re-announce-all waiting on the confirmation dialog.
+    pub confirm_maintenance: Option<MaintenanceKind>,
+    pub maintenance_choice: bool,
+    /// Running or just-finished recheck/re-announce; kept until its summary is dismissed.
+    pub maintenance: Option<MaintenanceJob>,
+    /// Network effect waiting to run again after a failure.
+    pub retry: Option<PendingRetry>,
+    pub busy: Option<BusyEffect>,
+    pub tasks_open: bool,
+    pub talkers_open: bool,
+    pub talker_selected: usize,
+    pub talkers_by_upload: bool,
+    pub duplicates: Option<DuplicateReport>,
+    pub torrent_file: Option<TorrentFileView>,
+    pub archive: Archive,
+    /// Offloads and restores still moving data.
+    pub archive_moves: Vec<ArchiveMove>,
+    pub archive_open: bool,
+    pub archive_selected: usize,
+    pub rule_tester: Option<RuleTester>,
+    pub activity_view: Option<ActivityView>,
+    pub context_menu: Option<ContextMenu>,
+    pub schedule_editor: Option<ScheduleEditor>,
+    /// `[[hooks]]` commands still running.
+    pub hook_runs: Vec<HookRun>,
+    pub task_selected: usize,
+    /// Shared with the input thread so Esc can stop an effect the main loop is awaiting.
+    pub effect_cancel: EffectCancel,
+    pub progress: ProgressBoard,
+    /// Lowercased `:source` query; only torrents whose source contains it are listed.
+    pub source_filter: Option<String>,
+    /// Show tracker passkeys on screen instead of masking them (`:reveal`).
+    pub reveal_secrets: bool,
+    pub pending_export: Option<ExportRequest>,
+    pub columns: ColumnWidths,
+    pub column_resize: Option<ColumnResize>,
+    pub group_by: GroupBy,
+    /// Names of collapsed sections in the grouped list.
+    pub collapsed_groups: HashSet<String>,
+    /// Set while the cursor is on a section header rather than a torrent.
+    pub selected_group: Option<String>,
+    /// Verified pieces of the torrent (by id) selected at the last refresh.
+    pub selected_pieces: Option<(usize, PieceProgress)>,
+    pub started_at: Instant,
+    /// Session fetched and uploaded byte counters at launch.
+    pub baseline_transfer: (u64, u64),
+    pub rate_limits: RateLimits,
+    pub toast: Option<(String, Instant)>,
 }
 
 impl App {
-    pub fn new(api: Api, download_dir: PathBuf) -> Self {
-        Self {
-            api,
+    pub fn new(backend: Arc<dyn TorrentBackend>, download_dir: PathBuf, config: Config) -> Self {
+        let data_dir = if backend.is_simulated() {
+            None
+        } else {
+            crate::config::data_dir()
+        };
+        let activity = ActivityLog::new(data_dir.as_ref().map(|d| d.join("activity.log")));
+        let pending_adds =
+            PendingAdds::load(data_dir.as_ref().map(|d| d.join("pending_adds.json")));
+        let mut bus = EventBus::default();
+        // Demo and replayed torrents must not run commands or send mail and chat messages.
+        if !config.hooks.is_empty() && !backend.is_simulated() {
+            bus.enable_hooks();
+        }
+        let archive = Archive::load(data_dir.as_ref().map(|d| d.join("archive.json")));
+        let meta = MetaStore::load(data_dir.as_ref().map(|d| d.join("metadata.json")));
+        let columns = ColumnWidths::load(data_dir.as_ref().map(|d| d.join("columns.json")));
+        let ui_state = UiStateFile::load(data_dir.as_ref().map(|d| d.join("ui_state.json")));
+        let rss_seen = RssSeen::load(data_dir.as_ref().map(|d| d.join("rss_seen.json")));
+        let schedule = ScheduleFile::load(data_dir.as_ref().map(|d| d.join("schedule.json")));
+        // A zero interval would panic the tick timer.
+        let mut tick_interval = Duration::from_millis(config.ui.tick_ms).max(MIN_INTERVAL);
+        let mut refresh_interval =
+            Duration::from_millis(config.ui.refresh_ms).max(MIN_INTERVAL);
+        if config.low_memory {
+            tick_interval = tick_interval.max(LOW_MEMORY_TICK);
+            refresh_interval = refresh_interval.max(LOW_MEMORY_REFRESH);
+        }
+        let torrent_logs = TorrentLogs::new(!config.low_memory);
+        let launch_stats = backend.session_stats();
+        let rate_limits = RateLimits {
+            download: config.speed.download_limit_kib.map(|kib| kib * 1024),
+            upload: config.speed.upload_limit_kib.map(|kib| kib * 1024),
+        };
+        if rate_limits != RateLimits::default() {
+            backend.set_rate_limits(rate_limits.download, rate_limits.upload);
+        }
+        let mut app = Self {
+            backend,
             torrents: Vec::new(),
             selected: 0,
             mode: Mode::Normal,
@@ -118,6 +478,7 @@ impl App {
             input_cursor: 0,
             last_char_at: None,
             download_dir,
+            dir_choices: Vec::new(),
             status: "Ready".to_string(),
             last_error: None,
             file_picker: None,
@@ -127,13 +488,127 @@ impl App {
             delete_choice: false,
             confirm_quit: false,
             quit_choice: false,
+            quit_confirm: config.ui.quit,
+            theme: config.ui.theme,
+            density: config.ui.density,
+            quit_pending_at: None,
+            locked: false,
+            unlock_typed: String::new(),
+            last_input_at: Instant::now(),
+            screensaver: false,
+            focused: true,
+            speed_history: VecDeque::new(),
             focus: FocusPanel::Torrents,
             filter_index: 0,
-            pending_add_input: None,
+            filter_summary: FilterSummary::default(),
+            start_label: None,
+            ui_state,
+            rss: RssState::default(),
+            rss_seen,
+            alerts: Vec::new(),
+            notifier: Notifier::default(),
+            api: ApiState::default(),
+            schedule,
+            schedule_slot: None,
+            adds: Vec::new(),
             show_help: false,
             help_scroll: 0,
             dialog: Dialog::None,
-        }
+            config,
+            activity,
+            safe_mode: false,
+            finished_state: HashMap::new(),
+            pending_adds,
+            confirm_space: None,
+            space_free: 0,
+            space_choice: false,
+            meta,
+            label_target: LabelTarget::Selected,
+            inspector: None,
+            debug: DebugStats::default(),
+            tick_interval,
+            refresh_interval,
+            last_refresh: None,
+            power_pause: None,
+            power_paused_ids: Vec::new(),
+            last_power_check: None,
+            seed_held_ids: Vec::new(),
+            last_seed_balance: None,
+            pause_clear_since: None,
+            start_queue: VecDeque::new(),
+            startup_pending: true,
+            links_synced: None,
+            collision: None,
+            collision_choice: 0,
+            path_review: None,
+            magnet_preview: None,
+            path_review_choice: true,
+            notes_editor: None,
+            source_filter: None,
+            reveal_secrets: false,
+            pending_export: None,
+            columns,
+            column_resize: None,
+            group_by: GroupBy::Off,
+            collapsed_groups: HashSet::new(),
+            selected_group: None,
+            selected_pieces: None,
+            started_at: Instant::now(),
+            baseline_transfer: (launch_stats.fetched_bytes, launch_stats.uploaded_bytes),
+            rate_limits,
+            toast: None,
+            disk_writes: None,
+            bus,
+            disk_write_sample: None,
+            disk_write_rate: None,
+            disks: Disks::default(),
+            rates: RateTracker::default(),
+            uploads_seen: HashMap::new(),
+            verifying: HashSet::new(),
+            last_scrub: None,
+            peer_history: PeerHistory::default(),
+            tracker_health: TrackerHealth::default(),
+            announce_debug: AnnounceDebug::default(),
+            files_torrent: None,
+            file_lines: Vec::new(),
+            file_selected: 0,
+            files_pick_largest: false,
+            info_scroll: 0,
+            info_details: None,
+            stream_addr: None,
+            cast: None,
+            torrent_logs,
+            missing_files: HashSet::new(),
+            storage_returned: Vec::new(),
+            completed_moves: Vec::new(),
+            completion_tasks: Vec::new(),
+            import: None,
+            confirm_maintenance: None,
+            maintenance_choice: false,
+            maintenance: None,
+            retry: None,
+            busy: None,
+            tasks_open: false,
+            talkers_open: false,
+            talker_selected: 0,
+            talkers_by_upload: false,
+            duplicates: None,
+            torrent_file: None,
+            archive,
+            archive_moves: Vec::new(),
+            archive_open: false,
+            archive_selected: 0,
+            rule_tester: None,
+            activity_view: None,
+            context_menu: None,
+            schedule_editor: None,
+            hook_runs: Vec::new(),
+            task_selected: 0,
+            effect_cancel: EffectCancel::default(),
+            progress: ProgressBoard::default(),
+        };
+        app.apply_start_state();
+        app
     }
 
     pub fn mode(&self) -> Mode {
@@ -160,7 +635,11 @@ impl App {
         self.file_picker.as_ref()
     }
 
+    /// None while a section header is selected in the grouped list.
     pub fn selected_torrent(&self) -> Option<&TorrentRow> {
+        if self.selected_group.is_some() {
+            return None;
+        }
         self.torrents.get(self.selected)
     }
 
@@ -196,10 +675,226 @@ impl App {
         self.quit_choice
     }
 
+    pub fn confirm_space(&self) -> Option<&PendingAdd> {
+        self.confirm_space.as_ref()
+    }
+
+    pub fn space_free(&self) -> u64 {
+        self.space_free
+    }
+
+    pub fn space_choice(&self) -> bool {
+        self.space_choice
+    }
+
+    pub fn collision(&self) -> Option<&PendingPlacement> {
+        self.collision.as_ref()
+    }
+
+    pub fn collision_choice(&self) -> usize {
+        self.collision_choice
+    }
+
+    pub fn path_review(&self) -> Option<&PathReview> {
+        self.path_review.as_ref()
+    }
+
+    pub fn magnet_preview(&self) -> Option<&MagnetPreview> {
+        self.magnet_preview.as_ref()
+    }
+
+    pub fn path_review_choice(&self) -> bool {
+        self.path_review_choice
+    }
+
+    pub fn pending_adds(&self) -> &[PendingAdd] {
+        self.pending_adds.items()
+    }
+
+    pub fn label_of(&self, t: &TorrentRow) -> Option<&str> {
+        t.info_hash.as_deref().and_then(|h| self.meta.label(h))
+    }
+
+    pub fn notes_of(&self, t: &TorrentRow) -> Option<&str> {
+        t.info_hash.as_deref().and_then(|h| self.meta.notes(h))
+    }
+
+    pub fn is_forced(&self, t: &TorrentRow) -> bool {
+        t.info_hash
+            .as_deref()
+            .and_then(|h| self.meta.get(h))
+            .is_some_and(|m| m.force_started)
+    }
+
+    pub fn source_of(&self, t: &TorrentRow) -> Option<&AddSource> {
+        t.info_hash
+            .as_deref()
+            .and_then(|h| self.meta.get(h))
+            .and_then(|m| m.source.as_ref())
+    }
+
+    pub fn storage_of(&self, t: &TorrentRow) -> StorageKind {
+        t.info_hash
+            .as_deref()
+            .and_then(|h| self.meta.get(h))
+            .map(|m| m.storage)
+            .unwrap_or_default()
+    }
+
+    pub fn source_filter(&self) -> Option<&str> {
+        self.source_filter.as_deref()
+    }
+
+    pub fn take_export(&mut self) -> Option<ExportRequest> {
+        self.pending_export.take()
+    }
+
+    pub fn exported(&mut self, request: &ExportRequest) {
+        let what = if request.full_list { "torrent list" } else { "screen" };
+        self.status = format!("Exported {what} to {}", request.path.display());
+        self.activity.push(self.status.clone());
+    }
+
+    pub fn import(&self) -> Option<&ImportJob> {
+        self.import.as_ref()
+    }
+
+    pub fn confirm_maintenance(&self) -> Option<MaintenanceKind> {
+        self.confirm_maintenance
+    }
+
+    pub fn maintenance_choice(&self) -> bool {
+        self.maintenance_choice
+    }
+
+    pub fn maintenance(&self) -> Option<&MaintenanceJob> {
+        self.maintenance.as_ref()
+    }
+
+    pub fn adds(&self) -> &[AddOperation] {
+        &self.adds
+    }
+
+    pub fn progress(&self) -> &ProgressBoard {
+        &self.progress
+    }
+
+    pub fn retry(&self) -> Option<&PendingRetry> {
+        self.retry.as_ref()
+    }
+
+    pub fn busy(&self) -> Option<&BusyEffect> {
+        self.busy.as_ref()
+    }
+
+    pub fn tasks_open(&self) -> bool {
+        self.tasks_open
+    }
+
+    pub fn task_selected(&self) -> usize {
+        self.task_selected
+    }
+
+    pub fn talkers_open(&self) -> bool {
+        self.talkers_open
+    }
+
+    pub fn talker_selected(&self) -> usize {
+        self.talker_selected
+    }
+
+    pub fn talkers_by_upload(&self) -> bool {
+        self.talkers_by_upload
+    }
+
+    pub fn file_lines(&self) -> &[FileLine] {
+        &self.file_lines
+    }
+
+    pub fn file_selected(&self) -> usize {
+        self.file_selected
+    }
+
+    pub fn info_scroll(&self) -> u16 {
+        self.info_scroll
+    }
+
+    pub fn cast_picker(&self) -> Option<&CastPicker> {
+        self.cast.as_ref()
+    }
+
+    pub fn duplicates(&self) -> Option<&DuplicateReport> {
+        self.duplicates.as_ref()
+    }
+
+    pub fn torrent_file(&self) -> Option<&TorrentFileView> {
+        self.torrent_file.as_ref()
+    }
+
+    pub fn archive_open(&self) -> bool {
+        self.archive_open
+    }
+
+    pub fn archive_selected(&self) -> usize {
+        self.archive_selected
+    }
+
+    pub fn notes_editor(&self) -> Option<&NotesEditor> {
+        self.notes_editor.as_ref()
+    }
+
+    pub fn pieces_of(&self, t: &TorrentRow) -> Option<PieceProgress> {
+        self.selected_pieces
+            .filter(|(id, _)| *id == t.id)
+            .map(|(_, pieces)| pieces)
+    }
+
+    pub fn column_widths(&self) -> [usize; COLUMNS] {
+        self.columns.widths()
+    }
+
+    pub fn column_resize(&self) -> Option<&ColumnResize> {
+        self.column_resize.as_ref()
+    }
+
+    pub fn inspector(&self) -> Option<&JsonInspector> {
+        self.inspector.as_ref()
+    }
+
+    pub fn queued_starts(&self) -> usize {
+        self.start_queue.len()
+    }
+
+    pub fn power_pause(&self) -> Option<&str> {
+        self.power_pause.as_deref()
+    }
+
+    pub fn debug(&self) -> &DebugStats {
+        &self.debug
+    }
+
+    /// `[ui] title`, with `{down}`-style placeholders still in it.
+    pub fn title_template(&self) -> &str {
+        &self.config.ui.title
+    }
+
+    pub fn density(&self) -> Density {
+        self.density
+    }
+
     pub fn focus(&self) -> FocusPanel {
         self.focus
     }
 
+    pub fn theme(&self) -> ThemeName {
+        self.theme
+    }
+
+    pub(super) fn cycle_theme(&mut self) {
+        self.theme = self.theme.next();
+        self.show_toast(format!("Theme: {}", self.theme.label()));
+    }
+
     pub fn selected_filter(&self) -> FilterKind {
         FILTERS
             .get(self.filter_index)
@@ -221,21 +916,62 @@ impl App {
             .collect()
     }
 
+    /// Current disk write throughput and cap (0 = unlimited), both in bytes per second.
+    pub fn disk_write_rate(&self) -> Option<(f64, u64)> {
+        let limit = self.disk_writes.as_ref()?.limit();
+        Some((self.disk_write_rate.unwrap_or(0.0), limit))
+    }
+
     pub fn session_stats(&self) -> Option<&SessionStatsSnapshot> {
         self.session_stats.as_ref()
     }
 
+    pub fn session_totals(&self) -> Option<SessionTotals> {
+        let stats = self.session_stats.as_ref()?;
+        let (fetched, uploaded) = self.baseline_transfer;
+        Some(SessionTotals {
+            uptime: self.started_at.elapsed(),
+            downloaded: stats.fetched_bytes.saturating_sub(fetched),
+            uploaded: stats.uploaded_bytes.saturating_sub(uploaded),
+        })
+    }
+
     pub fn set_error(&mut self, err: impl ToString) {
-        self.last_error = Some(err.to_string());
+        let message = err.to_string();
+        self.bus.publish(SessionEvent::Error {
+            info_hash: None,
+            name: None,
+            message: redact(&message).into_owned(),
+        });
+        self.last_error = Some(message);
         self.status = "Error".to_string();
         self.mode = Mode::Normal;
         self.view = View::Torrents;
         self.file_picker = None;
         self.confirm_delete = false;
         self.confirm_quit = false;
+        self.confirm_space = None;
+        self.confirm_maintenance = None;
+        self.retry = None;
+        self.busy = None;
+        self.tasks_open = false;
+        self.talkers_open = false;
+        self.duplicates = None;
+        self.torrent_file = None;
+        self.archive_open = false;
+        self.rule_tester = None;
+        self.activity_view = None;
+        self.context_menu = None;
+        self.schedule_editor = None;
+        self.cast = None;
+        self.collision = None;
+        self.path_review = None;
+        self.magnet_preview = None;
+        self.notes_editor = None;
+        self.inspector = None;
         self.show_help = false;
         self.help_scroll = 0;
-        self.pending_add_input = None;
+        self.adds.retain(|op| op.stage != AddStage::Prompting);
         self.input.clear();
         self.input_cursor = 0;
         self.last_char_at = None;
@@ -249,8 +985,17 @@ impl App {
         self.dialog = Dialog::None;
     }
 
-    pub fn last_error(&self) -> Option<&str> {
-        self.last_error.as_deref()
+    pub fn last_error(&self) -> Option<Cow<'_, str>> {
+        self.last_error.as_deref().map(|err| self.masked(err))
+    }
+
+    /// `text` with tracker passkeys masked, unless `:reveal` is on.
+    pub fn masked<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.reveal_secrets {
+            Cow::Borrowed(text)
+        } else {
+            redact(text)
+        }
     }
 
     pub fn has_same_destination(&self, info_hash: &str, output_folder: &str) -> bool {
@@ -277,12 +1022,12 @@ impl App {
             return false;
         }
         let now = Instant::now();
-        if let Some(last) = self.last_char_at {
-            if now.duration_since(last) <= Duration::from_millis(200) {
-                self.last_char_at = Some(now);
-                self.status = "Paste ignored".to_string();
-                return true;
-            }
+        if let Some(last) = self.last_char_at
+            && now.duration_since(last) <= Duration::from_millis(200)
+        {
+            self.last_char_at = Some(now);
+            self.status = "Paste ignored".to_string();
+            return true;
         }
         self.last_char_at = Some(now);
         false
@@ -324,48 +1069,40 @@ impl App {
     }
 
     pub fn filter_match(&self, t: &TorrentRow) -> bool {
-        use FilterKind::*;
-        let Some(stats) = t.stats.as_ref() else {
-            return matches!(self.selected_filter(), All | Stopped);
-        };
-        let is_seeding = stats.finished
-            || (stats.total_bytes > 0
-                && stats.progress_bytes >= stats.total_bytes
-                && matches!(stats.state, librqbit::TorrentStatsState::Live));
-        match self.selected_filter() {
-            All => true,
-            Downloading => {
-                matches!(stats.state, librqbit::TorrentStatsState::Live) && !stats.finished
+        if let Some(query) = self.source_filter.as_deref() {
+            let source = self.source_of(t).map(|s| s.to_string().to_lowercase());
+            if !source.is_some_and(|s| s.contains(query)) {
+                return false;
             }
-            Seeding => is_seeding,
-            Paused => matches!(stats.state, librqbit::TorrentStatsState::Paused),
-            Stopped => false,
-            Error => matches!(stats.state, librqbit::TorrentStatsState::Error),
         }
+        if let Some(label) = self.selected_label_filter() {
+            return self.label_of(t) == Some(label);
+        }
+        self.matches_kind(t, self.selected_filter())
     }
 
     pub fn ensure_selection_for_filter(&mut self) {
         if self.torrents.is_empty() {
             self.selected = 0;
+            self.selected_group = None;
             return;
         }
-        if self.selected < self.torrents.len() && self.filter_match(&self.torrents[self.selected]) {
-            return;
-        }
-        if let Some(idx) = self.torrents.iter().enumerate().find_map(|(idx, t)| {
-            if self.filter_match(t) {
-                Some(idx)
-            } else {
-                None
-            }
-        }) {
-            self.selected = idx;
-        } else {
-            self.selected = 0;
+        if self.selected >= self.torrents.len() || !self.filter_match(&self.torrents[self.selected])
+        {
+            self.selected = self
+                .torrents
+                .iter()
+                .position(|t| self.filter_match(t))
+                .unwrap_or(0);
         }
+        self.ensure_group_cursor();
     }
 
     pub fn move_selection(&mut self, delta: isize) {
+        if self.group_by != GroupBy::Off {
+            self.move_grouped_selection(delta);
+            return;
+        }
         let indices = self.filtered_indices();
         if indices.is_empty() {
             return;
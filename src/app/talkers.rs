@@ -0,0 +1,96 @@
+use super::{
+    FocusPanel,
+    groups::GroupBy,
+    state::{App, Dialog},
+};
+
+/// Rows shown in the Top talkers dialog.
+const TOP_TALKERS: usize = 10;
+
+/// A torrent moving data right now, for the Top talkers dialog.
+#[derive(Debug, Clone)]
+pub struct Talker {
+    pub id: usize,
+    pub name: String,
+    /// MiB/s.
+    pub down: f64,
+    pub up: f64,
+}
+
+impl App {
+    /// The torrents using the most bandwidth, by download or upload rate depending on the
+    /// dialog's sort.
+    pub fn top_talkers(&self) -> Vec<Talker> {
+        let mut talkers: Vec<Talker> = self
+            .torrents
+            .iter()
+            .filter_map(|t| {
+                let live = t.stats.as_ref()?.live.as_ref()?;
+                Some(Talker {
+                    id: t.id,
+                    name: t.name.clone(),
+                    down: live.download_speed.mbps,
+                    up: live.upload_speed.mbps,
+                })
+            })
+            .filter(|t| t.down > 0.0 || t.up > 0.0)
+            .collect();
+        let by_upload = self.talkers_by_upload;
+        let key = |t: &Talker| if by_upload { (t.up, t.down) } else { (t.down, t.up) };
+        talkers.sort_by(|a, b| {
+            let (a, b) = (key(a), key(b));
+            b.0.total_cmp(&a.0).then(b.1.total_cmp(&a.1))
+        });
+        talkers.truncate(TOP_TALKERS);
+        talkers
+    }
+
+    pub(super) fn open_talkers(&mut self) {
+        self.talkers_open = true;
+        self.talker_selected = 0;
+        self.dialog = Dialog::TopTalkers;
+    }
+
+    pub(super) fn close_talkers(&mut self) {
+        self.talkers_open = false;
+        if self.dialog == Dialog::TopTalkers {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    pub(super) fn move_talker_selection(&mut self, delta: isize) {
+        let count = self.top_talkers().len();
+        self.talker_selected = self
+            .talker_selected
+            .saturating_add_signed(delta)
+            .min(count.saturating_sub(1));
+    }
+
+    pub(super) fn toggle_talker_sort(&mut self) {
+        self.talkers_by_upload = !self.talkers_by_upload;
+        self.talker_selected = 0;
+    }
+
+    /// Closes the dialog with the torrent selected in the list, showing all torrents and
+    /// opening its group if the filter or a collapsed group would hide it.
+    pub(super) fn jump_to_talker(&mut self) {
+        let Some(talker) = self.top_talkers().into_iter().nth(self.talker_selected) else {
+            return;
+        };
+        let Some(idx) = self.torrents.iter().position(|t| t.id == talker.id) else {
+            return;
+        };
+        self.close_talkers();
+        if !self.filter_match(&self.torrents[idx]) {
+            self.filter_index = 0;
+        }
+        if self.group_by != GroupBy::Off {
+            let group = self.group_of(&self.torrents[idx]);
+            self.collapsed_groups.remove(&group);
+        }
+        self.selected = idx;
+        self.selected_group = None;
+        self.focus = FocusPanel::Torrents;
+        self.show_toast(format!("Selected {}", talker.name));
+    }
+}
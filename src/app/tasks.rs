@@ -0,0 +1,178 @@
+use std::time::Instant;
+
+use super::{
+    adding::AddStage,
+    state::{App, Dialog},
+};
+
+/// Which background operation a [`Task`] stands for, so it can be cancelled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskId {
+    /// A background add, by its magnet, URL or path.
+    Add(String),
+    /// A failed fetch or add waiting to run again.
+    Retry,
+    Import,
+    Maintenance,
+    /// librqbit checking a torrent's data, by info hash.
+    Check(String),
+}
+
+/// One row of the Tasks dialog.
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub id: TaskId,
+    pub kind: &'static str,
+    pub name: String,
+    pub state: String,
+    pub pct: Option<u8>,
+    pub started: Instant,
+}
+
+impl Task {
+    /// librqbit has no way to stop a check short of removing the torrent.
+    pub fn cancellable(&self) -> bool {
+        !matches!(self.id, TaskId::Check(_))
+    }
+}
+
+impl App {
+    /// Everything running in the background, built from each operation's own state so the
+    /// list can't drift out of sync with it.
+    pub fn tasks(&self) -> Vec<Task> {
+        let mut tasks = Vec::new();
+        if let Some(job) = self.import.as_ref().filter(|job| !job.finished()) {
+            let entry = self.progress.get("import");
+            tasks.push(Task {
+                id: TaskId::Import,
+                kind: "Import",
+                name: job.file.clone(),
+                state: match &job.waiting {
+                    Some((_, retry)) => {
+                        format!("{} of {}, retry {}", job.done(), job.total, retry.attempts + 1)
+                    }
+                    None => format!("{} of {}", job.done(), job.total),
+                },
+                pct: entry.and_then(|e| e.pct),
+                started: entry.map_or_else(Instant::now, |e| e.started),
+            });
+        }
+        if let Some(job) = self.maintenance.as_ref().filter(|job| !job.finished()) {
+            let entry = self.progress.get("maintenance");
+            tasks.push(Task {
+                id: TaskId::Maintenance,
+                kind: job.kind.label(),
+                name: "All torrents".to_string(),
+                state: if job.cancelled {
+                    "stopping".to_string()
+                } else {
+                    format!("{} of {}", job.handled(), job.total)
+                },
+                pct: entry.and_then(|e| e.pct),
+                started: entry.map_or_else(Instant::now, |e| e.started),
+            });
+        }
+        if let Some(retry) = &self.retry {
+            tasks.push(Task {
+                id: TaskId::Retry,
+                kind: retry.label(),
+                name: retry.last_error.clone(),
+                state: match retry.wait() {
+                    Some(wait) => format!("retry in {}s", wait.as_secs_f64().ceil() as u64),
+                    None => "retrying".to_string(),
+                },
+                pct: None,
+                started: retry.first_failed,
+            });
+        }
+        for op in &self.adds {
+            let state = match &op.stage {
+                AddStage::Fetching => "fetching".to_string(),
+                AddStage::Retrying { attempts, .. } => format!("retry {}", attempts + 1),
+                AddStage::Ready => "ready".to_string(),
+                AddStage::Prompting => "choose folder".to_string(),
+            };
+            tasks.push(Task {
+                id: TaskId::Add(op.input.clone()),
+                kind: "Fetch",
+                name: op
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| self.masked(&op.input).into_owned()),
+                state,
+                pct: None,
+                started: op.started,
+            });
+        }
+        for (id, entry) in self.progress.iter() {
+            let Some(hash) = id.strip_prefix("check:") else {
+                continue;
+            };
+            tasks.push(Task {
+                id: TaskId::Check(hash.to_string()),
+                kind: "Recheck",
+                name: entry.msg.strip_prefix("Checking ").unwrap_or(&entry.msg).to_string(),
+                state: "checking".to_string(),
+                pct: entry.pct,
+                started: entry.started,
+            });
+        }
+        tasks
+    }
+
+    pub(super) fn open_tasks(&mut self) {
+        self.tasks_open = true;
+        self.task_selected = 0;
+        self.dialog = Dialog::Tasks;
+    }
+
+    pub(super) fn close_tasks(&mut self) {
+        self.tasks_open = false;
+        if self.dialog == Dialog::Tasks {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    pub(super) fn move_task_selection(&mut self, delta: i32) {
+        let count = self.tasks().len();
+        self.task_selected = self
+            .task_selected
+            .saturating_add_signed(delta as isize)
+            .min(count.saturating_sub(1));
+    }
+
+    /// Cancels the selected task the way its own dialog or command would.
+    pub(super) fn cancel_selected_task(&mut self) {
+        let Some(task) = self.tasks().into_iter().nth(self.task_selected) else {
+            return;
+        };
+        match &task.id {
+            TaskId::Add(input) => {
+                self.cancel_add(input);
+            }
+            TaskId::Retry => {
+                // Shows its own toast.
+                self.cancel_retry();
+                self.dialog = Dialog::Tasks;
+                self.move_task_selection(0);
+                return;
+            }
+            TaskId::Import => {
+                if let Some(job) = &mut self.import {
+                    job.cancelled = true;
+                }
+            }
+            TaskId::Maintenance => {
+                if let Some(job) = &mut self.maintenance {
+                    job.cancelled = true;
+                }
+            }
+            TaskId::Check(_) => {
+                self.show_toast("Data checks can't be cancelled".to_string());
+                return;
+            }
+        }
+        self.show_toast(format!("{} cancelled", task.kind));
+        self.move_task_selection(0);
+    }
+}
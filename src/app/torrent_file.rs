@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::metainfo::{self, TorrentInfo};
+
+use super::state::{App, Dialog};
+
+/// A `.torrent` file opened with `:inspect`, shown without being added.
+#[derive(Debug, Clone)]
+pub struct TorrentFileView {
+    pub path: PathBuf,
+    pub info: TorrentInfo,
+    pub scroll: u16,
+}
+
+impl App {
+    pub(super) fn inspect_torrent_file(&mut self, path: PathBuf) -> Result<()> {
+        let info = metainfo::read(&path)?;
+        self.torrent_file = Some(TorrentFileView {
+            path,
+            info,
+            scroll: 0,
+        });
+        self.dialog = Dialog::TorrentFile;
+        Ok(())
+    }
+
+    pub(super) fn scroll_torrent_file(&mut self, delta: i16) {
+        if let Some(view) = &mut self.torrent_file {
+            view.scroll = view.scroll.saturating_add_signed(delta);
+        }
+    }
+
+    pub(super) fn close_torrent_file(&mut self) {
+        self.torrent_file = None;
+        if self.dialog == Dialog::TorrentFile {
+            self.dialog = Dialog::None;
+        }
+    }
+
+    /// Adds the inspected file through the usual add flow.
+    pub(super) fn add_torrent_file(&mut self) -> Result<()> {
+        let Some(view) = self.torrent_file.take() else {
+            return Ok(());
+        };
+        self.dialog = Dialog::None;
+        self.submit_add(view.path.to_string_lossy().into_owned())
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::Local;
+use librqbit::TorrentStatsState;
+
+use super::{
+    activity::ActivityEntry,
+    state::{App, TorrentRow},
+};
+
+const MAX_EVENTS: usize = 100;
+
+/// Bounded per-torrent event history keyed by info hash, kept for the session only.
+#[derive(Debug, Default)]
+pub struct TorrentLogs {
+    enabled: bool,
+    events: HashMap<String, VecDeque<ActivityEntry>>,
+    /// State seen at the previous refresh.
+    states: HashMap<String, StateSeen>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StateSeen {
+    label: &'static str,
+    error: Option<String>,
+}
+
+impl TorrentLogs {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    pub fn push(&mut self, info_hash: &str, message: impl Into<String>) {
+        if !self.enabled {
+            return;
+        }
+        let events = self.events.entry(info_hash.to_string()).or_default();
+        if events.len() >= MAX_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(ActivityEntry {
+            at: Local::now(),
+            message: message.into(),
+            run: None,
+        });
+    }
+
+    pub fn events(&self, info_hash: &str) -> impl DoubleEndedIterator<Item = &ActivityEntry> {
+        self.events.get(info_hash).into_iter().flatten()
+    }
+}
+
+impl App {
+    pub(super) fn log_torrent(&mut self, id: usize, message: impl Into<String>) {
+        let torrent = self.torrents.iter().find(|t| t.id == id);
+        if let Some(hash) = torrent.and_then(|t| t.info_hash.as_deref()) {
+            self.torrent_logs.push(hash, message);
+        }
+    }
+
+    /// Logs state transitions since the last refresh, with what the check found when a
+    /// torrent leaves initializing and the message when one errors.
+    pub(super) fn log_state_changes(&mut self) {
+        if !self.torrent_logs.enabled {
+            return;
+        }
+        for t in &self.torrents {
+            let (Some(hash), Some(stats)) = (t.info_hash.as_ref(), t.stats.as_ref()) else {
+                continue;
+            };
+            let seen = StateSeen {
+                label: state_label(&stats.state),
+                error: stats.error.clone(),
+            };
+            let previous = self.torrent_logs.states.insert(hash.clone(), seen.clone());
+            if previous.as_ref() == Some(&seen) {
+                continue;
+            }
+            let logs = &mut self.torrent_logs;
+            match previous {
+                None => logs.push(hash, format!("State: {}", seen.label)),
+                Some(prev) => {
+                    if prev.label == "initializing" {
+                        logs.push(
+                            hash,
+                            format!(
+                                "Check finished: {} of {} bytes present",
+                                stats.progress_bytes, stats.total_bytes
+                            ),
+                        );
+                    }
+                    if prev.label != seen.label {
+                        logs.push(hash, format!("State: {} -> {}", prev.label, seen.label));
+                    }
+                }
+            }
+            if let Some(error) = seen.error {
+                logs.push(hash, format!("Error: {error}"));
+            }
+        }
+    }
+
+    /// Events for `t`, oldest first.
+    pub fn torrent_events(&self, t: &TorrentRow) -> Vec<&ActivityEntry> {
+        t.info_hash
+            .as_deref()
+            .map(|h| self.torrent_logs.events(h).collect())
+            .unwrap_or_default()
+    }
+}
+
+pub(super) fn state_label(state: &TorrentStatsState) -> &'static str {
+    match state {
+        TorrentStatsState::Initializing => "initializing",
+        TorrentStatsState::Live => "live",
+        TorrentStatsState::Paused => "paused",
+        TorrentStatsState::Error => "error",
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use tokio::task::JoinHandle;
+
+use crate::scrape::ScrapeStats;
+
+use super::state::{App, View};
+
+/// How often the trackers of the torrent in the Trackers view are scraped again.
+const PROBE_EVERY: Duration = Duration::from_secs(60);
+
+type ProbeResults = Vec<(String, Result<ScrapeStats>)>;
+
+/// Scrape results for the torrent shown in the Trackers view, by announce URL.
+#[derive(Debug, Default)]
+pub struct TrackerHealth {
+    torrent_id: Option<usize>,
+    tiers: Vec<Vec<String>>,
+    status: HashMap<String, TrackerStatus>,
+    probe: Option<JoinHandle<ProbeResults>>,
+    probed_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct TrackerStatus {
+    failures: u32,
+    stats: Option<ScrapeStats>,
+    error: Option<String>,
+    checked: Instant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackerState {
+    /// The first tracker in its tier that answers; the one a tiered client announces to.
+    InUse,
+    /// Answers, but an earlier tracker in the tier is preferred.
+    Standby,
+    Failing,
+    Unchecked,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackerLine {
+    /// 1-based, as tiers are numbered in the metainfo.
+    pub tier: usize,
+    pub url: String,
+    pub state: TrackerState,
+    /// Failed scrapes in a row; reset by the next one that answers.
+    pub failures: u32,
+    pub stats: Option<ScrapeStats>,
+    pub error: Option<String>,
+    pub checked: Option<Duration>,
+}
+
+impl App {
+    /// Collects a finished scrape round and starts the next one while the Trackers view is
+    /// open, right away when another torrent is selected.
+    pub(super) async fn poll_trackers(&mut self) {
+        let selected = self
+            .selected_torrent()
+            .and_then(|t| Some((t.id, t.info_hash.clone()?)));
+        let Some((id, info_hash)) = selected.filter(|_| self.view == View::Trackers) else {
+            if let Some(probe) = self.tracker_health.probe.take() {
+                probe.abort();
+            }
+            self.tracker_health = TrackerHealth::default();
+            return;
+        };
+        if self.tracker_health.torrent_id != Some(id) {
+            if let Some(probe) = self.tracker_health.probe.take() {
+                probe.abort();
+            }
+            self.tracker_health = TrackerHealth {
+                torrent_id: Some(id),
+                tiers: self.backend.tracker_tiers(id),
+                ..TrackerHealth::default()
+            };
+        }
+        let health = &mut self.tracker_health;
+        if let Some(probe) = health.probe.take_if(|probe| probe.is_finished()) {
+            let now = Instant::now();
+            for (url, result) in probe.await.unwrap_or_default() {
+                let status = health.status.entry(url).or_insert(TrackerStatus {
+                    failures: 0,
+                    stats: None,
+                    error: None,
+                    checked: now,
+                });
+                status.checked = now;
+                match result {
+                    Ok(stats) => {
+                        status.failures = 0;
+                        status.stats = Some(stats);
+                        status.error = None;
+                    }
+                    Err(err) => {
+                        status.failures += 1;
+                        status.error = Some(format!("{err:#}"));
+                    }
+                }
+            }
+        }
+        let due = health.probe.is_none()
+            && health.probed_at.is_none_or(|at| at.elapsed() >= PROBE_EVERY);
+        if !due {
+            return;
+        }
+        health.tiers = self.backend.tracker_tiers(id);
+        let urls: Vec<String> = health.tiers.iter().flatten().cloned().collect();
+        if urls.is_empty() {
+            return;
+        }
+        let backend = self.backend.clone();
+        health.probed_at = Some(Instant::now());
+        health.probe = Some(tokio::spawn(async move {
+            let scrapes: Vec<_> = urls
+                .into_iter()
+                .map(|url| {
+                    let backend = backend.clone();
+                    let info_hash = info_hash.clone();
+                    tokio::spawn(async move {
+                        let result = backend.scrape(url.clone(), info_hash).await;
+                        (url, result)
+                    })
+                })
+                .collect();
+            let mut results = Vec::with_capacity(scrapes.len());
+            for scrape in scrapes {
+                // A scrape that panicked leaves its tracker unchecked.
+                if let Ok(result) = scrape.await {
+                    results.push(result);
+                }
+            }
+            results
+        }));
+    }
+
+    /// The selected torrent's trackers tier by tier, with which one of each tier is in use.
+    pub fn tracker_lines(&self) -> Vec<TrackerLine> {
+        let health = &self.tracker_health;
+        let mut lines = Vec::new();
+        for (tier, urls) in health.tiers.iter().enumerate() {
+            let mut in_use = false;
+            for url in urls {
+                let status = health.status.get(url);
+                let state = match status {
+                    None => TrackerState::Unchecked,
+                    Some(status) if status.failures > 0 => TrackerState::Failing,
+                    Some(_) if in_use => TrackerState::Standby,
+                    Some(_) => {
+                        in_use = true;
+                        TrackerState::InUse
+                    }
+                };
+                lines.push(TrackerLine {
+                    tier: tier + 1,
+                    url: url.clone(),
+                    state,
+                    failures: status.map_or(0, |s| s.failures),
+                    stats: status.and_then(|s| s.stats),
+                    error: status.and_then(|s| s.error.clone()),
+                    checked: status.map(|s| s.checked.elapsed()),
+                });
+            }
+        }
+        lines
+    }
+
+    /// Whether a scrape round is still out for the torrent in the Trackers view.
+    pub fn trackers_probing(&self) -> bool {
+        self.tracker_health.probe.is_some()
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{StartFocus, StartMode, StartView};
+
+use super::state::{App, FILTERS, FilterKind, FocusPanel, View};
+
+/// The view, filter and focus on screen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiState {
+    pub view: StartView,
+    /// A filter's name or a label, as in `[ui] start_filter`.
+    pub filter: String,
+    pub focus: StartFocus,
+}
+
+/// The last [`UiState`], persisted as `ui_state.json` in the data dir for
+/// `[ui] start = "last"`.
+#[derive(Debug)]
+pub struct UiStateFile {
+    saved: Option<UiState>,
+    path: Option<PathBuf>,
+}
+
+impl UiStateFile {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let saved = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok());
+        Self { saved, path }
+    }
+
+    /// Keeps what was loaded but stops writing changes back.
+    pub fn read_only(&mut self) {
+        self.path = None;
+    }
+
+    /// Writes `state` unless it is what was last written. A failed write isn't retried
+    /// until the state changes again.
+    fn save(&mut self, state: UiState) -> Result<()> {
+        if self.saved.as_ref() == Some(&state) {
+            return Ok(());
+        }
+        let text = serde_json::to_string_pretty(&state)?;
+        self.saved = Some(state);
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("failed to create data dir")?;
+        }
+        std::fs::write(path, text).context("failed to save UI state")
+    }
+}
+
+fn filter_name(kind: FilterKind) -> &'static str {
+    match kind {
+        FilterKind::All => "all",
+        FilterKind::Downloading => "downloading",
+        FilterKind::Seeding => "seeding",
+        FilterKind::Paused => "paused",
+        FilterKind::Stopped => "stopped",
+        FilterKind::Error => "error",
+        FilterKind::Idle => "idle",
+    }
+}
+
+impl App {
+    /// Opens on what `[ui]` asks for, before the first refresh.
+    pub(super) fn apply_start_state(&mut self) {
+        let saved = match self.config.ui.start {
+            StartMode::Last => self.ui_state.saved.clone(),
+            StartMode::Config => None,
+        };
+        let state = saved.unwrap_or_else(|| UiState {
+            view: self.config.ui.start_view,
+            filter: self.config.ui.start_filter.clone(),
+            focus: self.config.ui.start_focus,
+        });
+        self.view = match state.view {
+            StartView::Torrents => View::Torrents,
+            StartView::Peers => View::Peers,
+            StartView::Info => View::Info,
+            StartView::Files => View::Files,
+            StartView::Trackers => View::Trackers,
+            StartView::Timeline => View::Timeline,
+            StartView::Log => View::Log,
+            StartView::Rss => View::Rss,
+        };
+        self.focus = match state.focus {
+            StartFocus::Filters => FocusPanel::Filters,
+            StartFocus::Torrents => FocusPanel::Torrents,
+        };
+        let filter = state.filter.trim();
+        match FILTERS.iter().position(|&k| filter_name(k).eq_ignore_ascii_case(filter)) {
+            Some(idx) => self.filter_index = idx,
+            // Labels are only listed once the torrents are in.
+            None if !filter.is_empty() => self.start_label = Some(filter.to_string()),
+            None => {}
+        }
+    }
+
+    /// Picks the label filter asked for at startup, once the labels have been counted.
+    pub(super) fn apply_start_label(&mut self) {
+        let Some(label) = self.start_label.take() else {
+            return;
+        };
+        let labels = &self.filter_summary().labels;
+        if let Some(idx) = labels.iter().position(|(name, _)| *name == label) {
+            self.filter_index = FILTERS.len() + idx;
+            self.ensure_selection_for_filter();
+        }
+    }
+
+    /// Records what is on screen for the next `start = "last"`.
+    pub(super) fn remember_ui_state(&mut self) {
+        let view = match self.view {
+            View::Torrents => StartView::Torrents,
+            View::Peers => StartView::Peers,
+            View::Info => StartView::Info,
+            View::Files => StartView::Files,
+            View::Trackers => StartView::Trackers,
+            View::Timeline => StartView::Timeline,
+            View::Log => StartView::Log,
+            View::Rss => StartView::Rss,
+        };
+        let focus = match self.focus {
+            FocusPanel::Filters => StartFocus::Filters,
+            FocusPanel::Torrents => StartFocus::Torrents,
+        };
+        let filter = match self.selected_label_filter() {
+            Some(label) => label.to_string(),
+            None => filter_name(self.selected_filter()).to_string(),
+        };
+        if let Err(err) = self.ui_state.save(UiState { view, filter, focus }) {
+            self.activity.push(format!("UI state: {err:#}"));
+        }
+    }
+}
@@ -1,10 +1,14 @@
-use std::{borrow::Cow, path::PathBuf};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use bytes::Bytes;
 use librqbit::{AddTorrent, api::{ApiAddTorrentResponse, TorrentDetailsResponse}};
 
-use super::{FileEntry, FilePickerState, TorrentRow};
+use super::{FileEntry, FilePickerState, StorageKind, TorrentRow};
 
 pub fn build_add_torrent(input: &str) -> Result<AddTorrent<'static>> {
     let trimmed = input.trim();
@@ -28,6 +32,101 @@ pub fn build_add_torrent(input: &str) -> Result<AddTorrent<'static>> {
     ))
 }
 
+/// What a magnet URI says about its torrent, read without fetching anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MagnetLink {
+    /// Lowercase hex; v1 (`btih`) when the link has one, else the v2 (`btmh`) hash.
+    pub info_hash: String,
+    /// `dn`, the name suggested by whoever made the link.
+    pub name: Option<String>,
+    /// `tr`, in the order listed.
+    pub trackers: Vec<String>,
+    /// `xl`, the exact total size in bytes.
+    pub length: Option<u64>,
+    /// `ws`, HTTP web seeds.
+    pub web_seeds: Vec<String>,
+}
+
+/// Parses a `magnet:?` URI (BEP 9), checking that it names a usable info hash.
+pub fn parse_magnet(uri: &str) -> Result<MagnetLink> {
+    let cleaned: String = uri
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+    let Some(query) = cleaned.strip_prefix("magnet:?") else {
+        bail!("not a magnet link");
+    };
+    let mut v1 = None;
+    let mut v2 = None;
+    let mut link = MagnetLink {
+        info_hash: String::new(),
+        name: None,
+        trackers: Vec::new(),
+        length: None,
+        web_seeds: Vec::new(),
+    };
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "xt" => {
+                if let Some(hash) = value.strip_prefix("urn:btih:") {
+                    v1 = Some(btih_hex(hash)?);
+                } else if let Some(hash) = value.strip_prefix("urn:btmh:") {
+                    v2 = Some(btmh_hex(hash)?);
+                }
+            }
+            "dn" => link.name = Some(value.into_owned()).filter(|name| !name.is_empty()),
+            "tr" => link.trackers.push(value.into_owned()),
+            "ws" => link.web_seeds.push(value.into_owned()),
+            "xl" => {
+                let length = value.parse().map_err(|_| anyhow!("bad exact length: {value}"))?;
+                link.length = Some(length);
+            }
+            _ => {}
+        }
+    }
+    link.info_hash = v1
+        .or(v2)
+        .ok_or_else(|| anyhow!("magnet link has no BitTorrent info hash (xt=urn:btih:...)"))?;
+    Ok(link)
+}
+
+/// A v1 info hash as 40 hex digits or 32 base32 characters.
+fn btih_hex(hash: &str) -> Result<String> {
+    match hash.len() {
+        40 if hash.bytes().all(|b| b.is_ascii_hexdigit()) => Ok(hash.to_ascii_lowercase()),
+        32 => {
+            let mut bits = 0u64;
+            let mut count = 0;
+            let mut hex = String::with_capacity(40);
+            for c in hash.bytes() {
+                let value = match c.to_ascii_uppercase() {
+                    c @ b'A'..=b'Z' => c - b'A',
+                    c @ b'2'..=b'7' => c - b'2' + 26,
+                    _ => bail!("bad base32 info hash: {hash}"),
+                };
+                bits = bits << 5 | u64::from(value);
+                count += 5;
+                while count >= 4 {
+                    count -= 4;
+                    hex.push(char::from_digit((bits >> count & 0xf) as u32, 16).unwrap_or('0'));
+                }
+            }
+            Ok(hex)
+        }
+        _ => bail!("info hash must be 40 hex or 32 base32 characters: {hash}"),
+    }
+}
+
+/// A v2 info hash: a multihash whose `1220` prefix marks 32 bytes of SHA-256.
+fn btmh_hex(hash: &str) -> Result<String> {
+    match hash.strip_prefix("1220") {
+        Some(digest) if digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            Ok(digest.to_ascii_lowercase())
+        }
+        _ => bail!("v2 info hash must be a 1220-prefixed SHA-256 multihash: {hash}"),
+    }
+}
+
 pub fn build_picker(
     magnet: String,
     output_folder: String,
@@ -42,6 +141,8 @@ pub fn build_picker(
             name: f.name,
             length: f.length,
             included: f.included,
+            path_issue: false,
+            risky: false,
         })
         .collect();
     Ok(FilePickerState {
@@ -49,6 +150,11 @@ pub fn build_picker(
         output_folder,
         files,
         cursor: 0,
+        label: None,
+        storage: StorageKind::Disk,
+        network_fs: None,
+        risky_choice: None,
+        save_path: None,
     })
 }
 
@@ -60,23 +166,25 @@ pub fn to_row(details: TorrentDetailsResponse) -> Result<TorrentRow> {
         .unwrap_or_else(|| details.info_hash.clone());
     Ok(TorrentRow {
         id,
+        original_name: name.clone(),
         name,
         info_hash: Some(details.info_hash),
         output_folder: details.output_folder,
+        trackers: Vec::new(),
         stats: details.stats,
     })
 }
 
 pub fn derive_folder_suffix(response: &ApiAddTorrentResponse) -> String {
-    if let Some(name) = response.details.name.as_ref() {
-        if !name.trim().is_empty() {
-            return name.trim().to_string();
-        }
+    if let Some(name) = response.details.name.as_ref()
+        && !name.trim().is_empty()
+    {
+        return name.trim().to_string();
     }
-    if let Some(first) = response.details.files.as_ref().and_then(|f| f.first()) {
-        if !first.name.trim().is_empty() {
-            return first.name.trim().to_string();
-        }
+    if let Some(first) = response.details.files.as_ref().and_then(|f| f.first())
+        && !first.name.trim().is_empty()
+    {
+        return first.name.trim().to_string();
     }
     "download".to_string()
 }
@@ -93,17 +201,75 @@ pub fn sanitize_path_component(input: &str) -> String {
     out.trim().to_string()
 }
 
+/// Hands `path` to the desktop's default application for it, without waiting for it.
+pub fn open_with_system(path: &Path) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut opener = Command::new("open");
+    #[cfg(windows)]
+    let mut opener = {
+        let mut opener = Command::new("cmd");
+        opener.args(["/C", "start", ""]);
+        opener
+    };
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut opener = Command::new("xdg-open");
+    // Anything the opener prints would land on top of the TUI.
+    let mut child = opener
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Shows a desktop notification through `notify-send`, or Notification Center on macOS.
+pub fn desktop_notification(title: &str, body: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut notifier = {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut notifier = Command::new("osascript");
+        notifier.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            quote(body),
+            quote(title)
+        ));
+        notifier
+    };
+    #[cfg(windows)]
+    {
+        let _ = (title, body);
+        bail!("desktop notifications aren't supported on Windows");
+    }
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut notifier = {
+        let mut notifier = Command::new("notify-send");
+        // Names can start with `-`, which would be read as an option.
+        notifier.args(["--app-name", "ittybitty", "--", title, body]);
+        notifier
+    };
+    #[cfg(not(windows))]
+    {
+        let mut child = notifier
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to run the notifier")?;
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
 pub fn cursor_to_byte_index(s: &str, cursor: usize) -> usize {
     if cursor == 0 {
         return 0;
     }
-    let mut count = 0;
-    for (idx, _) in s.char_indices() {
-        if count == cursor {
-            return idx;
-        }
-        count += 1;
-    }
-    s.len()
+    s.char_indices()
+        .nth(cursor)
+        .map(|(idx, _)| idx)
+        .unwrap_or(s.len())
 }
 
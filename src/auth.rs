@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::config::{AuthConfig, data_dir};
+
+/// Random bytes in a generated token, hex-encoded.
+const TOKEN_BYTES: usize = 32;
+
+/// Where a generated token is kept between runs.
+pub fn token_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("auth_token"))
+}
+
+/// The configured token, or the one generated on an earlier run, or a new one saved to
+/// [`token_path`]. The bool is true when the token was just generated.
+pub fn load_or_create_token(config: &AuthConfig) -> Result<(String, bool)> {
+    if let Some(token) = config.token.as_deref().map(str::trim) {
+        if token.is_empty() {
+            bail!("[auth] token is empty");
+        }
+        return Ok((token.to_string(), false));
+    }
+    let path = token_path().context("no data directory for the generated auth token")?;
+    if let Ok(text) = std::fs::read_to_string(&path)
+        && !text.trim().is_empty()
+    {
+        return Ok((text.trim().to_string(), false));
+    }
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::fill(&mut bytes).map_err(|err| anyhow!("failed to generate auth token: {err}"))?;
+    let token: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("failed to create data dir")?;
+    }
+    write_private(&path, format!("{token}\n").as_bytes())
+        .with_context(|| format!("failed to save auth token to {}", path.display()))?;
+    Ok((token, true))
+}
+
+/// Writes a file only its owner can read, also when it already existed with wider
+/// permissions.
+#[cfg(unix)]
+pub fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::{
+        io::Write,
+        os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+pub fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+/// Whether the raw request headers carry `Authorization: Bearer <token>`.
+pub fn authorized(headers: &str, token: &str) -> bool {
+    headers
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .filter_map(|(_, value)| {
+            let (scheme, credentials) = value.trim().split_once(' ')?;
+            scheme
+                .eq_ignore_ascii_case("bearer")
+                .then(|| credentials.trim())
+        })
+        .any(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+/// Whether the query string carries `token=<token>`, for clients such as media players
+/// that can't send headers.
+pub fn authorized_query(query: &str, token: &str) -> bool {
+    query
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("token="))
+        .any(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+/// Compares without stopping at the first difference, so timing doesn't leak the token.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,798 @@
+use std::{
+    io::SeekFrom,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Result, anyhow, bail};
+use librqbit::{
+    AddTorrent, AddTorrentOptions, FileDetailsAttrs, TorrentStats, TorrentStatsState,
+    api::{ApiAddTorrentResponse, LiveStats, TorrentDetailsResponse, TorrentDetailsResponseFile},
+    session_stats::snapshot::SessionStatsSnapshot,
+};
+
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+use crate::scrape::ScrapeStats;
+
+use super::{
+    BackendFuture, FileFingerprint, PeerSample, PieceProgress, StreamFile, TorrentBackend,
+};
+
+const MIB: f64 = 1024.0 * 1024.0;
+const PIECE_LENGTH: u64 = 4 * 1024 * 1024;
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// (name, size in MiB, progress fraction, state)
+const SEED: [(&str, u64, f64, DemoState); 10] = [
+    ("ubuntu-24.04.1-desktop-amd64.iso", 5_900, 0.42, DemoState::Live),
+    ("Big Buck Bunny (2008) [1080p]", 720, 1.0, DemoState::Live),
+    ("debian-12.7.0-amd64-netinst.iso", 631, 1.0, DemoState::Live),
+    ("Sintel.2010.2160p.WEB", 8_300, 0.07, DemoState::Live),
+    ("archlinux-2026.10.01-x86_64.iso", 1_200, 0.88, DemoState::Paused),
+    ("Tears of Steel (2012) [4K]", 6_400, 1.0, DemoState::Paused),
+    ("fedora-workstation-live-41.iso", 2_300, 0.15, DemoState::Live),
+    ("Creative Commons Music Collection", 3_100, 1.0, DemoState::Live),
+    ("openstreetmap-europe-latest.osm.pbf", 28_000, 0.31, DemoState::Error),
+    ("linux-6.12.tar.xz", 140, 0.0, DemoState::Live),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DemoState {
+    Live,
+    Paused,
+    Error,
+}
+
+struct DemoTorrent {
+    id: usize,
+    info_hash: String,
+    name: String,
+    output_folder: String,
+    files: Vec<(String, u64)>,
+    included: Vec<bool>,
+    progress: f64,
+    uploaded: f64,
+    state: DemoState,
+    down_mbps: f64,
+    up_mbps: f64,
+    peers_live: usize,
+    peers_seen: usize,
+    /// Bytes received from each seen peer; the first `peers_live` are connected.
+    peer_bytes: Vec<f64>,
+}
+
+impl DemoTorrent {
+    fn total_bytes(&self) -> u64 {
+        self.files
+            .iter()
+            .zip(&self.included)
+            .filter(|(_, included)| **included)
+            .map(|((_, len), _)| *len)
+            .sum()
+    }
+
+    fn finished(&self) -> bool {
+        self.progress as u64 >= self.total_bytes()
+    }
+
+    fn files(&self) -> Vec<TorrentDetailsResponseFile> {
+        self.files
+            .iter()
+            .zip(&self.included)
+            .map(|((name, len), included)| file_entry(name, *len, *included))
+            .collect()
+    }
+
+    fn file_progress(&self) -> Vec<u64> {
+        let mut left = self.progress as u64;
+        self.files
+            .iter()
+            .zip(&self.included)
+            .map(|((_, len), included)| {
+                if !included {
+                    return 0;
+                }
+                let done = left.min(*len);
+                left -= done;
+                done
+            })
+            .collect()
+    }
+
+    fn stats(&self) -> TorrentStats {
+        let finished = self.finished();
+        let live = (self.state == DemoState::Live).then(|| {
+            let mut live = LiveStats {
+                download_speed: self.down_mbps.into(),
+                upload_speed: self.up_mbps.into(),
+                ..Default::default()
+            };
+            live.snapshot.downloaded_and_checked_bytes = self.progress as u64;
+            live.snapshot.uploaded_bytes = self.uploaded as u64;
+            live.snapshot.peer_stats.live = self.peers_live;
+            live.snapshot.peer_stats.seen = self.peers_seen;
+            live.snapshot.peer_stats.dead = self.peers_seen.saturating_sub(self.peers_live) / 3;
+            live
+        });
+        TorrentStats {
+            state: match self.state {
+                DemoState::Live => TorrentStatsState::Live,
+                DemoState::Paused => TorrentStatsState::Paused,
+                DemoState::Error => TorrentStatsState::Error,
+            },
+            file_progress: self.file_progress(),
+            error: (self.state == DemoState::Error)
+                .then(|| "error writing piece: No space left on device (os error 28)".to_string()),
+            progress_bytes: self.progress as u64,
+            uploaded_bytes: self.uploaded as u64,
+            total_bytes: self.total_bytes(),
+            finished,
+            live,
+        }
+    }
+
+    fn details(&self, with_stats: bool, with_files: bool) -> TorrentDetailsResponse {
+        TorrentDetailsResponse {
+            id: Some(self.id),
+            info_hash: self.info_hash.clone(),
+            name: Some(self.name.clone()),
+            output_folder: self.output_folder.clone(),
+            files: with_files.then(|| self.files()),
+            stats: with_stats.then(|| self.stats()),
+        }
+    }
+}
+
+struct Inner {
+    torrents: Vec<DemoTorrent>,
+    next_id: usize,
+    rng: Rng,
+    last_step: Instant,
+    started: Instant,
+    fetched: f64,
+    uploaded: f64,
+    /// Session limits in MiB/s, shared evenly between running torrents.
+    down_limit: Option<f64>,
+    up_limit: Option<f64>,
+}
+
+impl Inner {
+    fn step(&mut self) {
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_step).as_secs_f64();
+        self.last_step = now;
+        let live = self
+            .torrents
+            .iter()
+            .filter(|t| t.state == DemoState::Live)
+            .count()
+            .max(1) as f64;
+        let down_cap = self.down_limit.map_or(12.0, |limit| (limit / live).min(12.0));
+        let up_cap = self.up_limit.map_or(3.0, |limit| (limit / live).min(3.0));
+        for t in &mut self.torrents {
+            if t.state != DemoState::Live {
+                t.down_mbps = 0.0;
+                t.up_mbps = 0.0;
+                t.peers_live = 0;
+                continue;
+            }
+            if t.finished() {
+                t.down_mbps = 0.0;
+            } else {
+                t.down_mbps = self.rng.walk(t.down_mbps, 0.2, 12.0).min(down_cap);
+                let total = t.total_bytes() as f64;
+                let before = t.progress;
+                t.progress = (t.progress + t.down_mbps * MIB * dt).min(total);
+                self.fetched += t.progress - before;
+                let shares: Vec<f64> = (0..t.peers_live).map(|_| self.rng.next_f64()).collect();
+                let sum: f64 = shares.iter().sum();
+                for (bytes, share) in t.peer_bytes.iter_mut().zip(&shares) {
+                    *bytes += (t.progress - before) * share / sum;
+                }
+            }
+            t.up_mbps = self.rng.walk(t.up_mbps, 0.0, 3.0).min(up_cap);
+            let sent = t.up_mbps * MIB * dt;
+            t.uploaded += sent;
+            self.uploaded += sent;
+            let live = self.rng.walk(t.peers_live as f64, 1.0, t.peers_seen as f64);
+            t.peers_live = live.round() as usize;
+        }
+    }
+}
+
+/// Simulated backend generating believable torrents with changing speeds and states.
+/// Nothing touches the network or the disk.
+pub struct DemoBackend {
+    inner: Mutex<Inner>,
+    download_dir: String,
+}
+
+impl DemoBackend {
+    pub fn new(download_dir: String) -> Self {
+        let mut rng = Rng(0x1771_b177_d3b0_5eed);
+        let torrents = SEED
+            .iter()
+            .enumerate()
+            .map(|(id, (name, mib, fraction, state))| {
+                let info_hash = rng.hash();
+                let files = demo_files(name, mib * 1024 * 1024, &mut Rng::from_str(&info_hash));
+                let included = vec![true; files.len()];
+                let total: u64 = files.iter().map(|(_, len)| len).sum();
+                let peers_seen = 5 + (rng.next() % 60) as usize;
+                DemoTorrent {
+                    id,
+                    info_hash,
+                    name: name.to_string(),
+                    output_folder: format!("{download_dir}/{name}"),
+                    files,
+                    included,
+                    progress: total as f64 * fraction,
+                    uploaded: total as f64 * fraction * rng.next_f64() * 2.5,
+                    state: *state,
+                    down_mbps: 1.0,
+                    up_mbps: 0.2,
+                    peers_live: peers_seen / 3,
+                    peers_seen,
+                    peer_bytes: vec![0.0; peers_seen],
+                }
+            })
+            .collect::<Vec<_>>();
+        let now = Instant::now();
+        Self {
+            inner: Mutex::new(Inner {
+                next_id: torrents.len(),
+                torrents,
+                rng,
+                last_step: now,
+                started: now,
+                fetched: 0.0,
+                uploaded: 0.0,
+                down_limit: None,
+                up_limit: None,
+            }),
+            download_dir,
+        }
+    }
+
+    fn with_inner<R>(&self, f: impl FnOnce(&mut Inner) -> R) -> R {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.step();
+        f(&mut inner)
+    }
+
+    fn add(
+        &self,
+        add: AddTorrent<'static>,
+        opts: AddTorrentOptions,
+    ) -> Result<ApiAddTorrentResponse> {
+        let (name, info_hash) = match &add {
+            AddTorrent::Url(url) => parse_magnet(url),
+            _ => (None, None),
+        };
+        let info_hash = info_hash.unwrap_or_else(|| Rng(0x0add_5eed).hash());
+        let name = name.unwrap_or_else(|| format!("Demo torrent {}", &info_hash[..8]));
+        let output_folder = opts
+            .output_folder
+            .clone()
+            .unwrap_or_else(|| self.download_dir.clone());
+        let mut rng = Rng::from_str(&info_hash);
+        let size = (256 + rng.next() % 8_000) * 1024 * 1024;
+        let files = demo_files(&name, size, &mut rng);
+
+        if opts.list_only {
+            let included: Vec<bool> = (0..files.len())
+                .map(|idx| opts.only_files.as_ref().is_none_or(|o| o.contains(&idx)))
+                .collect();
+            return Ok(ApiAddTorrentResponse {
+                id: None,
+                details: TorrentDetailsResponse {
+                    id: None,
+                    info_hash,
+                    name: Some(name),
+                    output_folder: output_folder.clone(),
+                    files: Some(
+                        files
+                            .iter()
+                            .zip(included)
+                            .map(|((name, len), included)| file_entry(name, *len, included))
+                            .collect(),
+                    ),
+                    stats: None,
+                },
+                output_folder,
+                seen_peers: Some(Vec::new()),
+            });
+        }
+
+        self.with_inner(|inner| {
+            if let Some(existing) = inner
+                .torrents
+                .iter()
+                .find(|t| t.info_hash == info_hash && t.output_folder == output_folder)
+            {
+                return Ok(ApiAddTorrentResponse {
+                    id: Some(existing.id),
+                    details: existing.details(false, true),
+                    output_folder: existing.output_folder.clone(),
+                    seen_peers: None,
+                });
+            }
+            let id = inner.next_id;
+            inner.next_id += 1;
+            let included = (0..files.len())
+                .map(|idx| opts.only_files.as_ref().is_none_or(|o| o.contains(&idx)))
+                .collect();
+            let peers_seen = 3 + (inner.rng.next() % 40) as usize;
+            let torrent = DemoTorrent {
+                id,
+                info_hash,
+                name,
+                output_folder,
+                files,
+                included,
+                progress: 0.0,
+                uploaded: 0.0,
+                state: if opts.paused {
+                    DemoState::Paused
+                } else {
+                    DemoState::Live
+                },
+                down_mbps: 0.5,
+                up_mbps: 0.0,
+                peers_live: 1,
+                peers_seen,
+                peer_bytes: vec![0.0; peers_seen],
+            };
+            let response = ApiAddTorrentResponse {
+                id: Some(id),
+                details: torrent.details(false, true),
+                output_folder: torrent.output_folder.clone(),
+                seen_peers: None,
+            };
+            inner.torrents.push(torrent);
+            Ok(response)
+        })
+    }
+
+    fn set_state(&self, id: usize, state: DemoState) -> Result<()> {
+        self.with_inner(|inner| {
+            let t = inner
+                .torrents
+                .iter_mut()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow!("torrent {id} not found"))?;
+            t.state = state;
+            Ok(())
+        })
+    }
+
+    fn remove(&self, id: usize) -> Result<()> {
+        self.with_inner(|inner| {
+            let idx = inner
+                .torrents
+                .iter()
+                .position(|t| t.id == id)
+                .ok_or_else(|| anyhow!("torrent {id} not found"))?;
+            inner.torrents.remove(idx);
+            Ok(())
+        })
+    }
+}
+
+impl TorrentBackend for DemoBackend {
+    fn session_stats(&self) -> SessionStatsSnapshot {
+        self.with_inner(|inner| {
+            let (down, up) = inner
+                .torrents
+                .iter()
+                .fold((0.0, 0.0), |(d, u), t| (d + t.down_mbps, u + t.up_mbps));
+            let mut snapshot = SessionStatsSnapshot {
+                fetched_bytes: inner.fetched as u64,
+                uploaded_bytes: inner.uploaded as u64,
+                download_speed: down.into(),
+                upload_speed: up.into(),
+                peers: Default::default(),
+                uptime_seconds: inner.started.elapsed().as_secs(),
+            };
+            snapshot.peers.live = inner.torrents.iter().map(|t| t.peers_live).sum();
+            snapshot.peers.seen = inner.torrents.iter().map(|t| t.peers_seen).sum();
+            snapshot
+        })
+    }
+
+    fn torrent_list(&self, with_stats: bool) -> Vec<TorrentDetailsResponse> {
+        self.with_inner(|inner| {
+            inner
+                .torrents
+                .iter()
+                .map(|t| t.details(with_stats, false))
+                .collect()
+        })
+    }
+
+    fn torrent_details(&self, id: usize) -> Result<TorrentDetailsResponse> {
+        self.with_inner(|inner| {
+            inner
+                .torrents
+                .iter()
+                .find(|t| t.id == id)
+                .map(|t| t.details(false, true))
+                .ok_or_else(|| anyhow!("torrent {id} not found"))
+        })
+    }
+
+    fn trackers(&self, id: usize) -> Vec<String> {
+        self.with_inner(|inner| {
+            inner
+                .torrents
+                .iter()
+                .find(|t| t.id == id)
+                .map(|t| demo_trackers(&t.name).concat())
+                .unwrap_or_default()
+        })
+    }
+
+    fn tracker_tiers(&self, id: usize) -> Vec<Vec<String>> {
+        self.with_inner(|inner| {
+            inner
+                .torrents
+                .iter()
+                .find(|t| t.id == id)
+                .map(|t| demo_trackers(&t.name))
+                .unwrap_or_default()
+        })
+    }
+
+    /// First trackers in a tier are down now and then, so the view shows a failover.
+    fn scrape(&self, announce: String, info_hash: String) -> BackendFuture<'_, ScrapeStats> {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let minute = self.with_inner(|inner| inner.started.elapsed().as_secs() / 60);
+            let mut rng = Rng::from_str(&format!("{announce}{info_hash}{minute}"));
+            if announce.contains("opentrackr") && rng.next().is_multiple_of(2) {
+                bail!("connection refused");
+            }
+            let mut swarm = Rng::from_str(&format!("{announce}{info_hash}"));
+            Ok(ScrapeStats {
+                seeders: (swarm.next() % 400) as u32,
+                leechers: (swarm.next() % 80) as u32,
+                completed: (swarm.next() % 5000) as u32,
+            })
+        })
+    }
+
+    /// HTTP trackers answer with a bencoded dict, the Debian one with a failure reason.
+    fn debug_announce(&self, id: usize, announce: String) -> BackendFuture<'_, String> {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let mut swarm = Rng::from_str(&format!("{announce}{id}"));
+            let (seeders, leechers) = (swarm.next() % 400, swarm.next() % 80);
+            if announce.contains("debian") {
+                return Ok("{failure reason: \"torrent not registered with this tracker\"}"
+                    .to_string());
+            }
+            if announce.starts_with("udp:") {
+                return Ok(format!(
+                    "{{interval: 1800, leechers: {leechers}, seeders: {seeders}, \
+                     peers: <{} peers>}}",
+                    (seeders + leechers).min(50)
+                ));
+            }
+            Ok(format!(
+                "{{complete: {seeders}, incomplete: {leechers}, interval: 1800, \
+                 min interval: 900, peers: <{} peers>}}",
+                (seeders + leechers).min(50)
+            ))
+        })
+    }
+
+    fn piece_progress(&self, id: usize) -> Option<PieceProgress> {
+        self.with_inner(|inner| {
+            let t = inner.torrents.iter().find(|t| t.id == id)?;
+            let length: u64 = t.files.iter().map(|(_, len)| len).sum();
+            let total = length.div_ceil(PIECE_LENGTH) as u32;
+            let have = if t.progress as u64 >= length {
+                total
+            } else {
+                (t.progress as u64 / PIECE_LENGTH) as u32
+            };
+            Some(PieceProgress { have, total })
+        })
+    }
+
+    fn file_fingerprints(&self, id: usize) -> Vec<FileFingerprint> {
+        self.with_inner(|inner| {
+            let Some(t) = inner.torrents.iter().find(|t| t.id == id) else {
+                return Vec::new();
+            };
+            // No real metainfo; files with the same name and size count as the same data.
+            t.files
+                .iter()
+                .enumerate()
+                .map(|(index, (path, len))| {
+                    let file = path.rsplit('/').next().unwrap_or(path);
+                    FileFingerprint {
+                        index,
+                        path: path.clone(),
+                        len: *len,
+                        digest: format!("{file}:{len}").into_bytes(),
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn open_stream(&self, id: usize, file: usize) -> Result<StreamFile> {
+        let len = self.with_inner(|inner| {
+            let t = inner
+                .torrents
+                .iter()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow!("torrent {id} not found"))?;
+            match (t.files.get(file), t.included.get(file)) {
+                (Some((_, len)), Some(true)) => Ok(*len),
+                (Some(_), _) => Err(anyhow!("file {file} is not selected for download")),
+                (None, _) => Err(anyhow!("file {file} not found")),
+            }
+        })?;
+        Ok(StreamFile {
+            reader: Box::new(Zeros { pos: 0, len }),
+            len,
+            mime: None,
+        })
+    }
+
+    fn torrent_bytes(&self, _id: usize) -> Result<Vec<u8>> {
+        bail!("demo torrents have no metainfo")
+    }
+
+    fn peer_stats(&self, id: usize) -> Result<Vec<PeerSample>> {
+        self.with_inner(|inner| {
+            let t = inner
+                .torrents
+                .iter()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow!("torrent {id} not found"))?;
+            if t.state != DemoState::Live {
+                return Err(anyhow!("not live"));
+            }
+            Ok(t.peer_bytes[..t.peers_live]
+                .iter()
+                .enumerate()
+                .map(|(idx, bytes)| PeerSample {
+                    addr: format!("198.51.{}.{}:{}", t.id, idx + 1, 51413 + idx),
+                    fetched_bytes: *bytes as u64,
+                })
+                .collect())
+        })
+    }
+
+    fn add_torrent(
+        &self,
+        add: AddTorrent<'static>,
+        opts: AddTorrentOptions,
+    ) -> BackendFuture<'_, ApiAddTorrentResponse> {
+        Box::pin(async move { self.add(add, opts) })
+    }
+
+    fn set_rate_limits(&self, download_bps: Option<u64>, upload_bps: Option<u64>) {
+        self.with_inner(|inner| {
+            inner.down_limit = download_bps.map(|bps| bps as f64 / MIB);
+            inner.up_limit = upload_bps.map(|bps| bps as f64 / MIB);
+        });
+    }
+
+    fn start(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move { self.set_state(id, DemoState::Live) })
+    }
+
+    fn pause(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move { self.set_state(id, DemoState::Paused) })
+    }
+
+    fn forget(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move { self.remove(id) })
+    }
+
+    fn delete(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move { self.remove(id) })
+    }
+
+    fn relocate(
+        &self,
+        id: usize,
+        output_folder: String,
+        paused: bool,
+    ) -> BackendFuture<'_, usize> {
+        let result = self.with_inner(|inner| {
+            let t = inner
+                .torrents
+                .iter_mut()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow!("torrent {id} not found"))?;
+            t.output_folder = output_folder;
+            if paused {
+                t.state = DemoState::Paused;
+            }
+            Ok(id)
+        });
+        Box::pin(async move { result })
+    }
+
+    fn is_simulated(&self) -> bool {
+        true
+    }
+}
+
+fn file_entry(name: &str, length: u64, included: bool) -> TorrentDetailsResponseFile {
+    TorrentDetailsResponseFile {
+        name: name.to_string(),
+        components: name.split('/').map(str::to_string).collect(),
+        length,
+        included,
+        attributes: FileDetailsAttrs::default(),
+    }
+}
+
+/// Distro images announce to their project's tracker, Arch and kernel tarballs rely on DHT,
+/// and the rest use public trackers, in tiers.
+fn demo_trackers(name: &str) -> Vec<Vec<String>> {
+    let lower = name.to_lowercase();
+    let tiers: &[&[&str]] = if lower.starts_with("ubuntu") {
+        &[&["https://torrent.ubuntu.com/announce"]]
+    } else if lower.starts_with("debian") {
+        &[&["http://bttracker.debian.org:6969/announce"]]
+    } else if lower.starts_with("fedora") {
+        &[&["http://torrent.fedoraproject.org:6969/announce"]]
+    } else if lower.starts_with("archlinux") || lower.starts_with("linux") {
+        &[]
+    } else {
+        &[
+            &[
+                "udp://tracker.opentrackr.org:1337/announce",
+                "udp://open.demonii.com:1337/announce",
+            ],
+            &["udp://exodus.desync.com:6969/announce"],
+        ]
+    };
+    tiers
+        .iter()
+        .map(|tier| tier.iter().map(|u| u.to_string()).collect())
+        .collect()
+}
+
+/// A plausible file layout for a torrent of the given name and size.
+fn demo_files(name: &str, size: u64, rng: &mut Rng) -> Vec<(String, u64)> {
+    let single = [".iso", ".xz", ".pbf", ".zip", ".exe"]
+        .iter()
+        .any(|ext| name.ends_with(ext));
+    if single {
+        return vec![(name.to_string(), size)];
+    }
+    let sample = (20 + rng.next() % 80) * 1024 * 1024;
+    let extras = 4096 + rng.next() % (512 * 1024);
+    let main = size.saturating_sub(sample + extras * 2).max(GIB / 8);
+    vec![
+        (format!("{name}.mkv"), main),
+        (format!("{name}.en.srt"), extras),
+        (format!("Sample/{name}-sample.mkv"), sample),
+        ("info.nfo".to_string(), extras),
+    ]
+}
+
+fn parse_magnet(url: &str) -> (Option<String>, Option<String>) {
+    let Some(query) = url.strip_prefix("magnet:?") else {
+        return (None, None);
+    };
+    let mut name = None;
+    let mut hash = None;
+    for pair in query.split('&') {
+        match pair.split_once('=') {
+            Some(("dn", value)) => name = Some(percent_decode(value)),
+            Some(("xt", value)) => {
+                hash = value
+                    .strip_prefix("urn:btih:")
+                    .filter(|h| h.len() == 40)
+                    .map(|h| h.to_lowercase())
+            }
+            _ => {}
+        }
+    }
+    (name, hash)
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'+' => out.push(b' '),
+            b'%' => match input.get(idx + 1..idx + 3).map(|h| u8::from_str_radix(h, 16)) {
+                Some(Ok(b)) => {
+                    out.push(b);
+                    idx += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        idx += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Demo file contents: `len` zero bytes.
+struct Zeros {
+    pos: u64,
+    len: u64,
+}
+
+impl AsyncRead for Zeros {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let n = (self.len.saturating_sub(self.pos)).min(buf.remaining() as u64) as usize;
+        buf.initialize_unfilled_to(n).fill(0);
+        buf.advance(n);
+        self.pos += n as u64;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for Zeros {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let pos = match position {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+        self.pos = pos.ok_or_else(|| std::io::Error::other("seek before start"))?;
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(Ok(self.pos))
+    }
+}
+
+/// Small xorshift generator; the demo only needs plausible noise, not quality randomness.
+struct Rng(u64);
+
+impl Rng {
+    fn from_str(seed: &str) -> Self {
+        let hash = seed
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100_0000_01b3));
+        Self(hash.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Random walk step of `value` within `[lo, hi]`.
+    fn walk(&mut self, value: f64, lo: f64, hi: f64) -> f64 {
+        let hi = hi.max(lo);
+        let step = (self.next_f64() - 0.5) * (hi - lo) * 0.2;
+        (value + step).clamp(lo, hi)
+    }
+
+    fn hash(&mut self) -> String {
+        (0..5).map(|_| format!("{:08x}", self.next() as u32)).collect()
+    }
+}
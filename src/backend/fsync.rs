@@ -0,0 +1,112 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use librqbit::{
+    ManagedTorrentShared, TorrentMetadata,
+    storage::{BoxStorageFactory, StorageFactory, StorageFactoryExt, TorrentStorage},
+};
+use librqbit_core::lengths::ValidPieceIndex;
+
+/// Wraps another storage factory, fsyncing every file a piece touches once the piece is
+/// verified. For download folders on network filesystems that may lose cached writes.
+#[derive(Clone)]
+pub struct SyncedStorageFactory<U> {
+    underlying: U,
+    output_folder: PathBuf,
+}
+
+impl<U: StorageFactory + Clone> SyncedStorageFactory<U> {
+    pub fn new(underlying: U, output_folder: PathBuf) -> Self {
+        Self {
+            underlying,
+            output_folder,
+        }
+    }
+}
+
+impl<U: StorageFactory + Clone> StorageFactory for SyncedStorageFactory<U> {
+    type Storage = SyncedStorage<U::Storage>;
+
+    fn create(
+        &self,
+        shared: &ManagedTorrentShared,
+        metadata: &TorrentMetadata,
+    ) -> anyhow::Result<Self::Storage> {
+        let files = metadata
+            .file_infos
+            .iter()
+            .map(|f| (self.output_folder.join(&f.relative_filename), f.piece_range.clone()))
+            .collect();
+        Ok(SyncedStorage {
+            underlying: self.underlying.create(shared, metadata)?,
+            files,
+        })
+    }
+
+    fn is_type_id(&self, type_id: std::any::TypeId) -> bool {
+        self.underlying.is_type_id(type_id)
+    }
+
+    fn clone_box(&self) -> BoxStorageFactory {
+        self.clone().boxed()
+    }
+}
+
+pub struct SyncedStorage<U> {
+    underlying: U,
+    /// Path and piece range of each file, by file id.
+    files: Vec<(PathBuf, Range<u32>)>,
+}
+
+impl<U: TorrentStorage> TorrentStorage for SyncedStorage<U> {
+    fn init(
+        &mut self,
+        shared: &ManagedTorrentShared,
+        metadata: &TorrentMetadata,
+    ) -> anyhow::Result<()> {
+        self.underlying.init(shared, metadata)
+    }
+
+    fn pread_exact(&self, file_id: usize, offset: u64, buf: &mut [u8]) -> anyhow::Result<()> {
+        self.underlying.pread_exact(file_id, offset, buf)
+    }
+
+    fn pwrite_all(&self, file_id: usize, offset: u64, buf: &[u8]) -> anyhow::Result<()> {
+        self.underlying.pwrite_all(file_id, offset, buf)
+    }
+
+    fn remove_file(&self, file_id: usize, filename: &Path) -> anyhow::Result<()> {
+        self.underlying.remove_file(file_id, filename)
+    }
+
+    fn remove_directory_if_empty(&self, path: &Path) -> anyhow::Result<()> {
+        self.underlying.remove_directory_if_empty(path)
+    }
+
+    fn ensure_file_length(&self, file_id: usize, length: u64) -> anyhow::Result<()> {
+        self.underlying.ensure_file_length(file_id, length)
+    }
+
+    fn take(&self) -> anyhow::Result<Box<dyn TorrentStorage>> {
+        Ok(Box::new(SyncedStorage {
+            underlying: self.underlying.take()?,
+            files: self.files.clone(),
+        }))
+    }
+
+    fn on_piece_completed(&self, piece_index: ValidPieceIndex) -> anyhow::Result<()> {
+        self.underlying.on_piece_completed(piece_index)?;
+        for (path, pieces) in &self.files {
+            if pieces.contains(&piece_index.get()) {
+                // Any handle will do: fsync flushes the file, not just this descriptor.
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .open(path)?
+                    .sync_data()?;
+            }
+        }
+        Ok(())
+    }
+}
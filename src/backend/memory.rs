@@ -0,0 +1,99 @@
+use std::{path::Path, sync::RwLock};
+
+use anyhow::{Context, Result};
+use librqbit::{
+    ManagedTorrentShared, TorrentMetadata,
+    storage::{BoxStorageFactory, StorageFactory, StorageFactoryExt, TorrentStorage},
+};
+
+/// Keeps a torrent's files in RAM, for previews that shouldn't touch the disk.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MemoryStorageFactory;
+
+impl StorageFactory for MemoryStorageFactory {
+    type Storage = MemoryStorage;
+
+    fn create(
+        &self,
+        _shared: &ManagedTorrentShared,
+        metadata: &TorrentMetadata,
+    ) -> Result<Self::Storage> {
+        Ok(MemoryStorage {
+            files: metadata.file_infos.iter().map(|_| RwLock::default()).collect(),
+        })
+    }
+
+    fn clone_box(&self) -> BoxStorageFactory {
+        self.boxed()
+    }
+}
+
+/// One buffer per file, grown as pieces arrive so skipped files cost nothing.
+pub struct MemoryStorage {
+    files: Vec<RwLock<Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    fn file(&self, file_id: usize) -> Result<&RwLock<Vec<u8>>> {
+        self.files
+            .get(file_id)
+            .with_context(|| format!("no such file: {file_id}"))
+    }
+}
+
+impl TorrentStorage for MemoryStorage {
+    fn init(&mut self, _shared: &ManagedTorrentShared, _metadata: &TorrentMetadata) -> Result<()> {
+        Ok(())
+    }
+
+    fn pread_exact(&self, file_id: usize, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let data = self.file(file_id)?.read().unwrap_or_else(|e| e.into_inner());
+        let start = (offset as usize).min(data.len());
+        let end = (start + buf.len()).min(data.len());
+        let (stored, missing) = buf.split_at_mut(end - start);
+        stored.copy_from_slice(&data[start..end]);
+        // Never written: reads as zeros, like a sparse file.
+        missing.fill(0);
+        Ok(())
+    }
+
+    fn pwrite_all(&self, file_id: usize, offset: u64, buf: &[u8]) -> Result<()> {
+        let mut data = self.file(file_id)?.write().unwrap_or_else(|e| e.into_inner());
+        let start = offset as usize;
+        let end = start + buf.len();
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        data[start..end].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn remove_file(&self, file_id: usize, _filename: &Path) -> Result<()> {
+        *self.file(file_id)?.write().unwrap_or_else(|e| e.into_inner()) = Vec::new();
+        Ok(())
+    }
+
+    fn remove_directory_if_empty(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    fn ensure_file_length(&self, file_id: usize, length: u64) -> Result<()> {
+        self.file(file_id)?
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .truncate(length as usize);
+        Ok(())
+    }
+
+    fn take(&self) -> Result<Box<dyn TorrentStorage>> {
+        let files = self
+            .files
+            .iter()
+            .map(|f| {
+                let mut data = f.write().unwrap_or_else(|e| e.into_inner());
+                RwLock::new(std::mem::take(&mut *data))
+            })
+            .collect();
+        Ok(Box::new(MemoryStorage { files }))
+    }
+}
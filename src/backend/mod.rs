@@ -0,0 +1,388 @@
+mod demo;
+mod fsync;
+mod memory;
+mod throttle;
+
+use std::{future::Future, num::NonZeroU32, pin::Pin, sync::OnceLock};
+
+use anyhow::{Context, Result, anyhow, bail};
+use librqbit::{
+    AddTorrent, AddTorrentOptions, Api,
+    api::{ApiAddTorrentResponse, ApiTorrentListOpts, TorrentDetailsResponse},
+    session_stats::snapshot::SessionStatsSnapshot,
+};
+use librqbit_bencode::ByteBuf;
+use librqbit_core::{
+    hash_id::Id20, peer_id::generate_azereus_style, torrent_metainfo::torrent_from_bytes,
+};
+use tokio::io::{AsyncRead, AsyncSeek};
+
+use crate::{announce::AnnounceRequest, scrape::ScrapeStats};
+
+pub use demo::DemoBackend;
+pub use fsync::SyncedStorageFactory;
+pub use memory::MemoryStorageFactory;
+pub use throttle::{DiskWrites, ThrottledStorageFactory};
+
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// The peer id the session announces with, so debug announces look like the same client.
+pub fn session_peer_id() -> Id20 {
+    static PEER_ID: OnceLock<Id20> = OnceLock::new();
+    *PEER_ID.get_or_init(|| generate_azereus_style(*b"rQ", (8, 1, 1, 0)))
+}
+
+/// A live peer of one torrent.
+#[derive(Debug, Clone)]
+pub struct PeerSample {
+    pub addr: String,
+    /// Bytes received from this peer so far.
+    pub fetched_bytes: u64,
+}
+
+/// Verified pieces out of the pieces covering the selected files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PieceProgress {
+    pub have: u32,
+    pub total: u32,
+}
+
+/// What one file's content is known to be, from the metainfo alone.
+#[derive(Debug, Clone)]
+pub struct FileFingerprint {
+    /// Index in the torrent's file list.
+    pub index: usize,
+    /// Path relative to the torrent's output folder.
+    pub path: String,
+    pub len: u64,
+    /// The file's own SHA-1 (BEP 47) when the torrent lists one, otherwise the piece length
+    /// and hashes of the pieces it spans; files that share pieces with their neighbours
+    /// have no fingerprint.
+    pub digest: Vec<u8>,
+}
+
+/// Readable and seekable file data, for the stream server.
+pub trait StreamRead: AsyncRead + AsyncSeek + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncSeek + Send + Unpin> StreamRead for T {}
+
+/// One file of a torrent opened for streaming. Reads wait for missing pieces, which are
+/// fetched ahead of the others.
+pub struct StreamFile {
+    pub reader: Box<dyn StreamRead>,
+    pub len: u64,
+    pub mime: Option<&'static str>,
+}
+
+/// The torrent engine the app drives: librqbit's `Api` normally, or `DemoBackend` in demo mode.
+pub trait TorrentBackend: Send + Sync {
+    fn session_stats(&self) -> SessionStatsSnapshot;
+
+    fn torrent_list(&self, with_stats: bool) -> Vec<TorrentDetailsResponse>;
+
+    fn torrent_details(&self, id: usize) -> Result<TorrentDetailsResponse>;
+
+    /// Announce URLs the torrent was added with.
+    fn trackers(&self, id: usize) -> Vec<String>;
+
+    /// The same URLs in the metainfo's tiers (BEP 12), each tier in its listed order;
+    /// trackers from the magnet or session get a tier each.
+    fn tracker_tiers(&self, id: usize) -> Vec<Vec<String>>;
+
+    /// Swarm counts from one tracker, without announcing.
+    fn scrape(&self, announce: String, info_hash: String) -> BackendFuture<'_, ScrapeStats>;
+
+    /// Announces the torrent to one tracker as the session would, returning the decoded
+    /// response.
+    fn debug_announce(&self, id: usize, announce: String) -> BackendFuture<'_, String>;
+
+    /// Pieces that passed their hash check, for a paused or running torrent.
+    fn piece_progress(&self, id: usize) -> Option<PieceProgress>;
+
+    /// Live peers of a running torrent.
+    fn peer_stats(&self, id: usize) -> Result<Vec<PeerSample>>;
+
+    /// Fingerprints of the torrent's files that have one; empty until metadata is known.
+    fn file_fingerprints(&self, id: usize) -> Vec<FileFingerprint>;
+
+    /// Opens file `file` of the torrent for reading, downloading it as it is read.
+    fn open_stream(&self, id: usize, file: usize) -> Result<StreamFile>;
+
+    /// The torrent's metainfo as a `.torrent` file, once it has metadata.
+    fn torrent_bytes(&self, id: usize) -> Result<Vec<u8>>;
+
+    fn add_torrent(
+        &self,
+        add: AddTorrent<'static>,
+        opts: AddTorrentOptions,
+    ) -> BackendFuture<'_, ApiAddTorrentResponse>;
+
+    fn start(&self, id: usize) -> BackendFuture<'_, ()>;
+
+    /// Session-wide limits in bytes per second; `None` is unlimited.
+    fn set_rate_limits(&self, download_bps: Option<u64>, upload_bps: Option<u64>);
+
+    fn pause(&self, id: usize) -> BackendFuture<'_, ()>;
+
+    fn forget(&self, id: usize) -> BackendFuture<'_, ()>;
+
+    fn delete(&self, id: usize) -> BackendFuture<'_, ()>;
+
+    /// Re-adds a torrent with its data looked up under `output_folder`, keeping the file
+    /// selection and rechecking what's there. Returns the new id.
+    fn relocate(&self, id: usize, output_folder: String, paused: bool)
+    -> BackendFuture<'_, usize>;
+
+    /// Simulated backends keep everything in memory; the app then avoids touching the disk.
+    fn is_simulated(&self) -> bool {
+        false
+    }
+}
+
+impl TorrentBackend for Api {
+    fn session_stats(&self) -> SessionStatsSnapshot {
+        self.api_session_stats()
+    }
+
+    fn torrent_list(&self, with_stats: bool) -> Vec<TorrentDetailsResponse> {
+        self.api_torrent_list_ext(ApiTorrentListOpts { with_stats })
+            .torrents
+    }
+
+    fn torrent_details(&self, id: usize) -> Result<TorrentDetailsResponse> {
+        Ok(self.api_torrent_details(id.into())?)
+    }
+
+    fn trackers(&self, id: usize) -> Vec<String> {
+        self.mgr_handle(id.into())
+            .map(|handle| handle.shared().trackers.iter().map(|u| u.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    fn tracker_tiers(&self, id: usize) -> Vec<Vec<String>> {
+        let mut tiers: Vec<Vec<String>> = self
+            .mgr_handle(id.into())
+            .ok()
+            .and_then(|handle| {
+                handle
+                    .with_metadata(|m| {
+                        let meta = torrent_from_bytes::<ByteBuf>(&m.torrent_bytes).ok()?;
+                        Some(crate::metainfo::tracker_tiers(&meta))
+                    })
+                    .ok()
+                    .flatten()
+            })
+            .unwrap_or_default();
+        for url in self.trackers(id) {
+            if !tiers.iter().flatten().any(|known| *known == url) {
+                tiers.push(vec![url]);
+            }
+        }
+        tiers
+    }
+
+    fn scrape(&self, announce: String, info_hash: String) -> BackendFuture<'_, ScrapeStats> {
+        Box::pin(async move { crate::scrape::scrape(&announce, &info_hash).await })
+    }
+
+    fn debug_announce(&self, id: usize, announce: String) -> BackendFuture<'_, String> {
+        Box::pin(async move {
+            let info_hash = self.mgr_handle(id.into())?.info_hash().as_string();
+            let stats = self.api_stats_v1(id.into())?;
+            let request = AnnounceRequest {
+                info_hash,
+                peer_id: session_peer_id().0,
+                port: self.session().tcp_listen_port().unwrap_or(0),
+                uploaded: stats.uploaded_bytes,
+                downloaded: stats.progress_bytes,
+                left: stats.total_bytes.saturating_sub(stats.progress_bytes),
+            };
+            crate::announce::announce(&announce, &request).await
+        })
+    }
+
+    fn piece_progress(&self, id: usize) -> Option<PieceProgress> {
+        let handle = self.mgr_handle(id.into()).ok()?;
+        let (pieces, ranges) = handle
+            .with_metadata(|m| {
+                let ranges: Vec<_> = m.file_infos.iter().map(|f| f.piece_range.clone()).collect();
+                (m.lengths.total_pieces() as usize, ranges)
+            })
+            .ok()?;
+        let mut wanted = vec![true; pieces];
+        if let Some(only_files) = handle.only_files() {
+            wanted.fill(false);
+            for range in only_files.iter().filter_map(|&idx| ranges.get(idx)) {
+                for piece in range.clone() {
+                    wanted[piece as usize] = true;
+                }
+            }
+        }
+        // The have bitfield is only exposed through its debug rendering, which ends in a
+        // `[1, 0, ...]` list padded to whole bytes.
+        let dump = self.api_dump_haves(id.into()).ok()?;
+        let bits = dump[dump.rfind('[')?..].split(',').map(|bit| bit.contains('1'));
+        let have = bits.zip(&wanted).filter(|(bit, wanted)| *bit && **wanted).count();
+        Some(PieceProgress {
+            have: have as u32,
+            total: wanted.iter().filter(|w| **w).count() as u32,
+        })
+    }
+
+    fn file_fingerprints(&self, id: usize) -> Vec<FileFingerprint> {
+        let Ok(handle) = self.mgr_handle(id.into()) else {
+            return Vec::new();
+        };
+        handle
+            .with_metadata(|m| {
+                let piece_len = m.info.piece_length as u64;
+                let total = m.lengths.total_length();
+                let sha1s: Vec<Option<&[u8]>> = match &m.info.files {
+                    Some(files) => files.iter().map(|f| f.sha1.as_deref()).collect(),
+                    None => vec![m.info.sha1.as_deref()],
+                };
+                m.file_infos
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| !f.attrs.padding && f.len > 0)
+                    .filter_map(|(index, f)| {
+                        let digest = match sha1s.get(index).copied().flatten() {
+                            Some(sha1) => [b"sha1:".as_slice(), sha1].concat(),
+                            None => {
+                                let end = f.offset_in_torrent + f.len;
+                                let aligned = f.offset_in_torrent % piece_len == 0
+                                    && (end % piece_len == 0 || end == total);
+                                if !aligned {
+                                    return None;
+                                }
+                                let hashes = m.info.pieces.get(
+                                    f.piece_range.start as usize * 20
+                                        ..f.piece_range.end as usize * 20,
+                                )?;
+                                [piece_len.to_be_bytes().as_slice(), hashes].concat()
+                            }
+                        };
+                        Some(FileFingerprint {
+                            index,
+                            path: f.relative_filename.to_string_lossy().into_owned(),
+                            len: f.len,
+                            digest,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn open_stream(&self, id: usize, file: usize) -> Result<StreamFile> {
+        if let Some(only_files) = self.mgr_handle(id.into())?.only_files()
+            && !only_files.contains(&file)
+        {
+            bail!("file {file} is not selected for download");
+        }
+        let stream = self.api_stream(id.into(), file)?;
+        Ok(StreamFile {
+            len: stream.len(),
+            mime: self.torrent_file_mime_type(id.into(), file).ok(),
+            reader: Box::new(stream),
+        })
+    }
+
+    fn torrent_bytes(&self, id: usize) -> Result<Vec<u8>> {
+        self.mgr_handle(id.into())?
+            .with_metadata(|m| m.torrent_bytes.to_vec())
+    }
+
+    fn peer_stats(&self, id: usize) -> Result<Vec<PeerSample>> {
+        let snapshot = self.api_peer_stats(id.into(), Default::default())?;
+        Ok(snapshot
+            .peers
+            .into_iter()
+            .map(|(addr, stats)| PeerSample {
+                addr,
+                fetched_bytes: stats.counters.fetched_bytes,
+            })
+            .collect())
+    }
+
+    fn add_torrent(
+        &self,
+        add: AddTorrent<'static>,
+        opts: AddTorrentOptions,
+    ) -> BackendFuture<'_, ApiAddTorrentResponse> {
+        Box::pin(async move { Ok(self.api_add_torrent(add, Some(opts)).await?) })
+    }
+
+    fn set_rate_limits(&self, download_bps: Option<u64>, upload_bps: Option<u64>) {
+        let limit = |bps: Option<u64>| {
+            bps.and_then(|bps| NonZeroU32::new(bps.min(u32::MAX as u64) as u32))
+        };
+        let limits = &self.session().ratelimits;
+        limits.set_download_bps(limit(download_bps));
+        limits.set_upload_bps(limit(upload_bps));
+    }
+
+    fn start(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.api_torrent_action_start(id.into()).await?;
+            Ok(())
+        })
+    }
+
+    fn pause(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.api_torrent_action_pause(id.into()).await?;
+            Ok(())
+        })
+    }
+
+    fn forget(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.api_torrent_action_forget(id.into()).await?;
+            Ok(())
+        })
+    }
+
+    fn delete(&self, id: usize) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.api_torrent_action_delete(id.into()).await?;
+            Ok(())
+        })
+    }
+
+    fn relocate(
+        &self,
+        id: usize,
+        output_folder: String,
+        paused: bool,
+    ) -> BackendFuture<'_, usize> {
+        Box::pin(async move {
+            let handle = self.mgr_handle(id.into())?;
+            let torrent = handle.with_metadata(|m| m.torrent_bytes.clone())?;
+            let only_files = handle.only_files();
+            let previous = self.api_torrent_details(id.into())?.output_folder;
+            self.api_torrent_action_forget(id.into()).await?;
+            let readd = |folder: String| {
+                self.api_add_torrent(
+                    AddTorrent::TorrentFileBytes(torrent.clone()),
+                    Some(AddTorrentOptions {
+                        only_files: only_files.clone(),
+                        output_folder: Some(folder),
+                        overwrite: true,
+                        paused,
+                        ..Default::default()
+                    }),
+                )
+            };
+            let response = match readd(output_folder).await {
+                Ok(response) => response,
+                Err(err) => {
+                    // Put it back where it was rather than losing the torrent.
+                    readd(previous).await.context("error restoring torrent")?;
+                    return Err(err.into());
+                }
+            };
+            response.id.ok_or_else(|| anyhow!("torrent was not re-added"))
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use librqbit::{
+    ManagedTorrentShared, TorrentMetadata,
+    storage::{BoxStorageFactory, StorageFactory, StorageFactoryExt, TorrentStorage},
+};
+use librqbit_core::lengths::ValidPieceIndex;
+
+/// Session-wide disk write cap shared by every torrent's storage, plus a running byte
+/// count for showing write throughput.
+#[derive(Debug, Default)]
+pub struct DiskWrites {
+    /// Bytes per second; 0 means unlimited.
+    limit: u64,
+    written: AtomicU64,
+    /// When the bandwidth already handed out runs dry; writers sleep until their turn.
+    next_free: Mutex<Option<Instant>>,
+}
+
+impl DiskWrites {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Total bytes written since startup.
+    pub fn written(&self) -> u64 {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    fn throttle(&self, len: usize) {
+        if self.limit == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let start = {
+            let mut next_free = self.next_free.lock().unwrap_or_else(|e| e.into_inner());
+            let start = next_free.map_or(now, |at| at.max(now));
+            *next_free = Some(start + Duration::from_secs_f64(len as f64 / self.limit as f64));
+            start
+        };
+        // librqbit writes from blocking sections, so sleeping here is safe.
+        std::thread::sleep(start - now);
+    }
+}
+
+/// Wraps another storage factory, pacing writes through a shared `DiskWrites`.
+#[derive(Clone)]
+pub struct ThrottledStorageFactory<U> {
+    underlying: U,
+    writes: Arc<DiskWrites>,
+}
+
+impl<U: StorageFactory + Clone> ThrottledStorageFactory<U> {
+    pub fn new(underlying: U, writes: Arc<DiskWrites>) -> Self {
+        Self { underlying, writes }
+    }
+}
+
+impl<U: StorageFactory + Clone> StorageFactory for ThrottledStorageFactory<U> {
+    type Storage = ThrottledStorage<U::Storage>;
+
+    fn create(
+        &self,
+        shared: &ManagedTorrentShared,
+        metadata: &TorrentMetadata,
+    ) -> anyhow::Result<Self::Storage> {
+        Ok(ThrottledStorage {
+            underlying: self.underlying.create(shared, metadata)?,
+            writes: self.writes.clone(),
+        })
+    }
+
+    fn is_type_id(&self, type_id: std::any::TypeId) -> bool {
+        self.underlying.is_type_id(type_id)
+    }
+
+    fn clone_box(&self) -> BoxStorageFactory {
+        self.clone().boxed()
+    }
+}
+
+pub struct ThrottledStorage<U> {
+    underlying: U,
+    writes: Arc<DiskWrites>,
+}
+
+impl<U: TorrentStorage> TorrentStorage for ThrottledStorage<U> {
+    fn init(
+        &mut self,
+        shared: &ManagedTorrentShared,
+        metadata: &TorrentMetadata,
+    ) -> anyhow::Result<()> {
+        self.underlying.init(shared, metadata)
+    }
+
+    fn pread_exact(&self, file_id: usize, offset: u64, buf: &mut [u8]) -> anyhow::Result<()> {
+        self.underlying.pread_exact(file_id, offset, buf)
+    }
+
+    fn pwrite_all(&self, file_id: usize, offset: u64, buf: &[u8]) -> anyhow::Result<()> {
+        self.writes.throttle(buf.len());
+        self.underlying.pwrite_all(file_id, offset, buf)?;
+        self.writes
+            .written
+            .fetch_add(buf.len() as u64, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn remove_file(&self, file_id: usize, filename: &Path) -> anyhow::Result<()> {
+        self.underlying.remove_file(file_id, filename)
+    }
+
+    fn remove_directory_if_empty(&self, path: &Path) -> anyhow::Result<()> {
+        self.underlying.remove_directory_if_empty(path)
+    }
+
+    fn ensure_file_length(&self, file_id: usize, length: u64) -> anyhow::Result<()> {
+        self.underlying.ensure_file_length(file_id, length)
+    }
+
+    fn take(&self) -> anyhow::Result<Box<dyn TorrentStorage>> {
+        Ok(Box::new(ThrottledStorage {
+            underlying: self.underlying.take()?,
+            writes: self.writes.clone(),
+        }))
+    }
+
+    fn on_piece_completed(&self, piece_index: ValidPieceIndex) -> anyhow::Result<()> {
+        self.underlying.on_piece_completed(piece_index)
+    }
+}
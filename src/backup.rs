@@ -0,0 +1,201 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever the bundle layout changes incompatibly.
+const FORMAT: u32 = 1;
+
+/// Everything needed to move a setup to another machine, in one JSON file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle {
+    format: u32,
+    /// ittybitty version that wrote the bundle.
+    version: String,
+    created_at: DateTime<Local>,
+    files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleFile {
+    area: Area,
+    /// Relative to the area's directory, `/`-separated.
+    path: String,
+    contents: Contents,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Area {
+    /// librqbit's session persistence: `session.json`, `.torrent` and fastresume files.
+    Session,
+    /// Local metadata, pending adds and the activity log.
+    Data,
+    Config,
+}
+
+impl Area {
+    const ALL: [Area; 3] = [Area::Session, Area::Data, Area::Config];
+
+    fn dir(self) -> Result<PathBuf> {
+        match self {
+            Area::Session => crate::config::session_dir(),
+            Area::Data => crate::config::data_dir().context("no data directory"),
+            Area::Config => crate::config::config_path()
+                .and_then(|p| p.parent().map(Path::to_path_buf))
+                .context("no config directory"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Contents {
+    Text(String),
+    /// Hex-encoded, for `.torrent` and fastresume files.
+    Binary(String),
+}
+
+/// What a backup or restore touched.
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub files: usize,
+    pub bytes: u64,
+    /// Version of ittybitty that wrote the bundle (for restores).
+    pub version: String,
+}
+
+/// Writes the session, local data and config into a single bundle at `out`.
+pub fn backup(out: &Path) -> Result<Summary> {
+    let mut files = Vec::new();
+    let mut summary = Summary::default();
+    for area in Area::ALL {
+        let dir = area.dir()?;
+        let mut paths = Vec::new();
+        collect_files(&dir, &mut paths)?;
+        // Copies left by data upgrades would only be restored to be thrown away.
+        if area == Area::Data {
+            paths.retain(|p| !p.starts_with(dir.join(crate::migrate::BACKUP_DIR)));
+        }
+        for path in paths {
+            let bytes = std::fs::read(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            summary.files += 1;
+            summary.bytes += bytes.len() as u64;
+            let relative = path
+                .strip_prefix(&dir)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let contents = match String::from_utf8(bytes) {
+                Ok(text) => Contents::Text(text),
+                Err(err) => Contents::Binary(to_hex(err.as_bytes())),
+            };
+            files.push(BundleFile {
+                area,
+                path: relative,
+                contents,
+            });
+        }
+    }
+    let bundle = Bundle {
+        format: FORMAT,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Local::now(),
+        files,
+    };
+    let text = serde_json::to_string_pretty(&bundle)?;
+    // The bundle carries config secrets and the auth token.
+    crate::auth::write_private(out, text.as_bytes())
+        .with_context(|| format!("failed to write {}", out.display()))?;
+    summary.version = bundle.version;
+    Ok(summary)
+}
+
+/// Unpacks a bundle over the current setup. Run it while ittybitty is closed, since the
+/// session files are rewritten in place.
+pub fn restore(input: &Path) -> Result<Summary> {
+    let text = std::fs::read_to_string(input)
+        .with_context(|| format!("failed to read {}", input.display()))?;
+    let bundle: Bundle = serde_json::from_str(&text)
+        .with_context(|| format!("{} is not an ittybitty backup", input.display()))?;
+    if bundle.format > FORMAT {
+        bail!(
+            "backup was written by ittybitty {} (format {}); this build only reads format {FORMAT}",
+            bundle.version,
+            bundle.format
+        );
+    }
+    // Validate everything before writing anything.
+    let mut writes = Vec::with_capacity(bundle.files.len());
+    for file in bundle.files {
+        let relative = Path::new(&file.path);
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            bail!("backup contains an unsafe path: {}", file.path);
+        }
+        let bytes = match file.contents {
+            Contents::Text(text) => text.into_bytes(),
+            Contents::Binary(hex) => from_hex(&hex)
+                .with_context(|| format!("corrupt entry in backup: {}", file.path))?,
+        };
+        writes.push((file.area.dir()?.join(relative), bytes));
+    }
+    // Data from before formats were stamped must be upgraded from the start, not taken to
+    // be in whatever format the directory was in.
+    let data_dir = Area::Data.dir()?;
+    let stamp = data_dir.join(crate::migrate::STAMP_FILE);
+    if !writes.iter().any(|(path, _)| *path == stamp) && stamp.exists() {
+        std::fs::remove_file(&stamp)
+            .with_context(|| format!("failed to remove {}", stamp.display()))?;
+    }
+    let mut summary = Summary {
+        version: bundle.version,
+        ..Default::default()
+    };
+    for (path, bytes) in writes {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        // Owner-only, like the bundle, since config.toml and auth_token hold secrets.
+        crate::auth::write_private(&path, &bytes)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        summary.files += 1;
+        summary.bytes += bytes.len() as u64;
+    }
+    Ok(summary)
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err).with_context(|| format!("failed to list {}", dir.display())),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else if path.extension().is_none_or(|ext| ext != "tmp") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        bail!("not a hex string");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).context("invalid hex"))
+        .collect()
+}
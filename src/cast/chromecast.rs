@@ -0,0 +1,366 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+};
+
+use super::{CastDevice, CastKind, CastMedia};
+
+const MDNS: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(224, 0, 0, 251)), 5353);
+const SERVICE: &str = "_googlecast._tcp.local";
+const CAST_PORT: u16 = 8009;
+/// Google's Default Media Receiver, which plays plain media URLs.
+const MEDIA_RECEIVER: &str = "CC1AD845";
+const NS_CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
+const NS_HEARTBEAT: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const NS_RECEIVER: &str = "urn:x-cast:com.google.cast.receiver";
+const NS_MEDIA: &str = "urn:x-cast:com.google.cast.media";
+const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+
+/// Asks for `_googlecast._tcp` over mDNS and collects the answers for `wait`.
+pub(super) async fn discover(wait: Duration) -> Vec<CastDevice> {
+    let Ok(socket) = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await else {
+        return Vec::new();
+    };
+    if socket.send_to(&query(), MDNS).await.is_err() {
+        return Vec::new();
+    }
+    let mut found: HashMap<String, Instance> = HashMap::new();
+    let mut buf = vec![0u8; 9000];
+    let deadline = tokio::time::Instant::now() + wait;
+    while let Ok(Ok((len, from))) =
+        tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+    {
+        for record in parse_records(&buf[..len]).unwrap_or_default() {
+            record.apply(&mut found, from.ip());
+        }
+    }
+    found
+        .into_iter()
+        .filter_map(|(instance, found)| {
+            let ip = found.ip.or(found.from)?;
+            let name = found.friendly_name.unwrap_or_else(|| {
+                instance.split('.').next().unwrap_or(&instance).to_string()
+            });
+            Some(CastDevice {
+                name,
+                kind: CastKind::Chromecast,
+                addr: SocketAddr::new(ip, found.port.unwrap_or(CAST_PORT)),
+            })
+        })
+        .collect()
+}
+
+/// What the answers so far say about one cast instance.
+#[derive(Debug, Default)]
+struct Instance {
+    friendly_name: Option<String>,
+    port: Option<u16>,
+    target: Option<String>,
+    ip: Option<IpAddr>,
+    /// Where the answer came from, for devices that leave out their A record.
+    from: Option<IpAddr>,
+}
+
+#[derive(Debug)]
+enum Record {
+    Ptr { instance: String },
+    Srv { instance: String, port: u16, target: String },
+    Txt { instance: String, entries: Vec<String> },
+    A { host: String, ip: Ipv4Addr },
+}
+
+impl Record {
+    fn apply(self, found: &mut HashMap<String, Instance>, from: IpAddr) {
+        match self {
+            Record::Ptr { instance } => {
+                found.entry(instance).or_default().from = Some(from);
+            }
+            Record::Srv { instance, port, target } => {
+                let entry = found.entry(instance).or_default();
+                entry.port = Some(port);
+                entry.target = Some(target);
+            }
+            Record::Txt { instance, entries } => {
+                let name = entries.iter().find_map(|e| e.strip_prefix("fn="));
+                found.entry(instance).or_default().friendly_name = name.map(str::to_string);
+            }
+            Record::A { host, ip } => {
+                for entry in found.values_mut() {
+                    if entry.target.as_deref() == Some(host.as_str()) {
+                        entry.ip = Some(IpAddr::V4(ip));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One PTR question with the unicast-response bit set, so answers come to our port.
+fn query() -> Vec<u8> {
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in SERVICE.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&0x8001u16.to_be_bytes());
+    packet
+}
+
+/// The cast-related records of an mDNS response, answers and additional records alike,
+/// sorted so each instance is known before its SRV, and each SRV target before its A.
+fn parse_records(packet: &[u8]) -> Option<Vec<Record>> {
+    let count = |at: usize| Some(u16::from_be_bytes([*packet.get(at)?, *packet.get(at + 1)?]));
+    let questions = count(4)?;
+    let records = count(6)? as usize + count(8)? as usize + count(10)? as usize;
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = read_name(packet, pos)?.1 + 4;
+    }
+    let mut out = Vec::new();
+    for _ in 0..records {
+        let (name, next) = read_name(packet, pos)?;
+        let kind = count(next)?;
+        let len = count(next + 8)? as usize;
+        let data = next + 10;
+        let rdata = packet.get(data..data + len)?;
+        pos = data + len;
+        let record = match kind {
+            TYPE_PTR if name == SERVICE => Record::Ptr {
+                instance: read_name(packet, data)?.0,
+            },
+            TYPE_SRV if name.ends_with(SERVICE) && len > 6 => Record::Srv {
+                port: u16::from_be_bytes([rdata[4], rdata[5]]),
+                target: read_name(packet, data + 6)?.0,
+                instance: name,
+            },
+            TYPE_TXT if name.ends_with(SERVICE) => {
+                let mut entries = Vec::new();
+                let mut at = 0;
+                while let Some(&n) = rdata.get(at) {
+                    let entry = rdata.get(at + 1..at + 1 + n as usize)?;
+                    entries.push(String::from_utf8_lossy(entry).into_owned());
+                    at += 1 + n as usize;
+                }
+                Record::Txt {
+                    instance: name,
+                    entries,
+                }
+            }
+            TYPE_A if len == 4 => Record::A {
+                host: name,
+                ip: Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]),
+            },
+            _ => continue,
+        };
+        out.push(record);
+    }
+    out.sort_by_key(|r| match r {
+        Record::Ptr { .. } => 0,
+        Record::Srv { .. } | Record::Txt { .. } => 1,
+        Record::A { .. } => 2,
+    });
+    Some(out)
+}
+
+/// A possibly compressed DNS name at `pos`, and the position just after it.
+fn read_name(packet: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Pointers only go backwards in well-formed packets; the cap stops loops in bad ones.
+    for _ in 0..128 {
+        let len = *packet.get(pos)? as usize;
+        if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(pos + 1)));
+        }
+        if len & 0xc0 == 0xc0 {
+            end.get_or_insert(pos + 2);
+            pos = ((len & 0x3f) << 8) | *packet.get(pos + 1)? as usize;
+            continue;
+        }
+        let label = packet.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    None
+}
+
+/// Launches the Default Media Receiver and has it load `media`.
+pub(super) async fn play(addr: SocketAddr, media: &CastMedia) -> Result<()> {
+    let tcp = TcpStream::connect(addr)
+        .await
+        .with_context(|| format!("failed to connect to {addr}"))?;
+    // Cast devices present certificates signed by Google's device CA, not a public one.
+    let connector = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .build()
+        .context("failed to set up TLS")?;
+    let mut stream = tokio_native_tls::TlsConnector::from(connector)
+        .connect(&addr.ip().to_string(), tcp)
+        .await
+        .context("TLS handshake failed")?;
+
+    send(&mut stream, "receiver-0", NS_CONNECTION, json!({ "type": "CONNECT" })).await?;
+    send(
+        &mut stream,
+        "receiver-0",
+        NS_RECEIVER,
+        json!({ "type": "LAUNCH", "appId": MEDIA_RECEIVER, "requestId": 1 }),
+    )
+    .await?;
+    let transport = loop {
+        let (namespace, message) = receive(&mut stream).await?;
+        match (namespace.as_str(), message["type"].as_str()) {
+            (NS_RECEIVER, Some("RECEIVER_STATUS")) => {
+                let app = message["status"]["applications"]
+                    .as_array()
+                    .and_then(|apps| apps.iter().find(|a| a["appId"] == MEDIA_RECEIVER));
+                if let Some(transport) = app.and_then(|a| a["transportId"].as_str()) {
+                    break transport.to_string();
+                }
+            }
+            (NS_RECEIVER, Some("LAUNCH_ERROR")) => {
+                bail!("device refused to start its media player: {}", message["reason"])
+            }
+            _ => {}
+        }
+    };
+
+    send(&mut stream, &transport, NS_CONNECTION, json!({ "type": "CONNECT" })).await?;
+    let load = json!({
+        "type": "LOAD",
+        "requestId": 2,
+        "autoplay": true,
+        "media": {
+            "contentId": media.url,
+            "contentType": media.mime,
+            "streamType": "BUFFERED",
+            "metadata": { "metadataType": 0, "title": media.title },
+        },
+    });
+    send(&mut stream, &transport, NS_MEDIA, load).await?;
+    loop {
+        let (namespace, message) = receive(&mut stream).await?;
+        if namespace != NS_MEDIA {
+            continue;
+        }
+        match message["type"].as_str() {
+            Some("MEDIA_STATUS") => return Ok(()),
+            Some(kind @ ("LOAD_FAILED" | "LOAD_CANCELLED" | "INVALID_REQUEST")) => {
+                bail!("device couldn't play the stream ({kind})")
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sends a string-payload CastMessage, hand-encoded: the protobuf has six fields and no
+/// nesting.
+async fn send<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    destination: &str,
+    namespace: &str,
+    payload: Value,
+) -> Result<()> {
+    // protocol_version CASTV2_1_0, then the ids, namespace, payload_type STRING and payload.
+    let mut message = vec![0x08, 0x00];
+    put_string(&mut message, 2, "sender-0");
+    put_string(&mut message, 3, destination);
+    put_string(&mut message, 4, namespace);
+    message.extend_from_slice(&[0x28, 0x00]);
+    put_string(&mut message, 6, &payload.to_string());
+    stream.write_all(&(message.len() as u32).to_be_bytes()).await?;
+    stream.write_all(&message).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Reads the next message, answering heartbeats on the way, as (namespace, payload).
+async fn receive<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) -> Result<(String, Value)> {
+    loop {
+        let len = stream.read_u32().await.context("device closed the connection")? as usize;
+        if len > MAX_MESSAGE_BYTES {
+            bail!("oversized message from device");
+        }
+        let mut message = vec![0u8; len];
+        stream.read_exact(&mut message).await?;
+        let (source, namespace, payload) = decode(&message)?;
+        let payload: Value = serde_json::from_str(&payload).unwrap_or(Value::Null);
+        if namespace == NS_HEARTBEAT && payload["type"] == "PING" {
+            send(stream, &source, NS_HEARTBEAT, json!({ "type": "PONG" })).await?;
+            continue;
+        }
+        return Ok((namespace, payload));
+    }
+}
+
+/// Source, namespace and string payload of a CastMessage.
+fn decode(message: &[u8]) -> Result<(String, String, String)> {
+    let bad = || anyhow!("malformed message from device");
+    let (mut source, mut namespace, mut payload) = (String::new(), String::new(), String::new());
+    let mut pos = 0;
+    while pos < message.len() {
+        let key = take_varint(message, &mut pos).ok_or_else(bad)?;
+        match key & 7 {
+            0 => {
+                take_varint(message, &mut pos).ok_or_else(bad)?;
+            }
+            2 => {
+                let len = take_varint(message, &mut pos).ok_or_else(bad)? as usize;
+                let bytes = message.get(pos..pos + len).ok_or_else(bad)?;
+                pos += len;
+                let text = String::from_utf8_lossy(bytes).into_owned();
+                match key >> 3 {
+                    2 => source = text,
+                    4 => namespace = text,
+                    6 => payload = text,
+                    _ => {}
+                }
+            }
+            _ => return Err(bad()),
+        }
+    }
+    Ok((source, namespace, payload))
+}
+
+fn put_string(out: &mut Vec<u8>, field: u8, text: &str) {
+    out.push(field << 3 | 2);
+    put_varint(out, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn take_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
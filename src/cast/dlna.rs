@@ -0,0 +1,135 @@
+use std::{collections::HashSet, time::Duration};
+
+use anyhow::{Context, Result, bail};
+use librqbit_upnp::Device;
+use url::Url;
+
+use super::{CastDevice, CastKind, CastMedia};
+
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+/// Device descriptions that take longer than this are skipped.
+const DESCRIPTION_TIMEOUT: Duration = Duration::from_secs(2);
+const CONTROL_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(super) async fn discover(wait: Duration) -> Vec<CastDevice> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    if librqbit_upnp::discover_once(&tx, AV_TRANSPORT, wait).await.is_err() {
+        return Vec::new();
+    }
+    drop(tx);
+    let mut seen = HashSet::new();
+    let mut devices = Vec::new();
+    while let Some(response) = rx.recv().await {
+        if !seen.insert(response.location.clone()) {
+            continue;
+        }
+        let description = tokio::time::timeout(
+            DESCRIPTION_TIMEOUT,
+            librqbit_upnp::discover_services(response.location.clone()),
+        )
+        .await;
+        let Ok(Ok(root)) = description else {
+            continue;
+        };
+        for device in &root.devices {
+            if let Some((name, control)) = renderer(device)
+                && let Ok(control_url) = response.location.join(control)
+            {
+                devices.push(CastDevice {
+                    name: name.to_string(),
+                    kind: CastKind::Dlna { control_url },
+                    addr: response.received_from,
+                });
+            }
+        }
+    }
+    devices
+}
+
+/// The first device in the tree with an AVTransport service, and that service's control
+/// URL.
+fn renderer(device: &Device) -> Option<(&str, &str)> {
+    device
+        .service_list
+        .services
+        .iter()
+        .find(|s| s.service_type.starts_with("urn:schemas-upnp-org:service:AVTransport:"))
+        .map(|s| (device.name(), s.control_url.as_str()))
+        .or_else(|| device.device_list.devices.iter().find_map(renderer))
+}
+
+pub(super) async fn play(control_url: &Url, media: &CastMedia) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(CONTROL_TIMEOUT)
+        .build()
+        .context("failed to build HTTP client")?;
+    // Some renderers only take a new URI once stopped; the ones that are idle refuse this.
+    let _ = soap(&client, control_url, "Stop", "<InstanceID>0</InstanceID>").await;
+    let args = format!(
+        "<InstanceID>0</InstanceID><CurrentURI>{}</CurrentURI>\
+         <CurrentURIMetaData>{}</CurrentURIMetaData>",
+        escape(&media.url),
+        escape(&didl(media))
+    );
+    soap(&client, control_url, "SetAVTransportURI", &args).await?;
+    soap(&client, control_url, "Play", "<InstanceID>0</InstanceID><Speed>1</Speed>").await
+}
+
+async fn soap(client: &reqwest::Client, control_url: &Url, action: &str, args: &str) -> Result<()> {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body>\
+         <u:{action} xmlns:u=\"{AV_TRANSPORT}\">{args}</u:{action}></s:Body></s:Envelope>"
+    );
+    let response = client
+        .post(control_url.clone())
+        .header("Content-Type", "text/xml; charset=\"utf-8\"")
+        .header("SOAPAction", format!("\"{AV_TRANSPORT}#{action}\""))
+        .body(body)
+        .send()
+        .await
+        .with_context(|| format!("{action} failed"))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        match between(&text, "<errorDescription>", "</errorDescription>") {
+            Some(reason) => bail!("{action} refused: {reason}"),
+            None => bail!("{action} refused: HTTP {status}"),
+        }
+    }
+    Ok(())
+}
+
+/// DIDL-Lite item describing the stream, which most renderers need to pick a player.
+fn didl(media: &CastMedia) -> String {
+    let class = match media.mime.split('/').next() {
+        Some("audio") => "object.item.audioItem.musicTrack",
+        Some("image") => "object.item.imageItem.photo",
+        _ => "object.item.videoItem",
+    };
+    format!(
+        "<DIDL-Lite xmlns=\"urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+         xmlns:upnp=\"urn:schemas-upnp-org:metadata-1-0/upnp/\">\
+         <item id=\"0\" parentID=\"-1\" restricted=\"1\"><dc:title>{}</dc:title>\
+         <upnp:class>{class}</upnp:class>\
+         <res protocolInfo=\"http-get:*:{}:*\">{}</res></item></DIDL-Lite>",
+        escape(&media.title),
+        escape(&media.mime),
+        escape(&media.url)
+    )
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = text.find(start)? + start.len();
+    let to = text[from..].find(end)? + from;
+    Some(&text[from..to])
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
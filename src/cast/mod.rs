@@ -0,0 +1,70 @@
+mod chromecast;
+mod dlna;
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::Result;
+use url::Url;
+
+/// A device on the LAN that can play a stream URL.
+#[derive(Debug, Clone)]
+pub struct CastDevice {
+    pub name: String,
+    pub kind: CastKind,
+    pub addr: SocketAddr,
+}
+
+#[derive(Debug, Clone)]
+pub enum CastKind {
+    /// UPnP/DLNA media renderer, driven through its AVTransport service.
+    Dlna { control_url: Url },
+    Chromecast,
+}
+
+impl CastKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CastKind::Dlna { .. } => "DLNA",
+            CastKind::Chromecast => "Chromecast",
+        }
+    }
+}
+
+/// What to play on a device.
+#[derive(Debug, Clone)]
+pub struct CastMedia {
+    pub url: String,
+    pub mime: String,
+    pub title: String,
+}
+
+/// Looks for DLNA renderers (SSDP) and Chromecasts (mDNS) for `wait`, sorted by name.
+pub async fn discover(wait: Duration) -> Vec<CastDevice> {
+    let (mut devices, chromecasts) = tokio::join!(dlna::discover(wait), chromecast::discover(wait));
+    devices.extend(chromecasts);
+    devices.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.addr.cmp(&b.addr)));
+    devices
+}
+
+/// Starts playing `media` on `device`; returns once the device has accepted it.
+pub async fn play(device: &CastDevice, media: &CastMedia) -> Result<()> {
+    match &device.kind {
+        CastKind::Dlna { control_url } => dlna::play(control_url, media).await,
+        CastKind::Chromecast => chromecast::play(device.addr, media).await,
+    }
+}
+
+/// This machine's address on the device's network, for the stream URL the device fetches.
+pub fn local_ip_for(device: &CastDevice) -> Option<IpAddr> {
+    librqbit_upnp::get_local_ip_relative_to(device.addr).ok()
+}
+
+/// Whether a file of this type is worth offering to a player.
+pub fn is_media(mime: &str) -> bool {
+    ["video/", "audio/", "image/"]
+        .iter()
+        .any(|prefix| mime.starts_with(prefix))
+}
@@ -0,0 +1,74 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::json;
+use url::Url;
+
+use crate::config::{ChatConfig, ChatService};
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+pub fn service_name(service: ChatService) -> &'static str {
+    match service {
+        ChatService::Discord => "Discord",
+        ChatService::Telegram => "Telegram",
+        ChatService::Matrix => "Matrix",
+    }
+}
+
+/// Posts `text` to `service` as configured under `[chat]`.
+pub async fn send(config: &ChatConfig, service: ChatService, text: &str) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .context("failed to set up HTTP client")?;
+    let missing = || anyhow!("[chat.{}] isn't configured", service_name(service).to_lowercase());
+    let request = match service {
+        ChatService::Discord => {
+            let discord = config.discord.as_ref().ok_or_else(missing)?;
+            client
+                .post(&discord.webhook_url)
+                .json(&json!({ "content": text }))
+        }
+        ChatService::Telegram => {
+            let telegram = config.telegram.as_ref().ok_or_else(missing)?;
+            let url = format!("https://api.telegram.org/bot{}/sendMessage", telegram.bot_token);
+            client
+                .post(url)
+                .json(&json!({ "chat_id": telegram.chat_id, "text": text }))
+        }
+        ChatService::Matrix => {
+            let matrix = config.matrix.as_ref().ok_or_else(missing)?;
+            let mut url = Url::parse(&matrix.homeserver)
+                .with_context(|| format!("invalid homeserver URL {}", matrix.homeserver))?;
+            // Matrix wants a fresh transaction id per message so retries aren't duplicated.
+            let txn = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+                .to_string();
+            url.path_segments_mut()
+                .map_err(|_| anyhow!("invalid homeserver URL {}", matrix.homeserver))?
+                .pop_if_empty()
+                .extend(["_matrix", "client", "v3", "rooms", &matrix.room_id])
+                .extend(["send", "m.room.message", &txn]);
+            client
+                .put(url)
+                .bearer_auth(&matrix.access_token)
+                .json(&json!({ "msgtype": "m.text", "body": text }))
+        }
+    };
+    // Webhook and bot URLs carry their secret, so errors leave the URL out.
+    let response = request
+        .send()
+        .await
+        .map_err(reqwest::Error::without_url)
+        .context("request failed")?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let body: String = body.chars().take(200).collect();
+        bail!("HTTP {status} {}", body.trim());
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
+
+pub const USAGE: &str = "\
+Usage: ittybitty [OPTIONS]
+       ittybitty backup <file>   Save session, labels/notes/history and config to one file
+       ittybitty restore <file>  Restore a backup (run while ittybitty is closed)
+       ittybitty inspect <file>  Show what a .torrent file holds without adding it
+
+Options:
+  --demo           Run against a simulated backend (no network or disk access)
+  --import <file>  Bulk-add magnets from a text, CSV or JSON file on startup
+  --daemon         Run without the terminal UI until interrupted (SIGINT/SIGTERM)
+  --json-events    With --daemon, print session events to stdout as JSON lines
+  --safe-mode      Start with the default config and no hooks, without saving local data
+  --record <file>  Write the keys, pastes and resizes of this run to a replay script
+  --replay <file>  Play a recorded script against the demo backend, then take input as usual
+  -h, --help       Print this help
+  -V, --version    Print the version
+
+Environment:
+  ITTYBITTY_<KEY>  Override a config key, e.g. ITTYBITTY_SPEED__STEP_KIB=512
+  ITTYBITTY_CONFIG_DIR, ITTYBITTY_DATA_DIR, ITTYBITTY_SESSION_DIR
+                   Move the config, data and session directories";
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Backup(PathBuf),
+    Restore(PathBuf),
+    Inspect(PathBuf),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Cli {
+    pub demo: bool,
+    pub import: Option<PathBuf>,
+    pub daemon: bool,
+    pub json_events: bool,
+    pub safe_mode: bool,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub command: Option<Command>,
+    pub help: bool,
+    pub version: bool,
+}
+
+impl Cli {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--demo" => cli.demo = true,
+                "--daemon" => cli.daemon = true,
+                "--json-events" => cli.json_events = true,
+                "--safe-mode" => cli.safe_mode = true,
+                "--import" => {
+                    let Some(path) = args.next() else {
+                        bail!("--import needs a file\n\n{USAGE}");
+                    };
+                    cli.import = Some(PathBuf::from(path));
+                }
+                "--record" | "--replay" => {
+                    let Some(path) = args.next() else {
+                        bail!("{arg} needs a file\n\n{USAGE}");
+                    };
+                    let path = Some(PathBuf::from(path));
+                    if arg == "--record" {
+                        cli.record = path;
+                    } else {
+                        cli.replay = path;
+                    }
+                }
+                "-h" | "--help" => cli.help = true,
+                "-V" | "--version" => cli.version = true,
+                "backup" | "restore" | "inspect" if cli.command.is_none() => {
+                    let Some(path) = args.next() else {
+                        bail!("{arg} needs a file\n\n{USAGE}");
+                    };
+                    let path = PathBuf::from(path);
+                    cli.command = Some(match arg.as_str() {
+                        "backup" => Command::Backup(path),
+                        "restore" => Command::Restore(path),
+                        _ => Command::Inspect(path),
+                    });
+                }
+                other => bail!("unknown argument: {other}\n\n{USAGE}"),
+            }
+        }
+        if cli.json_events && !cli.daemon {
+            bail!("--json-events needs --daemon\n\n{USAGE}");
+        }
+        if cli.daemon && (cli.record.is_some() || cli.replay.is_some()) {
+            bail!("--record and --replay need the terminal UI\n\n{USAGE}");
+        }
+        // A replay has to run against the same simulated torrents it was recorded with.
+        if cli.replay.is_some() {
+            cli.demo = true;
+        }
+        Ok(cli)
+    }
+}
@@ -0,0 +1,1109 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use directories::ProjectDirs;
+use librqbit::SessionPersistenceConfig;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of environment variables that override config keys.
+const ENV_PREFIX: &str = "ITTYBITTY_";
+
+/// Variables that move ittybitty's directories rather than set a config key.
+const CONFIG_DIR_VAR: &str = "ITTYBITTY_CONFIG_DIR";
+const DATA_DIR_VAR: &str = "ITTYBITTY_DATA_DIR";
+const SESSION_DIR_VAR: &str = "ITTYBITTY_SESSION_DIR";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub download_dir: Option<PathBuf>,
+    /// More directories offered in the download directory prompt, shown with free space.
+    pub download_dirs: Vec<PathBuf>,
+    pub startup: StartupPolicy,
+    /// Flat directory of `<torrent name>` symlinks pointing at each torrent's folder.
+    pub links_dir: Option<PathBuf>,
+    /// Folder for each new torrent under the download directory, as a template such as
+    /// `{label}/{tracker}/{name}`; unset puts every torrent in `<download dir>/<name>`.
+    pub save_path: Option<String>,
+    /// What to do when a torrent's folder already exists in the download directory.
+    pub collision: CollisionStrategy,
+    /// Check folder and file names against Windows rules. Defaults to on for Windows,
+    /// useful elsewhere when downloads land on a share used from Windows.
+    pub windows_safe_paths: Option<bool>,
+    /// Trims memory for small VPSes: one torrent checked at a time, slower refreshes, and
+    /// no peer sparklines or per-torrent event logs.
+    pub low_memory: bool,
+    pub ui: UiConfig,
+    pub power: PowerConfig,
+    pub load: LoadConfig,
+    pub disk: DiskConfig,
+    pub speed: SpeedConfig,
+    pub seeding: SeedingConfig,
+    pub scrub: ScrubConfig,
+    pub announce: AnnounceConfig,
+    pub timeouts: TimeoutConfig,
+    pub completion: CompletionConfig,
+    pub notifications: NotificationConfig,
+    pub archive: ArchiveConfig,
+    pub rss: RssConfig,
+    pub health: HealthConfig,
+    pub api: ApiConfig,
+    pub stream: StreamConfig,
+    pub cast: CastConfig,
+    pub smart_select: SmartSelectConfig,
+    pub risky_files: RiskyFilesConfig,
+    pub auth: AuthConfig,
+    pub tls: TlsConfig,
+    /// Commands run on session events, each for the events it lists (`[[hooks]]`).
+    pub hooks: Vec<HookRule>,
+    /// Conditions that turn the top bar red while they hold (`[[alerts]]`).
+    pub alerts: Vec<AlertRule>,
+    pub email: EmailConfig,
+    pub chat: ChatConfig,
+    pub labels: HashMap<String, LabelConfig>,
+    /// Per path prefix settings for download folders on removable or network storage.
+    pub mounts: HashMap<String, MountConfig>,
+}
+
+/// Which torrents run right after launch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupPolicy {
+    /// Resume the torrents that were running at last shutdown and pause the rest.
+    #[default]
+    Restore,
+    ResumeAll,
+    Paused,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionStrategy {
+    /// Ask on each add.
+    #[default]
+    Prompt,
+    /// Download into the existing folder, keeping (and rechecking) what's there.
+    Reuse,
+    /// Append the first 8 characters of the info hash.
+    AppendHash,
+    /// Append ` (2)`, ` (3)`, ...
+    AppendNumber,
+}
+
+impl CollisionStrategy {
+    /// Choices offered in the prompt, in display order.
+    pub const CHOICES: [CollisionStrategy; 3] = [
+        CollisionStrategy::Reuse,
+        CollisionStrategy::AppendHash,
+        CollisionStrategy::AppendNumber,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CollisionStrategy::Prompt => "Prompt",
+            CollisionStrategy::Reuse => "Reuse folder",
+            CollisionStrategy::AppendHash => "Append hash",
+            CollisionStrategy::AppendNumber => "Append (2)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// How often the UI wakes up to redraw and run scheduled work, in milliseconds.
+    pub tick_ms: u64,
+    /// How often torrent and session stats are re-fetched, in milliseconds.
+    pub refresh_ms: u64,
+    pub quit: QuitConfirm,
+    /// Typed to unlock the keyboard after `K` or `:lock`.
+    pub unlock: String,
+    /// Seconds without a key press before the stats screensaver shows; 0 turns it off.
+    pub screensaver_secs: u64,
+    /// Slowest tick and refresh while the terminal is unfocused, in milliseconds; 0 keeps
+    /// the normal rate.
+    pub unfocused_ms: u64,
+    /// Terminal window title with `{down}`, `{up}`, `{active}`, `{seeding}` and `{total}`
+    /// filled in; empty leaves the title alone.
+    pub title: String,
+    /// Report mouse clicks: right-click opens the torrent context menu.
+    pub mouse: bool,
+    /// Colour scheme at startup; `C` switches while running.
+    pub theme: ThemeName,
+    /// Whether startup uses the `start_*` settings or reopens what was on screen at exit.
+    pub start: StartMode,
+    pub start_view: StartView,
+    /// A filter's name (`all`, `downloading`, `seeding`, `paused`, `stopped`, `error`,
+    /// `idle`) or a label.
+    pub start_filter: String,
+    pub start_focus: StartFocus,
+    pub density: Density,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            tick_ms: 500,
+            refresh_ms: 500,
+            quit: QuitConfirm::default(),
+            unlock: "unlock".to_string(),
+            screensaver_secs: 0,
+            unfocused_ms: 5000,
+            title: "ittybitty ↓{down} ↑{up} {active} active".to_string(),
+            mouse: false,
+            theme: ThemeName::default(),
+            start: StartMode::default(),
+            start_view: StartView::default(),
+            start_filter: "all".to_string(),
+            start_focus: StartFocus::default(),
+            density: Density::default(),
+        }
+    }
+}
+
+impl UiConfig {
+    /// An empty sequence couldn't be typed, so it falls back to the default.
+    pub fn unlock_sequence(&self) -> &str {
+        if self.unlock.is_empty() {
+            "unlock"
+        } else {
+            &self.unlock
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemeName {
+    /// The one `C` switches to.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::HighContrast,
+            Self::HighContrast => Self::Dark,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::HighContrast => "high contrast",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartMode {
+    #[default]
+    Config,
+    /// The view, filter and focus from the last exit, kept in `ui_state.json`.
+    Last,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartView {
+    #[default]
+    Torrents,
+    Peers,
+    Info,
+    Files,
+    Trackers,
+    Timeline,
+    Log,
+    Rss,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartFocus {
+    Filters,
+    #[default]
+    Torrents,
+}
+
+/// How much room the UI gives its rows and dialogs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Density {
+    /// Two-line torrent rows with a progress bar, padded cells, dialogs with a margin.
+    #[default]
+    Comfortable,
+    /// One line per torrent, less padding and dialogs that take most of the screen, for
+    /// small terminals and large fonts.
+    Compact,
+}
+
+/// How `q` asks before quitting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuitConfirm {
+    /// A yes/no dialog.
+    #[default]
+    Dialog,
+    /// Pressing `q` again within a second.
+    Twice,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AnnounceConfig {
+    /// Spread bulk resumes (and their first tracker announces) over this many seconds.
+    pub jitter_secs: u64,
+    /// Allow `:debug-announce`, which sends one extra announce for the selected torrent and
+    /// logs what its trackers answer.
+    pub debug_responses: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    /// Cold storage that `:offload` moves a torrent's folder into.
+    pub dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Show a desktop notification when a torrent finishes downloading.
+    pub desktop: bool,
+    /// At most one notification this often, in seconds; completions in between are
+    /// reported together in the next one.
+    pub throttle_secs: u64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            throttle_secs: 30,
+        }
+    }
+}
+
+/// Feeds polled for new torrents, and the rules that pick which ones are added.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RssConfig {
+    /// Minutes between fetches of every feed.
+    pub interval_mins: u64,
+    pub feeds: Vec<RssFeed>,
+    pub rules: Vec<RssRule>,
+}
+
+impl Default for RssConfig {
+    fn default() -> Self {
+        Self {
+            interval_mins: 15,
+            feeds: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RssFeed {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RssRule {
+    pub name: String,
+    /// Matched against entry titles, ignoring case.
+    pub pattern: RssPattern,
+    /// Feeds the rule watches, by name; empty watches every feed.
+    #[serde(default)]
+    pub feeds: Vec<String>,
+    /// Base directory for what it adds; the torrent still gets its own subfolder inside it.
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl RssRule {
+    pub fn watches(&self, feed: &str) -> bool {
+        self.feeds.is_empty() || self.feeds.iter().any(|name| name == feed)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct RssPattern(pub Regex);
+
+impl TryFrom<String> for RssPattern {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        RegexBuilder::new(&text)
+            .case_insensitive(true)
+            .build()
+            .map(RssPattern)
+            .map_err(|err| format!("invalid pattern {text:?}: {err}"))
+    }
+}
+
+/// How long backend operations may take before they fail, in seconds; 0 waits forever.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    /// Resolving a magnet or URL to its file list.
+    pub metadata_secs: u64,
+    /// Pausing and restarting a torrent so it announces again.
+    pub announce_secs: u64,
+    /// Adding a torrent once its files are picked.
+    pub add_secs: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            metadata_secs: 120,
+            announce_secs: 30,
+            add_secs: 60,
+        }
+    }
+}
+
+/// Pauses every running torrent while on battery or a metered link, resuming afterwards.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// Pause when discharging and the battery is below this percentage.
+    pub pause_on_battery_below: Option<u8>,
+    pub pause_on_metered: bool,
+    /// Interfaces treated as metered when they carry the default route; `wwan*` matches
+    /// by prefix.
+    pub metered_interfaces: Vec<String>,
+}
+
+impl PowerConfig {
+    pub fn enabled(&self) -> bool {
+        self.pause_on_battery_below.is_some() || self.pause_on_metered
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SeedingConfig {
+    /// Finished torrents with no uploads for this many days show under the Idle filter.
+    pub idle_days: u64,
+    /// While an upload limit is set, only this many finished torrents seed at once, picked
+    /// by priority; the rest are paused until they rank high enough again. Off when unset.
+    pub upload_slots: Option<usize>,
+    /// Upload/download ratio a torrent aims for, unless its label sets one.
+    pub ratio_goal: f64,
+    /// Tracker hosts whose torrents get `tracker_weight`, matched by suffix.
+    pub preferred_trackers: Vec<String>,
+    /// Priority for being below the ratio goal, scaled by how far below.
+    pub below_goal_weight: f64,
+    pub tracker_weight: f64,
+}
+
+impl Default for SeedingConfig {
+    fn default() -> Self {
+        Self {
+            idle_days: 7,
+            upload_slots: None,
+            ratio_goal: 1.0,
+            preferred_trackers: Vec::new(),
+            below_goal_weight: 1.0,
+            tracker_weight: 1.0,
+        }
+    }
+}
+
+/// Periodic rechecks of seeding torrents, oldest-verified first, to catch bit rot.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScrubConfig {
+    /// Torrents rechecked per week, spread evenly; 0 turns scrubbing off.
+    pub per_week: u32,
+}
+
+impl ScrubConfig {
+    pub fn enabled(&self) -> bool {
+        self.per_week > 0
+    }
+
+    /// Time between two scrub rechecks.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(7 * 24 * 3600) / self.per_week.max(1)
+    }
+}
+
+/// Global network limits, adjustable at runtime with `+`/`-` (download) and `]`/`[`
+/// (upload).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SpeedConfig {
+    /// Download limit at launch, in KiB/s.
+    pub download_limit_kib: Option<u64>,
+    /// Upload limit at launch, in KiB/s.
+    pub upload_limit_kib: Option<u64>,
+    /// How much each key press changes a limit, in KiB/s.
+    pub step_kib: u64,
+    pub schedule: SpeedSchedule,
+}
+
+/// Limits by time of day: a weekly grid of hours, each naming a profile.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SpeedSchedule {
+    /// One row per day, Monday first, one character per hour from midnight: a profile's key,
+    /// or `.` to leave the limits as they are. Short rows are padded with `.`.
+    pub week: Vec<String>,
+    /// Limits to switch to, by one-character key.
+    pub profiles: HashMap<String, SpeedProfile>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SpeedProfile {
+    /// Shown when the profile takes over; defaults to its key.
+    pub name: Option<String>,
+    /// Limit for downloading torrents, in KiB/s; unset is unlimited.
+    pub download_kib: Option<u64>,
+    /// Limit for seeding (all uploads), in KiB/s; unset is unlimited.
+    pub upload_kib: Option<u64>,
+}
+
+impl Default for SpeedConfig {
+    fn default() -> Self {
+        Self {
+            download_limit_kib: None,
+            upload_limit_kib: None,
+            step_kib: 256,
+            schedule: SpeedSchedule::default(),
+        }
+    }
+}
+
+/// HTTP health checks served in `--daemon` mode, for orchestrators and uptime monitors.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Address for `/healthz` and `/readyz`, e.g. `0.0.0.0:8080`; off when unset.
+    pub listen: Option<String>,
+    /// `/readyz` fails while the download directory has less free space than this, in MiB.
+    pub min_free_mib: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            listen: None,
+            min_free_mib: 1024,
+        }
+    }
+}
+
+/// HTTP remote control: list, add, pause, resume and delete torrents. Every request needs
+/// the `[auth]` token.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Address such as `127.0.0.1:3031`; off when unset.
+    pub listen: Option<String>,
+}
+
+/// HTTP server for playing torrent files on other devices, started the first time a stream
+/// URL is copied.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StreamConfig {
+    pub listen: String,
+    /// Host name or address put in copied URLs; defaults to this machine's LAN address.
+    pub host: Option<String>,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            listen: "0.0.0.0:3030".to_string(),
+            host: None,
+        }
+    }
+}
+
+/// Casting files from the Files view to DLNA renderers and Chromecasts on the LAN.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CastConfig {
+    /// Off by default: discovery sends multicast queries to the whole network.
+    pub enabled: bool,
+    /// How long to wait for devices to answer.
+    pub discover_secs: u64,
+    /// Connecting to a device and having it start playback.
+    pub timeout_secs: u64,
+}
+
+impl Default for CastConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            discover_secs: 3,
+            timeout_secs: 15,
+        }
+    }
+}
+
+/// What the file picker's smart select (`s`) keeps. Extensions are matched without the dot
+/// and words against the parts of a file's path, both case-insensitively.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SmartSelectConfig {
+    pub media_extensions: Vec<String>,
+    pub subtitle_extensions: Vec<String>,
+    /// Subtitles in folders with these names are kept even when named differently from the
+    /// media.
+    pub subtitle_folders: Vec<String>,
+    /// Media whose path contains one of these words is skipped.
+    pub skip_words: Vec<String>,
+}
+
+impl Default for SmartSelectConfig {
+    fn default() -> Self {
+        let list = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        Self {
+            media_extensions: list(&[
+                "mkv", "mp4", "m4v", "avi", "mov", "wmv", "webm", "ts", "m2ts", "mpg", "flac",
+                "mp3", "m4a", "m4b", "ogg", "opus", "wav",
+            ]),
+            subtitle_extensions: list(&["srt", "ass", "ssa", "sub", "idx", "vtt", "sup"]),
+            subtitle_folders: list(&["subs", "subtitles"]),
+            skip_words: list(&["sample", "proof", "trailer"]),
+        }
+    }
+}
+
+/// File types flagged in the file picker as possible malware, matched case-insensitively
+/// without the dot. An empty list turns the warning off.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RiskyFilesConfig {
+    pub extensions: Vec<String>,
+}
+
+impl Default for RiskyFilesConfig {
+    fn default() -> Self {
+        Self {
+            extensions: [
+                "exe", "scr", "bat", "cmd", "com", "msi", "pif", "lnk", "js", "jse", "vbs",
+                "vbe", "wsf", "ps1", "hta", "jar",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+}
+
+/// Token required by remote control surfaces, sent as `Authorization: Bearer <token>`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Shared token; when unset, one is generated on first use and kept as `auth_token` in
+    /// the data dir.
+    pub token: Option<String>,
+    /// Require the token on `/healthz` and `/readyz`. Off by default so orchestrator probes
+    /// work without it.
+    pub health: bool,
+    /// Require the token on stream URLs, which then carry it as `?token=`.
+    pub stream: bool,
+}
+
+/// Serves network control surfaces over HTTPS when both files are set.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM certificate chain.
+    pub cert: Option<PathBuf>,
+    /// PEM private key in PKCS#8 form (`BEGIN PRIVATE KEY`).
+    pub key: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DiskConfig {
+    /// Cap on disk writes across all torrents, in MiB/s, independent of network limits.
+    pub write_limit_mib: Option<f64>,
+    /// Refuse adds into folders on a network filesystem (NFS, SMB, ...) instead of warning.
+    pub refuse_network: bool,
+    /// Fsync the files a piece touches once it's verified, for adds on network
+    /// filesystems.
+    pub fsync_on_network: bool,
+}
+
+impl DiskConfig {
+    /// Write cap in bytes per second; 0 when unlimited.
+    pub fn write_limit_bytes(&self) -> u64 {
+        self.write_limit_mib
+            .map_or(0, |mib| (mib * 1024.0 * 1024.0) as u64)
+    }
+}
+
+/// Pauses every running torrent while the machine is busy, resuming once it calms down.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoadConfig {
+    /// Pause when the one-minute load average per core exceeds this, e.g. `1.5`.
+    pub pause_above_load: Option<f64>,
+    /// Pause when tasks were stalled on IO for more than this percentage of the last
+    /// 10 seconds (Linux pressure stall information).
+    pub pause_above_io_pressure: Option<f64>,
+    /// How long the load must stay below the thresholds before resuming.
+    pub resume_after_secs: u64,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            pause_above_load: None,
+            pause_above_io_pressure: None,
+            resume_after_secs: 60,
+        }
+    }
+}
+
+impl LoadConfig {
+    pub fn enabled(&self) -> bool {
+        self.pause_above_load.is_some() || self.pause_above_io_pressure.is_some()
+    }
+}
+
+/// Adjustments applied to a torrent's files once it finishes downloading.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CompletionConfig {
+    /// Mode for files, e.g. `0o664`.
+    pub file_mode: Option<u32>,
+    /// Mode for directories, e.g. `0o775`.
+    pub dir_mode: Option<u32>,
+    /// Numeric owner uid.
+    pub owner: Option<u32>,
+    /// Numeric group gid.
+    pub group: Option<u32>,
+    /// Directory that completed payloads are hard-linked into, leaving the seeding copy
+    /// in place. Must be on the same filesystem as the download directory.
+    pub library_dir: Option<PathBuf>,
+    /// Where finished torrents are moved, as a save-path template; relative ones are under
+    /// the download directory. Must be on the same filesystem.
+    pub move_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HookRule {
+    pub on: Vec<HookEvent>,
+    /// Run with `sh -c`, with the event in `IB_*` environment variables.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Also mail the event through `[email]`.
+    #[serde(default)]
+    pub email: bool,
+    /// Chat services under `[chat]` to post the event to.
+    #[serde(default)]
+    pub chat: Vec<ChatService>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRule {
+    /// Shown in the top bar; defaults to the condition.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub when: AlertCondition,
+}
+
+impl AlertRule {
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.when.text)
+    }
+}
+
+/// `<metric> <op> <number>`, such as `errors > 0`, `free_gb < 20` or
+/// `ratio(tracker.example.org) < 0.5`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct AlertCondition {
+    pub text: String,
+    pub metric: AlertMetric,
+    pub op: AlertOp,
+    pub threshold: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertMetric {
+    /// Torrents under one of the sidebar filters: `torrents`, `downloading`, `seeding`,
+    /// `paused`, `stopped`, `errors` or `idle`.
+    Count(&'static str),
+    /// Free space where new downloads go, in GiB.
+    FreeGb,
+    /// Uploaded over downloaded, for every torrent or those announcing to a tracker host.
+    Ratio(Option<String>),
+    /// Session rates in MiB/s.
+    DownMib,
+    UpMib,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl AlertOp {
+    pub fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertOp::Lt => value < threshold,
+            AlertOp::Le => value <= threshold,
+            AlertOp::Gt => value > threshold,
+            AlertOp::Ge => value >= threshold,
+            AlertOp::Eq => value == threshold,
+            AlertOp::Ne => value != threshold,
+        }
+    }
+}
+
+const ALERT_COUNTS: [&str; 7] =
+    ["torrents", "downloading", "seeding", "paused", "stopped", "errors", "idle"];
+
+impl TryFrom<String> for AlertCondition {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        let text = text.trim().to_string();
+        // Two-character operators first, so `<=` isn't read as `<`.
+        let ops = [
+            ("<=", AlertOp::Le),
+            (">=", AlertOp::Ge),
+            ("==", AlertOp::Eq),
+            ("!=", AlertOp::Ne),
+            ("<", AlertOp::Lt),
+            (">", AlertOp::Gt),
+        ];
+        let Some((left, op, right)) = ops
+            .iter()
+            .find_map(|(token, op)| text.split_once(token).map(|(l, r)| (l, *op, r)))
+        else {
+            return Err(format!("alert `{text}` has no comparison (<, <=, >, >=, ==, !=)"));
+        };
+        let threshold: f64 = right
+            .trim()
+            .parse()
+            .map_err(|_| format!("alert `{text}`: `{}` is not a number", right.trim()))?;
+        let left = left.trim().to_ascii_lowercase();
+        let metric = if let Some(name) = ALERT_COUNTS.iter().find(|&&name| name == left) {
+            AlertMetric::Count(name)
+        } else if let Some(rest) = left.strip_prefix("ratio") {
+            let rest = rest.trim();
+            if rest.is_empty() {
+                AlertMetric::Ratio(None)
+            } else {
+                let host = rest
+                    .strip_prefix('(')
+                    .and_then(|r| r.strip_suffix(')'))
+                    .map(str::trim)
+                    .filter(|h| !h.is_empty())
+                    .ok_or_else(|| format!("alert `{text}`: write ratio(<tracker host>)"))?;
+                AlertMetric::Ratio(Some(host.to_string()))
+            }
+        } else {
+            match left.as_str() {
+                "free_gb" => AlertMetric::FreeGb,
+                "down_mib" => AlertMetric::DownMib,
+                "up_mib" => AlertMetric::UpMib,
+                _ => {
+                    return Err(format!(
+                        "alert `{text}`: unknown metric `{left}` (one of {}, free_gb, \
+                         ratio, ratio(<tracker>), down_mib, up_mib)",
+                        ALERT_COUNTS.join(", ")
+                    ));
+                }
+            }
+        };
+        Ok(Self {
+            text,
+            metric,
+            op,
+            threshold,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatService {
+    Discord,
+    Telegram,
+    Matrix,
+}
+
+/// Where `[[hooks]]` with `chat = [...]` post to.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ChatConfig {
+    pub discord: Option<DiscordConfig>,
+    pub telegram: Option<TelegramConfig>,
+    pub matrix: Option<MatrixConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordConfig {
+    /// A channel webhook, `https://discord.com/api/webhooks/...`.
+    pub webhook_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MatrixConfig {
+    /// e.g. `https://matrix.org`.
+    pub homeserver: String,
+    pub access_token: String,
+    /// Room id (`!abc:example.org`) the account has joined.
+    pub room_id: String,
+}
+
+/// SMTP server that `[[hooks]]` with `email = true` send through.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub host: Option<String>,
+    /// Defaults to 465 for `tls = "implicit"`, 25 for `"none"` and 587 otherwise.
+    pub port: Option<u16>,
+    pub tls: SmtpTls,
+    /// Log in with AUTH PLAIN when set.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sender address; defaults to `username`.
+    pub from: Option<String>,
+    pub to: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Upgrade the connection with STARTTLS, refusing servers that don't offer it.
+    #[default]
+    Starttls,
+    /// TLS from the start (SMTPS).
+    Implicit,
+    /// Plain text, for a relay on localhost.
+    None,
+}
+
+/// Session events a hook can be run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    Added,
+    #[serde(rename = "progress_25")]
+    Progress25,
+    #[serde(rename = "progress_50")]
+    Progress50,
+    #[serde(rename = "progress_75")]
+    Progress75,
+    #[serde(alias = "progress_100")]
+    Completed,
+    /// A torrent connected to its first peer this session.
+    FirstPeer,
+    Error,
+    /// An `[[alerts]]` condition started to hold.
+    Alert,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LabelConfig {
+    /// Maximum total on-disk size of torrents with this label, in GiB.
+    pub budget_gb: Option<u64>,
+    pub budget_policy: BudgetPolicy,
+    /// Overrides `[seeding] ratio_goal` for torrents with this label.
+    pub ratio_goal: Option<f64>,
+    /// Override the global `save_path` and `[completion] move_to` for this label.
+    pub save_path: Option<String>,
+    pub move_to: Option<String>,
+}
+
+/// What to do when an add or relabel would push a label over its budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPolicy {
+    /// Refuse the add.
+    #[default]
+    Block,
+    /// Delete the oldest completed torrents (and their files) under the label until it fits.
+    RemoveOldest,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MountConfig {
+    pub on_return: ReturnPolicy,
+}
+
+/// What to do with a torrent whose missing download folder shows up again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReturnPolicy {
+    /// Leave it as it is.
+    #[default]
+    Ignore,
+    /// Recheck the data and leave the torrent paused.
+    Recheck,
+    /// Recheck the data and resume.
+    Resume,
+}
+
+impl CompletionConfig {
+    pub fn adjusts_permissions(&self) -> bool {
+        self.file_mode.is_some()
+            || self.dir_mode.is_some()
+            || self.owner.is_some()
+            || self.group.is_some()
+    }
+}
+
+impl Config {
+    pub fn checks_windows_paths(&self) -> bool {
+        self.windows_safe_paths.unwrap_or(cfg!(windows))
+    }
+
+    /// The save-path template for a torrent with `label`: the label's own, else the global one.
+    pub fn save_path_template(&self, label: Option<&str>) -> Option<&str> {
+        label
+            .and_then(|label| self.labels.get(label))
+            .and_then(|cfg| cfg.save_path.as_deref())
+            .or(self.save_path.as_deref())
+    }
+
+    pub fn move_to_template(&self, label: Option<&str>) -> Option<&str> {
+        label
+            .and_then(|label| self.labels.get(label))
+            .and_then(|cfg| cfg.move_to.as_deref())
+            .or(self.completion.move_to.as_deref())
+    }
+
+    /// What to do when `folder` comes back, from the longest matching `mounts` prefix.
+    pub fn return_policy(&self, folder: &Path) -> ReturnPolicy {
+        self.mounts
+            .iter()
+            .filter(|(prefix, _)| folder.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, mount)| mount.on_return)
+            .unwrap_or_default()
+    }
+
+    /// Reads `config.toml`, then applies `ITTYBITTY_*` overrides on top.
+    pub fn load() -> Result<Self> {
+        let mut table = toml::Table::new();
+        if let Some(path) = config_path().filter(|p| p.exists()) {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read config {}", path.display()))?;
+            table = toml::from_str(&text)
+                .with_context(|| format!("invalid config {}", path.display()))?;
+        }
+        // vars() would panic on an unrelated variable that isn't UTF-8.
+        let mut vars: Vec<(String, String)> = std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .filter(|(name, _)| {
+                name.starts_with(ENV_PREFIX)
+                    && ![CONFIG_DIR_VAR, DATA_DIR_VAR, SESSION_DIR_VAR].contains(&name.as_str())
+            })
+            .collect();
+        vars.sort();
+        for (name, raw) in &vars {
+            apply_env_override(&mut table, name, raw)?;
+        }
+        toml::Value::Table(table)
+            .try_into()
+            .context("invalid config after applying ITTYBITTY_* overrides")
+    }
+}
+
+/// Sets the key named by `ITTYBITTY_SECTION__KEY` (double underscores separate table
+/// levels) to `raw`, read as a TOML value when it parses as one and as a string otherwise.
+fn apply_env_override(table: &mut toml::Table, name: &str, raw: &str) -> Result<()> {
+    let path: Vec<&str> = name[ENV_PREFIX.len()..].split("__").collect();
+    if path.iter().any(|part| part.is_empty()) {
+        bail!("malformed config override {name}");
+    }
+    let value = toml::from_str::<toml::Table>(&format!("value = {raw}"))
+        .ok()
+        .and_then(|mut parsed| parsed.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()));
+    let (key, parents) = path.split_last().context("empty config override")?;
+    let mut current = table;
+    for part in parents {
+        let entry = current
+            .entry(table_key(current, part))
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        let Some(next) = entry.as_table_mut() else {
+            bail!("{name}: `{part}` is not a table");
+        };
+        current = next;
+    }
+    current.insert(table_key(current, key), value);
+    Ok(())
+}
+
+/// The existing key that `part` names regardless of case, e.g. a mixed-case
+/// `[labels.TV]`, otherwise `part` lowercased.
+fn table_key(table: &toml::Table, part: &str) -> String {
+    table
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case(part))
+        .cloned()
+        .unwrap_or_else(|| part.to_lowercase())
+}
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "ittybitty")
+}
+
+fn dir_from_env(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dir_from_env(CONFIG_DIR_VAR)
+        .or_else(|| project_dirs().map(|dirs| dirs.config_dir().to_path_buf()))
+        .map(|dir| dir.join("config.toml"))
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    dir_from_env(DATA_DIR_VAR).or_else(|| project_dirs().map(|dirs| dirs.data_dir().to_path_buf()))
+}
+
+/// Where librqbit keeps `session.json`, `.torrent` and fastresume files.
+pub fn session_dir() -> Result<PathBuf> {
+    match dir_from_env(SESSION_DIR_VAR) {
+        Some(dir) => Ok(dir),
+        None => SessionPersistenceConfig::default_json_persistence_folder(),
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use tokio::select;
+
+use crate::{
+    app::{App, redact},
+    auth,
+    health::{self, HealthPaths, Heartbeat},
+    tls,
+};
+
+/// Runs the session without the terminal UI until SIGINT or SIGTERM. With `json_events`,
+/// session events go to stdout as one JSON object per line.
+pub async fn run(mut app: App, json_events: bool) -> Result<()> {
+    if json_events {
+        app.bus.enable();
+    }
+    let heartbeat = Arc::new(Heartbeat::default());
+    if let Some(listen) = app.config.health.listen.clone() {
+        // The simulated backend never writes its data or session directories.
+        let persistence = if app.backend.is_simulated() {
+            Vec::new()
+        } else {
+            crate::config::data_dir()
+                .into_iter()
+                .chain(crate::config::session_dir().ok())
+                .collect()
+        };
+        let token = if app.config.auth.health {
+            let (token, generated) = auth::load_or_create_token(&app.config.auth)?;
+            if generated && let Some(path) = auth::token_path() {
+                eprintln!("generated an auth token in {}", path.display());
+            }
+            Some(token)
+        } else {
+            None
+        };
+        let paths = HealthPaths {
+            download_dir: app.download_dir.clone(),
+            persistence,
+            min_free_bytes: app.config.health.min_free_mib * 1024 * 1024,
+            token,
+        };
+        let tls = tls::acceptor(&app.config.tls)?;
+        let scheme = if tls.is_some() { "https" } else { "http" };
+        health::serve(&listen, heartbeat.clone(), paths, tls).await?;
+        if !json_events {
+            eprintln!("health checks on {scheme}://{listen}/healthz and /readyz");
+        }
+    }
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    let mut tick_interval = app.tick_interval();
+    let mut tick = tokio::time::interval(tick_interval);
+    loop {
+        select! {
+            _ = tick.tick() => {
+                if let Err(err) = app.tick().await {
+                    if !json_events {
+                        eprintln!("error: {}", redact(&format!("{err:#}")));
+                    }
+                    app.set_error(format!("{err:#}"));
+                }
+                heartbeat.beat(!app.startup_pending);
+            }
+            result = &mut shutdown => {
+                result?;
+                break;
+            }
+        }
+        write_events(&mut app)?;
+        if app.tick_interval() != tick_interval {
+            tick_interval = app.tick_interval();
+            tick = tokio::time::interval(tick_interval);
+        }
+    }
+    app.forget_previews().await;
+    write_events(&mut app)
+}
+
+fn write_events(app: &mut App) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    for event in app.bus.drain() {
+        serde_json::to_writer(&mut stdout, &event)?;
+        stdout.write_all(b"\n").context("failed to write event")?;
+    }
+    stdout.flush().context("failed to write event")
+}
+
+async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut term = signal(SignalKind::terminate()).context("failed to watch SIGTERM")?;
+        select! {
+            result = tokio::signal::ctrl_c() => result.context("failed to watch SIGINT"),
+            _ = term.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c()
+            .await
+            .context("failed to watch Ctrl+C")
+    }
+}
@@ -1,18 +1,50 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyCode, KeyEventKind};
 use tokio::sync::mpsc;
 
-pub fn start_event_thread() -> mpsc::UnboundedReceiver<Event> {
+use crate::{app::EffectCancel, replay::Recorder};
+
+/// Reads terminal events on their own thread. Esc is acted on here when an effect is
+/// running, since the main loop only takes the next event once the effect is done.
+///
+/// A `--replay` script is sent first, each event at its recorded time, and the terminal
+/// is read after it. With `--record`, every event passed on is written to the recorder.
+pub fn start_event_thread(
+    cancel: EffectCancel,
+    script: Vec<(Duration, Event)>,
+    mut recorder: Option<Recorder>,
+) -> mpsc::UnboundedReceiver<Event> {
     let (tx, rx) = mpsc::unbounded_channel();
     std::thread::spawn(move || {
+        let mut send = |ev: Event| {
+            if let Some(rec) = recorder.as_mut()
+                && rec.record(&ev).is_err()
+            {
+                // Nowhere to report it from this thread; the script stops where it is.
+                recorder = None;
+            }
+            if let Event::Key(key) = &ev
+                && key.code == KeyCode::Esc
+                && key.kind == KeyEventKind::Press
+                && cancel.cancel()
+            {
+                return;
+            }
+            let _ = tx.send(ev);
+        };
+        let started = Instant::now();
+        for (at, ev) in script {
+            std::thread::sleep(at.saturating_sub(started.elapsed()));
+            send(ev);
+        }
         loop {
             if let Ok(ready) = crossterm::event::poll(Duration::from_millis(200)) {
                 if !ready {
                     continue;
                 }
                 if let Ok(ev) = crossterm::event::read() {
-                    let _ = tx.send(ev);
+                    send(ev);
                 }
             }
         }
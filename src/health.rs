@@ -0,0 +1,199 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+};
+use tokio_native_tls::TlsAcceptor;
+
+use crate::{app::available_space, auth};
+
+/// `/healthz` fails once the daemon loop hasn't ticked for this long.
+const LIVENESS_TIMEOUT: Duration = Duration::from_secs(30);
+/// Requests that don't finish sending their headers in time are dropped.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// What the daemon loop reports to the health endpoint.
+#[derive(Debug, Default)]
+pub struct Heartbeat {
+    /// Milliseconds since the epoch of the last finished tick.
+    last_tick_ms: AtomicU64,
+    /// Whether the startup policy has run, which waits for the initial data checks.
+    settled: AtomicBool,
+}
+
+impl Heartbeat {
+    pub fn beat(&self, settled: bool) {
+        self.last_tick_ms.store(now_ms(), Ordering::Relaxed);
+        self.settled.store(settled, Ordering::Relaxed);
+    }
+
+    fn alive(&self) -> bool {
+        let last = self.last_tick_ms.load(Ordering::Relaxed);
+        last != 0 && now_ms().saturating_sub(last) <= LIVENESS_TIMEOUT.as_millis() as u64
+    }
+}
+
+/// Directories the readiness check probes, and the token requests must carry.
+#[derive(Debug, Clone)]
+pub struct HealthPaths {
+    pub download_dir: PathBuf,
+    /// Local data and session persistence directories, which must stay writable.
+    pub persistence: Vec<PathBuf>,
+    pub min_free_bytes: u64,
+    /// Set when `[auth] health` is on.
+    pub token: Option<String>,
+}
+
+/// Serves `/healthz` (the daemon loop is ticking) and `/readyz` (also settled, persistence
+/// writable and enough free disk space) on `listen` until the process exits, over HTTPS
+/// when given an acceptor.
+pub async fn serve(
+    listen: &str,
+    heartbeat: Arc<Heartbeat>,
+    paths: HealthPaths,
+    tls: Option<TlsAcceptor>,
+) -> Result<()> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("failed to listen on {listen}"))?;
+    let paths = Arc::new(paths);
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let heartbeat = heartbeat.clone();
+            let paths = paths.clone();
+            let tls = tls.clone();
+            tokio::spawn(async move {
+                let _ = tokio::time::timeout(REQUEST_TIMEOUT, async {
+                    match tls {
+                        Some(tls) => handle(tls.accept(stream).await?, &heartbeat, &paths).await,
+                        None => handle(stream, &heartbeat, &paths).await,
+                    }
+                })
+                .await;
+            });
+        }
+    });
+    Ok(())
+}
+
+async fn handle<S>(mut stream: S, heartbeat: &Heartbeat, paths: &HealthPaths) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+    let line = String::from_utf8_lossy(&request);
+    let mut parts = line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    let denied = paths
+        .token
+        .as_deref()
+        .is_some_and(|token| !auth::authorized(&line, token));
+    let (status, body) = match (method, path) {
+        _ if denied => ("401 Unauthorized", json!({ "error": "missing or wrong token" })),
+        ("GET" | "HEAD", "/healthz") => liveness(heartbeat),
+        ("GET" | "HEAD", "/readyz") => readiness(heartbeat, paths),
+        ("GET" | "HEAD", _) => ("404 Not Found", json!({ "error": "not found" })),
+        _ => ("405 Method Not Allowed", json!({ "error": "method not allowed" })),
+    };
+    let body = format!("{body}\n");
+    let challenge = if denied {
+        "WWW-Authenticate: Bearer\r\n"
+    } else {
+        ""
+    };
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         {challenge}Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    if method != "HEAD" {
+        response.push_str(&body);
+    }
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn liveness(heartbeat: &Heartbeat) -> (&'static str, serde_json::Value) {
+    let alive = heartbeat.alive();
+    (
+        status_line(alive),
+        json!({ "status": ok_label(alive), "session": ok_label(alive) }),
+    )
+}
+
+fn readiness(heartbeat: &Heartbeat, paths: &HealthPaths) -> (&'static str, serde_json::Value) {
+    let alive = heartbeat.alive();
+    let settled = heartbeat.settled.load(Ordering::Relaxed);
+    let unwritable: Vec<String> = paths
+        .persistence
+        .iter()
+        .filter(|dir| !writable(dir))
+        .map(|dir| dir.display().to_string())
+        .collect();
+    let free = available_space(&paths.download_dir);
+    let disk_ok = free.is_some_and(|free| free >= paths.min_free_bytes);
+    let ready = alive && settled && unwritable.is_empty() && disk_ok;
+    (
+        status_line(ready),
+        json!({
+            "status": ok_label(ready),
+            "session": ok_label(alive),
+            "settled": settled,
+            "persistence": {
+                "status": ok_label(unwritable.is_empty()),
+                "unwritable": unwritable,
+            },
+            "disk": {
+                "status": ok_label(disk_ok),
+                "free_bytes": free,
+                "min_free_bytes": paths.min_free_bytes,
+            },
+        }),
+    )
+}
+
+/// Creates and removes a scratch file, creating the directory first like the stores do.
+fn writable(dir: &std::path::Path) -> bool {
+    let probe = dir.join(format!(".health-{}.tmp", std::process::id()));
+    let ok = std::fs::create_dir_all(dir).is_ok() && std::fs::write(&probe, b"ok").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    ok
+}
+
+fn status_line(ok: bool) -> &'static str {
+    if ok { "200 OK" } else { "503 Service Unavailable" }
+}
+
+fn ok_label(ok: bool) -> &'static str {
+    if ok { "ok" } else { "failing" }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
@@ -1,4 +1,23 @@
+pub mod announce;
+pub mod api;
 pub mod app;
+pub mod auth;
+pub mod backend;
+pub mod backup;
+pub mod cast;
+pub mod chat;
+pub mod cli;
+pub mod config;
+pub mod daemon;
 pub mod ui;
 pub mod events;
+pub mod health;
+pub mod metainfo;
+pub mod migrate;
+pub mod replay;
+pub mod rss;
+pub mod scrape;
+pub mod smtp;
+pub mod stream;
+pub mod tls;
 pub mod tui;
@@ -1,59 +1,268 @@
-use std::{io, path::PathBuf, time::Duration};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
 
 use anyhow::{Context, Result};
 use directories::UserDirs;
-use librqbit::{Api, Session, SessionOptions, SessionPersistenceConfig};
-use ratatui::{Terminal, backend::CrosstermBackend};
+use librqbit::{
+    Api, Session, SessionOptions, SessionPersistenceConfig,
+    storage::{StorageFactoryExt, filesystem::FilesystemStorageFactory},
+};
+use crossterm::event::Event;
+use ratatui::{Terminal, TerminalOptions, Viewport, backend::CrosstermBackend, layout::Rect};
 use tokio::select;
 
-use ittybitty::{app::App, events::start_event_thread, tui};
+use ittybitty::{
+    app::App,
+    backend::{DemoBackend, DiskWrites, ThrottledStorageFactory, TorrentBackend},
+    backup,
+    cli::{Cli, Command, USAGE},
+    config::Config,
+    daemon,
+    events::start_event_thread,
+    metainfo,
+    migrate,
+    replay::{self, Recorder},
+    tui,
+};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let download_dir = default_download_dir();
-    let session = Session::new_with_opts(
-        download_dir.clone(),
-        SessionOptions {
-            fastresume: true,
-            persistence: Some(SessionPersistenceConfig::Json { folder: None }),
-            ..Default::default()
-        },
-    )
-    .await
-    .context("failed to create rqbit session")?;
-    let api = Api::new(session.clone(), None);
-
-    let mut app = App::new(api, download_dir);
+    let cli = Cli::parse(std::env::args().skip(1))?;
+    if cli.help {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    if cli.version {
+        println!("ittybitty {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+    match &cli.command {
+        Some(Command::Backup(path)) => {
+            let summary = backup::backup(path)?;
+            println!(
+                "Backed up {} files ({} bytes) to {}",
+                summary.files,
+                summary.bytes,
+                path.display()
+            );
+            return Ok(());
+        }
+        Some(Command::Restore(path)) => {
+            let summary = backup::restore(path)?;
+            println!(
+                "Restored {} files ({} bytes) from a backup made by ittybitty {}",
+                summary.files, summary.bytes, summary.version
+            );
+            return Ok(());
+        }
+        Some(Command::Inspect(path)) => {
+            for line in metainfo::read(path)?.lines() {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+    // Safe mode ignores config.toml and ITTYBITTY_* overrides, and leaves the data
+    // directory as it is, upgrades included.
+    let config = if cli.safe_mode {
+        Config::default()
+    } else {
+        Config::load()?
+    };
+    let migrated = match ittybitty::config::data_dir() {
+        Some(dir) if !cli.demo && !cli.safe_mode => {
+            migrate::run(&dir).context("failed to upgrade local data")?
+        }
+        _ => None,
+    };
+    let download_dir = config
+        .download_dir
+        .clone()
+        .unwrap_or_else(default_download_dir);
+    let mut disk_writes = None;
+    let backend: Arc<dyn TorrentBackend> = if cli.demo {
+        Arc::new(DemoBackend::new(download_dir.to_string_lossy().into_owned()))
+    } else {
+        let writes = Arc::new(DiskWrites::new(config.disk.write_limit_bytes()));
+        disk_writes = Some(writes.clone());
+        let session_dir = ittybitty::config::session_dir()?;
+        let session_dir = if cli.safe_mode {
+            scratch_session_dir(&session_dir)?
+        } else {
+            session_dir
+        };
+        let session = Session::new_with_opts(
+            download_dir.clone(),
+            SessionOptions {
+                fastresume: true,
+                peer_id: Some(ittybitty::backend::session_peer_id()),
+                persistence: Some(SessionPersistenceConfig::Json {
+                    folder: Some(session_dir),
+                }),
+                default_storage_factory: Some(
+                    ThrottledStorageFactory::new(FilesystemStorageFactory::default(), writes)
+                        .boxed(),
+                ),
+                // Each initial check holds its own read buffers.
+                concurrent_init_limit: config.low_memory.then_some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .context("failed to create rqbit session")?;
+        Arc::new(Api::new(session.clone(), None))
+    };
+
+    let mut app = App::new(backend, download_dir, config);
+    app.disk_writes = disk_writes;
+    if cli.safe_mode {
+        app.enter_safe_mode();
+    }
+    if let Some(report) = migrated {
+        app.activity.push(report.to_string());
+    }
+    if let Some(path) = cli.import.as_deref() {
+        app.start_import(path)?;
+    }
     app.refresh();
+    // Previews the last run didn't get to forget (a crash or kill) were reloaded from
+    // session.json with disk storage.
+    let stale = app.forget_previews().await;
+    if stale > 0 {
+        app.activity
+            .push(format!("Dropped {stale} in-memory previews left from the last run"));
+        app.refresh();
+    }
+    if cli.daemon {
+        return daemon::run(app, cli.json_events).await;
+    }
+    let script = match cli.replay.as_deref() {
+        Some(path) => replay::load(path)?,
+        None => Vec::new(),
+    };
 
-    tui::setup_terminal()?;
+    tui::setup_terminal(app.config.ui.mouse)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let recorder = match cli.record.as_deref() {
+        Some(path) => {
+            let size = terminal.size()?;
+            match Recorder::create(path, (size.width, size.height)) {
+                Ok(recorder) => Some(recorder),
+                Err(err) => {
+                    tui::restore_terminal()?;
+                    return Err(err);
+                }
+            }
+        }
+        None => None,
+    };
 
-    let mut events = start_event_thread();
-    let mut tick = tokio::time::interval(Duration::from_millis(500));
+    let mut events = start_event_thread(app.effect_cancel.clone(), script, recorder);
+    let mut tick_interval = app.tick_interval();
+    let mut tick = tokio::time::interval(tick_interval);
 
     let mut should_quit = false;
+    let mut title = None;
 
     while !should_quit {
+        let started = Instant::now();
         terminal.draw(|frame| ittybitty::ui::draw(frame, &app))?;
+        app.debug.record_draw(started.elapsed());
+        let next_title = ittybitty::ui::terminal_title(&app);
+        if next_title != title {
+            if let Some(text) = &next_title {
+                tui::set_title(text)?;
+            }
+            title = next_title;
+        }
+        app.debug.event_backlog = events.len();
+        if let Some(request) = app.take_export() {
+            match ittybitty::ui::export(&app, &request, terminal.size()?) {
+                Ok(()) => app.exported(&request),
+                Err(err) => app.set_error(err),
+            }
+        }
 
         select! {
             _ = tick.tick() => {
-                app.refresh();
+                match app.tick().await {
+                    Ok(quit) => should_quit = quit,
+                    Err(err) => app.set_error(err),
+                }
             }
             Some(ev) = events.recv() => {
+                // A replay draws at the recorded size rather than the terminal's own.
+                if cli.replay.is_some()
+                    && let Event::Resize(width, height) = ev
+                {
+                    let area = Rect::new(0, 0, width, height);
+                    terminal = Terminal::with_options(
+                        CrosstermBackend::new(io::stdout()),
+                        TerminalOptions {
+                            viewport: Viewport::Fixed(area),
+                        },
+                    )?;
+                    terminal.clear()?;
+                }
                 match app.handle_event(ev).await {
                     Ok(quit) => should_quit = quit,
                     Err(err) => app.set_error(err),
                 }
             }
         }
+
+        if app.tick_interval() != tick_interval {
+            tick_interval = app.tick_interval();
+            tick = tokio::time::interval(tick_interval);
+        }
     }
 
     tui::restore_terminal()?;
     Ok(())
 }
 
+/// A copy of the session directory for safe mode, so the session loads as it is but
+/// librqbit's own writes (session.json, fastresume) never reach the real one. It lives in
+/// the user's data directory, not the shared temp one, and the copy from the last
+/// safe-mode run is replaced.
+fn scratch_session_dir(from: &Path) -> Result<PathBuf> {
+    let scratch = ittybitty::config::data_dir()
+        .context("no data directory for the safe-mode session")?
+        .join("safe-mode-session");
+    match std::fs::remove_dir_all(&scratch) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to clear {}", scratch.display()));
+        }
+    }
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .create(&scratch)
+        .with_context(|| format!("failed to create {}", scratch.display()))?;
+    let entries = match std::fs::read_dir(from) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(scratch),
+        Err(err) => return Err(err).context("failed to read the session directory"),
+    };
+    for entry in entries {
+        let entry = entry.context("failed to read the session directory")?;
+        if entry.file_type()?.is_file() {
+            std::fs::copy(entry.path(), scratch.join(entry.file_name()))
+                .with_context(|| format!("failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(scratch)
+}
+
 fn default_download_dir() -> PathBuf {
     UserDirs::new()
         .and_then(|dirs| dirs.download_dir().map(|p| p.to_path_buf()))
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use librqbit_bencode::ByteBuf;
+use librqbit_core::torrent_metainfo::{TorrentMetaV1, torrent_from_bytes};
+
+/// What a `.torrent` file describes, decoded without adding it to a session.
+#[derive(Debug, Clone)]
+pub struct TorrentInfo {
+    pub name: String,
+    /// Lowercase hex of the v1 info hash.
+    pub info_hash: String,
+    /// Path within the torrent and length; padding files are left out.
+    pub files: Vec<(String, u64)>,
+    pub total_length: u64,
+    pub piece_length: u32,
+    pub pieces: usize,
+    pub trackers: Vec<Vec<String>>,
+    pub private: bool,
+    pub created: Option<DateTime<Local>>,
+    pub created_by: Option<String>,
+    pub comment: Option<String>,
+}
+
+pub fn read(path: &Path) -> Result<TorrentInfo> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    decode(&bytes).with_context(|| format!("{} is not a valid .torrent file", path.display()))
+}
+
+pub fn decode(bytes: &[u8]) -> Result<TorrentInfo> {
+    let meta = torrent_from_bytes::<ByteBuf>(bytes)?;
+    let info = &meta.info;
+    let mut files = Vec::new();
+    for file in info.iter_file_details()? {
+        if file.attrs().padding {
+            continue;
+        }
+        let path = file
+            .filename
+            .iter_components()
+            .map(|part| part.map(str::to_owned))
+            .collect::<Result<Vec<_>>>()
+            .map(|parts| parts.join("/"))
+            .unwrap_or_else(|_| "(name is not UTF-8)".to_string());
+        files.push((path, file.len));
+    }
+    Ok(TorrentInfo {
+        name: info.name.as_ref().map_or_else(|| meta.info_hash.as_string(), text),
+        info_hash: meta.info_hash.as_string(),
+        total_length: files.iter().map(|(_, len)| len).sum(),
+        files,
+        piece_length: info.piece_length,
+        pieces: info.pieces.as_ref().len() / 20,
+        trackers: tracker_tiers(&meta),
+        private: info.private,
+        created: meta
+            .creation_date
+            .and_then(|secs| DateTime::from_timestamp(secs as i64, 0))
+            .map(|at| at.with_timezone(&Local)),
+        created_by: meta.created_by.as_ref().map(text),
+        comment: meta.comment.as_ref().map(text),
+    })
+}
+
+/// The announce-list tiers (BEP 12), or the lone `announce` URL as one tier.
+pub fn tracker_tiers(meta: &TorrentMetaV1<ByteBuf>) -> Vec<Vec<String>> {
+    if meta.announce_list.iter().flatten().next().is_none() {
+        return meta.announce.iter().map(|url| vec![text(url)]).collect();
+    }
+    meta.announce_list
+        .iter()
+        .filter(|tier| !tier.is_empty())
+        .map(|tier| tier.iter().map(text).collect())
+        .collect()
+}
+
+impl TorrentInfo {
+    /// A plain-text description, one line each, as printed by `ittybitty inspect`.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Name:        {}", self.name),
+            format!("Info hash:   {}", self.info_hash),
+            format!("Size:        {} ({} bytes)", size(self.total_length), self.total_length),
+            format!(
+                "Pieces:      {} x {}",
+                self.pieces,
+                size(u64::from(self.piece_length))
+            ),
+            format!("Private:     {}", if self.private { "yes (DHT and PEX off)" } else { "no" }),
+            format!(
+                "Created:     {}",
+                self.created
+                    .map_or("-".to_string(), |at| at.format("%Y-%m-%d %H:%M").to_string())
+            ),
+            format!("Created by:  {}", self.created_by.as_deref().unwrap_or("-")),
+        ];
+        if let Some(comment) = &self.comment {
+            lines.push(format!("Comment:     {comment}"));
+        }
+        lines.push(String::new());
+        if self.trackers.is_empty() {
+            lines.push("Trackers: none".to_string());
+        } else {
+            lines.push("Trackers:".to_string());
+        }
+        for (tier, urls) in self.trackers.iter().enumerate() {
+            for (idx, url) in urls.iter().enumerate() {
+                let label = if idx == 0 { format!("{}", tier + 1) } else { String::new() };
+                lines.push(format!("  {label:>3}  {url}"));
+            }
+        }
+        lines.push(String::new());
+        lines.push(format!("Files ({}):", self.files.len()));
+        for (path, len) in &self.files {
+            lines.push(format!("  {:>10}  {path}", size(*len)));
+        }
+        lines
+    }
+}
+
+fn text(bytes: &ByteBuf) -> String {
+    String::from_utf8_lossy(bytes.as_ref()).into_owned()
+}
+
+fn size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Records which format the data directory is in.
+pub const STAMP_FILE: &str = "format.json";
+/// Where upgrades keep their copies of the directory.
+pub const BACKUP_DIR: &str = "backups";
+
+/// Format of the data directory this build reads and writes.
+pub const CURRENT: u32 = 1;
+
+struct Migration {
+    /// Format the step upgrades to, from `to - 1`.
+    to: u32,
+    what: &'static str,
+    /// None when the files keep their layout and only the stamp changes.
+    run: Option<fn(&Path) -> Result<()>>,
+}
+
+/// One step per format bump, oldest first. Formats only go up; a step that rewrites files
+/// gets a backup of the whole directory first.
+const MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    what: "start recording the data format",
+    run: None,
+}];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Stamp {
+    format: u32,
+    /// ittybitty version that last upgraded the directory.
+    written_by: String,
+}
+
+/// What an upgrade did, for the activity log.
+#[derive(Debug)]
+pub struct Report {
+    pub from: u32,
+    pub to: u32,
+    pub steps: Vec<&'static str>,
+    /// Copy of the directory as it was, when any step rewrote files.
+    pub backup: Option<PathBuf>,
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Data: upgraded from format {} to {} ({})",
+            self.from,
+            self.to,
+            self.steps.join(", ")
+        )?;
+        if let Some(backup) = &self.backup {
+            write!(f, "; the old files are in {}", backup.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Brings the data directory up to [`CURRENT`] before anything loads from it. Returns None
+/// when there was nothing to do. Data from a newer build is refused rather than read and
+/// overwritten.
+pub fn run(dir: &Path) -> Result<Option<Report>> {
+    let stamp_path = dir.join(STAMP_FILE);
+    let from = match std::fs::read_to_string(&stamp_path) {
+        Ok(text) => {
+            serde_json::from_str::<Stamp>(&text)
+                .with_context(|| format!("failed to read {}", stamp_path.display()))?
+                .format
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            if !has_data(dir)? {
+                // A fresh install starts out current.
+                write_stamp(dir, CURRENT)?;
+                return Ok(None);
+            }
+            0
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", stamp_path.display()));
+        }
+    };
+    if from > CURRENT {
+        bail!(
+            "{} holds data in format {from}, written by a newer ittybitty; this one reads \
+             format {CURRENT}. Upgrade ittybitty, or set ITTYBITTY_DATA_DIR to another directory",
+            dir.display()
+        );
+    }
+    if from == CURRENT {
+        return Ok(None);
+    }
+
+    let steps: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.to > from).collect();
+    let backup = if steps.iter().any(|m| m.run.is_some()) {
+        let backup = dir.join(BACKUP_DIR).join(format!(
+            "format-{from}-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        copy_tree(dir, &backup, true)
+            .with_context(|| format!("failed to back up {} before upgrading", dir.display()))?;
+        Some(backup)
+    } else {
+        None
+    };
+    for step in &steps {
+        if let Some(run) = step.run
+            && let Err(err) = run(dir)
+        {
+            // Every step that rewrites files comes after the backup above.
+            let Some(backup) = backup.as_deref() else {
+                return Err(err);
+            };
+            // Put back what the step may have half-written; the stamp still says `from`.
+            let restored = copy_tree(backup, dir, false);
+            let err = err.context(format!(
+                "failed to upgrade data to format {} ({}); the old files are in {}",
+                step.to,
+                step.what,
+                backup.display()
+            ));
+            return Err(match restored {
+                Ok(()) => err,
+                Err(restore) => err.context(format!("restoring them also failed: {restore:#}")),
+            });
+        }
+        write_stamp(dir, step.to)?;
+    }
+    Ok(Some(Report {
+        from,
+        to: CURRENT,
+        steps: steps.iter().map(|m| m.what).collect(),
+        backup,
+    }))
+}
+
+/// Whether anything but earlier backups is in `dir`.
+fn has_data(dir: &Path) -> Result<bool> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err).with_context(|| format!("failed to list {}", dir.display())),
+    };
+    for entry in entries {
+        let entry = entry.with_context(|| format!("failed to list {}", dir.display()))?;
+        if entry.file_name() != BACKUP_DIR {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn write_stamp(dir: &Path, format: u32) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+    let stamp = Stamp {
+        format,
+        written_by: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let path = dir.join(STAMP_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&stamp)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Copies every file under `from` into `to`, leaving out the backups directory when
+/// `skip_backups` is set.
+fn copy_tree(from: &Path, to: &Path, skip_backups: bool) -> Result<()> {
+    std::fs::create_dir_all(to).with_context(|| format!("failed to create {}", to.display()))?;
+    let entries =
+        std::fs::read_dir(from).with_context(|| format!("failed to list {}", from.display()))?;
+    for entry in entries {
+        let entry = entry.with_context(|| format!("failed to list {}", from.display()))?;
+        if skip_backups && entry.file_name() == BACKUP_DIR {
+            continue;
+        }
+        let (source, target) = (entry.path(), to.join(entry.file_name()));
+        if entry.file_type()?.is_dir() {
+            copy_tree(&source, &target, false)?;
+        } else {
+            std::fs::copy(&source, &target).with_context(|| {
+                format!("failed to copy {} to {}", source.display(), target.display())
+            })?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// One line of a `--record`/`--replay` script, e.g. `{"at_ms":1200,"key":"Ctrl+f"}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptEvent {
+    /// Milliseconds since the event thread started.
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub input: ScriptInput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptInput {
+    Key(String),
+    Paste(String),
+    Resize(u16, u16),
+}
+
+impl ScriptEvent {
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.at_ms)
+    }
+
+    pub fn to_event(&self) -> Result<Event> {
+        Ok(match &self.input {
+            ScriptInput::Key(key) => Event::Key(parse_key(key)?),
+            ScriptInput::Paste(text) => Event::Paste(text.clone()),
+            ScriptInput::Resize(width, height) => Event::Resize(*width, *height),
+        })
+    }
+}
+
+/// Reads a script, one JSON event per line. Blank lines and `#` comments are skipped.
+pub fn load(path: &Path) -> Result<Vec<(Duration, Event)>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut events = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let event: ScriptEvent = serde_json::from_str(line)
+            .with_context(|| format!("{}:{}: invalid event", path.display(), idx + 1))?;
+        let parsed = event
+            .to_event()
+            .with_context(|| format!("{}:{}", path.display(), idx + 1))?;
+        events.push((event.delay(), parsed));
+    }
+    Ok(events)
+}
+
+/// Writes the events passed to the app as a script. Every line is flushed, so a crash
+/// still leaves everything up to it.
+pub struct Recorder {
+    out: BufWriter<File>,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Starts the script with the terminal's current size, so a replay draws at it.
+    pub fn create(path: &Path, (width, height): (u16, u16)) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create {}", path.display()))?;
+        let mut recorder = Self {
+            out: BufWriter::new(file),
+            started: Instant::now(),
+        };
+        recorder.record(&Event::Resize(width, height))?;
+        Ok(recorder)
+    }
+
+    /// Mouse and focus events, and key releases, aren't recorded.
+    pub fn record(&mut self, event: &Event) -> Result<()> {
+        let input = match event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => match format_key(key) {
+                Some(key) => ScriptInput::Key(key),
+                None => return Ok(()),
+            },
+            Event::Paste(text) => ScriptInput::Paste(text.clone()),
+            Event::Resize(width, height) => ScriptInput::Resize(*width, *height),
+            _ => return Ok(()),
+        };
+        let event = ScriptEvent {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            input,
+        };
+        serde_json::to_writer(&mut self.out, &event)?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// `Ctrl+`, `Alt+` and `Shift+` prefixes, then a character or a key name such as
+/// `Enter` or `F5`.
+fn format_key(key: &KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BackTab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        _ => return None,
+    };
+    let mut out = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        out.push_str("Ctrl+");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        out.push_str("Alt+");
+    }
+    // A character's case already says whether shift was held.
+    if key.modifiers.contains(KeyModifiers::SHIFT) && !matches!(key.code, KeyCode::Char(_)) {
+        out.push_str("Shift+");
+    }
+    out.push_str(&name);
+    Some(out)
+}
+
+fn parse_key(text: &str) -> Result<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text;
+    loop {
+        if let Some(r) = rest.strip_prefix("Ctrl+").filter(|r| !r.is_empty()) {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("Alt+").filter(|r| !r.is_empty()) {
+            modifiers |= KeyModifiers::ALT;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("Shift+").filter(|r| !r.is_empty()) {
+            modifiers |= KeyModifiers::SHIFT;
+            rest = r;
+        } else {
+            break;
+        }
+    }
+    let code = match rest {
+        "Space" => KeyCode::Char(' '),
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "BackTab" => KeyCode::BackTab,
+        "Backspace" => KeyCode::Backspace,
+        "Delete" => KeyCode::Delete,
+        "Insert" => KeyCode::Insert,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        _ => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match rest.strip_prefix('F').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => bail!("unknown key: {text}"),
+                },
+            }
+        }
+    };
+    if code == KeyCode::BackTab {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    Ok(KeyEvent::new(code, modifiers))
+}
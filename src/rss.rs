@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use quick_xml::{Reader, events::Event};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One entry of an RSS or Atom feed that links to a torrent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedItem {
+    pub title: String,
+    /// A magnet or the URL of a `.torrent` file.
+    pub link: String,
+}
+
+pub async fn fetch(url: &str) -> Result<Vec<FeedItem>> {
+    let request = async {
+        let response = reqwest::Client::new()
+            .get(url)
+            .send()
+            .await
+            .context("request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("HTTP {status}");
+        }
+        let body = response.text().await.context("failed to read response")?;
+        parse(&body)
+    };
+    tokio::time::timeout(TIMEOUT, request)
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", TIMEOUT.as_secs()))?
+}
+
+/// Reads RSS `<item>`s and Atom `<entry>`s. A torrent link is taken from, in order of
+/// preference, a `magnetURI` element (as torrent feeds add), an enclosure, or the link.
+pub fn parse(xml: &str) -> Result<Vec<FeedItem>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut items = Vec::new();
+    let mut entry: Option<Entry> = None;
+    // The element whose text is being read inside an entry.
+    let mut field: Option<Field> = None;
+    loop {
+        let event = reader.read_event().context("invalid feed XML")?;
+        let empty = matches!(event, Event::Empty(_));
+        match event {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = e.local_name();
+                match name.as_ref() {
+                    b"item" | b"entry" => entry = Some(Entry::default()),
+                    _ if entry.is_none() => {}
+                    b"title" => field = Some(Field::Title),
+                    b"magnetURI" => field = Some(Field::Magnet),
+                    b"link" => {
+                        field = Some(Field::Link);
+                        // Atom puts the URL in an attribute, and the torrent in a link of
+                        // its own.
+                        if let Some(href) = e.try_get_attribute("href")?
+                            && let Some(entry) = entry.as_mut()
+                        {
+                            let href = href.unescape_value()?.into_owned();
+                            let rel = e.try_get_attribute("rel")?;
+                            if rel.is_some_and(|rel| rel.value.as_ref() == b"enclosure") {
+                                entry.enclosure = Some(href);
+                            } else {
+                                entry.link.get_or_insert(href);
+                            }
+                        }
+                    }
+                    b"enclosure" => {
+                        if let Some(url) = e.try_get_attribute("url")?
+                            && let Some(entry) = entry.as_mut()
+                        {
+                            entry.enclosure = Some(url.unescape_value()?.into_owned());
+                        }
+                    }
+                    _ => field = None,
+                }
+                if empty {
+                    field = None;
+                }
+            }
+            Event::Text(text) => {
+                if let (Some(field), Some(entry)) = (field, entry.as_mut()) {
+                    entry.set(field, text.unescape()?.into_owned());
+                }
+            }
+            Event::CData(text) => {
+                if let (Some(field), Some(entry)) = (field, entry.as_mut()) {
+                    entry.set(field, text.decode()?.into_owned());
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"item" | b"entry" => {
+                    if let Some(item) = entry.take().and_then(Entry::into_item) {
+                        items.push(item);
+                    }
+                    field = None;
+                }
+                _ => field = None,
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(items)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Title,
+    Link,
+    Magnet,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    title: Option<String>,
+    link: Option<String>,
+    enclosure: Option<String>,
+    magnet: Option<String>,
+}
+
+impl Entry {
+    fn set(&mut self, field: Field, text: String) {
+        let slot = match field {
+            Field::Title => &mut self.title,
+            Field::Link => &mut self.link,
+            Field::Magnet => &mut self.magnet,
+        };
+        slot.get_or_insert_default().push_str(&text);
+    }
+
+    fn into_item(self) -> Option<FeedItem> {
+        let link = self.magnet.or(self.enclosure).or(self.link)?;
+        Some(FeedItem {
+            title: self.title?.trim().to_string(),
+            link: link.trim().to_string(),
+        })
+    }
+}
@@ -0,0 +1,191 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{Context, Result, anyhow, bail};
+use librqbit_bencode::{BencodeValue, ByteBuf};
+use tokio::net::UdpSocket;
+use url::Url;
+
+pub(crate) const TIMEOUT: Duration = Duration::from_secs(10);
+/// BEP 15 magic for the connect request.
+const UDP_PROTOCOL_ID: u64 = 0x417_2710_1980;
+
+/// Swarm counts a tracker reports for one torrent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScrapeStats {
+    pub seeders: u32,
+    pub leechers: u32,
+    /// Downloads the tracker has seen finish.
+    pub completed: u32,
+}
+
+/// Asks a tracker for a torrent's swarm counts without announcing, over HTTP(S) (BEP 48) or
+/// UDP (BEP 15). `info_hash` is the 40-character hex form.
+pub async fn scrape(announce: &str, info_hash: &str) -> Result<ScrapeStats> {
+    let hash = decode_hash(info_hash)?;
+    let url = Url::parse(announce).with_context(|| format!("invalid tracker URL {announce}"))?;
+    let request = async {
+        match url.scheme() {
+            "http" | "https" => scrape_http(&url, &hash).await,
+            "udp" => scrape_udp(&url, &hash).await,
+            scheme => bail!("unsupported tracker scheme {scheme}"),
+        }
+    };
+    tokio::time::timeout(TIMEOUT, request)
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", TIMEOUT.as_secs()))?
+}
+
+async fn scrape_http(announce: &Url, hash: &[u8; 20]) -> Result<ScrapeStats> {
+    // Scrape lives next to announce by convention; trackers that don't follow it can't be
+    // scraped.
+    let mut url = announce.clone();
+    let path = url.path().to_string();
+    let (dir, last) = path.rsplit_once('/').unwrap_or(("", &path));
+    let Some(rest) = last.strip_prefix("announce") else {
+        bail!("tracker has no scrape URL");
+    };
+    url.set_path(&format!("{dir}/scrape{rest}"));
+    let encoded: String = hash.iter().map(|b| format!("%{b:02X}")).collect();
+    let query = match url.query() {
+        Some(query) => format!("{query}&info_hash={encoded}"),
+        None => format!("info_hash={encoded}"),
+    };
+    url.set_query(Some(&query));
+    let response = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .context("request failed")?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("HTTP {status}");
+    }
+    let body = response.bytes().await.context("failed to read response")?;
+    let value: BencodeValue<ByteBuf> =
+        librqbit_bencode::dyn_from_bytes(&body).context("invalid scrape response")?;
+    let BencodeValue::Dict(root) = value else {
+        bail!("invalid scrape response");
+    };
+    if let Some(BencodeValue::Bytes(reason)) = get(&root, "failure reason") {
+        bail!("{}", String::from_utf8_lossy(reason.as_ref()));
+    }
+    let Some(BencodeValue::Dict(files)) = get(&root, "files") else {
+        bail!("invalid scrape response");
+    };
+    let Some(BencodeValue::Dict(entry)) = files.get(&ByteBuf(hash.as_slice())) else {
+        bail!("tracker doesn't know this torrent");
+    };
+    let count = |key| match get(entry, key) {
+        Some(BencodeValue::Integer(n)) => (*n).clamp(0, u32::MAX as i64) as u32,
+        _ => 0,
+    };
+    Ok(ScrapeStats {
+        seeders: count("complete"),
+        leechers: count("incomplete"),
+        completed: count("downloaded"),
+    })
+}
+
+fn get<'a, 'b>(
+    dict: &'b HashMap<ByteBuf<'a>, BencodeValue<ByteBuf<'a>>>,
+    key: &'static str,
+) -> Option<&'b BencodeValue<ByteBuf<'a>>> {
+    dict.get(&ByteBuf(key.as_bytes()))
+}
+
+async fn scrape_udp(announce: &Url, hash: &[u8; 20]) -> Result<ScrapeStats> {
+    let (socket, connection) = udp_connect(announce).await?;
+    let transaction = transaction_id();
+    let mut request = Vec::with_capacity(36);
+    request.extend_from_slice(&connection.to_be_bytes());
+    request.extend_from_slice(&2u32.to_be_bytes());
+    request.extend_from_slice(&transaction.to_be_bytes());
+    request.extend_from_slice(hash);
+    let response = exchange(&socket, &request, transaction, 2).await?;
+    let field = |at: usize| {
+        response
+            .get(at..at + 4)
+            .and_then(|b| b.try_into().ok())
+            .map(u32::from_be_bytes)
+            .context("short scrape response")
+    };
+    Ok(ScrapeStats {
+        seeders: field(0)?,
+        completed: field(4)?,
+        leechers: field(8)?,
+    })
+}
+
+/// Opens a socket to a UDP tracker and gets a connection id for the requests that follow.
+pub(crate) async fn udp_connect(announce: &Url) -> Result<(UdpSocket, u64)> {
+    let host = announce.host_str().context("tracker URL has no host")?;
+    let port = announce.port().context("tracker URL has no port")?;
+    let addr = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+        .await
+        .with_context(|| format!("failed to resolve {host}"))?
+        .next()
+        .with_context(|| format!("{host} has no address"))?;
+    let bind = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    let socket = UdpSocket::bind(bind).await.context("failed to open a UDP socket")?;
+    socket.connect(addr).await.context("failed to reach tracker")?;
+
+    let transaction = transaction_id();
+    let mut request = Vec::with_capacity(16);
+    request.extend_from_slice(&UDP_PROTOCOL_ID.to_be_bytes());
+    request.extend_from_slice(&0u32.to_be_bytes());
+    request.extend_from_slice(&transaction.to_be_bytes());
+    let response = exchange(&socket, &request, transaction, 0).await?;
+    let connection = response
+        .get(..8)
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_be_bytes)
+        .context("short connect response")?;
+    Ok((socket, connection))
+}
+
+/// Sends a request and returns the payload after the action and transaction id of the
+/// matching response.
+pub(crate) async fn exchange(
+    socket: &UdpSocket,
+    request: &[u8],
+    transaction: u32,
+    action: u32,
+) -> Result<Vec<u8>> {
+    socket.send(request).await.context("failed to send to tracker")?;
+    let mut buf = vec![0u8; 2048];
+    loop {
+        let len = socket.recv(&mut buf).await.context("no answer from tracker")?;
+        let packet = &buf[..len];
+        if len < 8 || packet[4..8] != transaction.to_be_bytes() {
+            continue;
+        }
+        let got = u32::from_be_bytes(packet[..4].try_into().expect("4 bytes"));
+        if got == 3 {
+            bail!("{}", String::from_utf8_lossy(&packet[8..]));
+        }
+        if got != action {
+            bail!("unexpected tracker response");
+        }
+        return Ok(packet[8..].to_vec());
+    }
+}
+
+pub(crate) fn transaction_id() -> u32 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    nanos ^ std::process::id().rotate_left(16)
+}
+
+pub(crate) fn decode_hash(hex: &str) -> Result<[u8; 20]> {
+    let mut hash = [0u8; 20];
+    if hex.len() != 40 {
+        bail!("invalid info hash {hex}");
+    }
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| anyhow!("invalid info hash {hex}"))?;
+    }
+    Ok(hash)
+}
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use crate::config::{EmailConfig, SmtpTls};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Stream for S {}
+
+/// Sends a plain-text mail to `[email] to` through the configured server.
+pub async fn send(config: &EmailConfig, subject: &str, body: &str) -> Result<()> {
+    let host = config
+        .host
+        .as_deref()
+        .ok_or_else(|| anyhow!("no SMTP server; set [email] host"))?;
+    if config.to.is_empty() {
+        bail!("no recipient; set [email] to");
+    }
+    let from = config
+        .from
+        .as_deref()
+        .or(config.username.as_deref())
+        .ok_or_else(|| anyhow!("no sender; set [email] from"))?;
+    let port = config.port.unwrap_or(match config.tls {
+        SmtpTls::Implicit => 465,
+        SmtpTls::None => 25,
+        SmtpTls::Starttls => 587,
+    });
+    let message = format_message(from, &config.to, subject, body);
+    let session = async {
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .with_context(|| format!("failed to connect to {host}:{port}"))?;
+        let stream: Box<dyn Stream> = match config.tls {
+            SmtpTls::Implicit => Box::new(tls(host, tcp).await?),
+            _ => Box::new(tcp),
+        };
+        let mut conn = Connection::new(stream);
+        conn.reply(220).await?;
+        let mut features = conn.command("EHLO ittybitty", 250).await?;
+        if config.tls == SmtpTls::Starttls {
+            if !features.iter().any(|f| f.eq_ignore_ascii_case("STARTTLS")) {
+                bail!("{host} doesn't offer STARTTLS; set [email] tls = \"implicit\" or \"none\"");
+            }
+            conn.command("STARTTLS", 220).await?;
+            conn = Connection::new(Box::new(tls(host, conn.into_inner()).await?));
+            features = conn.command("EHLO ittybitty", 250).await?;
+        }
+        if let Some(username) = config.username.as_deref() {
+            let offers_plain = features.iter().any(|f| {
+                let f = f.to_ascii_uppercase();
+                f.starts_with("AUTH") && f.split_whitespace().any(|m| m == "PLAIN")
+            });
+            if !offers_plain {
+                bail!("{host} doesn't accept AUTH PLAIN");
+            }
+            let password = config.password.as_deref().unwrap_or_default();
+            let token = STANDARD.encode(format!("\0{username}\0{password}"));
+            conn.command(&format!("AUTH PLAIN {token}"), 235)
+                .await
+                .context("login failed")?;
+        }
+        conn.command(&format!("MAIL FROM:<{from}>"), 250).await?;
+        for to in &config.to {
+            conn.command(&format!("RCPT TO:<{to}>"), 250)
+                .await
+                .with_context(|| format!("{to} was refused"))?;
+        }
+        conn.command("DATA", 354).await?;
+        conn.write(&message).await?;
+        conn.command(".", 250).await?;
+        let _ = conn.command("QUIT", 221).await;
+        Ok(())
+    };
+    tokio::time::timeout(TIMEOUT, session)
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", TIMEOUT.as_secs()))?
+}
+
+async fn tls<S>(host: &str, stream: S) -> Result<tokio_native_tls::TlsStream<S>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let connector = native_tls::TlsConnector::new().context("failed to set up TLS")?;
+    tokio_native_tls::TlsConnector::from(connector)
+        .connect(host, stream)
+        .await
+        .with_context(|| format!("TLS with {host} failed"))
+}
+
+struct Connection {
+    stream: BufReader<Box<dyn Stream>>,
+}
+
+impl Connection {
+    fn new(stream: Box<dyn Stream>) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    fn into_inner(self) -> Box<dyn Stream> {
+        self.stream.into_inner()
+    }
+
+    async fn write(&mut self, text: &str) -> Result<()> {
+        let stream = self.stream.get_mut();
+        stream.write_all(text.as_bytes()).await.context("failed to send")?;
+        stream.flush().await.context("failed to send")
+    }
+
+    /// Sends one command line and reads the reply, which must have code `expect`.
+    async fn command(&mut self, line: &str, expect: u16) -> Result<Vec<String>> {
+        self.write(&format!("{line}\r\n")).await?;
+        self.reply(expect).await
+    }
+
+    /// Reads a (possibly multi-line) reply and returns the text of each line.
+    async fn reply(&mut self, expect: u16) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await.context("failed to read reply")? == 0 {
+                bail!("server closed the connection");
+            }
+            let line = line.trim_end();
+            let code: u16 = line
+                .get(..3)
+                .and_then(|c| c.parse().ok())
+                .ok_or_else(|| anyhow!("unexpected reply {line:?}"))?;
+            let last = line.as_bytes().get(3) != Some(&b'-');
+            let text = line.get(4..).unwrap_or_default().to_string();
+            if last {
+                if code != expect {
+                    bail!("server said {code} {text}");
+                }
+                lines.push(text);
+                return Ok(lines);
+            }
+            lines.push(text);
+        }
+    }
+}
+
+/// Headers and body with CRLF line ends and dot-stuffing, ready for DATA.
+fn format_message(from: &str, to: &[String], subject: &str, body: &str) -> String {
+    let subject: String = subject.chars().filter(|c| !c.is_control()).collect();
+    let subject = if subject.is_ascii() {
+        subject
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(subject))
+    };
+    let mut message = format!(
+        "From: {from}\r\nTo: {}\r\nSubject: {subject}\r\nDate: {}\r\nMIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        to.join(", "),
+        chrono::Local::now().to_rfc2822()
+    );
+    for line in body.lines() {
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
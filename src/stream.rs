@@ -0,0 +1,227 @@
+use std::{
+    io::SeekFrom,
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+};
+
+use crate::{auth, backend::TorrentBackend};
+
+/// Requests that don't finish sending their headers in time are dropped; the body can take
+/// as long as the player keeps reading.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Serves torrent files over HTTP at librqbit's stream paths,
+/// `/torrents/<id>/stream/<file index>/<file name>`, with range requests so players can
+/// seek. Returns the bound address; the server runs until the process exits.
+pub async fn serve(
+    listen: &str,
+    backend: Arc<dyn TorrentBackend>,
+    token: Option<String>,
+) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("failed to listen on {listen}"))?;
+    let addr = listener.local_addr()?;
+    let token = Arc::new(token);
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let backend = backend.clone();
+            let token = token.clone();
+            tokio::spawn(async move {
+                let _ = handle(stream, backend.as_ref(), token.as_deref()).await;
+            });
+        }
+    });
+    Ok(addr)
+}
+
+/// Stream URL of file `file` of torrent `id`, reachable from other devices on the LAN when
+/// the server listens on all interfaces.
+pub fn url(
+    addr: SocketAddr,
+    host: Option<&str>,
+    id: usize,
+    file: usize,
+    name: &str,
+    token: Option<&str>,
+) -> String {
+    let host = match host {
+        Some(host) => host.to_string(),
+        None if addr.ip().is_unspecified() => host_of(lan_address()),
+        None => host_of(addr.ip()),
+    };
+    let mut url = format!(
+        "http://{host}:{}/torrents/{id}/stream/{file}/{}",
+        addr.port(),
+        percent_encode(name)
+    );
+    if let Some(token) = token {
+        url.push_str(&format!("?token={token}"));
+    }
+    url
+}
+
+/// Whether `listen` only accepts connections from this machine.
+pub fn is_loopback(listen: &str) -> bool {
+    match listen.parse::<SocketAddr>() {
+        Ok(addr) => addr.ip().is_loopback(),
+        Err(_) => listen
+            .rsplit_once(':')
+            .is_some_and(|(host, _)| host.eq_ignore_ascii_case("localhost")),
+    }
+}
+
+/// `ip` as the host part of a URL.
+pub fn host_of(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("[{ip}]"),
+    }
+}
+
+/// The address other machines reach this one on: the source address of the default route,
+/// found without sending anything.
+fn lan_address() -> IpAddr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9))?;
+            socket.local_addr()
+        })
+        .map_or(IpAddr::V4(Ipv4Addr::LOCALHOST), |addr| addr.ip())
+}
+
+async fn handle<S>(mut stream: S, backend: &dyn TorrentBackend, token: Option<&str>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .context("request timed out")??;
+    let mut parts = request.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let denied = token.is_some_and(|token| {
+        !auth::authorized(&request, token) && !auth::authorized_query(query, token)
+    });
+    let file = match path.strip_prefix("/torrents/").map(|rest| rest.split('/')) {
+        Some(mut segments) => match (segments.next(), segments.next(), segments.next()) {
+            (Some(id), Some("stream"), Some(file)) => id.parse().ok().zip(file.parse().ok()),
+            _ => None,
+        },
+        None => None,
+    };
+    let (status, reason) = match (method, file) {
+        _ if denied => ("401 Unauthorized", "missing or wrong token"),
+        ("GET" | "HEAD", Some((id, file))) => match backend.open_stream(id, file) {
+            Ok(file) => return send_file(stream, &request, method == "HEAD", file).await,
+            Err(_) => ("404 Not Found", "no such torrent or file"),
+        },
+        ("GET" | "HEAD", None) => ("404 Not Found", "not found"),
+        _ => ("405 Method Not Allowed", "method not allowed"),
+    };
+    let body = format!("{reason}\n");
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn read_request<S: AsyncRead + Unpin>(stream: &mut S) -> Result<String> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+    Ok(String::from_utf8_lossy(&request).into_owned())
+}
+
+async fn send_file<S>(
+    mut stream: S,
+    request: &str,
+    head: bool,
+    mut file: crate::backend::StreamFile,
+) -> Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let range = request
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("range"))
+        .map(|(_, value)| parse_range(value.trim(), file.len));
+    let mime = file.mime.unwrap_or("application/octet-stream");
+    let (status, start, end, extra) = match range {
+        None => ("200 OK", 0, file.len, String::new()),
+        Some(Some((start, end))) => (
+            "206 Partial Content",
+            start,
+            end,
+            format!("Content-Range: bytes {start}-{}/{}\r\n", end - 1, file.len),
+        ),
+        Some(None) => {
+            let response = format!(
+                "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\n\
+                 Content-Length: 0\r\nConnection: close\r\n\r\n",
+                file.len
+            );
+            stream.write_all(response.as_bytes()).await?;
+            return Ok(());
+        }
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {mime}\r\nContent-Length: {}\r\n\
+         Accept-Ranges: bytes\r\n{extra}Connection: close\r\n\r\n",
+        end - start
+    );
+    stream.write_all(response.as_bytes()).await?;
+    if !head {
+        file.reader.seek(SeekFrom::Start(start)).await?;
+        tokio::io::copy(&mut (&mut file.reader).take(end - start), &mut stream).await?;
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// A single `bytes=start-end`, `bytes=start-` or `bytes=-suffix` range as a half-open span
+/// of a file of `len` bytes; None when it can't be satisfied.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => (len.saturating_sub(suffix.parse().ok()?), len),
+        (start, "") => (start.parse().ok()?, len),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.saturating_add(1)),
+    };
+    let end = end.min(len);
+    (start < end).then_some((start, end))
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,21 @@
+use anyhow::{Context, Result, bail};
+use tokio_native_tls::TlsAcceptor;
+
+use crate::config::TlsConfig;
+
+/// Acceptor for `[tls]`, or None when it isn't configured.
+pub fn acceptor(config: &TlsConfig) -> Result<Option<TlsAcceptor>> {
+    let (cert, key) = match (config.cert.as_ref(), config.key.as_ref()) {
+        (None, None) => return Ok(None),
+        (Some(cert), Some(key)) => (cert, key),
+        _ => bail!("[tls] needs both cert and key"),
+    };
+    let cert_pem = std::fs::read(cert)
+        .with_context(|| format!("failed to read certificate {}", cert.display()))?;
+    let key_pem =
+        std::fs::read(key).with_context(|| format!("failed to read key {}", key.display()))?;
+    let identity = native_tls::Identity::from_pkcs8(&cert_pem, &key_pem)
+        .context("invalid [tls] certificate or key (the key must be PKCS#8 PEM)")?;
+    let acceptor = native_tls::TlsAcceptor::new(identity).context("failed to set up TLS")?;
+    Ok(Some(acceptor.into()))
+}
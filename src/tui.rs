@@ -1,22 +1,62 @@
-use std::io;
+use std::io::{self, Write};
 
 use anyhow::{Context, Result};
+use base64::{Engine, engine::general_purpose::STANDARD};
 use crossterm::{
-    event::{DisableBracketedPaste, EnableBracketedPaste},
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 
-pub fn setup_terminal() -> Result<()> {
+/// `mouse` turns on mouse reporting, which also stops the terminal from selecting text
+/// without Shift.
+pub fn setup_terminal(mouse: bool) -> Result<()> {
     enable_raw_mode().context("failed to enable raw mode")?;
-    execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)
-        .context("failed to enter alt screen")?;
-    Ok(())
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableFocusChange
+    )
+    .context("failed to enter alt screen")?;
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture).context("failed to enable the mouse")?;
+    }
+    // Save the title on the terminal's stack so leaving can put it back.
+    write_escape("\x1b[22;0t")
 }
 
 pub fn restore_terminal() -> Result<()> {
     disable_raw_mode().context("failed to disable raw mode")?;
-    execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen)
-        .context("failed to leave alt screen")?;
-    Ok(())
+    execute!(
+        io::stdout(),
+        DisableMouseCapture,
+        DisableFocusChange,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )
+    .context("failed to leave alt screen")?;
+    write_escape("\x1b[23;0t")
+}
+
+/// Sets the window title (OSC 2), which tmux shows as the pane title.
+pub fn set_title(title: &str) -> Result<()> {
+    let title: String = title.chars().filter(|c| !c.is_control()).collect();
+    write_escape(&format!("\x1b]2;{title}\x07"))
+}
+
+fn write_escape(sequence: &str) -> Result<()> {
+    let mut out = io::stdout();
+    out.write_all(sequence.as_bytes())
+        .and_then(|_| out.flush())
+        .context("failed to write to the terminal")
+}
+
+/// Puts `text` on the clipboard with an OSC 52 escape, which most terminals (and tmux with
+/// `set-clipboard on`) honour, including over SSH.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    write_escape(&format!("\x1b]52;c;{}\x07", STANDARD.encode(text)))
 }
@@ -0,0 +1,127 @@
+use std::{fmt::Write as _, path::Path};
+
+use anyhow::{Context, Result};
+use ratatui::{
+    Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::Size,
+    style::{Color, Modifier},
+};
+
+use crate::app::{App, ExportRequest};
+
+use super::{Theme, draw, draw_table, entry_height};
+
+/// Renders the screen (or the whole torrent list) off-screen and writes it to the requested
+/// file as plain text or ANSI.
+pub fn export(app: &App, request: &ExportRequest, screen: Size) -> Result<()> {
+    let theme = Theme::named(app.theme());
+    let buffer = if request.full_list {
+        let rows: u16 = app.list_entries().iter().map(|e| entry_height(app, e)).sum();
+        render(screen.width, 1 + rows.max(1), |frame| {
+            draw_table(frame, theme, frame.area(), app)
+        })?
+    } else {
+        render(screen.width, screen.height, |frame| draw(frame, app))?
+    };
+    let text = if request.ansi {
+        to_ansi(&buffer)
+    } else {
+        to_text(&buffer)
+    };
+    write(&request.path, &text)
+}
+
+fn render(width: u16, height: u16, f: impl FnOnce(&mut ratatui::Frame)) -> Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    let completed = terminal.draw(f)?;
+    Ok(completed.buffer.clone())
+}
+
+fn write(path: &Path, text: &str) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn to_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut line = String::new();
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(x, y)];
+            if !cell.skip {
+                line.push_str(cell.symbol());
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut style = None;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(x, y)];
+            if cell.skip {
+                continue;
+            }
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                out.push_str("\x1b[0");
+                push_color(&mut out, cell.fg, false);
+                push_color(&mut out, cell.bg, true);
+                if cell.modifier.contains(Modifier::BOLD) {
+                    out.push_str(";1");
+                }
+                if cell.modifier.contains(Modifier::ITALIC) {
+                    out.push_str(";3");
+                }
+                if cell.modifier.contains(Modifier::UNDERLINED) {
+                    out.push_str(";4");
+                }
+                if cell.modifier.contains(Modifier::REVERSED) {
+                    out.push_str(";7");
+                }
+                out.push('m');
+                style = Some(current);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+fn push_color(out: &mut String, color: Color, background: bool) {
+    let base = if background { 40 } else { 30 };
+    let _ = match color {
+        Color::Reset => return,
+        Color::Rgb(r, g, b) => write!(out, ";{};2;{r};{g};{b}", base + 8),
+        Color::Indexed(idx) => write!(out, ";{};5;{idx}", base + 8),
+        Color::Black => write!(out, ";{base}"),
+        Color::Red => write!(out, ";{}", base + 1),
+        Color::Green => write!(out, ";{}", base + 2),
+        Color::Yellow => write!(out, ";{}", base + 3),
+        Color::Blue => write!(out, ";{}", base + 4),
+        Color::Magenta => write!(out, ";{}", base + 5),
+        Color::Cyan => write!(out, ";{}", base + 6),
+        Color::Gray => write!(out, ";{}", base + 7),
+        Color::DarkGray => write!(out, ";{}", base + 60),
+        Color::LightRed => write!(out, ";{}", base + 61),
+        Color::LightGreen => write!(out, ";{}", base + 62),
+        Color::LightYellow => write!(out, ";{}", base + 63),
+        Color::LightBlue => write!(out, ";{}", base + 64),
+        Color::LightMagenta => write!(out, ";{}", base + 65),
+        Color::LightCyan => write!(out, ";{}", base + 66),
+        Color::White => write!(out, ";{}", base + 67),
+    };
+}
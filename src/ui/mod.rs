@@ -1,27 +1,46 @@
+mod export;
+mod screensaver;
+mod theme;
+mod title;
+
+use std::time::Duration;
+
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, Gauge, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, TableState, Wrap,
+    },
+};
+
+use crate::app::{
+    ActivityView, AddStage, App, ArchiveDirection, BusyEffect, COLUMN_NAMES, COLUMNS, CastPicker,
+    ContextMenu, KEEP_LIMITS,
+    DuplicateReport, FilePickerState, FocusPanel, GroupBy, ImportJob, JsonInspector, ListEntry,
+    MagnetPreview, MaintenanceJob, MaintenanceKind, Mode, NotesEditor, PendingRetry,
+    RULE_TESTER_FIELDS, RateLimits, RssOutcome, RuleTester, SCHEDULE_DAYS, SCHEDULE_HOURS,
+    ScheduleEditor, StorageKind, TorrentFileView, TorrentRow, TrackerState, View, format_limit,
+    in_end_game,
 };
+use crate::config::{CollisionStrategy, Density};
+use theme::Theme;
+
+pub use export::export;
+pub use title::terminal_title;
 
-use crate::app::{App, FilePickerState, FocusPanel, Mode, TorrentRow, View};
-
-const COLOR_BG: Color = Color::Rgb(14, 16, 14);
-const COLOR_PANEL: Color = Color::Rgb(20, 22, 20);
-const COLOR_GREEN: Color = Color::Rgb(0, 245, 150);
-const COLOR_BORDER: Color = Color::Rgb(0, 205, 110);
-const COLOR_FOCUS_BG: Color = Color::Rgb(6, 8, 6);
-const COLOR_CYAN: Color = Color::Rgb(0, 255, 255);
-const COLOR_YELLOW: Color = Color::Rgb(255, 255, 0);
-const COLOR_MUTED: Color = Color::Rgb(136, 136, 136);
-const COLOR_BLACK: Color = Color::Rgb(0, 0, 0);
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn draw(frame: &mut Frame, app: &App) {
+    let theme = Theme::named(app.theme());
+    if app.screensaver() {
+        screensaver::draw_screensaver(frame, theme, app);
+        return;
+    }
     let area = frame.area();
-    let bg = Block::default().style(Style::default().bg(COLOR_BG));
+    let bg = Block::default().style(Style::default().bg(theme.bg));
     frame.render_widget(bg, area);
 
     let layout = Layout::default()
@@ -32,43 +51,151 @@ pub fn draw(frame: &mut Frame, app: &App) {
         ])
         .split(area);
 
-    draw_top_bar(frame, layout[0]);
-    draw_main(frame, layout[1], app);
+    draw_top_bar(frame, theme, layout[0], app);
+    draw_main(frame, theme, layout[1], app);
+    let adds_height = if app.adds().is_empty() {
+        0
+    } else {
+        draw_adds(frame, theme, app)
+    };
+    draw_progress_panel(frame, theme, app, adds_height);
+
+    if let Some(inspector) = app.inspector() {
+        draw_inspector(frame, theme, app.density(), inspector);
+    }
+    if let Some(editor) = app.notes_editor() {
+        draw_notes_editor(frame, theme, app.density(), editor);
+    }
 
     match app.mode() {
-        Mode::EnterMagnet | Mode::EnterTorrentDir => draw_input_modal(frame, app),
+        mode if mode.is_text_input() => draw_input_modal(frame, theme, app),
         Mode::FilePicker => {
             if let Some(picker) = app.file_picker() {
-                draw_file_picker(frame, picker);
+                draw_file_picker(frame, theme, app.density(), picker);
+                if let Some(choice) = picker.risky_choice {
+                    draw_confirm_risky(frame, theme, app.density(), picker, choice);
+                }
             }
         }
-        Mode::Normal => {}
+        _ => {}
     }
 
     if app.confirm_delete() {
-        draw_confirm_delete(frame, app);
+        draw_confirm_delete(frame, theme, app);
     }
     if app.confirm_quit() {
-        draw_confirm_quit(frame, app);
+        draw_confirm_quit(frame, theme, app);
+    }
+    if app.confirm_space().is_some() {
+        draw_confirm_space(frame, theme, app);
+    }
+    if app.collision().is_some() {
+        draw_collision(frame, theme, app);
+    }
+    if app.path_review().is_some() {
+        draw_path_review(frame, theme, app);
+    }
+    if let Some(preview) = app.magnet_preview() {
+        draw_magnet_preview(frame, theme, app, preview);
+    }
+    if app.tasks_open() {
+        draw_tasks(frame, theme, app);
+    }
+    if app.talkers_open() {
+        draw_top_talkers(frame, theme, app);
+    }
+    if let Some(report) = app.duplicates() {
+        draw_duplicates(frame, theme, app, report);
+    }
+    if let Some(view) = app.torrent_file() {
+        draw_torrent_file(frame, theme, app, view);
+    }
+    if app.archive_open() {
+        draw_archive(frame, theme, app);
+    }
+    if let Some(tester) = app.rule_tester() {
+        draw_rule_tester(frame, theme, app, tester);
+    }
+    if let Some(editor) = app.schedule_editor() {
+        draw_schedule(frame, theme, app, editor);
+    }
+    if let Some(view) = app.activity_view() {
+        draw_activity(frame, theme, app, view);
+    }
+    if let Some(menu) = app.context_menu() {
+        draw_context_menu(frame, theme, menu);
+    }
+    if let Some(picker) = app.cast_picker() {
+        draw_cast(frame, theme, app.density(), picker);
+    }
+    if let Some(job) = app.import() {
+        draw_import(frame, theme, app, job);
+    }
+    if let Some(kind) = app.confirm_maintenance() {
+        draw_confirm_maintenance(frame, theme, app, kind);
+    }
+    if let Some(job) = app.maintenance() {
+        draw_maintenance(frame, theme, app, job);
+    }
+    if let Some(retry) = app.retry() {
+        draw_retry(frame, theme, app, retry);
+    }
+    if let Some(busy) = app.busy() {
+        draw_busy(frame, theme, app.density(), busy);
     }
 
     if app.show_help() {
-        draw_help_modal(frame, app.help_scroll());
+        draw_help_modal(frame, theme, app.density(), app.help_scroll());
+    }
+
+    if let Some(message) = app.toast() {
+        draw_toast(frame, theme, message);
     }
 
     if let Some(err) = app.last_error() {
-        draw_error_modal(frame, err);
+        draw_error_modal(frame, theme, app.density(), &err);
+    }
+
+    if app.debug().visible {
+        draw_debug_overlay(frame, theme, app);
     }
 
 }
 
-fn draw_top_bar(frame: &mut Frame, area: Rect) {
-    let block = Block::default().style(Style::default().bg(COLOR_GREEN).fg(COLOR_BLACK));
+fn draw_top_bar(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let alerts: Vec<&str> = app.firing_alerts().map(|a| a.name.as_str()).collect();
+    let bg = if !alerts.is_empty() {
+        theme.error
+    } else if app.power_pause().is_some() {
+        theme.highlight
+    } else {
+        theme.accent
+    };
+    let block = Block::default().style(Style::default().bg(bg).fg(theme.on_accent));
     frame.render_widget(block, area);
 
-    let left = Line::from(format!(
-        "IttyBitty - BitTorrent Client v{APP_VERSION}"
-    ));
+    let mut left = format!("IttyBitty - BitTorrent Client v{APP_VERSION}");
+    if app.safe_mode() {
+        left.push_str(" | SAFE MODE");
+    }
+    if app.locked() {
+        left.push_str(" | 🔒 Locked");
+    }
+    if !alerts.is_empty() {
+        left.push_str(&format!(" | ALERT: {}", alerts.join(", ")));
+    }
+    if let Some(reason) = app.power_pause() {
+        left.push_str(&format!(" | Paused: {reason}"));
+    }
+    let limits = app.rate_limits();
+    if limits != RateLimits::default() {
+        left.push_str(&format!(
+            " | Limits: D {} U {}",
+            format_limit(limits.download),
+            format_limit(limits.upload)
+        ));
+    }
+    let left = Line::from(left);
     let right = Line::from("[q: Quit] [?: Help]");
 
     let chunks = Layout::default()
@@ -78,50 +205,122 @@ fn draw_top_bar(frame: &mut Frame, area: Rect) {
 
     frame.render_widget(
         Paragraph::new(left)
-            .style(Style::default().fg(COLOR_BLACK))
+            .style(Style::default().fg(theme.on_accent))
             .alignment(Alignment::Left),
         chunks[0],
     );
     frame.render_widget(
         Paragraph::new(right)
-            .style(Style::default().fg(COLOR_BLACK))
+            .style(Style::default().fg(theme.on_accent))
             .alignment(Alignment::Right),
         chunks[1],
     );
 }
 
-fn draw_main(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_main(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
     let columns = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(30), Constraint::Min(10)])
         .split(area);
-    draw_sidebar(frame, columns[0], app);
-    draw_right_panel(frame, columns[1], app);
+    draw_sidebar(frame, theme, columns[0], app);
+    draw_right_panel(frame, theme, columns[1], app);
 }
 
-fn draw_sidebar(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_sidebar(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
     let block = Block::default()
         .borders(Borders::RIGHT)
-        .border_style(Style::default().fg(COLOR_BORDER))
-        .style(Style::default().bg(COLOR_BG));
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.bg));
     frame.render_widget(&block, area);
 
     let inner = block.inner(area);
     let sections = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(7),
-            Constraint::Length(8),
+            Constraint::Length(stats_panel_height(app)),
+            Constraint::Length(disks_panel_height(app)),
+            Constraint::Length(filters_panel_height(app)),
             Constraint::Min(1),
         ])
         .split(inner);
 
-    draw_stats_panel(frame, sections[0], app, app.focus());
-    draw_filters_panel(frame, sections[1], app);
-    draw_keys_panel(frame, sections[2]);
+    draw_stats_panel(frame, theme, sections[0], app, app.focus());
+    draw_disks_panel(frame, theme, sections[1], app);
+    draw_filters_panel(frame, theme, sections[2], app);
+    draw_keys_panel(frame, theme, sections[3]);
+}
+
+fn disks_panel_height(app: &App) -> u16 {
+    match app.disk_stats().len() {
+        0 => 0,
+        disks => 2 + 4 * disks as u16,
+    }
+}
+
+/// Per filesystem holding torrents: count and payload, free space, and write/read rates.
+fn draw_disks_panel(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    if area.height == 0 {
+        return;
+    }
+    let title_style = Style::default().fg(theme.border);
+    let label = Style::default().fg(theme.accent);
+    let value = Style::default().fg(theme.text);
+    let mut lines = vec![Line::from(Span::styled("+- DISKS -------------+", title_style))];
+    for disk in app.disk_stats() {
+        let mount = disk.mount.to_string_lossy();
+        let chars = mount.chars().count();
+        let mount = if chars > 26 {
+            let tail: String = mount.chars().skip(chars - 25).collect();
+            format!("…{tail}")
+        } else {
+            mount.into_owned()
+        };
+        let torrents = match disk.torrents {
+            1 => "1 torrent".to_string(),
+            n => format!("{n} torrents"),
+        };
+        let free = disk.free.map_or_else(|| "?".to_string(), format_bytes);
+        lines.extend([
+            Line::from(vec![Span::styled("| ", label), Span::styled(mount, label)]),
+            Line::from(vec![
+                Span::styled("|  ", label),
+                Span::styled(format!("{torrents}, {}", format_bytes(disk.payload)), value),
+            ]),
+            Line::from(vec![
+                Span::styled("|  Free: ", label),
+                Span::styled(free, value),
+            ]),
+            Line::from(vec![
+                Span::styled("|  W ", label),
+                Span::styled(
+                    format!("{}/s", format_bytes(disk.write_rate as u64)),
+                    Style::default().fg(theme.info),
+                ),
+                Span::styled("  R ", label),
+                Span::styled(
+                    format!("{}/s", format_bytes(disk.read_rate as u64)),
+                    Style::default().fg(theme.highlight),
+                ),
+            ]),
+        ]);
+    }
+    lines.push(Line::from(Span::styled("+---------------------+", title_style)));
+    let block = Block::default().style(Style::default().bg(theme.bg));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn stats_panel_height(app: &App) -> u16 {
+    let mut height = 8;
+    if app.session_totals().is_some() {
+        height += 4;
+    }
+    if app.disk_write_rate().is_some() {
+        height += 1;
+    }
+    height
 }
 
-fn draw_stats_panel(frame: &mut Frame, area: Rect, app: &App, _focus: FocusPanel) {
+fn draw_stats_panel(frame: &mut Frame, theme: &Theme, area: Rect, app: &App, _focus: FocusPanel) {
     let stats = app.session_stats();
     let down = stats
         .map(|s| format!("{}", s.download_speed))
@@ -131,142 +330,166 @@ fn draw_stats_panel(frame: &mut Frame, area: Rect, app: &App, _focus: FocusPanel
         .unwrap_or_else(|| "-".to_string());
 
     let (active, seeding, total) = counts(app);
-    let title_style = Style::default().fg(COLOR_BORDER);
+    let title_style = Style::default().fg(theme.border);
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(Span::styled("+- STATS -------------+", title_style)),
         Line::from(vec![
-            Span::styled("| Global Down: ", Style::default().fg(COLOR_GREEN)),
-            Span::styled(down, Style::default().fg(COLOR_CYAN)),
+            Span::styled("| Global Down: ", Style::default().fg(theme.accent)),
+            Span::styled(down, Style::default().fg(theme.info)),
         ]),
         Line::from(vec![
-            Span::styled("| Global Up:   ", Style::default().fg(COLOR_GREEN)),
-            Span::styled(up, Style::default().fg(COLOR_YELLOW)),
+            Span::styled("| Global Up:   ", Style::default().fg(theme.accent)),
+            Span::styled(up, Style::default().fg(theme.highlight)),
         ]),
         Line::from(vec![
-            Span::styled("| Active:      ", Style::default().fg(COLOR_GREEN)),
-            Span::styled(active.to_string(), Style::default().fg(Color::White)),
+            Span::styled("| Active:      ", Style::default().fg(theme.accent)),
+            Span::styled(active.to_string(), Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("| Seeding:     ", Style::default().fg(COLOR_GREEN)),
-            Span::styled(seeding.to_string(), Style::default().fg(Color::White)),
+            Span::styled("| Seeding:     ", Style::default().fg(theme.accent)),
+            Span::styled(seeding.to_string(), Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("| Total:       ", Style::default().fg(COLOR_GREEN)),
-            Span::styled(total.to_string(), Style::default().fg(Color::White)),
+            Span::styled("| Total:       ", Style::default().fg(theme.accent)),
+            Span::styled(total.to_string(), Style::default().fg(theme.text)),
+        ]),
+        Line::from(vec![
+            Span::styled("| Waiting:     ", Style::default().fg(theme.accent)),
+            Span::styled(
+                match app.queued_starts() {
+                    0 => app.pending_adds().len().to_string(),
+                    starts => format!("{} (+{starts} starting)", app.pending_adds().len()),
+                },
+                Style::default().fg(theme.text),
+            ),
         ]),
-        Line::from(Span::styled("+---------------------+", title_style)),
     ];
+    if let Some(totals) = app.session_totals() {
+        let ratio = totals.ratio().map_or_else(|| "-".to_string(), |r| format!("{r:.2}"));
+        lines.extend([
+            Line::from(vec![
+                Span::styled("| Uptime:      ", Style::default().fg(theme.accent)),
+                Span::styled(format_duration(totals.uptime), Style::default().fg(theme.text)),
+            ]),
+            Line::from(vec![
+                Span::styled("| Sess. Down:  ", Style::default().fg(theme.accent)),
+                Span::styled(format_bytes(totals.downloaded), Style::default().fg(theme.info)),
+            ]),
+            Line::from(vec![
+                Span::styled("| Sess. Up:    ", Style::default().fg(theme.accent)),
+                Span::styled(format_bytes(totals.uploaded), Style::default().fg(theme.highlight)),
+            ]),
+            Line::from(vec![
+                Span::styled("| Sess. Ratio: ", Style::default().fg(theme.accent)),
+                Span::styled(ratio, Style::default().fg(theme.text)),
+            ]),
+        ]);
+    }
+    if let Some((rate, limit)) = app.disk_write_rate() {
+        let value = match limit {
+            0 => format!("{}/s", format_bytes(rate as u64)),
+            limit => format!("{}/{}/s", format_bytes(rate as u64), format_bytes(limit)),
+        };
+        lines.push(Line::from(vec![
+            Span::styled("| Disk Write:  ", Style::default().fg(theme.accent)),
+            Span::styled(value, Style::default().fg(theme.text)),
+        ]));
+    }
+    lines.push(Line::from(Span::styled("+---------------------+", title_style)));
 
-    let block = Block::default().style(Style::default().bg(COLOR_BG));
+    let block = Block::default().style(Style::default().bg(theme.bg));
     frame.render_widget(Paragraph::new(lines).block(block), area);
 }
 
-fn draw_filters_panel(frame: &mut Frame, area: Rect, app: &App) {
-    let (downloading, seeding, paused, errors, total) = filter_counts(app);
-    let selected = app.selected_filter();
+fn draw_filters_panel(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    use crate::app::FilterKind;
+    let summary = app.filter_summary();
+    let selected = app.filter_index;
     let focus = app.focus();
     let panel_bg = if focus == FocusPanel::Filters {
-        COLOR_FOCUS_BG
+        theme.focus_bg
     } else {
-        COLOR_BG
+        theme.bg
     };
-    let lines = vec![
-        Line::from(Span::styled(
-            "+- FILTERS -----------+",
-            if focus == FocusPanel::Filters {
-                Style::default().fg(COLOR_GREEN)
-            } else {
-                Style::default().fg(COLOR_BORDER)
-            },
-        )),
-        filter_line(
-            focus,
-            selected,
-            crate::app::FilterKind::All,
-            format!("| [1] All Torrents ({total})"),
-        ),
-        filter_line(
-            focus,
-            selected,
-            crate::app::FilterKind::Downloading,
-            format!("| [2] Downloading ({downloading})"),
-        ),
-        filter_line(
-            focus,
-            selected,
-            crate::app::FilterKind::Seeding,
-            format!("| [3] Seeding ({seeding})"),
-        ),
-        filter_line(
-            focus,
-            selected,
-            crate::app::FilterKind::Paused,
-            format!("| [4] Paused ({paused})"),
-        ),
-        filter_line(
-            focus,
-            selected,
-            crate::app::FilterKind::Stopped,
-            "| [5] Stopped (0)".to_string(),
-        ),
-        filter_line(
-            focus,
-            selected,
-            crate::app::FilterKind::Error,
-            format!("| [6] Error ({errors})"),
-        ),
-        Line::from(Span::styled(
-            "+---------------------+",
-            if focus == FocusPanel::Filters {
-                Style::default().fg(COLOR_GREEN)
-            } else {
-                Style::default().fg(COLOR_BORDER)
-            },
-        )),
-    ];
+    let border_style = if focus == FocusPanel::Filters {
+        Style::default().fg(theme.accent)
+    } else {
+        Style::default().fg(theme.border)
+    };
+    let mut lines = vec![Line::from(Span::styled("+- FILTERS -----------+", border_style))];
+    for (idx, kind) in crate::app::FILTERS.iter().enumerate() {
+        let name = match kind {
+            FilterKind::All => "All Torrents",
+            FilterKind::Downloading => "Downloading",
+            FilterKind::Seeding => "Seeding",
+            FilterKind::Paused => "Paused",
+            FilterKind::Stopped => "Stopped",
+            FilterKind::Error => "Error",
+            FilterKind::Idle => "Idle",
+        };
+        let label = format!("| [{}] {name} ({})", idx + 1, summary.count(*kind));
+        lines.push(filter_line(theme, focus, selected == idx, label));
+    }
+    if !summary.labels.is_empty() {
+        lines.push(Line::from(Span::styled("+- LABELS ------------+", border_style)));
+    }
+    for (idx, (name, count)) in summary.labels.iter().enumerate() {
+        let count = format!(" ({count})");
+        let name = fit_text(name, 15usize.saturating_sub(count.len()));
+        let label = format!("|     {name}{count}");
+        lines.push(filter_line(theme, focus, selected == crate::app::FILTERS.len() + idx, label));
+    }
+    lines.push(Line::from(Span::styled("+---------------------+", border_style)));
     let block = Block::default().style(Style::default().bg(panel_bg));
     frame.render_widget(Paragraph::new(lines).block(block), area);
 }
 
-fn draw_keys_panel(frame: &mut Frame, area: Rect) {
+/// The state filters and, under them, one row per label.
+fn filters_panel_height(app: &App) -> u16 {
+    let labels = app.filter_summary().labels.len();
+    let label_rows = if labels == 0 { 0 } else { labels + 1 };
+    (crate::app::FILTERS.len() + 2 + label_rows) as u16
+}
+
+fn draw_keys_panel(frame: &mut Frame, theme: &Theme, area: Rect) {
     let lines = vec![
         Line::from(Span::styled(
             "[TAB] Select Filters/Torrents",
-            Style::default().fg(COLOR_CYAN),
+            Style::default().fg(theme.info),
         )),
         Line::from(Span::styled(
             "[↑/↓] Select",
-            Style::default().fg(COLOR_MUTED),
+            Style::default().fg(theme.muted),
         )),
-        Line::from(Span::styled("[d] Delete", Style::default().fg(COLOR_MUTED))),
+        Line::from(Span::styled("[d] Delete", Style::default().fg(theme.muted))),
         Line::from(Span::styled(
             "[p] Pause/Resume",
-            Style::default().fg(COLOR_MUTED),
+            Style::default().fg(theme.muted),
         )),
         Line::from(Span::styled(
             "[a] Add torrent",
-            Style::default().fg(COLOR_MUTED),
+            Style::default().fg(theme.muted),
         )),
         Line::from(Span::styled(
             "[q] Quit",
-            Style::default().fg(COLOR_MUTED),
+            Style::default().fg(theme.muted),
         )),
-        Line::from(Span::styled("[?] Help", Style::default().fg(COLOR_MUTED))),
+        Line::from(Span::styled("[?] Help", Style::default().fg(theme.muted))),
         Line::from(Span::styled(
             "+---------------------+",
-            Style::default().fg(COLOR_BORDER),
+            Style::default().fg(theme.border),
         )),
     ];
     let text = Text::from(lines);
-    let block = Block::default().style(Style::default().bg(COLOR_BG));
+    let block = Block::default().style(Style::default().bg(theme.bg));
     frame.render_widget(
         Paragraph::new(text).block(block).wrap(Wrap { trim: true }),
         area,
     );
 }
 
-fn draw_right_panel(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_right_panel(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
     let sections = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -276,63 +499,91 @@ fn draw_right_panel(frame: &mut Frame, area: Rect, app: &App) {
         ])
         .split(area);
 
-    draw_actions_bar(frame, sections[0]);
+    draw_actions_bar(frame, theme, sections[0], app);
     match app.view() {
-        View::Torrents => draw_table(frame, sections[1], app),
-        View::Peers => draw_peers_view(frame, sections[1], app),
-        View::Info => draw_info_view(frame, sections[1], app),
+        View::Torrents => draw_table(frame, theme, sections[1], app),
+        View::Peers => draw_peers_view(frame, theme, sections[1], app),
+        View::Info => draw_info_view(frame, theme, sections[1], app),
+        View::Files => draw_files_view(frame, theme, sections[1], app),
+        View::Trackers => draw_trackers_view(frame, theme, sections[1], app),
+        View::Timeline => draw_timeline_view(frame, theme, sections[1], app),
+        View::Log => draw_log_view(frame, theme, sections[1], app),
+        View::Rss => draw_rss_view(frame, theme, sections[1], app),
     }
-    draw_selected_panel(frame, sections[2], app);
+    draw_selected_panel(frame, theme, sections[2], app);
 }
 
-fn draw_actions_bar(frame: &mut Frame, area: Rect) {
+fn draw_actions_bar(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
     let block = Block::default()
         .borders(Borders::BOTTOM)
-        .border_style(Style::default().fg(COLOR_BORDER))
-        .style(Style::default().bg(COLOR_BG));
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.bg));
     frame.render_widget(block, area);
 
-    let left = Line::from(Span::styled(
-        "View: [F]iles [V]Peers [I]nfo",
-        Style::default().fg(COLOR_MUTED),
-    ));
-    let right = Line::from("");
+    let left = if app.column_resize().is_some() {
+        Line::from(Span::styled(
+            "[Tab] column [←/→] width [r]eset [Enter] save [Esc] cancel",
+            Style::default().fg(theme.highlight),
+        ))
+    } else {
+        Line::from(Span::styled(
+            "View: [F]Torrents [O]Files [V]Peers [U]Trackers [I]nfo [E]TA [L]og [R]SS",
+            Style::default().fg(theme.muted),
+        ))
+    };
+    let mut right = Vec::new();
+    if app.group_by() != GroupBy::Off {
+        right.push(Span::styled(
+            format!("Grouped by {} ", app.group_by().label()),
+            Style::default().fg(theme.info),
+        ));
+    }
+    if let Some(query) = app.source_filter() {
+        right.push(Span::styled(
+            format!("Source: {query} "),
+            Style::default().fg(theme.highlight),
+        ));
+    }
+    let right = Line::from(right);
 
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(100), Constraint::Percentage(0)])
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
         .split(area);
 
     frame.render_widget(
         Paragraph::new(left)
-            .style(Style::default().fg(COLOR_GREEN))
+            .style(Style::default().fg(theme.accent))
             .alignment(Alignment::Left),
         chunks[0],
     );
     frame.render_widget(Paragraph::new(right).alignment(Alignment::Right), chunks[1]);
 }
 
-fn draw_table(frame: &mut Frame, area: Rect, app: &App) {
-    let header_style = Style::default().fg(COLOR_BLACK).bg(COLOR_CYAN);
+fn draw_table(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let header_style = Style::default().fg(theme.on_accent).bg(theme.info);
     let row_style = if app.focus() == FocusPanel::Torrents {
-        Style::default().fg(COLOR_GREEN).bg(COLOR_FOCUS_BG)
+        Style::default().fg(theme.accent).bg(theme.focus_bg)
     } else {
-        Style::default().fg(COLOR_GREEN).bg(COLOR_BG)
+        Style::default().fg(theme.accent).bg(theme.bg)
     };
 
-    let header = Row::new(vec![
-        "NAME", " STATUS", " PROG%", " DOWN", " UP", " PEERS", " SIZE", " RATIO",
-    ])
+    let resizing = app.column_resize().map(|r| r.column);
+    let header = Row::new(COLUMN_NAMES.iter().enumerate().map(|(idx, name)| {
+        let text = if idx == 0 { name.to_string() } else { format!(" {name}") };
+        let cell = Cell::from(text);
+        if resizing == Some(idx) {
+            cell.style(Style::default().fg(theme.on_accent).bg(theme.highlight))
+        } else {
+            cell
+        }
+    }))
     .style(header_style)
     .height(1);
 
-    let filtered: Vec<(usize, &TorrentRow)> = app
-        .filtered_indices()
-        .into_iter()
-        .filter_map(|idx| app.torrents().get(idx).map(|t| (idx, t)))
-        .collect();
-    let col_widths = table_column_widths(area.width, 8);
-    let rows: Vec<Row> = if filtered.is_empty() {
+    let entries = app.list_entries();
+    let col_widths = table_column_widths(area.width, &app.column_widths());
+    let rows: Vec<Row> = if entries.is_empty() {
         vec![Row::new(vec![
             Cell::from(Text::from("No torrents in this filter")),
             Cell::from(Text::from(" ")),
@@ -342,18 +593,29 @@ fn draw_table(frame: &mut Frame, area: Rect, app: &App) {
             Cell::from(Text::from(" ")),
             Cell::from(Text::from(" ")),
             Cell::from(Text::from(" ")),
+            Cell::from(Text::from(" ")),
         ])]
     } else {
-        filtered
+        entries
             .iter()
-            .map(|(_, t)| torrent_row(t, &col_widths))
+            .map(|entry| match entry {
+                ListEntry::Header {
+                    name,
+                    count,
+                    collapsed,
+                } => group_header_row(theme, name, *count, *collapsed),
+                ListEntry::Torrent(idx) => {
+                    let t = &app.torrents()[*idx];
+                    torrent_row(theme, app, t, &col_widths)
+                }
+            })
             .collect()
     };
 
     let table = Table::new(
         rows,
         vec![
-            Constraint::Length(col_widths.get(0).copied().unwrap_or(0) as u16),
+            Constraint::Length(col_widths.first().copied().unwrap_or(0) as u16),
             Constraint::Length(col_widths.get(1).copied().unwrap_or(0) as u16),
             Constraint::Length(col_widths.get(2).copied().unwrap_or(0) as u16),
             Constraint::Length(col_widths.get(3).copied().unwrap_or(0) as u16),
@@ -361,151 +623,708 @@ fn draw_table(frame: &mut Frame, area: Rect, app: &App) {
             Constraint::Length(col_widths.get(5).copied().unwrap_or(0) as u16),
             Constraint::Length(col_widths.get(6).copied().unwrap_or(0) as u16),
             Constraint::Length(col_widths.get(7).copied().unwrap_or(0) as u16),
+            Constraint::Length(col_widths.get(8).copied().unwrap_or(0) as u16),
         ],
     )
     .header(header)
     .block(Block::default().style(row_style))
     .highlight_symbol("")
     .row_highlight_style(match app.focus() {
-        FocusPanel::Torrents => Style::default().bg(Color::Rgb(0, 30, 0)),
-        FocusPanel::Filters => Style::default().bg(Color::Rgb(0, 60, 0)),
+        FocusPanel::Torrents => Style::default().bg(theme.focus_tint),
+        FocusPanel::Filters => Style::default().bg(theme.row_highlight),
     })
     .column_spacing(0);
 
     let mut state = TableState::default();
-    if !filtered.is_empty() {
-        if let Some(pos) = filtered
-            .iter()
-            .position(|(idx, _)| *idx == app.selected_index())
-        {
-            state.select(Some(pos));
-        }
-    }
+    state.select(entries.iter().position(|entry| match (entry, app.selected_group()) {
+        (ListEntry::Header { name, .. }, Some(selected)) => name == selected,
+        (ListEntry::Torrent(idx), None) => *idx == app.selected_index(),
+        _ => false,
+    }));
     frame.render_stateful_widget(table, area, &mut state);
 }
 
-fn draw_peers_view(frame: &mut Frame, area: Rect, app: &App) {
-    let block = Block::default().style(Style::default().bg(COLOR_BG));
+/// Renders `lines` in `block` starting `scroll` lines down, clamped so the end can't scroll
+/// past the bottom, with a scrollbar when they don't all fit.
+fn draw_scrollable(
+    frame: &mut Frame,
+    theme: &Theme,
+    area: Rect,
+    block: Block,
+    lines: Vec<Line>,
+    scroll: usize,
+) {
+    let inner = block.inner(area);
+    let overflow = lines.len().saturating_sub(inner.height as usize);
+    let scroll = scroll.min(overflow);
+    frame.render_widget(
+        Paragraph::new(lines).block(block).scroll((scroll as u16, 0)),
+        area,
+    );
+    if overflow > 0 {
+        let mut state = ScrollbarState::new(overflow).position(scroll);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .style(Style::default().fg(theme.muted)),
+            inner,
+            &mut state,
+        );
+    }
+}
+
+fn draw_peers_view(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let block = Block::default()
+        .style(Style::default().bg(theme.bg))
+        .title_bottom(Span::styled(
+            " [j/k] Select  [PgUp/PgDn] Page  [y] Copy address ",
+            Style::default().fg(theme.muted),
+        ));
+    let mut scroll = 0;
     let text = if let Some(t) = app.selected_torrent() {
         if let Some(stats) = t.stats.as_ref() {
             if let Some(live) = stats.live.as_ref() {
                 let p = &live.snapshot.peer_stats;
-                Text::from(vec![
-                    Line::from(Span::styled("Peers", Style::default().fg(COLOR_GREEN))),
+                let mut lines = vec![
+                    Line::from(Span::styled("Peers", Style::default().fg(theme.accent))),
+                    Line::from(""),
+                    Line::from(format!(
+                        "Live: {}  Seen: {}  Queued: {}  Connecting: {}  Dead: {}",
+                        p.live, p.seen, p.queued, p.connecting, p.dead
+                    )),
                     Line::from(""),
-                    Line::from(format!("Live: {}", p.live)),
-                    Line::from(format!("Seen: {}", p.seen)),
-                    Line::from(format!("Queued: {}", p.queued)),
-                    Line::from(format!("Connecting: {}", p.connecting)),
-                    Line::from(format!("Dead: {}", p.dead)),
-                ])
+                ];
+                let peers = app.peer_lines();
+                let selected = app.peer_selected(&peers);
+                if !peers.is_empty() {
+                    lines.push(Line::from(Span::styled(
+                        format!("{:<24}{:>11}  {:<30}  {:>9}", "ADDRESS", "DOWN", "", "RECEIVED"),
+                        Style::default().fg(theme.accent),
+                    )));
+                }
+                // Keep the highlighted peer on screen.
+                let height = block.inner(area).height as usize;
+                scroll = (lines.len() + selected + 1).saturating_sub(height);
+                for (row, peer) in peers.into_iter().enumerate() {
+                    let line = Line::from(vec![
+                        Span::raw(format!("{:<24}", fit_text(&peer.addr, 23))),
+                        Span::styled(
+                            format!("{:>11}  ", format!("{}/s", format_bytes(peer.rate as u64))),
+                            Style::default().fg(theme.info),
+                        ),
+                        Span::styled(
+                            format!("{:<30}", sparkline(&peer.history)),
+                            Style::default().fg(theme.info),
+                        ),
+                        Span::raw(format!("  {:>9}", format_bytes(peer.fetched))),
+                    ]);
+                    lines.push(if row == selected {
+                        line.style(Style::default().bg(theme.row_highlight))
+                    } else {
+                        line
+                    });
+                }
+                lines
             } else {
-                Text::from("No live peer data yet.")
+                vec![Line::from("No live peer data yet.")]
             }
         } else {
-            Text::from("No torrent selected.")
+            vec![Line::from("No torrent selected.")]
+        }
+    } else {
+        vec![Line::from("No torrent selected.")]
+    };
+    draw_scrollable(frame, theme, area, block, text, scroll);
+}
+
+/// One block character per sample, scaled to the largest sample.
+fn sparkline(samples: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = samples.iter().copied().fold(0.0, f64::max);
+    samples
+        .iter()
+        .map(|v| {
+            if max <= 0.0 {
+                BARS[0]
+            } else {
+                BARS[((v / max) * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
+fn draw_timeline_view(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let block = Block::default().style(Style::default().bg(theme.bg));
+    let (entries, all_done) = app.timeline();
+    let mut lines = vec![
+        Line::from(Span::styled("ETA timeline", Style::default().fg(theme.accent))),
+        Line::from(""),
+    ];
+    if entries.is_empty() {
+        lines.push(Line::from("No active downloads."));
+    }
+    let now = chrono::Local::now();
+    for e in &entries {
+        let finish = match e.eta {
+            Some(eta) => format!("{} (in {})", finish_time(now, eta), format_duration(eta)),
+            None => "stalled".to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{finish:<24}"), Style::default().fg(theme.info)),
+            Span::styled(
+                format!(
+                    "{:>9} left @ {:>9}/s  ",
+                    format_bytes(e.remaining),
+                    format_bytes(e.rate as u64)
+                ),
+                Style::default().fg(theme.muted),
+            ),
+            Span::raw(e.name.clone()),
+        ]));
+    }
+    if !entries.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(match all_done {
+            Some(eta) => format!(
+                "All done by {} (in {})",
+                finish_time(now, eta),
+                format_duration(eta)
+            ),
+            None => "All done by: unknown while a download is stalled".to_string(),
+        }));
+    }
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_rss_view(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let block = Block::default().style(Style::default().bg(theme.bg));
+    let heading = |text: &'static str| Line::styled(text, Style::default().fg(theme.accent));
+    let mut lines = vec![heading(if app.rss_fetching() {
+        "RSS feeds (fetching)"
+    } else {
+        "RSS feeds"
+    })];
+    let mut feeds = app.rss_feeds().peekable();
+    if feeds.peek().is_none() {
+        lines.push(Line::from(
+            "No feeds. Add [[rss.feeds]] and [[rss.rules]] to the config.",
+        ));
+    }
+    for (feed, status) in feeds {
+        let checked = match status.and_then(|s| s.checked) {
+            Some(at) => format!("{} ago", format_duration(at.elapsed())),
+            None => "not fetched yet".to_string(),
+        };
+        let detail = match status.and_then(|s| s.error.as_deref()) {
+            Some(err) => Span::styled(err.to_string(), Style::default().fg(theme.error)),
+            None => Span::styled(feed.url.clone(), Style::default().fg(theme.muted)),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<20} ", feed.name), Style::default().fg(theme.info)),
+            Span::raw(format!("{:>4} items  ", status.map_or(0, |s| s.items))),
+            Span::styled(format!("{checked:<16}"), Style::default().fg(theme.muted)),
+            detail,
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(heading("Rules"));
+    for (rule, status) in app.rss_rules() {
+        let status = status.cloned().unwrap_or_default();
+        let dir = rule
+            .dir
+            .as_ref()
+            .map_or_else(|| "download dir".to_string(), |d| d.display().to_string());
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<20} ", rule.name), Style::default().fg(theme.info)),
+            Span::raw(format!(
+                "{} matched, {} added, {} failed  ",
+                status.matched, status.added, status.failed
+            )),
+            Span::styled(
+                format!("/{}/ -> {dir}", rule.pattern.0.as_str()),
+                Style::default().fg(theme.muted),
+            ),
+        ]));
+        if let Some(last) = status.last {
+            lines.push(Line::styled(
+                format!("{:<21}last: {last}", ""),
+                Style::default().fg(theme.muted),
+            ));
         }
+    }
+    lines.push(Line::from(""));
+    lines.push(heading("Recent matches"));
+    let mut matches = app.rss_matches().peekable();
+    if matches.peek().is_none() {
+        lines.push(Line::from("Nothing matched yet."));
+    }
+    for found in matches {
+        let (outcome, color) = match &found.outcome {
+            RssOutcome::Queued => ("queued".to_string(), theme.muted),
+            RssOutcome::Retrying(err) => (format!("retrying: {err}"), theme.warning),
+            RssOutcome::Added => ("added".to_string(), theme.accent),
+            RssOutcome::Duplicate => ("already added".to_string(), theme.muted),
+            RssOutcome::Failed(err) => (format!("failed: {err}"), theme.error),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(found.at.format("%H:%M ").to_string(), Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{:<20} ", app.rss_rule_name(found.rule)),
+                Style::default().fg(theme.info),
+            ),
+            Span::raw(format!("{} ", found.title)),
+            Span::styled(outcome, Style::default().fg(color)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Wall-clock finish time, with the date once it's past today.
+fn finish_time(now: chrono::DateTime<chrono::Local>, eta: Duration) -> String {
+    let at = now + chrono::Duration::from_std(eta).unwrap_or(chrono::Duration::MAX);
+    if at.date_naive() == now.date_naive() {
+        at.format("%H:%M").to_string()
     } else {
-        Text::from("No torrent selected.")
+        at.format("%a %d %b %H:%M").to_string()
+    }
+}
+
+/// Days since the last clean recheck; "-" for never.
+fn format_verified(at: Option<chrono::DateTime<chrono::Local>>) -> String {
+    match at.map(|at| (chrono::Local::now() - at).num_days()) {
+        None => "-".to_string(),
+        Some(0) => "today".to_string(),
+        Some(days) => format!("{days}d ago"),
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    match (secs / 86_400, secs / 3600 % 24, secs / 60 % 60) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, m) => format!("{m}m"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (days, h, _) => format!("{days}d {h}h"),
+    }
+}
+
+fn draw_log_view(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let block = Block::default().style(Style::default().bg(theme.bg));
+    let Some(t) = app.selected_torrent() else {
+        frame.render_widget(Paragraph::new("No torrent selected.").block(block), area);
+        return;
     };
-    frame.render_widget(Paragraph::new(text).block(block), area);
+    let events = app.torrent_events(t);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Log: {}", t.name),
+            Style::default().fg(theme.accent),
+        )),
+        Line::from(""),
+    ];
+    if events.is_empty() {
+        lines.push(Line::from("No events yet."));
+    }
+    // Newest last; keep the tail in view.
+    let room = (area.height as usize).saturating_sub(lines.len());
+    let skip = events.len().saturating_sub(room);
+    for e in events.into_iter().skip(skip) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", e.at.format("%Y-%m-%d %H:%M:%S")),
+                Style::default().fg(theme.muted),
+            ),
+            Span::raw(app.masked(&e.message).into_owned()),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines).block(block), area);
 }
 
-fn draw_info_view(frame: &mut Frame, area: Rect, app: &App) {
-    let block = Block::default().style(Style::default().bg(COLOR_BG));
+fn draw_info_view(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let block = Block::default()
+        .style(Style::default().bg(theme.bg))
+        .title_bottom(Span::styled(
+            " [j/k] Scroll  [PgUp/PgDn] Page  [Home] Top  [y] Copy info hash ",
+            Style::default().fg(theme.muted),
+        ));
     let text = if let Some(t) = app.selected_torrent() {
+        let date = |at: Option<chrono::DateTime<chrono::Local>>| {
+            at.map_or("-".to_string(), |at| at.format("%Y-%m-%d %H:%M").to_string())
+        };
+        let heading =
+            |text: &'static str| Line::from(Span::styled(text, Style::default().fg(theme.accent)));
         let mut lines = vec![
-            Line::from(Span::styled("Info", Style::default().fg(COLOR_GREEN))),
+            heading("Info"),
             Line::from(""),
             Line::from(format!("Name: {}", t.name)),
-            Line::from(format!("Output: {}", t.output_folder)),
         ];
+        if t.name != t.original_name {
+            lines.push(Line::from(format!("Original name: {}", t.original_name)));
+        }
+        lines.extend([
+            Line::from(format!("Info hash: {}", t.info_hash.as_deref().unwrap_or("-"))),
+            Line::from(format!("Output: {}", t.output_folder)),
+            Line::from(format!("Label: {}", app.label_of(t).unwrap_or("-"))),
+            Line::from(format!(
+                "Added by: {}",
+                app.source_of(t).map_or_else(|| "-".to_string(), |s| s.to_string())
+            )),
+            Line::from(format!("Storage: {}", app.storage_of(t).label())),
+            // librqbit's only strategy; there is nothing to switch to yet.
+            Line::from("Piece order: file by file, first and last piece first"),
+            Line::from(""),
+            heading("Metainfo"),
+        ]);
+        match app.info_metainfo(t) {
+            None => lines.push(Line::from("Reading...")),
+            Some(Err(reason)) => lines.push(Line::from(Span::styled(
+                format!("Not available: {reason}"),
+                Style::default().fg(theme.muted),
+            ))),
+            Some(Ok(info)) => {
+                lines.extend([
+                    Line::from(format!(
+                        "Size: {} in {} files",
+                        format_bytes(info.total_length),
+                        info.files.len()
+                    )),
+                    Line::from(format!(
+                        "Pieces: {} x {}",
+                        info.pieces,
+                        format_bytes(u64::from(info.piece_length))
+                    )),
+                    Line::from(format!(
+                        "Private: {}",
+                        if info.private { "yes (DHT and PEX off)" } else { "no" }
+                    )),
+                    Line::from(format!("Created: {}", date(info.created))),
+                    Line::from(format!(
+                        "Created by: {}",
+                        info.created_by.as_deref().unwrap_or("-")
+                    )),
+                ]);
+                if let Some(comment) = &info.comment {
+                    lines.push(Line::from(format!("Comment: {}", app.masked(comment))));
+                }
+            }
+        }
+        lines.push(Line::from(format!("Trackers: {}", t.trackers.len())));
+        lines.extend([
+            Line::from(""),
+            heading("History"),
+            Line::from(format!("Added: {}", date(app.added_at_of(t)))),
+            Line::from(format!("Completed: {}", date(app.completed_at_of(t)))),
+        ]);
+        if let Some(at) = app.last_upload_of(t) {
+            lines.push(Line::from(format!("Last upload: {}", date(Some(at)))));
+        }
         if let Some(stats) = t.stats.as_ref() {
-            lines.push(Line::from(format!(
-                "Progress: {} / {}",
-                format_bytes(stats.progress_bytes),
-                format_bytes(stats.total_bytes)
-            )));
-            lines.push(Line::from(format!(
-                "Uploaded: {}",
-                format_bytes(stats.uploaded_bytes)
-            )));
+            lines.extend([
+                Line::from(""),
+                heading("Transfer"),
+                Line::from(format!(
+                    "Progress: {} / {}",
+                    format_bytes(stats.progress_bytes),
+                    format_bytes(stats.total_bytes)
+                )),
+                Line::from(format!("Uploaded: {}", format_bytes(stats.uploaded_bytes))),
+            ]);
+            if let Some(live) = stats.live.as_ref() {
+                lines.push(Line::from(format!(
+                    "This session: {} down, {} up",
+                    format_bytes(live.snapshot.fetched_bytes),
+                    format_bytes(live.snapshot.uploaded_bytes)
+                )));
+                lines.push(Line::from(format!(
+                    "Re-requested pieces: {} (slow peer's requests cancelled){}",
+                    live.snapshot.peer_stats.steals,
+                    if in_end_game(t) { ", end-game" } else { "" }
+                )));
+            }
         }
-        Text::from(lines)
+        if let Some(notes) = app.notes_of(t) {
+            lines.extend([Line::from(""), heading("Notes")]);
+            lines.extend(notes.lines().map(|l| Line::from(format!("  {l}"))));
+        }
+        lines
+    } else {
+        vec![Line::from("No torrent selected.")]
+    };
+    draw_scrollable(frame, theme, area, block, text, app.info_scroll() as usize);
+}
+
+fn draw_files_view(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let block = Block::default()
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Files", Style::default().fg(theme.accent)))
+        .title_bottom(Span::styled(
+            " [j/k] Select  [y] Copy stream URL  [c] Cast  [o] Open ",
+            Style::default().fg(theme.muted),
+        ));
+    let files = app.file_lines();
+    if files.is_empty() {
+        let text = if app.selected_torrent().is_some() {
+            "No file list yet (waiting for metadata)."
+        } else {
+            "No torrent selected."
+        };
+        frame.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+    let rows: Vec<Row> = files
+        .iter()
+        .map(|file| {
+            let done = if file.included {
+                format!("{:.0}%", file.have as f64 * 100.0 / file.len.max(1) as f64)
+            } else {
+                "skip".to_string()
+            };
+            let style = if file.included {
+                Style::default().fg(theme.text)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+            Row::new(vec![
+                Span::raw(file.path.as_str()),
+                Span::raw(format_bytes(file.len)),
+                Span::styled(done, Style::default().fg(theme.info)),
+            ])
+            .style(style)
+        })
+        .collect();
+    let header = Row::new(vec!["NAME", "SIZE", "DONE"]).style(Style::default().fg(theme.muted));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Length(5),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .row_highlight_style(Style::default().bg(theme.row_highlight))
+    .column_spacing(1);
+    let mut state = TableState::default();
+    state.select(Some(app.file_selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_trackers_view(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
+    let note = if app.trackers_probing() {
+        " Scraping trackers... "
     } else {
-        Text::from("No torrent selected.")
+        " First answering tracker in a tier is in use; scraped every minute "
     };
-    frame.render_widget(Paragraph::new(text).block(block), area);
+    let block = Block::default()
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Trackers", Style::default().fg(theme.accent)))
+        .title_bottom(Span::styled(note, Style::default().fg(theme.muted)));
+    let trackers = app.tracker_lines();
+    if trackers.is_empty() {
+        let text = if app.selected_torrent().is_some() {
+            "No trackers (DHT and peer exchange only)."
+        } else {
+            "No torrent selected."
+        };
+        frame.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+    let mut last_tier = 0;
+    let rows: Vec<Row> = trackers
+        .iter()
+        .map(|tracker| {
+            let tier = if tracker.tier == last_tier {
+                String::new()
+            } else {
+                tracker.tier.to_string()
+            };
+            last_tier = tracker.tier;
+            let (state, color) = match tracker.state {
+                TrackerState::InUse => ("in use".to_string(), theme.accent),
+                TrackerState::Standby => ("standby".to_string(), theme.text),
+                TrackerState::Failing => (
+                    tracker
+                        .error
+                        .as_deref()
+                        .map_or("failing".to_string(), |err| app.masked(err).into_owned()),
+                    theme.error,
+                ),
+                TrackerState::Unchecked => ("checking".to_string(), theme.muted),
+            };
+            let count = |n: Option<u32>| n.map_or("-".to_string(), |n| n.to_string());
+            Row::new(vec![
+                Cell::from(tier),
+                Cell::from(app.masked(&tracker.url).into_owned()),
+                Cell::from(Span::styled(state, Style::default().fg(color))),
+                Cell::from(tracker.failures.to_string()),
+                Cell::from(count(tracker.stats.map(|s| s.seeders))),
+                Cell::from(count(tracker.stats.map(|s| s.leechers))),
+                Cell::from(tracker.checked.map_or("-".to_string(), format_duration)),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["TIER", "TRACKER", "STATUS", "FAILS", "SEEDS", "PEERS", "CHECKED"])
+        .style(Style::default().fg(theme.muted));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+            Constraint::Length(5),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(7),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .column_spacing(1);
+    frame.render_widget(table, area);
 }
 
-fn draw_selected_panel(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_selected_panel(frame: &mut Frame, theme: &Theme, area: Rect, app: &App) {
     let block = Block::default()
         .borders(Borders::TOP)
-        .border_style(Style::default().fg(COLOR_BORDER))
-        .style(Style::default().bg(COLOR_BG));
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.bg));
     frame.render_widget(&block, area);
     let inner = block.inner(area);
 
     let title = Line::from(Span::styled(
         "+- SELECTED TORRENT --------------------------------------------+",
-        Style::default().fg(COLOR_GREEN),
+        Style::default().fg(theme.accent),
     ));
     frame.render_widget(
         Paragraph::new(title),
         Rect::new(inner.x, inner.y, inner.width, 1),
     );
 
-    let (name, downloaded, eta) = selected_details(app);
-    let line1 = Line::from(vec![
-        Span::styled("Name: ", Style::default().fg(COLOR_MUTED)),
-        Span::styled(name, Style::default().fg(Color::White)),
-    ]);
-    let line2 = Line::from(vec![
-        Span::styled("Downloaded: ", Style::default().fg(COLOR_MUTED)),
-        Span::styled(downloaded, Style::default().fg(COLOR_CYAN)),
-    ]);
-    let line3 = Line::from(vec![
-        Span::styled("ETA: ", Style::default().fg(COLOR_MUTED)),
-        Span::styled(eta, Style::default().fg(Color::White)),
-    ]);
+    if let Some(t) = app.selected_torrent() {
+        frame.render_widget(
+            selected_gauge(theme, app, t),
+            Rect::new(inner.x, inner.y + 1, inner.width, 1),
+        );
+    } else {
+        frame.render_widget(
+            Paragraph::new("No torrent selected.").style(Style::default().fg(theme.muted)),
+            Rect::new(inner.x, inner.y + 1, inner.width, 1),
+        );
+    }
+    if inner.height <= 2 {
+        return;
+    }
 
-    let cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(40),
-            Constraint::Percentage(30),
-            Constraint::Percentage(30),
-        ])
-        .split(Rect::new(
-            inner.x,
-            inner.y + 1,
-            inner.width,
-            inner.height - 1,
-        ));
+    // Problems take the details line; the name is in the table anyway.
+    let line = match app.selected_torrent().and_then(|t| app.problem_of(t)) {
+        Some(problem) => Line::from(vec![
+            Span::styled("! ", Style::default().fg(theme.highlight)),
+            Span::styled(app.masked(&problem).into_owned(), Style::default().fg(theme.highlight)),
+        ]),
+        None => {
+            let (name, downloaded, _) = selected_details(app);
+            Line::from(vec![
+                Span::styled("Name: ", Style::default().fg(theme.muted)),
+                Span::styled(name, Style::default().fg(theme.text)),
+                Span::styled("  Downloaded: ", Style::default().fg(theme.muted)),
+                Span::styled(downloaded, Style::default().fg(theme.info)),
+            ])
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(line),
+        Rect::new(inner.x, inner.y + 2, inner.width, 1),
+    );
+}
 
-    frame.render_widget(Paragraph::new(line1), cols[0]);
-    frame.render_widget(Paragraph::new(line2), cols[1]);
-    frame.render_widget(Paragraph::new(line3), cols[2]);
+/// Progress of the selected torrent with rates, ETA and peers in the label. The fill
+/// follows verified pieces when they're known, which is what a recheck actually found.
+fn selected_gauge(theme: &Theme, app: &App, t: &TorrentRow) -> Gauge<'static> {
+    let bytes_ratio = t
+        .stats
+        .as_ref()
+        .filter(|s| s.total_bytes > 0)
+        .map_or(0.0, |s| s.progress_bytes as f64 / s.total_bytes as f64);
+    let pieces = app.pieces_of(t).filter(|p| p.total > 0);
+    let ratio = pieces.map_or(bytes_ratio, |p| p.have as f64 / p.total as f64);
+    let (_, down, up, peers, _, _) = format_metrics(t);
+    let (_, _, eta) = selected_details(app);
+    let mut label = format!(
+        "{:.1}%  Down {down}  Up {up}  ETA {eta}  Peers {peers}",
+        ratio * 100.0
+    );
+    if let Some(p) = pieces {
+        label.push_str(&format!("  Pieces {}/{}", p.have, p.total));
+    }
+    Gauge::default()
+        .gauge_style(Style::default().fg(theme.gauge).bg(theme.panel))
+        .label(Span::styled(label, Style::default().fg(theme.text)))
+        .ratio(ratio.clamp(0.0, 1.0))
 }
 
-fn draw_input_modal(frame: &mut Frame, app: &App) {
-    let area = centered_rect(70, 20, frame.area());
+fn draw_input_modal(frame: &mut Frame, theme: &Theme, app: &App) {
+    let choices = if app.mode() == Mode::EnterTorrentDir {
+        app.dir_choices()
+    } else {
+        &[]
+    };
+    let height = if choices.is_empty() { 20 } else { 40 };
+    let area = centered_rect(app.density(), 70, height, frame.area());
     frame.render_widget(Clear, area);
     let title = match app.mode() {
         Mode::EnterMagnet => "Add torrent (magnet/URL/path)",
         Mode::EnterTorrentDir => "Torrent download directory (Enter to use)",
+        Mode::EnterLabel => "Label (empty to clear)",
+        Mode::EnterName => "Display name (empty restores the original)",
+        Mode::EnterLocation => "Locate data (folder that now holds the files)",
+        Mode::EnterSearch => "Search JSON (empty to clear)",
+        Mode::EnterCommand => "Command (set tick|refresh <250ms|2s>)",
         _ => "Input",
     };
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(COLOR_GREEN))
-        .style(Style::default().bg(COLOR_PANEL))
-        .title(Span::styled(title, Style::default().fg(COLOR_GREEN)));
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(title, Style::default().fg(theme.accent)));
+    let block = if choices.len() > 1 {
+        block.title_bottom(Span::styled(
+            " Up/Down: pick a directory ",
+            Style::default().fg(theme.muted),
+        ))
+    } else {
+        block
+    };
     let inner = block.inner(area);
     let (visible, cursor_x) = visible_input(app.input(), app.input_cursor(), inner.width);
-    let paragraph = Paragraph::new(visible)
+    let mut lines = vec![Line::from(visible)];
+    if !choices.is_empty() {
+        lines.push(Line::from(""));
+    }
+    let roomiest = app.roomiest_dir_choice();
+    let typed = std::path::Path::new(app.input().trim());
+    for (idx, choice) in choices.iter().enumerate() {
+        let marker = if choice.path == typed { "> " } else { "  " };
+        let free = choice.free.map_or_else(
+            || "free space unknown".to_string(),
+            |bytes| format!("{} free", format_bytes(bytes)),
+        );
+        let style = if roomiest == Some(idx) {
+            Style::default().fg(theme.accent)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        let mut spans = vec![
+            Span::styled(format!("{marker}{}", choice.path.display()), style),
+            Span::styled(format!("  {free}"), style),
+        ];
+        if roomiest == Some(idx) {
+            spans.push(Span::styled("  most room", style));
+        }
+        lines.push(Line::from(spans));
+    }
+    let paragraph = Paragraph::new(lines)
         .block(block)
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(theme.text));
     frame.render_widget(paragraph, area);
     if let Some(x) = cursor_x {
         let y = inner.y;
@@ -513,17 +1332,75 @@ fn draw_input_modal(frame: &mut Frame, app: &App) {
     }
 }
 
-fn draw_file_picker(frame: &mut Frame, picker: &FilePickerState) {
-    let area = centered_rect(90, 80, frame.area());
+fn draw_notes_editor(frame: &mut Frame, theme: &Theme, density: Density, editor: &NotesEditor) {
+    let area = centered_rect(density, 70, 50, frame.area());
     frame.render_widget(Clear, area);
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(COLOR_GREEN))
-        .style(Style::default().bg(COLOR_PANEL))
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
         .title(Span::styled(
-            "Select files (space to toggle, a all, n none, Enter to start)",
-            Style::default().fg(COLOR_GREEN),
+            format!("Notes: {} (Ctrl+S save, Esc discard)", editor.title),
+            Style::default().fg(theme.accent),
         ));
+    let inner = block.inner(area);
+    let (line, col) = editor.cursor_position();
+    let height = inner.height.max(1) as usize;
+    let scroll = line.saturating_sub(height - 1);
+    let width = inner.width.max(1) as usize;
+    let scroll_x = col.saturating_sub(width - 1);
+    frame.render_widget(
+        Paragraph::new(editor.text())
+            .block(block)
+            .style(Style::default().fg(theme.text))
+            .scroll((scroll as u16, scroll_x as u16)),
+        area,
+    );
+    frame.set_cursor_position((
+        inner.x + (col - scroll_x) as u16,
+        inner.y + (line - scroll) as u16,
+    ));
+}
+
+fn draw_file_picker(frame: &mut Frame, theme: &Theme, density: Density, picker: &FilePickerState) {
+    let area = centered_rect(density, 90, 80, frame.area());
+    frame.render_widget(Clear, area);
+    let mut title =
+        "Select files (space to toggle, a all, n none, s smart, l label, m memory, Enter to start)"
+            .to_string();
+    if let Some(label) = picker.label.as_deref() {
+        title.push_str(&format!(" [{label}]"));
+    }
+    if picker.storage == StorageKind::Memory {
+        title.push_str(" [in memory]");
+    } else if let Some(fstype) = picker.network_fs.as_deref() {
+        title.push_str(&format!(" [on {fstype}: network storage]"));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(title, Style::default().fg(theme.accent)));
+    let risky = picker.files.iter().filter(|f| f.risky).count();
+    let block = if risky > 0 {
+        block.title_bottom(Span::styled(
+            format!(" * {risky} executable or script file(s): check before downloading "),
+            Style::default().fg(theme.risky),
+        ))
+    } else {
+        block
+    };
+    let block = if picker.storage == StorageKind::Disk {
+        block.title_bottom(
+            Line::from(Span::styled(
+                format!(" Into {} ", picker.output_folder),
+                Style::default().fg(theme.muted),
+            ))
+            .alignment(Alignment::Right),
+        )
+    } else {
+        block
+    };
 
     let rows: Vec<Row> = picker
         .files
@@ -532,14 +1409,21 @@ fn draw_file_picker(frame: &mut Frame, picker: &FilePickerState) {
         .map(|(idx, f)| {
             let checkbox = if f.included { "[x]" } else { "[ ]" };
             let style = if idx == picker.cursor {
-                Style::default().bg(Color::Rgb(0, 120, 0)).fg(Color::White)
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            let name = if f.path_issue {
+                Span::styled(format!("! {}", f.name), Style::default().fg(theme.error))
+            } else if f.risky {
+                Span::styled(format!("* {}", f.name), Style::default().fg(theme.risky))
             } else {
-                Style::default().fg(Color::White)
+                Span::raw(f.name.as_str())
             };
             Row::new(vec![
                 Span::raw(checkbox),
                 Span::raw(" "),
-                Span::raw(&f.name),
+                name,
                 Span::raw(" "),
                 Span::raw(format_bytes(f.length)),
             ])
@@ -563,253 +1447,1796 @@ fn draw_file_picker(frame: &mut Frame, picker: &FilePickerState) {
     frame.render_widget(table, area);
 }
 
-fn draw_error_modal(frame: &mut Frame, message: &str) {
-    let area = centered_rect(70, 30, frame.area());
+/// Actions for one torrent, with the key that does the same outside the menu.
+fn draw_context_menu(frame: &mut Frame, theme: &Theme, menu: &ContextMenu) {
+    const WIDTH: u16 = 40;
+    let screen = frame.area();
+    let height = (menu.items.len() as u16 + 2).min(screen.height);
+    let width = WIDTH.min(screen.width);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(
+            format!(" {} ", fit_text(&menu.name, WIDTH as usize - 4)),
+            Style::default().fg(theme.accent),
+        ))
+        .title_bottom(Span::styled(
+            " [Enter] Run  [Esc] Close ",
+            Style::default().fg(theme.muted),
+        ));
+    let inner = (width as usize).saturating_sub(2);
+    let lines: Vec<Line> = menu
+        .items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let key = item.key().unwrap_or("");
+            let label = item.label();
+            let gap = inner.saturating_sub(label.chars().count() + key.len() + 2);
+            let style = if idx == menu.selected {
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            Line::from(vec![
+                Span::raw(format!(" {label}{}", " ".repeat(gap))),
+                Span::styled(format!("{key} "), Style::default().fg(theme.muted)),
+            ])
+            .style(style)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_tasks(frame: &mut Frame, theme: &Theme, app: &App) {
+    let area = centered_rect(app.density(), 80, 60, frame.area());
+    frame.render_widget(Clear, area);
+    let tasks = app.tasks();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(
+            format!("Tasks ({})", tasks.len()),
+            Style::default().fg(theme.accent),
+        ))
+        .title_bottom(Span::styled(
+            " [j/k] Select  [c] Cancel  [Esc] Close ",
+            Style::default().fg(theme.muted),
+        ));
+    if tasks.is_empty() {
+        frame.render_widget(
+            Paragraph::new("Nothing is running in the background.").block(block),
+            area,
+        );
+        return;
+    }
+    let rows: Vec<Row> = tasks
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            let style = if idx == app.task_selected() {
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            let progress = task.pct.map_or_else(String::new, |pct| format!("{pct}%"));
+            Row::new(vec![
+                Span::styled(task.kind, Style::default().fg(theme.info)),
+                Span::raw(task.name.as_str()),
+                Span::raw(task.state.as_str()),
+                Span::raw(progress),
+                Span::raw(format_duration(task.started.elapsed())),
+            ])
+            .style(style)
+        })
+        .collect();
+    let header = Row::new(vec!["KIND", "NAME", "STATE", "PROG", "TIME"])
+        .style(Style::default().fg(theme.muted));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Fill(1),
+            Constraint::Length(14),
+            Constraint::Length(5),
+            Constraint::Length(8),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+fn draw_top_talkers(frame: &mut Frame, theme: &Theme, app: &App) {
+    let area = centered_rect(app.density(), 70, 50, frame.area());
+    frame.render_widget(Clear, area);
+    let by_upload = app.talkers_by_upload();
+    let talkers = app.top_talkers();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(
+            format!("Top talkers by {}", if by_upload { "upload" } else { "download" }),
+            Style::default().fg(theme.accent),
+        ))
+        .title_bottom(Span::styled(
+            " [j/k] Select  [Enter] Go to torrent  [s] Sort down/up  [Esc] Close ",
+            Style::default().fg(theme.muted),
+        ));
+    if talkers.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No torrent is transferring right now.").block(block),
+            area,
+        );
+        return;
+    }
+    // Share of the session rate in the sort direction, to spot the one saturating a link.
+    let total = app.session_stats().map_or(0.0, |s| {
+        if by_upload {
+            s.upload_speed.mbps
+        } else {
+            s.download_speed.mbps
+        }
+    });
+    let rows: Vec<Row> = talkers
+        .iter()
+        .enumerate()
+        .map(|(idx, talker)| {
+            let style = if idx == app.talker_selected() {
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            let rate = if by_upload { talker.up } else { talker.down };
+            let share = if total > 0.0 {
+                format!("{:.0}%", (rate / total * 100.0).min(100.0))
+            } else {
+                "-".to_string()
+            };
+            Row::new(vec![
+                Span::raw(talker.name.as_str()),
+                Span::styled(format!("{:.2} MiB/s", talker.down), Style::default().fg(theme.info)),
+                Span::styled(
+                    format!("{:.2} MiB/s", talker.up),
+                    Style::default().fg(theme.highlight),
+                ),
+                Span::raw(share),
+            ])
+            .style(style)
+        })
+        .collect();
+    let header =
+        Row::new(vec!["NAME", "DOWN", "UP", "SHARE"]).style(Style::default().fg(theme.muted));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(5),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+fn draw_archive(frame: &mut Frame, theme: &Theme, app: &App) {
+    let area = centered_rect(app.density(), 70, 50, frame.area());
+    frame.render_widget(Clear, area);
+    let title = match app.offloads_running() {
+        0 => "Archive".to_string(),
+        running => format!("Archive ({running} offloading)"),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(title, Style::default().fg(theme.accent)))
+        .title_bottom(Span::styled(
+            " [j/k] Select  [Enter] Restore and recheck  [Esc] Close ",
+            Style::default().fg(theme.muted),
+        ));
+    let archived = app.archived();
+    if archived.is_empty() {
+        frame.render_widget(
+            Paragraph::new("Nothing is archived yet; offloads in progress show up when done.")
+                .block(block),
+            area,
+        );
+        return;
+    }
+    let rows: Vec<Row> = archived
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let style = if idx == app.archive_selected() {
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            let name = match app.archive_move(&item.info_hash) {
+                Some(ArchiveDirection::Restore) => format!("{} (restoring...)", item.name),
+                _ => item.name.clone(),
+            };
+            Row::new(vec![
+                Span::raw(name),
+                Span::raw(format_bytes(item.bytes)),
+                Span::raw(item.archived_at.format("%Y-%m-%d").to_string()),
+                Span::styled(item.archived_folder.as_str(), Style::default().fg(theme.muted)),
+            ])
+            .style(style)
+        })
+        .collect();
+    let header = Row::new(vec!["NAME", "SIZE", "ARCHIVED", "PATH"])
+        .style(Style::default().fg(theme.muted));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(2),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Fill(2),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+fn draw_activity(frame: &mut Frame, theme: &Theme, app: &App, view: &ActivityView) {
+    let area = centered_rect(app.density(), 90, 70, frame.area());
+    frame.render_widget(Clear, area);
+    let entries = app.activity_entries();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(
+            format!("Activity: {} ({})", view.filter.label(), entries.len()),
+            Style::default().fg(theme.accent),
+        ))
+        .title_bottom(Span::styled(
+            " [j/k] Scroll  [f] Filter  [Esc] Close ",
+            Style::default().fg(theme.muted),
+        ));
+    if entries.is_empty() {
+        frame.render_widget(
+            Paragraph::new("Nothing matches this filter yet; [f] shows the next one.")
+                .block(block),
+            area,
+        );
+        return;
+    }
+    let rows: Vec<Row> = entries
+        .iter()
+        .skip(view.scroll)
+        .map(|entry| {
+            let (kind, rule, result) = match &entry.run {
+                Some(run) if run.ok => (run.kind.label(), run.rule.as_str(), Span::raw("ok")),
+                Some(run) => (
+                    run.kind.label(),
+                    run.rule.as_str(),
+                    Span::styled("failed", Style::default().fg(theme.error)),
+                ),
+                None => ("", "", Span::raw("")),
+            };
+            Row::new(vec![
+                Span::styled(
+                    entry.at.format("%m-%d %H:%M:%S").to_string(),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::raw(kind),
+                Span::raw(rule),
+                result,
+                Span::raw(app.masked(&entry.message).into_owned()),
+            ])
+            .style(Style::default().fg(theme.text))
+        })
+        .collect();
+    let header = Row::new(vec!["TIME", "KIND", "RULE", "RESULT", "MESSAGE"])
+        .style(Style::default().fg(theme.muted));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(14),
+            Constraint::Length(11),
+            Constraint::Length(22),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+fn draw_rule_tester(frame: &mut Frame, theme: &Theme, app: &App, tester: &RuleTester) {
+    let area = centered_rect(app.density(), 70, 60, frame.area());
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled("Test rules", Style::default().fg(theme.accent)))
+        .title_bottom(Span::styled(
+            " [Tab/Up/Down] Field  [Ctrl+U] Clear  [Esc] Close ",
+            Style::default().fg(theme.muted),
+        ));
+    let inner = block.inner(area);
+    let mut lines = Vec::new();
+    for (idx, (label, value)) in RULE_TESTER_FIELDS.iter().zip(&tester.fields).enumerate() {
+        let style = if idx == tester.field {
+            Style::default().fg(theme.accent)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{label:<9}"), style),
+            Span::styled(value.as_str(), Style::default().fg(theme.text)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    let checks = app.rule_checks();
+    if checks.is_empty() {
+        lines.push(Line::styled(
+            "Type a torrent name (and optionally a tracker URL and label) to see what applies.",
+            Style::default().fg(theme.muted),
+        ));
+    }
+    for check in &checks {
+        let style = if check.matched {
+            Style::default().fg(theme.accent)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<18}", check.rule), style),
+            Span::styled(check.outcome.as_str(), Style::default().fg(theme.text)),
+        ]));
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(block).wrap(Wrap { trim: false }),
+        area,
+    );
+    let field = &tester.fields[tester.field];
+    frame.set_cursor_position((
+        (inner.x + 9 + field.chars().count() as u16).min(inner.right().saturating_sub(1)),
+        inner.y + tester.field as u16,
+    ));
+}
+
+fn draw_schedule(frame: &mut Frame, theme: &Theme, app: &App, editor: &ScheduleEditor) {
+    // Five columns of day names, then three per hour.
+    const WIDTH: u16 = 5 + SCHEDULE_HOURS as u16 * 3 + 2;
+    let profiles = app.schedule_profiles();
+    let screen = frame.area();
+    let height = (SCHEDULE_DAYS.len() as u16 + profiles.len() as u16 + 4).min(screen.height);
+    let width = WIDTH.min(screen.width);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled("Speed schedule", Style::default().fg(theme.accent)))
+        .title_bottom(Span::styled(
+            " [Arrows] Move  [key] Set  [.] Leave as is  [Enter] Save  [Esc] Cancel ",
+            Style::default().fg(theme.muted),
+        ));
+    let hours: String = (0..SCHEDULE_HOURS).map(|hour| format!("{hour:>3}")).collect();
+    let mut lines = vec![Line::styled(
+        format!("     {hours}"),
+        Style::default().fg(theme.muted),
+    )];
+    for (day, (name, row)) in SCHEDULE_DAYS.iter().zip(&editor.week).enumerate() {
+        let mut spans = vec![Span::styled(format!("{name:<5}"), Style::default().fg(theme.muted))];
+        for (hour, &key) in row.iter().enumerate() {
+            let style = if day == editor.day && hour == editor.hour {
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else if key == KEEP_LIMITS {
+                Style::default().fg(theme.muted)
+            } else {
+                Style::default().fg(theme.info)
+            };
+            spans.push(Span::styled(format!(" {key} "), style));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+    for (key, profile) in profiles {
+        let name = profile.name.as_deref().unwrap_or("");
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {key}  "), Style::default().fg(theme.info)),
+            Span::styled(format!("{name:<16}"), Style::default().fg(theme.text)),
+            Span::styled(
+                format!(
+                    "down {}, up {}",
+                    format_limit(profile.download_kib.map(|kib| kib * 1024)),
+                    format_limit(profile.upload_kib.map(|kib| kib * 1024))
+                ),
+                Style::default().fg(theme.muted),
+            ),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_cast(frame: &mut Frame, theme: &Theme, density: Density, picker: &CastPicker) {
+    let area = centered_rect(density, 70, 50, frame.area());
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(
+            format!("Cast {}", picker.file.name),
+            Style::default().fg(theme.accent),
+        ))
+        .title_bottom(Span::styled(
+            " [j/k] Select  [Enter] Play  [r] Search again  [Esc] Close ",
+            Style::default().fg(theme.muted),
+        ));
+    let rows: Vec<Row> = picker
+        .devices
+        .iter()
+        .enumerate()
+        .map(|(idx, device)| {
+            let style = if idx == picker.selected {
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            Row::new(vec![
+                Span::raw(device.name.as_str()),
+                Span::styled(device.kind.label(), Style::default().fg(theme.info)),
+                Span::raw(device.addr.ip().to_string()),
+            ])
+            .style(style)
+        })
+        .collect();
+    let header =
+        Row::new(vec!["DEVICE", "TYPE", "ADDRESS"]).style(Style::default().fg(theme.muted));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Length(16),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+fn draw_torrent_file(frame: &mut Frame, theme: &Theme, app: &App, view: &TorrentFileView) {
+    let area = centered_rect(app.density(), 90, 80, frame.area());
     frame.render_widget(Clear, area);
+    let muted = Style::default().fg(theme.muted);
+    let lines: Vec<Line> = view
+        .info
+        .lines()
+        .into_iter()
+        .map(|line| {
+            if line.starts_with("Trackers:") || line.starts_with("Files (") {
+                Line::from(Span::styled(line, Style::default().fg(theme.info)))
+            } else {
+                Line::from(app.masked(&line).into_owned())
+            }
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled(
+            format!("Torrent file: {}", view.path.display()),
+            Style::default().fg(theme.accent),
+        ))
+        .title_bottom(Span::styled(" [j/k] Scroll  [a] Add  [Esc] Close ", muted));
+    let view_height = block.inner(area).height as usize;
+    let scroll = view.scroll.min(lines.len().saturating_sub(view_height) as u16);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+fn draw_duplicates(frame: &mut Frame, theme: &Theme, app: &App, report: &DuplicateReport) {
+    let area = centered_rect(app.density(), 90, 80, frame.area());
+    frame.render_widget(Clear, area);
+    let muted = Style::default().fg(theme.muted);
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{} duplicated file{}, {} reclaimable",
+            report.groups.len(),
+            if report.groups.len() == 1 { "" } else { "s" },
+            format_bytes(report.wasted())
+        ),
+        Style::default().fg(theme.highlight),
+    ))];
+    if report.unknown > 0 {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{} torrent(s) couldn't be fully compared (no metadata yet, or files sharing \
+                 pieces)",
+                report.unknown
+            ),
+            muted,
+        )));
+    }
+    for group in &report.groups {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{} x{}, {} reclaimable",
+                format_bytes(group.len),
+                group.copies.len(),
+                format_bytes(group.wasted())
+            ),
+            Style::default().fg(theme.info),
+        )));
+        for copy in &group.copies {
+            lines.push(Line::from(format!(
+                "  {}: {}",
+                copy.torrent,
+                app.masked(&copy.path.to_string_lossy())
+            )));
+        }
+        for suggestion in group.suggestions() {
+            lines.push(Line::from(Span::styled(format!("    {suggestion}"), muted)));
+        }
+    }
+    if !report.redundant.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Safe to remove (every file is also in another torrent)",
+            Style::default().fg(theme.highlight),
+        )));
+        for r in &report.redundant {
+            lines.push(Line::from(format!(
+                "  {} ({}), covered by {}",
+                r.torrent,
+                format_bytes(r.bytes),
+                r.covered_by
+            )));
+        }
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled("Duplicate content", Style::default().fg(theme.accent)))
+        .title_bottom(Span::styled(" [j/k] Scroll  [Esc] Close ", muted));
+    let view_height = block.inner(area).height as usize;
+    let scroll = report.scroll.min(lines.len().saturating_sub(view_height) as u16);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+fn draw_inspector(frame: &mut Frame, theme: &Theme, density: Density, inspector: &JsonInspector) {
+    let area = centered_rect(density, 90, 85, frame.area());
+    frame.render_widget(Clear, area);
+    let title = match inspector.query() {
+        Some(query) => format!("Raw JSON: {} [/{query}]", inspector.title),
+        None => format!("Raw JSON: {}", inspector.title),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.info))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled(title, Style::default().fg(theme.info)));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let body_height = inner.height.saturating_sub(1) as usize;
+    let lines = inspector.lines();
+    let cursor = inspector.cursor();
+    let offset = cursor.saturating_sub(body_height.saturating_sub(1));
+    let rendered: Vec<Line> = lines
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(body_height)
+        .map(|(idx, line)| {
+            let marker = match (line.foldable, line.collapsed) {
+                (true, true) => "▸ ",
+                (true, false) => "▾ ",
+                _ => "  ",
+            };
+            let key = line.key.as_deref().map(|k| format!("{k}: ")).unwrap_or_default();
+            let value_color = if line.foldable {
+                theme.muted
+            } else if inspector.is_match(line) {
+                theme.highlight
+            } else {
+                theme.text
+            };
+            let key_color = if inspector.is_match(line) {
+                theme.highlight
+            } else {
+                theme.accent
+            };
+            let mut out = Line::from(vec![
+                Span::raw("  ".repeat(line.depth)),
+                Span::styled(marker, Style::default().fg(theme.muted)),
+                Span::styled(key, Style::default().fg(key_color)),
+                Span::styled(line.value.clone(), Style::default().fg(value_color)),
+            ]);
+            if idx == cursor {
+                out = out.style(Style::default().bg(theme.row_highlight));
+            }
+            out
+        })
+        .collect();
+    let body = Rect::new(inner.x, inner.y, inner.width, body_height as u16);
+    frame.render_widget(Paragraph::new(Text::from(rendered)), body);
+
+    let footer = Rect::new(
+        inner.x,
+        inner.y + inner.height.saturating_sub(1),
+        inner.width,
+        1,
+    );
+    frame.render_widget(
+        Paragraph::new(format!(
+            "[Enter] Fold  [c/e] Collapse/Expand all  [/] Search  [n/N] Next/Prev  [r] Reload  [x] Close   {}/{}",
+            (cursor + 1).min(lines.len()),
+            lines.len()
+        ))
+        .style(Style::default().fg(theme.muted)),
+        footer,
+    );
+}
+
+fn draw_debug_overlay(frame: &mut Frame, theme: &Theme, app: &App) {
+    let debug = app.debug();
+    let ms = |d: std::time::Duration| format!("{:.1}ms", d.as_secs_f64() * 1000.0);
+    let effect = debug
+        .effect_last
+        .map(|(name, d)| format!("{name} {}", ms(d)))
+        .unwrap_or_else(|| "-".to_string());
+    let rows = [
+        ("Frames", debug.frames.to_string()),
+        (
+            "Draw",
+            format!(
+                "{} avg {} max {}",
+                ms(debug.draw_last),
+                ms(debug.draw_avg),
+                ms(debug.draw_max)
+            ),
+        ),
+        ("Refresh", ms(debug.refresh_last)),
+        (
+            "Tick",
+            if app.focused() {
+                ms(app.tick_interval())
+            } else {
+                format!("{} (unfocused)", ms(app.tick_interval()))
+            },
+        ),
+        ("Effect", effect),
+        ("Effect max", ms(debug.effect_max)),
+        ("Queue peak", debug.queue_peak.to_string()),
+        ("Event backlog", debug.event_backlog.to_string()),
+        ("Torrents", app.torrents().len().to_string()),
+        ("RSS", debug.rss.map_or_else(|| "-".to_string(), format_bytes)),
+    ];
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{label:<14}"), Style::default().fg(theme.muted)),
+                Span::styled(value, Style::default().fg(theme.text)),
+            ])
+        })
+        .collect();
+    let screen = frame.area();
+    let width = 46.min(screen.width);
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    let area = Rect::new(screen.x + screen.width - width, screen.y + 1, width, height);
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.highlight))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Debug (F12)", Style::default().fg(theme.highlight)));
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
+
+/// Corner panel listing the adds still fetching metadata or waiting for their prompt.
+/// Returns its height.
+fn draw_adds(frame: &mut Frame, theme: &Theme, app: &App) -> u16 {
+    const WIDTH: u16 = 56;
+    let shown = app.adds().len().min(5);
+    let mut lines: Vec<Line> = app
+        .adds()
+        .iter()
+        .take(shown)
+        .map(|op| {
+            let (state, color) = match &op.stage {
+                AddStage::Fetching => (
+                    format!("fetching {}s", op.started.elapsed().as_secs()),
+                    theme.info,
+                ),
+                AddStage::Retrying {
+                    attempts, next_at, ..
+                } => (
+                    format!(
+                        "retry {} in {}s",
+                        attempts + 1,
+                        next_at
+                            .saturating_duration_since(std::time::Instant::now())
+                            .as_secs_f64()
+                            .ceil() as u64
+                    ),
+                    theme.highlight,
+                ),
+                AddStage::Ready => ("ready".to_string(), theme.accent),
+                AddStage::Prompting => ("choose folder".to_string(), theme.accent),
+            };
+            let name = op
+                .name
+                .clone()
+                .unwrap_or_else(|| app.masked(&op.input).into_owned());
+            let room = (WIDTH as usize).saturating_sub(state.chars().count() + 5);
+            let name = if name.chars().count() > room {
+                let cut: String = name.chars().take(room.saturating_sub(1)).collect();
+                format!("{cut}…")
+            } else {
+                name
+            };
+            Line::from(vec![
+                Span::raw(format!("{name:<room$} ")),
+                Span::styled(state, Style::default().fg(color)),
+            ])
+        })
+        .collect();
+    if app.adds().len() > shown {
+        lines.push(Line::from(Span::styled(
+            format!("... and {} more", app.adds().len() - shown),
+            Style::default().fg(theme.muted),
+        )));
+    }
+    let full = frame.area();
+    let width = WIDTH.min(full.width);
+    let height = (lines.len() as u16 + 2).min(full.height);
+    let area = Rect::new(
+        full.x + full.width - width,
+        (full.y + full.height).saturating_sub(height + 1),
+        width,
+        height,
+    )
+    .intersection(full);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.highlight))
+                .style(Style::default().bg(theme.panel))
+                .title(Span::styled("Adding…", Style::default().fg(theme.highlight))),
+        ),
+        area,
+    );
+    area.height
+}
+
+/// Progress bars for reported operations without a dialog of their own (data checks),
+/// stacked `below` rows above the bottom-right corner.
+fn draw_progress_panel(frame: &mut Frame, theme: &Theme, app: &App, below: u16) {
+    const WIDTH: u16 = 56;
+    let entries: Vec<_> = app
+        .progress()
+        .iter()
+        .filter(|(id, _)| !matches!(*id, "import" | "maintenance"))
+        .take(5)
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+    let full = frame.area();
+    let width = WIDTH.min(full.width);
+    let height = entries.len() as u16 + 2;
+    let area = Rect::new(
+        full.x + full.width - width,
+        (full.y + full.height).saturating_sub(height + below + 1),
+        width,
+        height,
+    )
+    .intersection(full);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.highlight))
+        .style(Style::default().bg(theme.panel))
+        .title(Span::styled("Progress", Style::default().fg(theme.highlight)));
+    frame.render_widget(Clear, area);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); entries.len()])
+        .split(inner);
+    for ((_, entry), row) in entries.iter().zip(rows.iter()) {
+        let label = match entry.pct {
+            Some(pct) => format!("{} {pct}%", entry.msg),
+            None => entry.msg.clone(),
+        };
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(theme.gauge).bg(theme.bg))
+                .label(Span::styled(label, Style::default().fg(theme.text)))
+                .ratio(entry.pct.unwrap_or(0) as f64 / 100.0),
+            *row,
+        );
+    }
+}
+
+fn draw_toast(frame: &mut Frame, theme: &Theme, message: &str) {
+    let full = frame.area();
+    let width = (message.chars().count() as u16 + 4).min(full.width);
+    let area = Rect::new(full.x + full.width - width, full.y + 1, width, 3).intersection(full);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(message.to_string())
+            .style(Style::default().fg(theme.text).bg(theme.panel))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.highlight)),
+            ),
+        area,
+    );
+}
+
+fn draw_error_modal(frame: &mut Frame, theme: &Theme, density: Density, message: &str) {
+    let area = centered_rect(density, 70, 30, frame.area());
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.error))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Error", Style::default().fg(theme.error)));
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "An error occurred",
+            Style::default().fg(theme.error),
+        )),
+        Line::from(""),
+    ];
+    if let Some((head, tail)) = message.split_once("Caused by:") {
+        let head = head.trim();
+        if !head.is_empty() {
+            lines.push(Line::from(Span::styled(
+                head,
+                Style::default().fg(theme.text),
+            )));
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            "Caused by:",
+            Style::default().fg(theme.error),
+        )));
+        let tail = tail.trim();
+        if !tail.is_empty() {
+            lines.push(Line::from(Span::styled(
+                tail,
+                Style::default().fg(theme.text),
+            )));
+        }
+    } else {
+        lines.push(Line::from(Span::styled(
+            message,
+            Style::default().fg(theme.text),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press x to dismiss",
+        Style::default().fg(theme.muted),
+    )));
+
+    let text = Text::from(lines);
+
+    let paragraph = Paragraph::new(text).block(block).alignment(Alignment::Left);
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_help_modal(frame: &mut Frame, theme: &Theme, density: Density, scroll: u16) {
+    let area = centered_rect(density, 70, 40, frame.area());
+    frame.render_widget(Clear, area);
+    let lines = vec![
+        Line::from(""),
+        Line::from("Selection"),
+        Line::from("  [TAB]  Select Filters/Torrents"),
+        Line::from("  [↑/↓]  Select item"),
+        Line::from(""),
+        Line::from("Actions"),
+        Line::from("  [d]  Delete (confirm dialog)"),
+        Line::from("  [p]  Pause/Resume"),
+        Line::from("  [F]  Force start (ignores pause-all and power/load pauses)"),
+        Line::from("  [a]  Add torrent"),
+        Line::from("  [L]  Set label"),
+        Line::from("  [n]  Rename (display name)"),
+        Line::from("  [m]  Locate moved data and recheck"),
+        Line::from("  [N]  Edit notes"),
+        Line::from("  [w]  Resize table columns"),
+        Line::from("  [+/-] Download limit, ]/[ upload limit, [=] clear"),
+        Line::from("  [G]  Group by state / label / tracker"),
+        Line::from("  [z]  Collapse or expand a group"),
+        Line::from("  [J]  Inspect raw JSON"),
+        Line::from("  [T]  Background tasks (fetches, checks, imports)"),
+        Line::from("  [b]  Top talkers: torrents using the most bandwidth"),
+        Line::from(""),
+        Line::from("Views"),
+        Line::from("  [f]  Torrents"),
+        Line::from("  [o]  Files of the selected torrent ([y] copies a stream URL, [c] casts)"),
+        Line::from("  [Enter] Actions for the selected torrent (right-click with [ui] mouse)"),
+        Line::from("         Files in it opens the largest file's row; there [o] opens it"),
+        Line::from("  [v]  Peers ([j/k] select, [y] copies the address)"),
+        Line::from("  [u]  Trackers by tier, with scrape status and failures in a row"),
+        Line::from("  [i]  Info ([j/k]/PgUp/PgDn scroll, Home to the top, [y] copies the hash)"),
+        Line::from("  [e]  ETA timeline"),
+        Line::from("  [l]  Torrent event log"),
+        Line::from("  [R]  RSS feeds, rules and recent matches"),
+        Line::from("  [F12] Debug overlay"),
+        Line::from("  [K]  Lock the keyboard (type the unlock sequence to undo)"),
+        Line::from("  [C]  Switch colour scheme"),
+        Line::from(""),
+        Line::from("Commands"),
+        Line::from("  [:]  set tick <interval>, set refresh <interval>"),
+        Line::from("       set quit <dialog|twice>, set density <comfortable|compact>"),
+        Line::from("       pause-all, resume-all"),
+        Line::from("       recheck-all, reannounce-all, cancel-adds, tasks"),
+        Line::from("       duplicates, inspect <file.torrent>"),
+        Line::from("       offload, archive, test-rules, schedule, debug-announce"),
+        Line::from("       activity [failed|automation|hook|budget|move|...]"),
+        Line::from("       export [--all] [--ansi] <file>"),
+        Line::from(""),
+        Line::from("Exit"),
+        Line::from("  [q]  Quit"),
+        Line::from(""),
+        Line::from("Press ? / x / Esc to close"),
+    ];
+    let text = Text::from(lines.clone());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.info))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Help", Style::default().fg(theme.info)));
+    let inner = block.inner(area);
+    let view_height = inner.height.saturating_sub(1) as usize;
+    let max_scroll = lines.len().saturating_sub(view_height) as u16;
+    let scroll = scroll.min(max_scroll);
+    frame.render_widget(
+        Paragraph::new(text).block(block).scroll((scroll, 0)),
+        area,
+    );
+    if max_scroll > 0 {
+        let indicator = format!("Scroll {}/{}", scroll, max_scroll);
+        let indicator_area = Rect::new(
+            inner.x,
+            inner.y + inner.height.saturating_sub(1),
+            inner.width,
+            1,
+        );
+        frame.render_widget(
+            Paragraph::new(indicator)
+                .style(Style::default().fg(theme.muted))
+                .alignment(Alignment::Right),
+            indicator_area,
+        );
+    }
+}
+fn filter_line(
+    theme: &Theme,
+    focus: FocusPanel,
+    is_selected: bool,
+    label: String,
+) -> Line<'static> {
+    let style = if is_selected {
+        match focus {
+            FocusPanel::Filters => Style::default().bg(theme.accent).fg(theme.on_accent),
+            FocusPanel::Torrents => Style::default().bg(theme.row_highlight).fg(theme.accent),
+        }
+    } else {
+        Style::default().fg(theme.muted)
+    };
+    let prefix = if is_selected { "> " } else { "  " };
+    Line::from(Span::styled(format!("{prefix}{label}"), style))
+}
+
+fn draw_confirm_delete(frame: &mut Frame, theme: &Theme, app: &App) {
+    let name = app
+        .selected_torrent()
+        .map(|t| t.name.as_str())
+        .unwrap_or("-");
+    let yes_style = if app.delete_choice() {
+        Style::default().bg(theme.warning).fg(theme.on_accent)
+    } else {
+        Style::default().fg(theme.warning)
+    };
+    let no_style = if app.delete_choice() {
+        Style::default().fg(theme.muted)
+    } else {
+        Style::default().bg(theme.warning).fg(theme.on_accent)
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            "Delete files on disk too?",
+            Style::default().fg(theme.warning),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(name, Style::default().fg(theme.text))),
+        Line::from(""),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[Y]es", yes_style),
+            Span::raw("   "),
+            Span::styled("[N]o", no_style),
+        ]),
+        Line::from(Span::styled(
+            "[<-] [->] Select  [Enter] Confirm  [Esc] Cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+    let text = Text::from(lines.clone());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Confirm", Style::default().fg(theme.warning)));
+    let area_height = ((lines.len() + 2) as u16)
+        .min(frame.area().height.saturating_sub(2))
+        .max(7);
+    let area = centered_rect_fixed(app.density(), 70, area_height, frame.area());
+    frame.render_widget(Clear, area);
+    let inner = block.inner(area);
+    let view_height = inner.height.saturating_sub(1) as usize;
+    let scroll = lines.len().saturating_sub(view_height) as u16;
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+fn draw_confirm_quit(frame: &mut Frame, theme: &Theme, app: &App) {
+    let yes_style = if app.quit_choice() {
+        Style::default().bg(theme.warning).fg(theme.on_accent)
+    } else {
+        Style::default().fg(theme.warning)
+    };
+    let no_style = if app.quit_choice() {
+        Style::default().fg(theme.muted)
+    } else {
+        Style::default().bg(theme.warning).fg(theme.on_accent)
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            "Are you sure you want to quit?",
+            Style::default().fg(theme.warning),
+        )),
+        Line::from(""),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[Y]es", yes_style),
+            Span::raw("   "),
+            Span::styled("[N]o", no_style),
+        ]),
+        Line::from(Span::styled(
+            "[<-] [->] Select  [Enter] Confirm  [Esc] Cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+    let text = Text::from(lines.clone());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Confirm", Style::default().fg(theme.warning)));
+    let area_height = ((lines.len() + 2) as u16)
+        .min(frame.area().height.saturating_sub(2))
+        .max(6);
+    let area = centered_rect_fixed(app.density(), 70, area_height, frame.area());
+    frame.render_widget(Clear, area);
+    let inner = block.inner(area);
+    let view_height = inner.height.saturating_sub(1) as usize;
+    let scroll = lines.len().saturating_sub(view_height) as u16;
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+fn draw_confirm_space(frame: &mut Frame, theme: &Theme, app: &App) {
+    let Some(add) = app.confirm_space() else {
+        return;
+    };
+    let yes_style = if app.space_choice() {
+        Style::default().bg(theme.warning).fg(theme.on_accent)
+    } else {
+        Style::default().fg(theme.warning)
+    };
+    let no_style = if app.space_choice() {
+        Style::default().fg(theme.muted)
+    } else {
+        Style::default().bg(theme.warning).fg(theme.on_accent)
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            "Not enough free disk space",
+            Style::default().fg(theme.warning),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            add.name.as_str(),
+            Style::default().fg(theme.text),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "Needs {} (plus in-progress downloads), {} free",
+                format_bytes(add.bytes),
+                format_bytes(app.space_free())
+            ),
+            Style::default().fg(theme.muted),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Queue it until enough space is free?",
+            Style::default().fg(theme.text),
+        )),
+        Line::from(vec![
+            Span::styled("[Y]es", yes_style),
+            Span::raw("   "),
+            Span::styled("[N]o", no_style),
+        ]),
+        Line::from(Span::styled(
+            "[<-] [->] Select  [Enter] Confirm  [Esc] Cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+    let text = Text::from(lines.clone());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Confirm", Style::default().fg(theme.warning)));
+    let area_height = ((lines.len() + 2) as u16)
+        .min(frame.area().height.saturating_sub(2))
+        .max(7);
+    let area = centered_rect_fixed(app.density(), 70, area_height, frame.area());
+    frame.render_widget(Clear, area);
+    let inner = block.inner(area);
+    let view_height = inner.height.saturating_sub(1) as usize;
+    let scroll = lines.len().saturating_sub(view_height) as u16;
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+fn draw_confirm_risky(
+    frame: &mut Frame,
+    theme: &Theme,
+    density: Density,
+    picker: &FilePickerState,
+    choice: bool,
+) {
+    let (yes_style, no_style) = if choice {
+        (
+            Style::default().bg(theme.risky).fg(theme.on_accent),
+            Style::default().fg(theme.muted),
+        )
+    } else {
+        (
+            Style::default().fg(theme.risky),
+            Style::default().bg(theme.risky).fg(theme.on_accent),
+        )
+    };
+    let selected: Vec<&str> = picker
+        .files
+        .iter()
+        .filter(|f| f.included && f.risky)
+        .map(|f| f.name.as_str())
+        .collect();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} executable or script files selected", selected.len()),
+            Style::default().fg(theme.risky),
+        )),
+        Line::from(""),
+    ];
+    for name in selected.iter().take(5) {
+        lines.push(Line::from(Span::styled(*name, Style::default().fg(theme.text))));
+    }
+    if selected.len() > 5 {
+        lines.push(Line::from(Span::styled(
+            format!("and {} more", selected.len() - 5),
+            Style::default().fg(theme.muted),
+        )));
+    }
+    lines.extend([
+        Line::from(""),
+        Line::from(Span::styled(
+            "Files like these are a common way to spread malware. Download them anyway?",
+            Style::default().fg(theme.text),
+        )),
+        Line::from(vec![
+            Span::styled("[Y]es", yes_style),
+            Span::raw("   "),
+            Span::styled("[N]o", no_style),
+        ]),
+        Line::from(Span::styled(
+            "[<-] [->] Select  [Enter] Confirm  [Esc] Back",
+            Style::default().fg(theme.muted),
+        )),
+    ]);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.risky))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Confirm", Style::default().fg(theme.risky)));
+    let height = ((lines.len() + 2) as u16).min(frame.area().height.saturating_sub(2));
+    let area = centered_rect_fixed(density, 70, height, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn draw_collision(frame: &mut Frame, theme: &Theme, app: &App) {
+    let Some(collision) = app.collision() else {
+        return;
+    };
+    let mut choices = Vec::new();
+    for (idx, strategy) in CollisionStrategy::CHOICES.iter().enumerate() {
+        let style = if idx == app.collision_choice() {
+            Style::default().bg(theme.warning).fg(theme.on_accent)
+        } else {
+            Style::default().fg(theme.warning)
+        };
+        if idx > 0 {
+            choices.push(Span::raw("   "));
+        }
+        choices.push(Span::styled(format!("[{}] {}", idx + 1, strategy.label()), style));
+    }
+    let lines = vec![
+        Line::from(Span::styled(
+            "Destination folder already exists",
+            Style::default().fg(theme.warning),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            collision.base.join(&collision.folder_name).display().to_string(),
+            Style::default().fg(theme.text),
+        )),
+        Line::from(""),
+        Line::from(choices),
+        Line::from(Span::styled(
+            "[<-] [->] Select  [Enter] Confirm  [Esc] Cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Folder exists", Style::default().fg(theme.warning)));
+    let area_height = ((lines.len() + 2) as u16)
+        .min(frame.area().height.saturating_sub(2))
+        .max(7);
+    let area = centered_rect_fixed(app.density(), 70, area_height, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn draw_import(frame: &mut Frame, theme: &Theme, app: &App, job: &ImportJob) {
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Importing {}: {} of {}", job.file, job.done(), job.total),
+            Style::default().fg(theme.warning),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "Added: {}   Duplicates: {}   Failed: {}",
+            job.added,
+            job.duplicates,
+            job.failures.len()
+        )),
+    ];
+    if let Some((item, retry)) = job.waiting.as_ref().filter(|_| !job.finished()) {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Retrying {} in {}s ({} failed): {}",
+                item.magnet,
+                retry.wait().as_secs_f64().ceil() as u64,
+                retry.attempts,
+                app.masked(&retry.last_error)
+            ),
+            Style::default().fg(theme.warning),
+        )));
+    }
+    if let Some(next) = job.queue.front().filter(|_| !job.finished()) {
+        lines.push(Line::from(Span::styled(
+            format!("Next: {}", next.magnet),
+            Style::default().fg(theme.muted),
+        )));
+    }
+    if !job.failures.is_empty() {
+        lines.push(Line::from(""));
+        for (magnet, err) in job.failures.iter().take(8) {
+            lines.push(Line::from(Span::styled(
+                format!("{magnet}: {err}"),
+                Style::default().fg(theme.error),
+            )));
+        }
+        if job.failures.len() > 8 {
+            lines.push(Line::from(format!("... and {} more", job.failures.len() - 8)));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        if job.finished() {
+            "[Enter] Close"
+        } else {
+            "[Esc] Stop after the current torrent"
+        },
+        Style::default().fg(theme.muted),
+    )));
+    let title = if job.cancelled {
+        "Import stopped"
+    } else if job.finished() {
+        "Import finished"
+    } else {
+        "Import"
+    };
+    let ratio = progress_ratio(app, "import", job.finished());
+    draw_progress_dialog(frame, theme, app.density(), title, ratio, lines);
+}
+
+fn draw_confirm_maintenance(frame: &mut Frame, theme: &Theme, app: &App, kind: MaintenanceKind) {
+    let (yes_style, no_style) = if app.maintenance_choice() {
+        (
+            Style::default().bg(theme.warning).fg(theme.on_accent),
+            Style::default().fg(theme.muted),
+        )
+    } else {
+        (
+            Style::default().fg(theme.warning),
+            Style::default().bg(theme.warning).fg(theme.on_accent),
+        )
+    };
+    let count = app.torrents().len();
+    let detail = match kind {
+        MaintenanceKind::Recheck => {
+            "Each torrent is re-added in place and its data verified, one at a time. \
+             Running torrents stay running; the rest stay paused."
+        }
+        MaintenanceKind::Reannounce => {
+            "Each running torrent is restarted so it announces to its trackers again, \
+             one at a time. Paused torrents are skipped."
+        }
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("{} all {count} torrents?", kind.label()),
+            Style::default().fg(theme.warning),
+        )),
+        Line::from(""),
+        Line::from(detail),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[Y]es", yes_style),
+            Span::raw("   "),
+            Span::styled("[N]o", no_style),
+        ]),
+        Line::from(Span::styled(
+            "[<-] [->] Select  [Enter] Confirm  [Esc] Cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ];
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red))
-        .style(Style::default().bg(COLOR_BG))
-        .title(Span::styled("Error", Style::default().fg(Color::Red)));
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Confirm", Style::default().fg(theme.warning)));
+    let area_height = ((lines.len() + 4) as u16).min(frame.area().height.saturating_sub(2));
+    let area = centered_rect_fixed(app.density(), 70, area_height, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
 
+fn draw_maintenance(frame: &mut Frame, theme: &Theme, app: &App, job: &MaintenanceJob) {
     let mut lines = vec![
         Line::from(Span::styled(
-            "An error occurred",
-            Style::default().fg(Color::Red),
+            format!("{}: {} of {}", job.kind.verb(), job.handled(), job.total),
+            Style::default().fg(theme.warning),
         )),
         Line::from(""),
+        Line::from(format!(
+            "Done: {}   Skipped: {}   Failed: {}",
+            job.done,
+            job.skipped,
+            job.failures.len()
+        )),
     ];
-    if let Some((head, tail)) = message.split_once("Caused by:") {
-        let head = head.trim();
-        if !head.is_empty() {
+    if !job.failures.is_empty() {
+        lines.push(Line::from(""));
+        for (name, err) in job.failures.iter().take(8) {
             lines.push(Line::from(Span::styled(
-                head,
-                Style::default().fg(Color::White),
+                format!("{name}: {err}"),
+                Style::default().fg(theme.error),
             )));
-            lines.push(Line::from(""));
         }
-        lines.push(Line::from(Span::styled(
-            "Caused by:",
-            Style::default().fg(Color::Red),
-        )));
-        let tail = tail.trim();
-        if !tail.is_empty() {
-            lines.push(Line::from(Span::styled(
-                tail,
-                Style::default().fg(Color::White),
-            )));
+        if job.failures.len() > 8 {
+            lines.push(Line::from(format!("... and {} more", job.failures.len() - 8)));
         }
-    } else {
-        lines.push(Line::from(Span::styled(
-            message,
-            Style::default().fg(Color::White),
-        )));
     }
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
-        "Press x to dismiss",
-        Style::default().fg(COLOR_MUTED),
+        if job.finished() {
+            "[Enter] Close"
+        } else {
+            "[Esc] Stop after the current torrent"
+        },
+        Style::default().fg(theme.muted),
     )));
+    let title = if job.cancelled && job.finished() {
+        format!("{} stopped", job.kind.label())
+    } else if job.finished() {
+        format!("{} finished", job.kind.label())
+    } else {
+        job.kind.label().to_string()
+    };
+    let ratio = progress_ratio(app, "maintenance", job.finished());
+    draw_progress_dialog(frame, theme, app.density(), &title, ratio, lines);
+}
 
-    let text = Text::from(lines);
-
-    let paragraph = Paragraph::new(text).block(block).alignment(Alignment::Left);
-    frame.render_widget(paragraph, area);
+/// How far the operation reported as `id` has got; it's only reported while running.
+fn progress_ratio(app: &App, id: &str, finished: bool) -> f64 {
+    match app.progress().get(id) {
+        Some(entry) => entry.pct.unwrap_or(0) as f64 / 100.0,
+        None if finished => 1.0,
+        None => 0.0,
+    }
 }
 
-fn draw_help_modal(frame: &mut Frame, scroll: u16) {
-    let area = centered_rect(70, 40, frame.area());
-    frame.render_widget(Clear, area);
-    let lines = vec![
-        Line::from(""),
-        Line::from("Selection"),
-        Line::from("  [TAB]  Select Filters/Torrents"),
-        Line::from("  [↑/↓]  Select item"),
-        Line::from(""),
-        Line::from("Actions"),
-        Line::from("  [d]  Delete (confirm dialog)"),
-        Line::from("  [p]  Pause/Resume"),
-        Line::from("  [a]  Add torrent"),
-        Line::from(""),
-        Line::from("Views"),
-        Line::from("  [f]  Files"),
-        Line::from("  [v]  Peers"),
-        Line::from("  [i]  Info"),
-        Line::from(""),
-        Line::from("Exit"),
-        Line::from("  [q]  Quit"),
-        Line::from(""),
-        Line::from("Press ? / x / Esc to close"),
-    ];
-    let text = Text::from(lines.clone());
+/// A dialog with a progress gauge above `lines`.
+fn draw_progress_dialog(
+    frame: &mut Frame,
+    theme: &Theme,
+    density: Density,
+    title: &str,
+    ratio: f64,
+    lines: Vec<Line>,
+) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(COLOR_CYAN))
-        .style(Style::default().bg(COLOR_BG))
-        .title(Span::styled("Help", Style::default().fg(COLOR_CYAN)));
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled(title.to_string(), Style::default().fg(theme.warning)));
+    let area_height = ((lines.len() + 3) as u16)
+        .min(frame.area().height.saturating_sub(2))
+        .max(8);
+    let area = centered_rect_fixed(density, 70, area_height, frame.area());
+    frame.render_widget(Clear, area);
     let inner = block.inner(area);
-    let view_height = inner.height.saturating_sub(1) as usize;
-    let max_scroll = lines.len().saturating_sub(view_height) as u16;
-    let scroll = scroll.min(max_scroll);
+    frame.render_widget(block, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
     frame.render_widget(
-        Paragraph::new(text).block(block).scroll((scroll, 0)),
-        area,
+        Gauge::default()
+            .gauge_style(Style::default().fg(theme.accent).bg(theme.panel))
+            .ratio(ratio.clamp(0.0, 1.0)),
+        chunks[0],
+    );
+    frame.render_widget(
+        Paragraph::new(Text::from(lines)).wrap(Wrap { trim: false }),
+        chunks[1],
     );
-    if max_scroll > 0 {
-        let indicator = format!("Scroll {}/{}", scroll, max_scroll);
-        let indicator_area = Rect::new(
-            inner.x,
-            inner.y + inner.height.saturating_sub(1),
-            inner.width,
-            1,
-        );
-        frame.render_widget(
-            Paragraph::new(indicator)
-                .style(Style::default().fg(COLOR_MUTED))
-                .alignment(Alignment::Right),
-            indicator_area,
-        );
-    }
 }
-fn filter_line(
-    focus: FocusPanel,
-    selected: crate::app::FilterKind,
-    kind: crate::app::FilterKind,
-    label: String,
-) -> Line<'static> {
-    let is_selected = selected == kind;
-    let style = if is_selected {
-        match focus {
-            FocusPanel::Filters => Style::default().bg(COLOR_GREEN).fg(COLOR_BLACK),
-            FocusPanel::Torrents => Style::default().bg(Color::Rgb(0, 70, 0)).fg(COLOR_GREEN),
-        }
-    } else {
-        Style::default().fg(COLOR_MUTED)
-    };
-    let prefix = if is_selected { "> " } else { "  " };
-    Line::from(Span::styled(format!("{prefix}{label}"), style))
+
+fn draw_busy(frame: &mut Frame, theme: &Theme, density: Density, busy: &BusyEffect) {
+    let lines = vec![
+        Line::from(format!("{}...", busy.label)),
+        Line::from(""),
+        Line::from(Span::styled("[Esc] Cancel", Style::default().fg(theme.muted))),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Working", Style::default().fg(theme.accent)));
+    let area = centered_rect_fixed(density, 50, 5, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
 }
 
-fn draw_confirm_delete(frame: &mut Frame, app: &App) {
-    let name = app
-        .selected_torrent()
-        .map(|t| t.name.as_str())
-        .unwrap_or("-");
-    let yes_style = if app.delete_choice() {
-        Style::default().bg(Color::Yellow).fg(Color::Black)
-    } else {
-        Style::default().fg(Color::Yellow)
-    };
-    let no_style = if app.delete_choice() {
-        Style::default().fg(COLOR_MUTED)
-    } else {
-        Style::default().bg(Color::Yellow).fg(Color::Black)
+fn draw_retry(frame: &mut Frame, theme: &Theme, app: &App, retry: &PendingRetry) {
+    let seconds = |d: Duration| format!("{}s", d.as_secs_f64().ceil() as u64);
+    let next = match retry.wait() {
+        Some(wait) => format!("Retrying in {}", seconds(wait)),
+        None => "Retrying now...".to_string(),
     };
     let lines = vec![
         Line::from(Span::styled(
-            "Delete files on disk too?",
-            Style::default().fg(Color::Yellow),
+            format!(
+                "{} failed ({} attempt{})",
+                retry.label(),
+                retry.attempts,
+                if retry.attempts == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(theme.warning),
         )),
         Line::from(""),
-        Line::from(Span::styled(name, Style::default().fg(Color::White))),
+        Line::from(Span::styled(
+            app.masked(&retry.last_error).into_owned(),
+            Style::default().fg(theme.error),
+        )),
         Line::from(""),
+        Line::from(format!(
+            "{next}; giving up in {}",
+            seconds(retry.give_up_in())
+        )),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("[Y]es", yes_style),
-            Span::raw("   "),
-            Span::styled("[N]o", no_style),
-        ]),
         Line::from(Span::styled(
-            "[<-] [->] Select  [Enter] Confirm  [Esc] Cancel",
-            Style::default().fg(COLOR_MUTED),
+            "[Enter] Retry now   [Esc] Cancel",
+            Style::default().fg(theme.muted),
         )),
     ];
-    let text = Text::from(lines.clone());
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
-        .style(Style::default().bg(COLOR_BG))
-        .title(Span::styled("Confirm", Style::default().fg(Color::Yellow)));
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Retrying", Style::default().fg(theme.warning)));
+    let area = centered_rect_fixed(app.density(), 70, 10, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn draw_magnet_preview(frame: &mut Frame, theme: &Theme, app: &App, preview: &MagnetPreview) {
+    let link = &preview.link;
+    let label = |text: &str| Span::styled(format!("{text:<11}"), Style::default().fg(theme.muted));
+    let hash_kind = if link.info_hash.len() == 64 { " (v2)" } else { "" };
+    let mut lines = vec![
+        Line::from(vec![
+            label("Name"),
+            match &link.name {
+                Some(name) => Span::styled(name.clone(), Style::default().fg(theme.text)),
+                None => Span::styled("(none given)", Style::default().fg(theme.muted)),
+            },
+        ]),
+        Line::from(vec![
+            label("Info hash"),
+            Span::styled(
+                format!("{}{hash_kind}", link.info_hash),
+                Style::default().fg(theme.info),
+            ),
+        ]),
+        Line::from(vec![
+            label("Size"),
+            Span::raw(link.length.map_or("unknown until fetched".to_string(), format_bytes)),
+        ]),
+        Line::from(""),
+    ];
+    if link.trackers.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No trackers: peers can only be found through the DHT",
+            Style::default().fg(theme.warning),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!("Trackers ({})", link.trackers.len()),
+            Style::default().fg(theme.accent),
+        )));
+    }
+    for tracker in &link.trackers {
+        lines.push(Line::from(format!("  {}", app.masked(tracker))));
+    }
+    if !link.web_seeds.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("Web seeds ({})", link.web_seeds.len()),
+            Style::default().fg(theme.accent),
+        )));
+        for seed in &link.web_seeds {
+            lines.push(Line::from(format!("  {}", app.masked(seed))));
+        }
+    }
+    if let Some(name) = &preview.existing {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Already in the session as {name}"),
+            Style::default().fg(theme.warning),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[Enter] Fetch metadata  [e] Edit  [Esc] Cancel",
+        Style::default().fg(theme.muted),
+    )));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Magnet preview", Style::default().fg(theme.accent)));
     let area_height = ((lines.len() + 2) as u16)
         .min(frame.area().height.saturating_sub(2))
         .max(7);
-    let area = centered_rect_fixed(70, area_height, frame.area());
+    let area = centered_rect_fixed(app.density(), 70, area_height, frame.area());
     frame.render_widget(Clear, area);
-    let inner = block.inner(area);
-    let view_height = inner.height.saturating_sub(1) as usize;
-    let scroll = lines.len().saturating_sub(view_height) as u16;
     frame.render_widget(
-        Paragraph::new(text)
+        Paragraph::new(Text::from(lines))
             .block(block)
-            .wrap(Wrap { trim: false })
-            .scroll((scroll, 0)),
+            .wrap(Wrap { trim: false }),
         area,
     );
 }
 
-fn draw_confirm_quit(frame: &mut Frame, app: &App) {
-    let yes_style = if app.quit_choice() {
-        Style::default().bg(Color::Yellow).fg(Color::Black)
-    } else {
-        Style::default().fg(Color::Yellow)
+fn draw_path_review(frame: &mut Frame, theme: &Theme, app: &App) {
+    let Some(review) = app.path_review() else {
+        return;
     };
-    let no_style = if app.quit_choice() {
-        Style::default().fg(COLOR_MUTED)
+    let (sanitize_style, keep_style) = if app.path_review_choice() {
+        (
+            Style::default().bg(theme.warning).fg(theme.on_accent),
+            Style::default().fg(theme.muted),
+        )
     } else {
-        Style::default().bg(Color::Yellow).fg(Color::Black)
+        (
+            Style::default().fg(theme.warning),
+            Style::default().bg(theme.warning).fg(theme.on_accent),
+        )
     };
-    let lines = vec![
+    let original = review
+        .placement
+        .base
+        .join(&review.placement.folder_name)
+        .display()
+        .to_string();
+    let mut lines = vec![
         Line::from(Span::styled(
-            "Are you sure you want to quit?",
-            Style::default().fg(Color::Yellow),
+            "This destination won't work on Windows",
+            Style::default().fg(theme.warning),
         )),
         Line::from(""),
+        Line::from(Span::styled(original, Style::default().fg(theme.text))),
+    ];
+    for issue in &review.issues {
+        lines.push(Line::from(Span::styled(
+            format!("  - {issue}"),
+            Style::default().fg(theme.muted),
+        )));
+    }
+    lines.extend([
         Line::from(""),
+        Line::from(Span::styled(
+            format!("Suggested: {}", review.sanitized.display()),
+            Style::default().fg(theme.text),
+        )),
         Line::from(vec![
-            Span::styled("[Y]es", yes_style),
+            Span::styled("[S]anitize", sanitize_style),
             Span::raw("   "),
-            Span::styled("[N]o", no_style),
+            Span::styled("[K]eep as is", keep_style),
         ]),
         Line::from(Span::styled(
             "[<-] [->] Select  [Enter] Confirm  [Esc] Cancel",
-            Style::default().fg(COLOR_MUTED),
+            Style::default().fg(theme.muted),
         )),
-    ];
-    let text = Text::from(lines.clone());
+    ]);
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
-        .style(Style::default().bg(COLOR_BG))
-        .title(Span::styled("Confirm", Style::default().fg(Color::Yellow)));
+        .border_style(Style::default().fg(theme.warning))
+        .style(Style::default().bg(theme.bg))
+        .title(Span::styled("Path check", Style::default().fg(theme.warning)));
     let area_height = ((lines.len() + 2) as u16)
         .min(frame.area().height.saturating_sub(2))
-        .max(6);
-    let area = centered_rect_fixed(70, area_height, frame.area());
+        .max(7);
+    let area = centered_rect_fixed(app.density(), 70, area_height, frame.area());
     frame.render_widget(Clear, area);
-    let inner = block.inner(area);
-    let view_height = inner.height.saturating_sub(1) as usize;
-    let scroll = lines.len().saturating_sub(view_height) as u16;
     frame.render_widget(
-        Paragraph::new(text)
+        Paragraph::new(Text::from(lines))
             .block(block)
-            .wrap(Wrap { trim: false })
-            .scroll((scroll, 0)),
+            .wrap(Wrap { trim: false }),
         area,
     );
 }
 
-fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
-    let (status, status_color) = format_status(t);
+fn torrent_row(theme: &Theme, app: &App, t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
+    let has_notes = app.notes_of(t).is_some();
+    let has_problem = app.problem_of(t).is_some();
+    let missing_files = app.is_missing_files(t);
+    let forced = app.is_forced(t);
+    let verified_at = app.verified_at_of(t);
+    let density = app.density();
+    let running = t.stats.as_ref().is_some_and(|s| {
+        matches!(
+            s.state,
+            librqbit::TorrentStatsState::Live | librqbit::TorrentStatsState::Initializing
+        )
+    });
+    let (status, status_color) = if missing_files {
+        ("Missing".to_string(), theme.highlight)
+    } else if forced && running {
+        ("Force".to_string(), Color::Magenta)
+    } else {
+        format_status(theme, t)
+    };
     let (prog, down, up, peers, size, ratio) = format_metrics(t);
     let spacing = 0usize;
-    let gap_style = Style::default().fg(COLOR_GREEN);
-    let status = format!("  {status}");
-    let prog = format!("  {prog}");
-    let down = format!("  {down}");
-    let up = format!("  {up}");
-    let peers = format!("  {peers}");
-    let size = format!("  {size}");
-    let ratio = format!("  {ratio}");
+    let gap_style = Style::default().fg(theme.accent);
+    let pad = match density {
+        Density::Comfortable => "  ",
+        Density::Compact => "",
+    };
+    let status = format!("{pad}{status}");
+    let prog = format!("{pad}{prog}");
+    let down = format!("{pad}{down}");
+    let up = format!("{pad}{up}");
+    let peers = format!("{pad}{peers}");
+    let size = format!("{pad}{size}");
+    let ratio = format!("{pad}{ratio}");
+    let verified = format!("{pad}{}", format_verified(verified_at));
     let bar_len: usize = col_widths
         .iter()
         .sum::<usize>()
@@ -817,7 +3244,7 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
         .max(1);
     let filled = progress_filled(t, bar_len);
 
-    let name_width = col_widths.get(0).copied().unwrap_or(0);
+    let name_width = col_widths.first().copied().unwrap_or(0);
     let status_width = col_widths.get(1).copied().unwrap_or(0);
     let prog_width = col_widths.get(2).copied().unwrap_or(0);
     let down_width = col_widths.get(3).copied().unwrap_or(0);
@@ -825,8 +3252,16 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
     let peers_width = col_widths.get(5).copied().unwrap_or(0);
     let size_width = col_widths.get(6).copied().unwrap_or(0);
     let ratio_width = col_widths.get(7).copied().unwrap_or(0);
+    let verified_width = col_widths.get(8).copied().unwrap_or(0);
 
-    let name_text = fit_text(&t.name, name_width);
+    // The problem badge takes two columns off the name; details are in the footer.
+    let badge = if has_problem { "! " } else { "" };
+    let name_width = name_width.saturating_sub(badge.len());
+    let name_text = if has_notes {
+        fit_text(&format!("✎ {}", t.name), name_width)
+    } else {
+        fit_text(&t.name, name_width)
+    };
     let status = fit_text_padded(&status, status_width, 1);
     let prog = fit_text_padded(&prog, prog_width, 1);
     let down = fit_text_padded(&down, down_width, 1);
@@ -834,16 +3269,18 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
     let peers = fit_text_padded(&peers, peers_width, 1);
     let size = fit_text_padded(&size, size_width, 1);
     let ratio = fit_text_padded(&ratio, ratio_width, 1);
+    let verified = fit_text_padded(&verified, verified_width, 1);
 
     let name_cell = Text::from(vec![
-        Line::from(Span::styled(
-            name_text,
-            Style::default().fg(COLOR_GREEN),
-        )),
+        Line::from(vec![
+            Span::styled(badge, Style::default().fg(theme.highlight)),
+            Span::styled(name_text, Style::default().fg(theme.accent)),
+        ]),
         bar_segment(
+            theme,
             filled,
             0,
-            col_widths.get(0).copied().unwrap_or(0),
+            col_widths.first().copied().unwrap_or(0),
             spacing,
             gap_style,
         ),
@@ -851,6 +3288,7 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
     let status_cell = Text::from(vec![
         Line::from(Span::styled(status, Style::default().fg(status_color))),
         bar_segment(
+            theme,
             filled,
             col_offset(col_widths, 1, spacing),
             col_widths.get(1).copied().unwrap_or(0),
@@ -859,8 +3297,9 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
         ),
     ]);
     let prog_cell = Text::from(vec![
-        Line::from(Span::styled(prog, Style::default().fg(COLOR_GREEN))),
+        Line::from(Span::styled(prog, Style::default().fg(theme.accent))),
         bar_segment(
+            theme,
             filled,
             col_offset(col_widths, 2, spacing),
             col_widths.get(2).copied().unwrap_or(0),
@@ -869,8 +3308,9 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
         ),
     ]);
     let down_cell = Text::from(vec![
-        Line::from(Span::styled(down, Style::default().fg(COLOR_CYAN))),
+        Line::from(Span::styled(down, Style::default().fg(theme.info))),
         bar_segment(
+            theme,
             filled,
             col_offset(col_widths, 3, spacing),
             col_widths.get(3).copied().unwrap_or(0),
@@ -879,8 +3319,9 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
         ),
     ]);
     let up_cell = Text::from(vec![
-        Line::from(Span::styled(up, Style::default().fg(COLOR_YELLOW))),
+        Line::from(Span::styled(up, Style::default().fg(theme.highlight))),
         bar_segment(
+            theme,
             filled,
             col_offset(col_widths, 4, spacing),
             col_widths.get(4).copied().unwrap_or(0),
@@ -889,8 +3330,9 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
         ),
     ]);
     let peers_cell = Text::from(vec![
-        Line::from(Span::styled(peers, Style::default().fg(COLOR_GREEN))),
+        Line::from(Span::styled(peers, Style::default().fg(theme.accent))),
         bar_segment(
+            theme,
             filled,
             col_offset(col_widths, 5, spacing),
             col_widths.get(5).copied().unwrap_or(0),
@@ -899,8 +3341,9 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
         ),
     ]);
     let size_cell = Text::from(vec![
-        Line::from(Span::styled(size, Style::default().fg(COLOR_GREEN))),
+        Line::from(Span::styled(size, Style::default().fg(theme.accent))),
         bar_segment(
+            theme,
             filled,
             col_offset(col_widths, 6, spacing),
             col_widths.get(6).copied().unwrap_or(0),
@@ -909,8 +3352,9 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
         ),
     ]);
     let ratio_cell = Text::from(vec![
-        Line::from(Span::styled(ratio, Style::default().fg(COLOR_GREEN))),
+        Line::from(Span::styled(ratio, Style::default().fg(theme.accent))),
         bar_segment(
+            theme,
             filled,
             col_offset(col_widths, 7, spacing),
             col_widths.get(7).copied().unwrap_or(0),
@@ -918,34 +3362,79 @@ fn torrent_row(t: &TorrentRow, col_widths: &[usize]) -> Row<'static> {
             gap_style,
         ),
     ]);
+    let verified_cell = Text::from(vec![
+        Line::from(Span::styled(verified, Style::default().fg(theme.muted))),
+        bar_segment(
+            theme,
+            filled,
+            col_offset(col_widths, 8, spacing),
+            col_widths.get(8).copied().unwrap_or(0),
+            spacing,
+            gap_style,
+        ),
+    ]);
+
+    let cells = [
+        name_cell,
+        status_cell,
+        prog_cell,
+        down_cell,
+        up_cell,
+        peers_cell,
+        size_cell,
+        ratio_cell,
+        verified_cell,
+    ];
+    // Compact rows leave out the progress bar line.
+    let height = torrent_row_height(density);
+    Row::new(cells.map(|mut text| {
+        text.lines.truncate(height as usize);
+        Cell::from(text)
+    }))
+    .height(height)
+}
+
+/// Lines per torrent in the table: the metrics row and, unless compact, the progress bar.
+fn torrent_row_height(density: Density) -> u16 {
+    match density {
+        Density::Comfortable => 2,
+        Density::Compact => 1,
+    }
+}
+
+fn group_header_row(theme: &Theme, name: &str, count: usize, collapsed: bool) -> Row<'static> {
+    let marker = if collapsed { "▸" } else { "▾" };
+    Row::new(vec![Cell::from(Span::styled(
+        format!("{marker} {name} ({count})"),
+        Style::default().fg(theme.info),
+    ))])
+    .height(1)
+}
 
-    Row::new(vec![
-        Cell::from(name_cell),
-        Cell::from(status_cell),
-        Cell::from(prog_cell),
-        Cell::from(down_cell),
-        Cell::from(up_cell),
-        Cell::from(peers_cell),
-        Cell::from(size_cell),
-        Cell::from(ratio_cell),
-    ])
-    .height(2)
+/// Lines a table entry takes up.
+fn entry_height(app: &App, entry: &ListEntry) -> u16 {
+    match entry {
+        ListEntry::Header { .. } => 1,
+        ListEntry::Torrent(_) => torrent_row_height(app.density()),
+    }
 }
 
-fn table_column_widths(area_width: u16, _columns: usize) -> Vec<usize> {
+/// Lays out the configured widths; the name column takes the rest, and when it would drop
+/// below its configured minimum the other columns give up space from the right.
+fn table_column_widths(area_width: u16, configured: &[usize; COLUMNS]) -> Vec<usize> {
     let available = area_width as usize;
-    let min_name = 24;
-    let mut cols = vec![0, 10, 8, 15, 13, 9, 10, 8];
+    let min_name = configured[0];
+    let mut cols = configured.to_vec();
     let mut fixed: usize = cols.iter().skip(1).sum();
     let mut name = available.saturating_sub(fixed);
     if name < min_name {
-        let mins = [0, 8, 7, 10, 10, 7, 8, 7];
+        let mins = [0, 8, 7, 10, 10, 7, 8, 7, 0];
         let mut deficit = min_name.saturating_sub(name);
         for idx in (1..cols.len()).rev() {
             if deficit == 0 {
                 break;
             }
-            let min = mins[idx];
+            let min = mins[idx].min(configured[idx]);
             if cols[idx] > min {
                 let take = (cols[idx] - min).min(deficit);
                 cols[idx] -= take;
@@ -976,6 +3465,7 @@ fn progress_filled(t: &TorrentRow, width: usize) -> usize {
 }
 
 fn bar_segment(
+    theme: &Theme,
     filled: usize,
     start: usize,
     len: usize,
@@ -995,13 +3485,13 @@ fn bar_segment(
     if seg_filled > 0 {
         spans.push(Span::styled(
             "\u{2588}".repeat(seg_filled),
-            Style::default().fg(COLOR_GREEN),
+            Style::default().fg(theme.accent),
         ));
     }
     if seg_empty > 0 {
         spans.push(Span::styled(
             "\u{2588}".repeat(seg_empty),
-            Style::default().fg(COLOR_PANEL),
+            Style::default().fg(theme.panel),
         ));
     }
     if gap > 0 {
@@ -1014,22 +3504,24 @@ fn col_offset(widths: &[usize], idx: usize, spacing: usize) -> usize {
     widths.iter().take(idx).sum::<usize>() + spacing * idx
 }
 
-fn format_status(t: &TorrentRow) -> (String, Color) {
+fn format_status(theme: &Theme, t: &TorrentRow) -> (String, Color) {
     let Some(stats) = t.stats.as_ref() else {
-        return ("-".to_string(), COLOR_MUTED);
+        return ("-".to_string(), theme.muted);
     };
     use librqbit::TorrentStatsState as S;
     match stats.state {
         S::Live => {
             if stats.finished {
-                ("Seed".to_string(), COLOR_GREEN)
+                ("Seed".to_string(), theme.accent)
+            } else if in_end_game(t) {
+                ("End".to_string(), theme.highlight)
             } else {
-                ("Down".to_string(), COLOR_CYAN)
+                ("Down".to_string(), theme.info)
             }
         }
-        S::Initializing => ("Init".to_string(), COLOR_CYAN),
-        S::Paused => ("Pause".to_string(), COLOR_YELLOW),
-        S::Error => ("Error".to_string(), Color::Red),
+        S::Initializing => ("Init".to_string(), theme.info),
+        S::Paused => ("Pause".to_string(), theme.highlight),
+        S::Error => ("Error".to_string(), theme.error),
     }
 }
 
@@ -1132,35 +3624,6 @@ fn counts(app: &App) -> (usize, usize, usize) {
     (active, seeding, total)
 }
 
-fn filter_counts(app: &App) -> (usize, usize, usize, usize, usize) {
-    let total = app.torrents().len();
-    let mut downloading = 0;
-    let mut seeding = 0;
-    let mut paused = 0;
-    let mut errors = 0;
-    for t in app.torrents() {
-        if let Some(stats) = t.stats.as_ref() {
-            use librqbit::TorrentStatsState as S;
-            let is_seeding = stats.finished
-                || (stats.total_bytes > 0
-                    && stats.progress_bytes >= stats.total_bytes
-                    && matches!(stats.state, S::Live));
-            match stats.state {
-                S::Live | S::Initializing => {
-                    if is_seeding {
-                        seeding += 1;
-                    } else {
-                        downloading += 1;
-                    }
-                }
-                S::Paused => paused += 1,
-                S::Error => errors += 1,
-            }
-        }
-    }
-    (downloading, seeding, paused, errors, total)
-}
-
 fn format_bytes(bytes: u64) -> String {
     const KB: f64 = 1024.0;
     const MB: f64 = KB * 1024.0;
@@ -1203,7 +3666,17 @@ fn fit_text_padded(s: &str, width: usize, pad: usize) -> String {
     format!("{}{}", " ".repeat(pad), trimmed)
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+/// Compact density gives dialogs half of the margin back.
+fn dialog_percent(density: Density, percent: u16) -> u16 {
+    match density {
+        Density::Comfortable => percent,
+        Density::Compact => percent + (100 - percent) / 2,
+    }
+}
+
+fn centered_rect(density: Density, percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let percent_x = dialog_percent(density, percent_x);
+    let percent_y = dialog_percent(density, percent_y);
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -1222,7 +3695,8 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn centered_rect_fixed(percent_x: u16, height: u16, r: Rect) -> Rect {
+fn centered_rect_fixed(density: Density, percent_x: u16, height: u16, r: Rect) -> Rect {
+    let percent_x = dialog_percent(density, percent_x);
     let height = height.min(r.height.saturating_sub(2)).max(5);
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -0,0 +1,145 @@
+use chrono::Timelike;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Sparkline},
+};
+
+use crate::app::App;
+
+use super::{Theme, counts};
+
+const DIGIT_ROWS: usize = 5;
+
+/// Five rows of three cells per glyph.
+fn glyph(c: char) -> [&'static str; DIGIT_ROWS] {
+    match c {
+        '0' => ["███", "█ █", "█ █", "█ █", "███"],
+        '1' => ["  █", "  █", "  █", "  █", "  █"],
+        '2' => ["███", "  █", "███", "█  ", "███"],
+        '3' => ["███", "  █", "███", "  █", "███"],
+        '4' => ["█ █", "█ █", "███", "  █", "  █"],
+        '5' => ["███", "█  ", "███", "  █", "███"],
+        '6' => ["███", "█  ", "███", "█ █", "███"],
+        '7' => ["███", "  █", "  █", "  █", "  █"],
+        '8' => ["███", "█ █", "███", "█ █", "███"],
+        '9' => ["███", "█ █", "███", "  █", "███"],
+        '.' => ["   ", "   ", "   ", "   ", " █ "],
+        _ => ["   ", "   ", "   ", "   ", "   "],
+    }
+}
+
+fn big_text(text: &str) -> Vec<String> {
+    (0..DIGIT_ROWS)
+        .map(|row| {
+            text.chars()
+                .map(|c| glyph(c)[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// A speed in MiB/s as a number and its unit, in KiB/s below 1 MiB/s.
+fn split_speed(mib: f64) -> (String, &'static str) {
+    if mib >= 1.0 {
+        (format!("{mib:.1}"), "MiB/s")
+    } else {
+        (format!("{:.0}", mib * 1024.0), "KiB/s")
+    }
+}
+
+fn big_speed(theme: &Theme, arrow: &str, mib: f64, style: Style) -> Vec<Line<'static>> {
+    let (number, unit) = split_speed(mib);
+    let mut lines: Vec<Line> = big_text(&number)
+        .into_iter()
+        .map(|row| Line::from(Span::styled(row, style)))
+        .collect();
+    lines.push(Line::from(Span::styled(
+        format!("{arrow} {unit}"),
+        Style::default().fg(theme.muted),
+    )));
+    lines
+}
+
+/// Big session speeds, torrent counts and a bandwidth graph on a dark screen. The block
+/// drifts a little every minute so nothing stays lit in one place.
+pub(super) fn draw_screensaver(frame: &mut Frame, theme: &Theme, app: &App) {
+    let area = frame.area();
+    frame.render_widget(Block::default().style(Style::default().bg(theme.bg)), area);
+    let stats = app.session_stats();
+    let down = stats.map_or(0.0, |s| s.download_speed.mbps);
+    let up = stats.map_or(0.0, |s| s.upload_speed.mbps);
+    let (active, seeding, total) = counts(app);
+
+    let minute = chrono::Local::now().minute() as u16;
+    let width = area.width.min(72);
+    let height = area.height.min(24);
+    let slack_x = area.width - width;
+    let slack_y = area.height - height;
+    let block = Rect::new(
+        area.x + (slack_x / 2 + minute % 7).min(slack_x),
+        area.y + (slack_y / 2 + minute % 3).min(slack_y),
+        width,
+        height,
+    );
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(DIGIT_ROWS as u16 + 1),
+            Constraint::Length(1),
+            Constraint::Length(DIGIT_ROWS as u16 + 1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .split(block);
+    frame.render_widget(
+        Paragraph::new(big_speed(theme, "↓", down, Style::default().fg(theme.info)))
+            .alignment(Alignment::Center),
+        rows[0],
+    );
+    frame.render_widget(
+        Paragraph::new(big_speed(theme, "↑", up, Style::default().fg(theme.highlight)))
+            .alignment(Alignment::Center),
+        rows[2],
+    );
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            format!("{active} active · {seeding} seeding · {total} torrents"),
+            Style::default().fg(theme.accent),
+        )))
+        .alignment(Alignment::Center),
+        rows[4],
+    );
+
+    // Newest samples on the right, as many as fit.
+    let history = app.speed_history();
+    let skip = history.len().saturating_sub(block.width as usize);
+    let kib = |mib: f64| (mib * 1024.0) as u64;
+    let down_graph: Vec<u64> = history.iter().skip(skip).map(|&(d, _)| kib(d)).collect();
+    let up_graph: Vec<u64> = history.iter().skip(skip).map(|&(_, u)| kib(u)).collect();
+    frame.render_widget(
+        Sparkline::default()
+            .data(&down_graph)
+            .style(Style::default().fg(theme.info)),
+        rows[6],
+    );
+    frame.render_widget(
+        Sparkline::default()
+            .data(&up_graph)
+            .style(Style::default().fg(theme.highlight)),
+        rows[7],
+    );
+    frame.render_widget(
+        Paragraph::new(Span::styled("Press any key", Style::default().fg(theme.muted)))
+            .alignment(Alignment::Center),
+        rows[8],
+    );
+}
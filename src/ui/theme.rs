@@ -0,0 +1,102 @@
+use ratatui::style::Color;
+
+use crate::config::ThemeName;
+
+/// The colours every draw function paints with.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub bg: Color,
+    pub panel: Color,
+    /// Borders of focused panels, titles and most highlights.
+    pub accent: Color,
+    pub border: Color,
+    pub focus_bg: Color,
+    pub info: Color,
+    pub highlight: Color,
+    pub muted: Color,
+    /// Text on an accent, highlight or warning background.
+    pub on_accent: Color,
+    pub text: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// The selected row in lists and dialogs.
+    pub selection: Color,
+    /// The selected row of a panel without focus, and the sidebar's cursor.
+    pub row_highlight: Color,
+    /// Behind the selected torrent while the table has focus.
+    pub focus_tint: Color,
+    pub gauge: Color,
+    /// Executable and script files in the file picker.
+    pub risky: Color,
+}
+
+const DARK: Theme = Theme {
+    bg: Color::Rgb(14, 16, 14),
+    panel: Color::Rgb(20, 22, 20),
+    accent: Color::Rgb(0, 245, 150),
+    border: Color::Rgb(0, 205, 110),
+    focus_bg: Color::Rgb(6, 8, 6),
+    info: Color::Rgb(0, 255, 255),
+    highlight: Color::Rgb(255, 255, 0),
+    muted: Color::Rgb(136, 136, 136),
+    on_accent: Color::Rgb(0, 0, 0),
+    text: Color::White,
+    warning: Color::Yellow,
+    error: Color::Red,
+    selection: Color::Rgb(0, 120, 0),
+    row_highlight: Color::Rgb(0, 60, 0),
+    focus_tint: Color::Rgb(0, 30, 0),
+    gauge: Color::Rgb(0, 90, 50),
+    risky: Color::Rgb(255, 80, 200),
+};
+
+const LIGHT: Theme = Theme {
+    bg: Color::Rgb(248, 248, 244),
+    panel: Color::Rgb(236, 236, 230),
+    accent: Color::Rgb(0, 120, 70),
+    border: Color::Rgb(0, 140, 80),
+    focus_bg: Color::Rgb(255, 255, 255),
+    info: Color::Rgb(0, 100, 160),
+    highlight: Color::Rgb(150, 100, 0),
+    muted: Color::Rgb(110, 110, 110),
+    on_accent: Color::Rgb(255, 255, 255),
+    text: Color::Rgb(20, 20, 20),
+    warning: Color::Rgb(170, 100, 0),
+    error: Color::Rgb(190, 0, 0),
+    selection: Color::Rgb(170, 220, 180),
+    row_highlight: Color::Rgb(200, 235, 205),
+    focus_tint: Color::Rgb(225, 242, 228),
+    gauge: Color::Rgb(120, 200, 150),
+    risky: Color::Rgb(190, 0, 130),
+};
+
+/// Pure colours on black.
+const HIGH_CONTRAST: Theme = Theme {
+    bg: Color::Rgb(0, 0, 0),
+    panel: Color::Rgb(0, 0, 0),
+    accent: Color::Rgb(0, 255, 0),
+    border: Color::Rgb(255, 255, 255),
+    focus_bg: Color::Rgb(0, 0, 0),
+    info: Color::Rgb(0, 255, 255),
+    highlight: Color::Rgb(255, 255, 0),
+    muted: Color::Rgb(210, 210, 210),
+    on_accent: Color::Rgb(0, 0, 0),
+    text: Color::Rgb(255, 255, 255),
+    warning: Color::Rgb(255, 255, 0),
+    error: Color::Rgb(255, 70, 70),
+    selection: Color::Rgb(0, 0, 200),
+    row_highlight: Color::Rgb(0, 0, 140),
+    focus_tint: Color::Rgb(0, 0, 110),
+    gauge: Color::Rgb(0, 200, 0),
+    risky: Color::Rgb(255, 0, 255),
+};
+
+impl Theme {
+    pub fn named(name: ThemeName) -> &'static Theme {
+        match name {
+            ThemeName::Dark => &DARK,
+            ThemeName::Light => &LIGHT,
+            ThemeName::HighContrast => &HIGH_CONTRAST,
+        }
+    }
+}
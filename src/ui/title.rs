@@ -0,0 +1,27 @@
+use crate::app::App;
+
+use super::{counts, format_bytes};
+
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// The `[ui] title` template filled in with session speeds and counts, or None when it's
+/// empty.
+pub fn terminal_title(app: &App) -> Option<String> {
+    let template = app.title_template();
+    if template.is_empty() {
+        return None;
+    }
+    let stats = app.session_stats();
+    let speed = |mib: f64| format!("{}/s", format_bytes((mib * MIB) as u64));
+    let down = speed(stats.map_or(0.0, |s| s.download_speed.mbps));
+    let up = speed(stats.map_or(0.0, |s| s.upload_speed.mbps));
+    let (active, seeding, total) = counts(app);
+    Some(
+        template
+            .replace("{down}", &down)
+            .replace("{up}", &up)
+            .replace("{active}", &active.to_string())
+            .replace("{seeding}", &seeding.to_string())
+            .replace("{total}", &total.to_string()),
+    )
+}